@@ -29,8 +29,13 @@
 mod test;
 
 use {
-    cryptohash::sha256::{self, Digest, DIGESTBYTES},
+    cryptohash::sha256::{Digest, DIGESTBYTES},
     globutils::Commas,
+    light_verifier::bitmap::{
+        block_checksum, chain_checksum, ChecksumData, BITS_SIZE, BIT_ARRAY,
+        BIT_DESC_CLEAR, BIT_DESC_SET, BIT_HEADER, BLOCK_BITS, BLOCK_SIZE, CHECK_SIZE,
+        HEADER_MAGIC, INDEX_SIZE,
+    },
     ruc::*,
     std::{
         cmp,
@@ -48,8 +53,6 @@ const INCLUDE_BITS: bool = true;
 /// Define a structure for a checksum on a block. We use
 /// the first CHECK_SIZE bytes of the sha256 digest as the
 /// checksum.
-const CHECK_SIZE: usize = 16;
-
 #[repr(C)]
 #[derive(Copy, Clone, Debug, Default, PartialEq)]
 struct CheckBlock {
@@ -65,17 +68,9 @@ impl CheckBlock {
     }
 }
 
-// Define various constants for use in the file
-// data block header.
-const HEADER_MAGIC: u32 = 0x0204_0600;
-const HEADER_SIZE: usize = 48;
-
-// Define the types of headers.
+// The layout of the data blocks and the types of headers
+// are shared with the light verifier of the UTXO map.
 const BIT_INVALID: u16 = 0; // This value is used for testing.
-const BIT_ARRAY: u16 = 1;
-const BIT_DESC_SET: u16 = 2;
-const BIT_DESC_CLEAR: u16 = 3;
-const BIT_HEADER: u16 = 4;
 
 /// For users who download the bitmap, this is what they
 /// get for a block header.
@@ -139,6 +134,7 @@ pub type BlockBits = [u8; BITS_SIZE];
 // 8-byte version and a checksum for the tree.
 
 const BLOCK_INFO_SIZE: usize = mem::size_of::<BlockInfo>();
+const _: () = assert!(BLOCK_INFO_SIZE == light_verifier::bitmap::BLOCK_INFO_SIZE);
 const DESCRIPTOR_SIZE: usize = 8 + DIGESTBYTES;
 const BLOCK_BITS_SIZE: usize = mem::size_of::<BlockBits>();
 
@@ -250,7 +246,7 @@ impl SparseMap {
 
             checksum_data[0..CHECK_SIZE]
                 .clone_from_slice(&info.checksum.bytes[0..CHECK_SIZE]);
-            digest = sha256::hash(&checksum_data);
+            digest = Digest(chain_checksum(&checksum_data));
             checksum_data[CHECK_SIZE..].clone_from_slice(&digest.0[0..]);
         }
 
@@ -368,12 +364,6 @@ impl BlockHeader {
     }
 }
 
-// Define the block structure of the server version
-// of the bitmap.
-const BLOCK_SIZE: usize = 32 * 1024;
-const BITS_SIZE: usize = BLOCK_SIZE - HEADER_SIZE;
-const BLOCK_BITS: usize = BITS_SIZE * 8;
-
 /// Export the number of bits per block to the user.
 pub const BITMAP_BLOCK_SIZE: usize = BLOCK_BITS;
 
@@ -381,7 +371,6 @@ pub const BITMAP_BLOCK_SIZE: usize = BLOCK_BITS;
 // modes. The bits are stored as a full bit map, or a list of
 // bits. A list of bits can be those bits that are clear or
 // those that are set.
-const INDEX_SIZE: usize = 3;
 const LOWER_LIMIT: u32 = BITS_SIZE as u32 / INDEX_SIZE as u32;
 const UPPER_LIMIT: u32 = (BLOCK_BITS - BITS_SIZE / INDEX_SIZE) as u32;
 
@@ -408,11 +397,7 @@ impl BitBlock {
     // Compute a checksum for the block.
     #[inline(always)]
     fn compute_checksum(&self) -> [u8; CHECK_SIZE] {
-        let digest = sha256::hash(self.as_checksummed_region());
-        let mut result: [u8; CHECK_SIZE] = Default::default();
-
-        result.clone_from_slice(&digest[0..CHECK_SIZE]);
-        result
+        block_checksum(self.as_checksummed_region())
     }
 
     // Set the block check bits with the current checksum for the block.
@@ -474,7 +459,6 @@ impl BitBlock {
 
 // Define a type for the checksum operation.
 
-type ChecksumData = [u8; CHECK_SIZE + DIGESTBYTES];
 const EMPTY_CHECKSUM: ChecksumData = [0_u8; CHECK_SIZE + DIGESTBYTES];
 
 /// Define the in-memory structure for managing a
@@ -936,7 +920,7 @@ impl BitMap {
             }
 
             // Compute the next sha256 digest.
-            digest = sha256::hash(&self.checksum_data[i]);
+            digest = Digest(chain_checksum(&self.checksum_data[i]));
         }
 
         self.first_invalid = self.blocks.len();
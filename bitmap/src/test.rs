@@ -2,6 +2,7 @@
 
 use super::*;
 use cryptohash::sha256::{Digest, DIGESTBYTES};
+use light_verifier::bitmap::HEADER_SIZE;
 use rand::Rng;
 use std::fs;
 use std::fs::OpenOptions;
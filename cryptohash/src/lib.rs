@@ -211,15 +211,7 @@ pub fn hash_single(hash: &HashValue) -> HashValue {
 /// the hash of the left side.
 #[inline(always)]
 pub fn hash_partial(left: &HashValue, right: &HashValue) -> HashValue {
-    let empty_hash = HashValue::new();
-    let left_present = *left != empty_hash;
-    let right_present = *right != empty_hash;
-
-    if left_present && right_present {
-        hash_pair(left, right)
-    } else if left_present {
-        hash_single(left)
-    } else {
-        empty_hash
+    HashValue {
+        hash: light_verifier::hash_partial(&left.hash, &right.hash),
     }
 }
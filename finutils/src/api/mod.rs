@@ -4,7 +4,10 @@
 
 use {
     ledger::staking::{
-        self, StakerMemo, TendermintAddr, MAX_POWER_PERCENT_PER_VALIDATOR,
+        self,
+        proposal::{Proposal, ProposalTally, StakingParams},
+        uptime::UptimeStats,
+        BlockHeight, StakerMemo, TendermintAddr, MAX_POWER_PERCENT_PER_VALIDATOR,
     },
     serde::{Deserialize, Serialize},
 };
@@ -28,6 +31,12 @@ impl ValidatorList {
             validators,
         }
     }
+
+    /// Use the effective threshold which may be changed by proposals.
+    pub fn with_threshold(mut self, threshold: [u128; 2]) -> Self {
+        self.threshold = threshold;
+        self
+    }
 }
 
 /// The basic inforamtion of a validator
@@ -39,6 +48,8 @@ pub struct Validator {
     accept_delegation: bool,
     rank: u64,
     extra: StakerMemo,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    uptime: Option<UptimeStats>,
 }
 
 impl Validator {
@@ -57,8 +68,15 @@ impl Validator {
             accept_delegation,
             rank,
             extra: v.memo.clone(),
+            uptime: None,
         }
     }
+
+    /// Attach the signing statistics in the rolling window.
+    pub fn with_uptime(mut self, uptime: Option<UptimeStats>) -> Self {
+        self.uptime = uptime;
+        self
+    }
 }
 
 /// The detail information of a validator which includes
@@ -95,6 +113,12 @@ pub struct ValidatorDetail {
     pub validator_realtime_apy: [u128; 2],
     /// total number of its delegators
     pub delegator_cnt: u64,
+    /// signing statistics in the rolling window
+    #[serde(default)]
+    pub uptime: Option<UptimeStats>,
+    /// the validator is jailed until this height
+    #[serde(default)]
+    pub jailed_until: Option<BlockHeight>,
 }
 
 #[allow(missing_docs)]
@@ -124,6 +148,48 @@ impl DelegatorList {
     }
 }
 
+/// A parameter proposal together with its tally
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ProposalDetail {
+    proposal: Proposal,
+    tally: ProposalTally,
+}
+
+impl ProposalDetail {
+    #[allow(missing_docs)]
+    pub fn new(proposal: Proposal, tally: ProposalTally) -> Self {
+        ProposalDetail { proposal, tally }
+    }
+}
+
+/// All parameter proposals and the effective staking parameters
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ProposalList {
+    cur_height: BlockHeight,
+    params: StakingParams,
+    proposals: Vec<ProposalDetail>,
+}
+
+impl ProposalList {
+    #[allow(missing_docs)]
+    pub fn new(
+        cur_height: BlockHeight,
+        params: StakingParams,
+        proposals: Vec<ProposalDetail>,
+    ) -> Self {
+        ProposalList {
+            cur_height,
+            params,
+            proposals,
+        }
+    }
+
+    /// The staking parameters in effect.
+    pub fn params(&self) -> &StakingParams {
+        &self.params
+    }
+}
+
 /// Delegation information of a findora account which includes
 /// total bond amount, bond entries, begin and end height of delegation, and reward info etc.
 #[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
@@ -0,0 +1,198 @@
+//!
+//! # Multi-party Signing
+//!
+//! Collect the co-signatures of a `CoSigOp` without gathering all keys
+//! in one place:
+//!
+//! 1. the coordinator creates a draft and exports it to a file
+//! 2. every signer adds its signature to its own copy of the draft
+//! 3. the coordinator merges all the partial files, checks the progress
+//!    against a `ValidatorData`, and submits it when the threshold is reached
+//!
+
+use {
+    super::utils,
+    crate::txn_builder::TransactionBuilder,
+    ledger::{
+        data_model::{NoReplayToken, Operation},
+        staking::{
+            cosig::{CoSigOp, CoSigReport},
+            ops::{
+                fra_distribution::{Data as FraDistributionData, FraDistributionOps},
+                governance::{ByzantineKind, Data as GovernanceData, GovernanceOps},
+                update_validator::UpdateValidatorOps,
+            },
+            BlockHeight, Validator, ValidatorData,
+        },
+    },
+    ruc::*,
+    serde::{Deserialize, Serialize},
+    std::{collections::BTreeMap, fmt::Debug, fs},
+    zei::xfr::sig::{XfrKeyPair, XfrPublicKey},
+};
+
+/// A `CoSigOp` waiting for signatures,
+/// along with the builder that holds its nonce.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct CoSigDraft<T>
+where
+    T: Clone + Debug + Serialize + for<'a> Deserialize<'a>,
+{
+    builder: TransactionBuilder,
+    op: CoSigOp<T>,
+}
+
+impl<T> CoSigDraft<T>
+where
+    T: Clone + Debug + Serialize + for<'a> Deserialize<'a>,
+{
+    #[inline(always)]
+    fn new(builder: TransactionBuilder, op: CoSigOp<T>) -> Self {
+        CoSigDraft { builder, op }
+    }
+
+    /// Create a draft with a new builder from the network,
+    /// `f` generates the operation from the nonce of the builder.
+    pub fn create(
+        serv_url: Option<&str>,
+        f: impl FnOnce(NoReplayToken) -> Result<CoSigOp<T>>,
+    ) -> Result<Self> {
+        let builder = utils::new_tx_builder_to(serv_url).c(d!())?;
+        f(builder.transaction().body.no_replay_token)
+            .c(d!())
+            .map(|op| Self::new(builder, op.to_draft()))
+    }
+
+    /// Return a copy without any signatures.
+    #[inline(always)]
+    pub fn to_draft(&self) -> Self {
+        Self::new(self.builder.clone(), self.op.to_draft())
+    }
+
+    /// Attach the signature of `kp`, no network access is needed.
+    #[inline(always)]
+    pub fn sign(&mut self, kp: &XfrKeyPair) -> Result<()> {
+        self.op.sign(kp).c(d!())
+    }
+
+    /// Merge the signatures of another copy of the same draft.
+    #[inline(always)]
+    pub fn merge(&mut self, other: Self) -> Result<()> {
+        self.op.merge(other.op).c(d!())
+    }
+
+    /// Report the signing progress based on the given validators.
+    #[inline(always)]
+    pub fn check(&self, vd: &ValidatorData) -> Result<CoSigReport> {
+        self.op.check_cosigs_report(vd).c(d!())
+    }
+
+    #[inline(always)]
+    #[allow(missing_docs)]
+    pub fn get_op(&self) -> &CoSigOp<T> {
+        &self.op
+    }
+
+    /// Wrap the operation into a transaction,
+    /// `fee_kp` should be set for the operations that need a fee.
+    pub fn into_transaction(
+        self,
+        wrap: impl FnOnce(CoSigOp<T>) -> Operation,
+        fee_kp: Option<&XfrKeyPair>,
+    ) -> Result<TransactionBuilder> {
+        let mut builder = self.builder;
+        builder.add_operation(wrap(self.op));
+
+        if let Some(kp) = fee_kp {
+            utils::gen_fee_op(kp)
+                .c(d!())
+                .map(|op| builder.add_operation(op))?;
+            builder.sign_to_map(kp);
+        }
+
+        Ok(builder)
+    }
+
+    #[allow(missing_docs)]
+    pub fn store(&self, path: &str) -> Result<()> {
+        serde_json::to_vec_pretty(self)
+            .c(d!())
+            .and_then(|bytes| fs::write(path, bytes).c(d!()))
+    }
+
+    #[allow(missing_docs)]
+    pub fn load(path: &str) -> Result<Self> {
+        fs::read(path)
+            .c(d!())
+            .and_then(|bytes| serde_json::from_slice(&bytes).c(d!()))
+    }
+}
+
+/// Sign the draft in `draft_path`, and store the partial result to `out_path`.
+pub fn cosig_sign_file<T>(
+    draft_path: &str,
+    kp: &XfrKeyPair,
+    out_path: &str,
+) -> Result<()>
+where
+    T: Clone + Debug + Serialize + for<'a> Deserialize<'a>,
+{
+    let mut draft = CoSigDraft::<T>::load(draft_path).c(d!())?.to_draft();
+    draft.sign(kp).c(d!())?;
+    draft.store(out_path).c(d!())
+}
+
+/// Merge all the partial files into one draft.
+pub fn cosig_merge_files<T>(paths: &[&str]) -> Result<CoSigDraft<T>>
+where
+    T: Clone + Debug + Serialize + for<'a> Deserialize<'a>,
+{
+    let mut iter = paths.iter();
+    let mut merged = iter
+        .next()
+        .c(d!("no partial files"))
+        .and_then(|p| CoSigDraft::<T>::load(p).c(d!()))?;
+
+    for p in iter {
+        CoSigDraft::<T>::load(p).c(d!()).and_then(|partial| {
+            merged
+                .merge(partial)
+                .c(d!(format!("invalid partial file: {p}")))
+        })?;
+    }
+
+    Ok(merged)
+}
+
+/// Create a draft of `UpdateValidator`,
+/// with the cosig threshold voted on chain.
+pub fn gen_update_validator_draft(
+    h: BlockHeight,
+    v_set: Vec<Validator>,
+) -> Result<CoSigDraft<ValidatorData>> {
+    let threshold = utils::get_staking_params().c(d!())?.cosig_threshold;
+    CoSigDraft::create(None, |nonce| {
+        UpdateValidatorOps::new(&[], h, v_set, threshold, nonce).c(d!())
+    })
+}
+
+/// Create a draft of `Governance`.
+pub fn gen_governance_draft(
+    byzantine_id: XfrPublicKey,
+    kind: ByzantineKind,
+    custom_percent: Option<[u64; 2]>,
+) -> Result<CoSigDraft<GovernanceData>> {
+    CoSigDraft::create(None, |nonce| {
+        GovernanceOps::new(&[], byzantine_id, kind, custom_percent, nonce).c(d!())
+    })
+}
+
+/// Create a draft of `FraDistribution`.
+pub fn gen_fra_distribution_draft(
+    alloc_table: BTreeMap<XfrPublicKey, u64>,
+) -> Result<CoSigDraft<FraDistributionData>> {
+    CoSigDraft::create(None, |nonce| {
+        FraDistributionOps::new(&[], alloc_table, nonce).c(d!())
+    })
+}
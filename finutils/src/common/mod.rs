@@ -6,6 +6,8 @@
 //! This module is the library part of FN.
 //!
 
+pub mod cosig;
+pub mod offline;
 pub mod utils;
 
 use {
@@ -27,8 +29,8 @@ use {
     std::{env, fs},
     tendermint::PrivateKey,
     utils::{
-        get_block_height, get_local_block_height, get_validator_detail,
-        parse_td_validator_keys,
+        get_block_height, get_local_block_height, get_staking_params,
+        get_validator_detail, parse_td_validator_keys,
     },
     zei::{
         setup::PublicParams,
@@ -97,7 +99,8 @@ pub fn stake(
     force: bool,
 ) -> Result<()> {
     let am = amount.parse::<u64>().c(d!("'amount' must be an integer"))?;
-    check_delegation_amount(am, false).c(d!())?;
+    let params = get_staking_params().c(d!())?;
+    check_delegation_amount(am, false, &params).c(d!())?;
     let cr = commission_rate
         .parse::<f64>()
         .c(d!("commission rate must be a float number"))
@@ -160,7 +163,8 @@ pub fn stake_append(
     td_addr: Option<TendermintAddrRef>,
 ) -> Result<()> {
     let am = amount.parse::<u64>().c(d!("'amount' must be an integer"))?;
-    check_delegation_amount(am, true).c(d!())?;
+    let params = get_staking_params().c(d!())?;
+    check_delegation_amount(am, true, &params).c(d!())?;
 
     let td_addr = td_addr.map(|ta| ta.to_owned()).c(d!()).or_else(|_| {
         get_td_pubkey()
@@ -264,6 +268,24 @@ pub fn claim(am: Option<&str>, sk_str: Option<&str>) -> Result<()> {
     utils::send_tx(&tx).c(d!())
 }
 
+/// Return the jailed validator to the active set
+/// after its jail period has passed.
+pub fn unjail(sk_str: Option<&str>) -> Result<()> {
+    let kp = restore_keypair_from_str_with_default(sk_str)?;
+
+    let mut builder = utils::new_tx_builder().c(d!())?;
+
+    utils::gen_fee_op(&kp).c(d!()).map(|op| {
+        builder.add_operation(op);
+        builder.add_operation_unjail(&kp);
+    })?;
+
+    let mut tx = builder.take_transaction();
+    tx.sign_to_map(&kp);
+
+    utils::send_tx(&tx).c(d!())
+}
+
 /// Show information of current node, including following sections:
 ///     Server URL
 ///     Findora Wallet Address
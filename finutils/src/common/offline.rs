@@ -0,0 +1,475 @@
+//!
+//! # Offline Signing
+//!
+//! Split the `build -> sign -> submit` flow of the common helpers into three steps,
+//! so that the secret key can stay on a machine without network access:
+//!
+//! 1. `gen_unsigned_*`, on an online machine, collect everything from the network
+//!    and emit an unsigned envelope, the `serv_url` of them is the node to query,
+//!    the configured one is used if it is `None`
+//! 2. `sign_tx`, on an offline machine, build all operations and sign them
+//! 3. `broadcast_tx`, on an online machine, submit the signed transaction
+//!
+
+use {
+    super::utils::{self, gen_transfer_op_with_utxos, get_owned_utxos_x},
+    crate::txn_builder::TransactionBuilder,
+    ledger::{
+        data_model::{
+            gen_random_keypair, AssetRules, AssetTypeCode, AssetTypePrefix, Operation,
+            Transaction, TxoRef, TxoSID, Utxo, BLACK_HOLE_PUBKEY_STAKING,
+        },
+        staking::{td_addr_to_bytes, PartialUnDelegation, FRA_TOTAL_AMOUNT},
+    },
+    ruc::*,
+    serde::{Deserialize, Serialize},
+    std::collections::BTreeMap,
+    zei::{
+        setup::PublicParams,
+        xfr::{
+            asset_record::AssetRecordType,
+            sig::{XfrKeyPair, XfrPublicKey},
+            structs::OwnerMemo,
+        },
+    },
+};
+
+/// An operation that will be built when signing.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum UnsignedOp {
+    /// Transfer assets to some addresses, the fee is included.
+    Transfer {
+        #[allow(missing_docs)]
+        targets: Vec<(XfrPublicKey, u64)>,
+        /// `None` means FRA
+        token_code: Option<AssetTypeCode>,
+        #[allow(missing_docs)]
+        confidential_am: bool,
+        #[allow(missing_docs)]
+        confidential_ty: bool,
+    },
+    /// Pay a standalone fee.
+    Fee,
+    /// Delegate to a validator, the principal transfer is included.
+    Delegation {
+        #[allow(missing_docs)]
+        amount: u64,
+        #[allow(missing_docs)]
+        validator: String,
+    },
+    #[allow(missing_docs)]
+    UnDelegation { pu: Option<PartialUnDelegation> },
+    #[allow(missing_docs)]
+    Claim { amount: Option<u64> },
+    #[allow(missing_docs)]
+    DefineAsset {
+        code: AssetTypeCode,
+        rules: AssetRules,
+        memo: String,
+    },
+    #[allow(missing_docs)]
+    IssueAsset {
+        code: AssetTypeCode,
+        amount: u64,
+        confidential: bool,
+    },
+}
+
+impl UnsignedOp {
+    fn build(
+        &self,
+        builder: &mut TransactionBuilder,
+        kp: &XfrKeyPair,
+        utxos: &mut BTreeMap<TxoSID, (Utxo, Option<OwnerMemo>)>,
+    ) -> Result<()> {
+        match self {
+            UnsignedOp::Transfer {
+                targets,
+                token_code,
+                confidential_am,
+                confidential_ty,
+            } => {
+                let targets = targets.iter().map(|(pk, am)| (pk, *am)).collect();
+                gen_transfer_op_with_utxos(
+                    kp,
+                    utxos.clone(),
+                    targets,
+                    *token_code,
+                    Some(builder.get_fee()),
+                    *confidential_am,
+                    *confidential_ty,
+                    None,
+                )
+                .c(d!())
+                .map(|op| add_spending_op(builder, utxos, op))?;
+            }
+            UnsignedOp::Fee => {
+                gen_transfer_op_with_utxos(
+                    kp,
+                    utxos.clone(),
+                    vec![],
+                    None,
+                    Some(builder.get_fee()),
+                    false,
+                    false,
+                    None,
+                )
+                .c(d!())
+                .map(|op| add_spending_op(builder, utxos, op))?;
+            }
+            UnsignedOp::Delegation { amount, validator } => {
+                gen_transfer_op_with_utxos(
+                    kp,
+                    utxos.clone(),
+                    vec![(&BLACK_HOLE_PUBKEY_STAKING, *amount)],
+                    None,
+                    Some(builder.get_fee()),
+                    false,
+                    false,
+                    Some(
+                        AssetRecordType::NonConfidentialAmount_NonConfidentialAssetType,
+                    ),
+                )
+                .c(d!())
+                .map(|op| add_spending_op(builder, utxos, op))?;
+                builder.add_operation_delegation(kp, *amount, validator.clone());
+            }
+            UnsignedOp::UnDelegation { pu } => {
+                builder.add_operation_undelegation(kp, pu.clone());
+            }
+            UnsignedOp::Claim { amount } => {
+                builder.add_operation_claim(kp, *amount);
+            }
+            UnsignedOp::DefineAsset { code, rules, memo } => {
+                builder
+                    .add_operation_create_asset(kp, Some(*code), rules.clone(), memo)
+                    .c(d!())?;
+            }
+            UnsignedOp::IssueAsset {
+                code,
+                amount,
+                confidential,
+            } => {
+                builder
+                    .add_basic_issue_asset(
+                        kp,
+                        code,
+                        builder.get_seq_id(),
+                        *amount,
+                        AssetRecordType::from_flags(*confidential, false),
+                        &PublicParams::default(),
+                    )
+                    .c(d!())?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// inputs spent by an operation can not be used by the following ones
+fn add_spending_op(
+    builder: &mut TransactionBuilder,
+    utxos: &mut BTreeMap<TxoSID, (Utxo, Option<OwnerMemo>)>,
+    op: Operation,
+) {
+    if let Operation::TransferAsset(ref x) = op {
+        x.body.inputs.iter().for_each(|i| {
+            if let TxoRef::Absolute(sid) = i {
+                utxos.remove(sid);
+            }
+        });
+    }
+    builder.add_operation(op);
+}
+
+/// An unsigned transaction envelope.
+///
+/// Confidential records can only be opened with the secret key,
+/// so all the UTXOs of the owner are carried with their owner memos,
+/// and the inputs are selected when signing.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct UnsignedTx {
+    // holds the no-replay token of the final transaction
+    builder: TransactionBuilder,
+    owner: XfrPublicKey,
+    utxos: BTreeMap<TxoSID, (Utxo, Option<OwnerMemo>)>,
+    ops: Vec<UnsignedOp>,
+}
+
+impl UnsignedTx {
+    /// Collect the no-replay token and the UTXOs of `owner` from the network.
+    pub fn new(
+        owner: &XfrPublicKey,
+        ops: Vec<UnsignedOp>,
+        serv_url: Option<&str>,
+    ) -> Result<Self> {
+        let builder = utils::new_tx_builder_to(serv_url).c(d!())?;
+        let utxos = get_owned_utxos_x(serv_url, owner)
+            .c(d!())?
+            .into_iter()
+            .collect();
+
+        Ok(UnsignedTx {
+            builder,
+            owner: *owner,
+            utxos,
+            ops,
+        })
+    }
+
+    #[allow(missing_docs)]
+    pub fn get_owner(&self) -> &XfrPublicKey {
+        &self.owner
+    }
+
+    #[allow(missing_docs)]
+    pub fn get_ops(&self) -> &[UnsignedOp] {
+        &self.ops
+    }
+
+    /// Build and sign all the operations, no network access is needed.
+    pub fn sign(self, kp: &XfrKeyPair) -> Result<Transaction> {
+        if kp.get_pk_ref() != &self.owner {
+            return Err(eg!("the keypair does not match the owner"));
+        }
+
+        let mut builder = self.builder;
+        let mut utxos = self.utxos;
+        for op in self.ops.iter() {
+            op.build(&mut builder, kp, &mut utxos).c(d!())?;
+        }
+
+        let mut tx = builder.take_transaction();
+        tx.sign_to_map(kp);
+
+        Ok(tx)
+    }
+
+    #[allow(missing_docs)]
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).c(d!())
+    }
+
+    #[allow(missing_docs)]
+    pub fn from_json(s: &str) -> Result<Self> {
+        serde_json::from_str(s).c(d!())
+    }
+}
+
+/// Offline version of `utils::transfer_batch`.
+pub fn gen_unsigned_transfer(
+    owner: &XfrPublicKey,
+    target_list: Vec<(XfrPublicKey, u64)>,
+    token_code: Option<AssetTypeCode>,
+    confidential_am: bool,
+    confidential_ty: bool,
+    serv_url: Option<&str>,
+) -> Result<UnsignedTx> {
+    let am = target_list.iter().map(|(_, am)| *am).sum::<u64>();
+    if token_code.is_none() && FRA_TOTAL_AMOUNT < am {
+        return Err(eg!("Requested amount exceeds limit!"));
+    }
+
+    let op = UnsignedOp::Transfer {
+        targets: target_list,
+        token_code,
+        confidential_am,
+        confidential_ty,
+    };
+    UnsignedTx::new(owner, vec![op], serv_url).c(d!())
+}
+
+/// Offline version of `common::delegate`.
+pub fn gen_unsigned_delegate(
+    owner: &XfrPublicKey,
+    amount: u64,
+    validator: &str,
+    serv_url: Option<&str>,
+) -> Result<UnsignedTx> {
+    let op = UnsignedOp::Delegation {
+        amount,
+        validator: validator.to_owned(),
+    };
+    UnsignedTx::new(owner, vec![op], serv_url).c(d!())
+}
+
+/// Offline version of `common::undelegate`.
+pub fn gen_unsigned_undelegate(
+    owner: &XfrPublicKey,
+    param: Option<(u64, &str)>,
+    serv_url: Option<&str>,
+) -> Result<UnsignedTx> {
+    let pu = if let Some((amount, validator)) = param {
+        // partial undelegation
+        Some(PartialUnDelegation::new(
+            amount,
+            gen_random_keypair().get_pk(),
+            td_addr_to_bytes(validator).c(d!())?,
+        ))
+    } else {
+        None
+    };
+
+    let ops = vec![UnsignedOp::Fee, UnsignedOp::UnDelegation { pu }];
+    UnsignedTx::new(owner, ops, serv_url).c(d!())
+}
+
+/// Offline version of `common::claim`.
+pub fn gen_unsigned_claim(
+    owner: &XfrPublicKey,
+    amount: Option<u64>,
+    serv_url: Option<&str>,
+) -> Result<UnsignedTx> {
+    let ops = vec![UnsignedOp::Fee, UnsignedOp::Claim { amount }];
+    UnsignedTx::new(owner, ops, serv_url).c(d!())
+}
+
+/// Offline version of `common::create_asset_x`,
+/// the returned code is the one that will be used on chain.
+pub fn gen_unsigned_create_asset(
+    owner: &XfrPublicKey,
+    memo: &str,
+    decimal: u8,
+    max_units: Option<u64>,
+    transferable: bool,
+    code: Option<AssetTypeCode>,
+    serv_url: Option<&str>,
+) -> Result<(UnsignedTx, AssetTypeCode)> {
+    let code = code.unwrap_or_else(AssetTypeCode::gen_random);
+    let asset_code = AssetTypeCode::from_prefix_and_raw_asset_type_code(
+        AssetTypePrefix::UserDefined,
+        &code,
+    );
+
+    let mut rules = AssetRules::default();
+    rules.set_decimals(decimal).c(d!())?;
+    rules.set_max_units(max_units);
+    rules.set_transferable(transferable);
+
+    let ops = vec![
+        UnsignedOp::DefineAsset {
+            code,
+            rules,
+            memo: memo.to_owned(),
+        },
+        UnsignedOp::Fee,
+    ];
+    UnsignedTx::new(owner, ops, serv_url)
+        .c(d!())
+        .map(|tx| (tx, asset_code))
+}
+
+/// Offline version of `common::issue_asset_x`.
+pub fn gen_unsigned_issue_asset(
+    owner: &XfrPublicKey,
+    code: &AssetTypeCode,
+    amount: u64,
+    hidden: bool,
+    serv_url: Option<&str>,
+) -> Result<UnsignedTx> {
+    let ops = vec![
+        UnsignedOp::IssueAsset {
+            code: *code,
+            amount,
+            confidential: hidden,
+        },
+        UnsignedOp::Fee,
+    ];
+    UnsignedTx::new(owner, ops, serv_url).c(d!())
+}
+
+/// Sign an unsigned envelope, return the signed transaction in json format.
+pub fn sign_tx(unsigned_tx: &str, kp: &XfrKeyPair) -> Result<String> {
+    UnsignedTx::from_json(unsigned_tx)
+        .c(d!())
+        .and_then(|tx| tx.sign(kp).c(d!()))
+        .and_then(|tx| serde_json::to_string(&tx).c(d!()))
+}
+
+/// Submit a transaction signed by `sign_tx`.
+pub fn broadcast_tx(signed_tx: &str, to: Option<&str>) -> Result<()> {
+    serde_json::from_str::<Transaction>(signed_tx)
+        .c(d!("invalid transaction"))
+        .and_then(|tx| {
+            tx.check_tx().c(d!())?;
+            utils::send_tx_to(&tx, to).c(d!())
+        })
+}
+
+#[cfg(test)]
+#[allow(missing_docs)]
+mod test {
+    use {
+        super::*,
+        ledger::data_model::{TxOutput, ASSET_TYPE_FRA, TX_FEE_MIN},
+        rand_chacha::ChaChaRng,
+        rand_core::SeedableRng,
+        zei::xfr::{
+            asset_record::build_blind_asset_record, structs::AssetRecordTemplate,
+        },
+    };
+
+    #[test]
+    fn offline_sign() {
+        let mut prng = ChaChaRng::from_entropy();
+        let params = PublicParams::default();
+        let kp = XfrKeyPair::generate(&mut prng);
+
+        let utxos = (0..2)
+            .map(|i| {
+                let ar = AssetRecordTemplate::with_no_asset_tracing(
+                    10 * TX_FEE_MIN,
+                    ASSET_TYPE_FRA,
+                    AssetRecordType::from_flags(1 == i, false),
+                    kp.get_pk(),
+                );
+                let (record, _, om) =
+                    build_blind_asset_record(&mut prng, &params.pc_gens, &ar, vec![]);
+                let utxo = Utxo(TxOutput {
+                    id: Some(TxoSID(i)),
+                    record,
+                    lien: None,
+                    version: Default::default(),
+                    lock: None,
+                });
+                (TxoSID(i), (utxo, om))
+            })
+            .collect();
+
+        // the fee of the network when the envelope is generated
+        let mut builder = TransactionBuilder::from_seq_id(1);
+        builder.set_fee(2 * TX_FEE_MIN);
+
+        let unsigned = UnsignedTx {
+            builder,
+            owner: kp.get_pk(),
+            utxos,
+            ops: vec![
+                UnsignedOp::Fee,
+                UnsignedOp::Claim { amount: None },
+                UnsignedOp::Transfer {
+                    targets: vec![(
+                        XfrKeyPair::generate(&mut prng).get_pk(),
+                        TX_FEE_MIN,
+                    )],
+                    token_code: None,
+                    confidential_am: false,
+                    confidential_ty: false,
+                },
+            ],
+        };
+        let unsigned = pnk!(unsigned.to_json());
+
+        let other = XfrKeyPair::generate(&mut prng);
+        assert!(sign_tx(&unsigned, &other).is_err());
+
+        let tx = pnk!(serde_json::from_str::<Transaction>(&pnk!(sign_tx(
+            &unsigned, &kp
+        ))));
+        assert_eq!(3, tx.body.operations.len());
+        assert!(tx.check_fee());
+        assert_eq!(4 * TX_FEE_MIN, tx.fee_amount());
+        pnk!(tx.check_tx());
+    }
+}
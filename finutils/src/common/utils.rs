@@ -6,18 +6,21 @@ use std::collections::BTreeMap;
 
 use {
     crate::{
-        api::{DelegationInfo, ValidatorDetail},
+        api::{DelegationInfo, ProposalList, ValidatorDetail},
         common::get_serv_addr,
         txn_builder::{TransactionBuilder, TransferOperationBuilder},
     },
     globutils::{wallet, HashOf, SignatureOf},
     ledger::{
         data_model::{
-            AssetType, AssetTypeCode, DefineAsset, Operation, StateCommitmentData,
-            Transaction, TransferType, TxoRef, TxoSID, Utxo, ASSET_TYPE_FRA,
-            BLACK_HOLE_PUBKEY, TX_FEE_MIN,
+            AssetType, AssetTypeCode, DefineAsset, FeeInfo, Operation,
+            StateCommitmentData, Transaction, TransferType, TxoRef, TxoSID, Utxo,
+            ASSET_TYPE_FRA, BLACK_HOLE_PUBKEY, TX_FEE_MIN,
+        },
+        staking::{
+            init::get_inital_validators, proposal::StakingParams, TendermintAddrRef,
+            FRA_TOTAL_AMOUNT,
         },
-        staking::{init::get_inital_validators, TendermintAddrRef, FRA_TOTAL_AMOUNT},
     },
     ruc::*,
     serde::{self, Deserialize, Serialize},
@@ -44,9 +47,12 @@ pub fn new_tx_builder() -> Result<TransactionBuilder> {
 #[inline(always)]
 #[allow(missing_docs)]
 pub fn new_tx_builder_to(url: Option<&str>) -> Result<TransactionBuilder> {
-    get_seq_id_of(url)
+    let mut builder = get_seq_id_of(url)
         .c(d!())
-        .map(TransactionBuilder::from_seq_id)
+        .map(TransactionBuilder::from_seq_id)?;
+    builder.set_fee(get_suggested_fee_of(url));
+
+    Ok(builder)
 }
 
 #[inline(always)]
@@ -84,7 +90,10 @@ pub fn set_initial_validators() -> Result<()> {
     let mut builder = new_tx_builder().c(d!())?;
 
     let vs = get_inital_validators().c(d!())?;
-    builder.add_operation_update_validator(&[], 1, vs).c(d!())?;
+    let threshold = get_staking_params().c(d!())?.cosig_threshold;
+    builder
+        .add_operation_update_validator(&[], 1, vs, threshold)
+        .c(d!())?;
 
     send_tx(&builder.take_transaction()).c(d!())
 }
@@ -193,17 +202,46 @@ pub fn gen_transfer_op_x(
 pub fn gen_transfer_op_xx(
     rpc_endpoint: Option<&str>,
     owner_kp: &XfrKeyPair,
-    mut target_list: Vec<(&XfrPublicKey, u64)>,
+    target_list: Vec<(&XfrPublicKey, u64)>,
     token_code: Option<AssetTypeCode>,
     auto_fee: bool,
     confidential_am: bool,
     confidential_ty: bool,
     balance_type: Option<AssetRecordType>,
+) -> Result<Operation> {
+    let utxos = get_owned_utxos_x(rpc_endpoint, owner_kp.get_pk_ref()).c(d!())?;
+    let fee = alt!(auto_fee, Some(get_suggested_fee_of(rpc_endpoint)), None);
+    gen_transfer_op_with_utxos(
+        owner_kp,
+        utxos,
+        target_list,
+        token_code,
+        fee,
+        confidential_am,
+        confidential_ty,
+        balance_type,
+    )
+    .c(d!())
+}
+
+/// Same as `gen_transfer_op_xx`, but select inputs from the given UTXOs
+/// instead of querying them from the network,
+/// and `fee` is the FRA amount to pay, such as `TransactionBuilder::get_fee`.
+#[allow(clippy::too_many_arguments)]
+pub fn gen_transfer_op_with_utxos(
+    owner_kp: &XfrKeyPair,
+    utxos: impl IntoIterator<Item = (TxoSID, (Utxo, Option<OwnerMemo>))>,
+    mut target_list: Vec<(&XfrPublicKey, u64)>,
+    token_code: Option<AssetTypeCode>,
+    fee: Option<u64>,
+    confidential_am: bool,
+    confidential_ty: bool,
+    balance_type: Option<AssetRecordType>,
 ) -> Result<Operation> {
     let mut op_fee: u64 = 0;
-    if auto_fee {
-        target_list.push((&*BLACK_HOLE_PUBKEY, TX_FEE_MIN));
-        op_fee += TX_FEE_MIN;
+    if let Some(fee) = fee {
+        target_list.push((&*BLACK_HOLE_PUBKEY, fee));
+        op_fee += fee;
     }
     let asset_type = token_code.map(|code| code.val).unwrap_or(ASSET_TYPE_FRA);
 
@@ -217,9 +255,6 @@ pub fn gen_transfer_op_xx(
         op_fee = 0;
     }
     let mut i_am;
-    let utxos = get_owned_utxos_x(rpc_endpoint, owner_kp.get_pk_ref())
-        .c(d!())?
-        .into_iter();
 
     for (sid, (utxo, owner_memo)) in utxos {
         let oar =
@@ -254,12 +289,12 @@ pub fn gen_transfer_op_xx(
         return Err(eg!("insufficient balance"));
     }
 
-    if auto_fee {
+    if let Some(fee) = fee {
         target_list.pop();
         trans_builder
             .add_output(
                 &AssetRecordTemplate::with_no_asset_tracing(
-                    TX_FEE_MIN,
+                    fee,
                     ASSET_TYPE_FRA,
                     AssetRecordType::NonConfidentialAmount_NonConfidentialAssetType,
                     *BLACK_HOLE_PUBKEY,
@@ -515,7 +550,7 @@ fn get_owned_utxos(
     get_owned_utxos_x(None, addr).c(d!())
 }
 
-fn get_owned_utxos_x(
+pub(crate) fn get_owned_utxos_x(
     rpc_endpoint: Option<&str>,
     addr: &XfrPublicKey,
 ) -> Result<HashMap<TxoSID, (Utxo, Option<OwnerMemo>)>> {
@@ -563,6 +598,36 @@ fn get_seq_id_of(serv_url: Option<&str>) -> Result<u64> {
         .map(|resp| resp.1)
 }
 
+#[inline(always)]
+#[allow(missing_docs)]
+pub fn get_fee_info() -> Result<FeeInfo> {
+    get_fee_info_of(None)
+}
+
+/// The fee to pay, the `suggested_fee` of the network,
+/// the nodes without this route just use `TX_FEE_MIN`.
+fn get_suggested_fee_of(serv_url: Option<&str>) -> u64 {
+    get_fee_info_of(serv_url)
+        .map(|fee| fee.suggested_fee.max(TX_FEE_MIN))
+        .unwrap_or(TX_FEE_MIN)
+}
+
+fn get_fee_info_of(serv_url: Option<&str>) -> Result<FeeInfo> {
+    let url = format!(
+        "{}:8668/suggested_fee",
+        serv_url.unwrap_or_else(|| get_serv_addr().expect("failed to get serv address"))
+    );
+
+    attohttpc::get(url)
+        .send()
+        .c(d!())?
+        .error_for_status()
+        .c(d!())?
+        .bytes()
+        .c(d!())
+        .and_then(|b| serde_json::from_slice::<FeeInfo>(&b).c(d!()))
+}
+
 #[inline(always)]
 #[allow(missing_docs)]
 pub fn get_owner_memo_batch(ids: &[TxoSID]) -> Result<Vec<Option<OwnerMemo>>> {
@@ -623,6 +688,21 @@ pub fn get_validator_detail(td_addr: TendermintAddrRef) -> Result<ValidatorDetai
         .and_then(|b| serde_json::from_slice::<ValidatorDetail>(&b).c(d!()))
 }
 
+/// Get the staking parameters voted on chain.
+pub fn get_staking_params() -> Result<StakingParams> {
+    let url = format!("{}:8668/proposal_list", get_serv_addr().c(d!())?);
+
+    attohttpc::get(url)
+        .send()
+        .c(d!())?
+        .error_for_status()
+        .c(d!())?
+        .bytes()
+        .c(d!())
+        .and_then(|b| serde_json::from_slice::<ProposalList>(&b).c(d!()))
+        .map(|l| l.params().clone())
+}
+
 #[allow(missing_docs)]
 #[derive(Serialize, Deserialize)]
 pub struct ValidatorKey {
@@ -13,12 +13,13 @@ use {
     ledger::{
         converter::ConvertAccount,
         data_model::{
-            AssetRules, AssetTypeCode, ConfidentialMemo, DefineAsset, DefineAssetBody,
-            IndexedSignature, IssueAsset, IssueAssetBody, IssuerKeyPair,
-            IssuerPublicKey, Memo, NoReplayToken, Operation, Transaction,
+            AssetRules, AssetTypeCode, ClawbackAsset, ClawbackAssetBody,
+            ConfidentialMemo, DefineAsset, DefineAssetBody, FreezeAsset,
+            FreezeAssetBody, FreezeTarget, IndexedSignature, IssueAsset, IssueAssetBody,
+            IssuerKeyPair, IssuerPublicKey, Memo, NoReplayToken, Operation, Transaction,
             TransactionBody, TransferAsset, TransferAssetBody, TransferType, TxOutput,
-            TxoRef, UpdateMemo, UpdateMemoBody, ASSET_TYPE_FRA, BLACK_HOLE_PUBKEY,
-            TX_FEE_MIN,
+            TxoLock, TxoRef, TxoSID, UpdateMemo, UpdateMemoBody, ASSET_TYPE_FRA,
+            BLACK_HOLE_PUBKEY, TX_FEE_MIN,
         },
         staking::{
             is_valid_tendermint_addr,
@@ -27,13 +28,20 @@ use {
                 delegation::DelegationOps,
                 fra_distribution::FraDistributionOps,
                 governance::{ByzantineKind, GovernanceOps},
+                proposal::ProposalOps,
                 replace_staker::ReplaceStakerOps,
+                schedule_upgrade::ScheduleUpgradeOps,
                 undelegation::UnDelegationOps,
+                unjail::UnjailOps,
+                update_penalty_rule::UpdatePenaltyRuleOps,
                 update_staker::UpdateStakerOps,
                 update_validator::UpdateValidatorOps,
+                vote::VoteOps,
             },
-            td_addr_to_string, BlockHeight, PartialUnDelegation, StakerMemo,
-            TendermintAddr, Validator,
+            proposal::{ParamChange, ProposalId, VoteOption},
+            td_addr_to_string,
+            upgrade::Feature,
+            BlockHeight, PartialUnDelegation, StakerMemo, TendermintAddr, Validator,
         },
     },
     rand_chacha::ChaChaRng,
@@ -119,6 +127,9 @@ pub struct TransactionBuilder {
     outputs: u64,
     #[allow(missing_docs)]
     pub no_replay_token: NoReplayToken,
+    // the fee paid by `add_fee_relative_auto` and `add_fee`, `TX_FEE_MIN` if not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    fee: Option<u64>,
 }
 
 impl TransactionBuilder {
@@ -167,7 +178,20 @@ impl TransactionBuilder {
             .collect()
     }
 
-    /// @param am: amount to pay
+    /// Set the fee paid by `add_fee_relative_auto` and `add_fee`,
+    /// such as the `suggested_fee` of the network, at least `TX_FEE_MIN`.
+    pub fn set_fee(&mut self, am: u64) -> &mut Self {
+        self.fee = Some(am.max(TX_FEE_MIN));
+        self
+    }
+
+    #[allow(missing_docs)]
+    pub fn get_fee(&self) -> u64 {
+        self.fee.unwrap_or(TX_FEE_MIN)
+    }
+
+    /// Pay the fee(see `set_fee`) by the relative outputs of the owner.
+    ///
     /// @param kp: owner's XfrKeyPair
     pub fn add_fee_relative_auto(
         &mut self,
@@ -176,7 +200,8 @@ impl TransactionBuilder {
         let mut opb = TransferOperationBuilder::default();
         let outputs = self.get_relative_outputs();
 
-        let mut am = TX_FEE_MIN;
+        let fee = self.get_fee();
+        let mut am = fee;
         for (idx, (o, om)) in outputs.into_iter().enumerate() {
             if 0 < am {
                 if let Ok(oar) = open_blind_asset_record(&o, &om, &kp) {
@@ -194,7 +219,7 @@ impl TransactionBuilder {
 
         opb.add_output(
             &AssetRecordTemplate::with_no_asset_tracing(
-                TX_FEE_MIN,
+                fee,
                 ASSET_TYPE_FRA,
                 AssetRecordType::from_flags(false, false),
                 *BLACK_HOLE_PUBKEY,
@@ -212,8 +237,9 @@ impl TransactionBuilder {
     }
 
     /// As the last operation of any transaction,
-    /// add a static fee to the transaction.
+    /// add the fee(see `set_fee`) to the transaction.
     pub fn add_fee(&mut self, inputs: FeeInputs) -> Result<&mut TransactionBuilder> {
+        let fee = self.get_fee();
         let mut kps = vec![];
         let mut opb = TransferOperationBuilder::default();
 
@@ -231,7 +257,7 @@ impl TransactionBuilder {
 
         opb.add_output(
             &AssetRecordTemplate::with_no_asset_tracing(
-                TX_FEE_MIN,
+                fee,
                 ASSET_TYPE_FRA,
                 AssetRecordType::from_flags(false, false),
                 *BLACK_HOLE_PUBKEY,
@@ -282,6 +308,7 @@ impl TransactionBuilder {
             txn: Transaction::from_seq_id(seq_id),
             outputs: 0,
             no_replay_token,
+            fee: None,
         }
     }
 
@@ -321,6 +348,8 @@ impl TransactionBuilder {
                     id: None,
                     record: ba,
                     lien: None,
+                    version: Default::default(),
+                    lock: None,
                 },
                 owner_memo,
             )],
@@ -469,6 +498,51 @@ impl TransactionBuilder {
         self
     }
 
+    /// Add a operation to freeze or unfreeze the outputs of a freezable asset,
+    /// `key_pair` must be the issuer of the asset.
+    pub fn add_operation_freeze_asset(
+        &mut self,
+        key_pair: &XfrKeyPair,
+        code: AssetTypeCode,
+        target: FreezeTarget,
+        frozen: bool,
+    ) -> &mut Self {
+        let iss_keypair = IssuerKeyPair { keypair: &key_pair };
+        let op = FreezeAsset::new(
+            FreezeAssetBody {
+                code,
+                target,
+                frozen,
+                no_replay_token: self.txn.body.no_replay_token,
+            },
+            &iss_keypair,
+        );
+        self.add_operation(Operation::FreezeAsset(op))
+    }
+
+    /// Add a operation to reclaim a non-confidential output to `receiver`,
+    /// `key_pair` must be the issuer of an asset with the clawback rule.
+    pub fn add_operation_clawback_asset(
+        &mut self,
+        key_pair: &XfrKeyPair,
+        code: AssetTypeCode,
+        input: TxoSID,
+        input_record: TxOutput,
+        receiver: XfrPublicKey,
+    ) -> Result<&mut Self> {
+        let iss_keypair = IssuerKeyPair { keypair: &key_pair };
+        let body = ClawbackAssetBody::new(
+            code,
+            input,
+            input_record,
+            receiver,
+            self.txn.body.no_replay_token,
+        )
+        .c(d!())?;
+        let op = ClawbackAsset::new(body, &iss_keypair);
+        Ok(self.add_operation(Operation::ClawbackAsset(op)))
+    }
+
     /// Add a operation to delegating findora account to a tendermint validator.
     /// The transfer operation to BLACK_HOLE_PUBKEY_STAKING should be sent along with.
     pub fn add_operation_delegation(
@@ -606,16 +680,83 @@ impl TransactionBuilder {
         .map(move |op| self.add_operation(Operation::Governance(op)))
     }
 
-    /// Add a operation update the validator set at specified block height.
+    /// Add a operation to update the penalty rules of some byzantine kinds.
+    pub fn add_operation_update_penalty_rule(
+        &mut self,
+        kps: &[&XfrKeyPair],
+        percents: Vec<(ByzantineKind, [u64; 2])>,
+    ) -> Result<&mut Self> {
+        UpdatePenaltyRuleOps::from_percents(kps, percents, self.txn.body.no_replay_token)
+            .c(d!())
+            .map(move |op| self.add_operation(Operation::UpdatePenaltyRule(op)))
+    }
+
+    /// Add a operation to schedule the activation heights of some protocol features.
+    pub fn add_operation_schedule_upgrade(
+        &mut self,
+        kps: &[&XfrKeyPair],
+        schedule: Vec<(Feature, BlockHeight)>,
+    ) -> Result<&mut Self> {
+        ScheduleUpgradeOps::new(kps, schedule, self.txn.body.no_replay_token)
+            .c(d!())
+            .map(move |op| self.add_operation(Operation::ScheduleUpgrade(op)))
+    }
+
+    /// Add a operation to propose a staking parameter change,
+    /// which takes effect at `activation_height` if passed.
+    pub fn add_operation_proposal(
+        &mut self,
+        keypair: &XfrKeyPair,
+        change: ParamChange,
+        activation_height: BlockHeight,
+    ) -> Result<&mut Self> {
+        ProposalOps::new(
+            keypair,
+            change,
+            activation_height,
+            self.txn.body.no_replay_token,
+        )
+        .c(d!())
+        .map(move |op| self.add_operation(Operation::Proposal(op)))
+    }
+
+    /// Add a operation to vote on a staking parameter proposal.
+    pub fn add_operation_vote(
+        &mut self,
+        keypair: &XfrKeyPair,
+        proposal_id: ProposalId,
+        option: VoteOption,
+    ) -> &mut Self {
+        let op =
+            VoteOps::new(keypair, proposal_id, option, self.txn.body.no_replay_token);
+        self.add_operation(Operation::Vote(op))
+    }
+
+    /// Add a operation to return a jailed validator to the active set,
+    /// `keypair` is the key of the validator itself.
+    pub fn add_operation_unjail(&mut self, keypair: &XfrKeyPair) -> &mut Self {
+        let op = UnjailOps::new(keypair, self.txn.body.no_replay_token);
+        self.add_operation(Operation::Unjail(op))
+    }
+
+    /// Add a operation update the validator set at specified block height,
+    /// `cosig_threshold` is usually the `StakingParams::cosig_threshold` in effect.
     pub fn add_operation_update_validator(
         &mut self,
         kps: &[&XfrKeyPair],
         h: BlockHeight,
         v_set: Vec<Validator>,
+        cosig_threshold: [u64; 2],
     ) -> Result<&mut Self> {
-        UpdateValidatorOps::new(kps, h, v_set, self.txn.body.no_replay_token)
-            .c(d!())
-            .map(move |op| self.add_operation(Operation::UpdateValidator(op)))
+        UpdateValidatorOps::new(
+            kps,
+            h,
+            v_set,
+            cosig_threshold,
+            self.txn.body.no_replay_token,
+        )
+        .c(d!())
+        .map(move |op| self.add_operation(Operation::UpdateValidator(op)))
     }
 
     /// Add an operation to replace the staker of validator.
@@ -786,6 +927,9 @@ pub struct TransferOperationBuilder {
     transfer: Option<TransferAsset>,
     transfer_type: TransferType,
     auto_refund: bool,
+    // (index of output, lock)
+    #[serde(default)]
+    output_locks: Vec<(usize, TxoLock)>,
 }
 
 impl TransferOperationBuilder {
@@ -871,6 +1015,39 @@ impl TransferOperationBuilder {
         Ok(self)
     }
 
+    /// Add an output that can only be spent after `lock` has been unlocked,
+    /// the index of the output is kept even if the transfer is balanced.
+    pub fn add_output_with_lock(
+        &mut self,
+        asset_record_template: &AssetRecordTemplate,
+        lock: TxoLock,
+    ) -> Result<&mut Self> {
+        self.add_output(asset_record_template, None, None, None)
+            .c(d!())?;
+        self.output_locks
+            .push((self.output_records.len() - 1, lock));
+        Ok(self)
+    }
+
+    /// Spend an output with an optional lock, which must have been unlocked
+    /// at `height` and `time` (unix seconds), usually the next block.
+    pub fn add_input_with_lock(
+        &mut self,
+        txo_sid: TxoRef,
+        open_ar: OpenAssetRecord,
+        lock: Option<TxoLock>,
+        height: BlockHeight,
+        time: u64,
+        amount: u64,
+    ) -> Result<&mut Self> {
+        if let Some(lock) = lock {
+            if !lock.is_unlocked(height, time) {
+                return Err(eg!(format!("the input is still locked: {lock:?}")));
+            }
+        }
+        self.add_input(txo_sid, open_ar, None, None, amount)
+    }
+
     /// Adds output to the records, and stores the asset amount blinds and type blind in the blinds parameter passed in.
     pub fn add_output_and_store_blinds<R: CryptoRng + RngCore>(
         &mut self,
@@ -1048,7 +1225,7 @@ impl TransferOperationBuilder {
             self.outputs_tracing_policies.clone(),
             vec![None; num_outputs],
         );
-        let body = TransferAssetBody::new(
+        let mut body = TransferAssetBody::new(
             &mut prng,
             self.input_sids.clone(),
             &self.input_records,
@@ -1058,6 +1235,9 @@ impl TransferOperationBuilder {
             transfer_type,
         )
         .c(d!())?;
+        for (idx, lock) in self.output_locks.iter() {
+            body.outputs.get_mut(*idx).c(d!())?.set_lock(Some(*lock));
+        }
         self.transfer = Some(TransferAsset::new(body).c(d!())?);
         Ok(self)
     }
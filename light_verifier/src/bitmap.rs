@@ -0,0 +1,224 @@
+//!
+//! # Partial Bitmaps
+//!
+//! Parse the output of `BitMap::serialize_partial` and `BitMap::serialize`,
+//! same as `SparseMap` of the `bitmap` crate.
+//!
+//! The layout of a serialized bitmap:
+//!
+//! ```text
+//! version(u64) | checksum([u8; 32]) | [ BlockInfo | optional bits ] ...
+//! ```
+//!
+//! All integers are little-endian.
+//!
+//! The layout constants and checksums are shared with the `bitmap` crate.
+//!
+
+use {
+    super::{sha256, Hash, VerifyError, HASH_SIZE},
+    alloc::{collections::BTreeMap, vec, vec::Vec},
+};
+
+/// Size of the checksum of a block, the first bytes of its sha256 digest.
+pub const CHECK_SIZE: usize = 16;
+/// Magic number of block headers.
+pub const HEADER_MAGIC: u32 = 0x0204_0600;
+/// Size of a block header on the disk.
+pub const HEADER_SIZE: usize = 48;
+/// Size of a block on the disk.
+pub const BLOCK_SIZE: usize = 32 * 1024;
+/// Size of the bits of a block.
+pub const BITS_SIZE: usize = BLOCK_SIZE - HEADER_SIZE;
+/// Number of bits in a block.
+pub const BLOCK_BITS: usize = BITS_SIZE * 8;
+/// Size of a bit index in a list of bits.
+pub const INDEX_SIZE: usize = 3;
+
+/// A block with a full array of bits.
+pub const BIT_ARRAY: u16 = 1;
+/// A block with a list of the set bits.
+pub const BIT_DESC_SET: u16 = 2;
+/// A block with a list of the clear bits.
+pub const BIT_DESC_CLEAR: u16 = 3;
+/// A block header without bits.
+pub const BIT_HEADER: u16 = 4;
+
+/// Size of a serialized `BlockInfo`.
+pub const BLOCK_INFO_SIZE: usize = 40;
+const DESCRIPTOR_SIZE: usize = 8 + HASH_SIZE;
+
+/// Input of a step of the checksum chain:
+/// the checksum of a block followed by the digest of the previous step.
+pub type ChecksumData = [u8; CHECK_SIZE + HASH_SIZE];
+
+/// The checksum of a block over the region after its checksum field.
+#[inline(always)]
+pub fn block_checksum(region: &[u8]) -> [u8; CHECK_SIZE] {
+    let mut checksum = [0_u8; CHECK_SIZE];
+    checksum.copy_from_slice(&sha256(region)[..CHECK_SIZE]);
+    checksum
+}
+
+/// A step of the checksum chain of a bitmap,
+/// the last digest is the checksum of the whole map.
+#[inline(always)]
+pub fn chain_checksum(data: &ChecksumData) -> Hash {
+    sha256(data)
+}
+
+// The fields of a `BlockInfo` used by the verification.
+struct BlockInfo {
+    count: u32,
+    checksum: [u8; CHECK_SIZE],
+}
+
+/// A bitmap which may only contain some of its blocks.
+pub struct PartialBitMap {
+    checksum: Hash,
+    infos: Vec<BlockInfo>,
+    blocks: BTreeMap<usize, Vec<u8>>,
+}
+
+impl PartialBitMap {
+    /// Parse a serialized bitmap.
+    pub fn new(bytes: &[u8]) -> Result<Self, VerifyError> {
+        if bytes.len() < DESCRIPTOR_SIZE {
+            return Err(VerifyError::UtxoMap);
+        }
+
+        let mut checksum = [0_u8; HASH_SIZE];
+        checksum.copy_from_slice(&bytes[8..DESCRIPTOR_SIZE]);
+
+        let mut infos = Vec::new();
+        let mut blocks = BTreeMap::new();
+        let mut index = DESCRIPTOR_SIZE;
+
+        while index < bytes.len() {
+            let raw = bytes
+                .get(index..index + BLOCK_INFO_SIZE)
+                .ok_or(VerifyError::UtxoMap)?;
+            index += BLOCK_INFO_SIZE;
+
+            let magic = u32_at(raw, 0);
+            let count = u32_at(raw, 4);
+            let bit_id = u64::from_le_bytes(raw[8..16].try_into().unwrap());
+            let contents = u16::from_le_bytes([raw[18], raw[19]]);
+            let list_size = u32_at(raw, 20) as usize;
+
+            let block = infos.len();
+            if magic != HEADER_MAGIC
+                || count as usize > BLOCK_BITS
+                || list_size > BITS_SIZE / INDEX_SIZE
+                || bit_id != (block * BLOCK_BITS) as u64
+            {
+                return Err(VerifyError::UtxoMap);
+            }
+
+            let bits = match contents {
+                BIT_HEADER => None,
+                BIT_ARRAY => {
+                    let bits = bytes
+                        .get(index..index + BITS_SIZE)
+                        .ok_or(VerifyError::UtxoMap)?;
+                    index += BITS_SIZE;
+                    Some(bits.to_vec())
+                }
+                BIT_DESC_SET | BIT_DESC_CLEAR => {
+                    let set = BIT_DESC_SET == contents;
+                    let list = bytes
+                        .get(index..index + list_size * INDEX_SIZE)
+                        .ok_or(VerifyError::UtxoMap)?;
+                    index += list_size * INDEX_SIZE;
+
+                    let mut bits = vec![if set { 0_u8 } else { 0xff_u8 }; BITS_SIZE];
+                    for id in list.chunks(INDEX_SIZE) {
+                        let id = id
+                            .iter()
+                            .enumerate()
+                            .fold(0, |acc, (i, b)| acc | ((*b as usize) << (8 * i)));
+                        if id >= BLOCK_BITS {
+                            return Err(VerifyError::UtxoMap);
+                        }
+                        if set {
+                            bits[id / 8] |= 1 << (id % 8);
+                        } else {
+                            bits[id / 8] &= !(1 << (id % 8));
+                        }
+                    }
+                    Some(bits)
+                }
+                _ => return Err(VerifyError::UtxoMap),
+            };
+
+            let mut checksum = [0_u8; CHECK_SIZE];
+            checksum.copy_from_slice(&raw[24..BLOCK_INFO_SIZE]);
+            infos.push(BlockInfo { count, checksum });
+            if let Some(bits) = bits {
+                blocks.insert(block, bits);
+            }
+        }
+
+        Ok(PartialBitMap {
+            checksum,
+            infos,
+            blocks,
+        })
+    }
+
+    /// Check the blocks and the checksum chain of all blocks,
+    /// and that the checksum of the whole map is `expected`.
+    pub fn validate(&self, expected: &Hash) -> bool {
+        let mut checksum_data: ChecksumData = [0_u8; CHECK_SIZE + HASH_SIZE];
+        let mut digest = [0_u8; HASH_SIZE];
+
+        for (i, info) in self.infos.iter().enumerate() {
+            if let Some(bits) = self.blocks.get(&i) {
+                if array_checksum(i, info.count, bits) != info.checksum {
+                    return false;
+                }
+            }
+
+            checksum_data[..CHECK_SIZE].copy_from_slice(&info.checksum);
+            digest = chain_checksum(&checksum_data);
+            checksum_data[CHECK_SIZE..].copy_from_slice(&digest);
+        }
+
+        digest == self.checksum && self.checksum == *expected
+    }
+
+    /// Query the value of a bit,
+    /// `None` if its block is not in this map.
+    pub fn query(&self, id: u64) -> Option<bool> {
+        let block = (id / BLOCK_BITS as u64) as usize;
+        let index = (id % BLOCK_BITS as u64) as usize;
+
+        if index >= self.infos.get(block)?.count as usize {
+            return None;
+        }
+
+        self.blocks
+            .get(&block)
+            .map(|bits| bits[index / 8] & (1 << (index % 8)) != 0)
+    }
+}
+
+#[inline(always)]
+fn u32_at(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+// The checksum of a `BIT_ARRAY` block as it is on the disk of the ledger:
+// the header after its checksum field, followed by the bits.
+fn array_checksum(block: usize, count: u32, bits: &[u8]) -> [u8; CHECK_SIZE] {
+    let mut region = Vec::with_capacity(BLOCK_SIZE - CHECK_SIZE);
+    region.extend_from_slice(&HEADER_MAGIC.to_le_bytes());
+    region.extend_from_slice(&count.to_le_bytes());
+    region.extend_from_slice(&((block * BLOCK_BITS) as u64).to_le_bytes());
+    region.extend_from_slice(&(block as u64).to_le_bytes());
+    region.extend_from_slice(&BIT_ARRAY.to_le_bytes());
+    region.extend_from_slice(&[0_u8; 2 + 4]);
+    region.extend_from_slice(bits);
+
+    block_checksum(&region)
+}
@@ -0,0 +1,297 @@
+//!
+//! # A Verifier For Light Clients
+//!
+//! Verify the proofs served by the query server against a trusted
+//! state commitment, so that wallets need not trust the node itself.
+//!
+//! The state commitment is the sha256 digest of the JSON form of
+//! `StateCommitmentData`, so a `LightProof` carries the exact JSON strings
+//! hashed by the ledger, and only the needed fields are parsed out of them:
+//!
+//! - transactions are checked against the `transaction_merkle_commitment`
+//!   with the same algorithm as the `merkle_tree` crate
+//! - the spent status of UTXOs is checked against the `bitmap` checksum
+//!   with a partially serialized bitmap of the `bitmap` crate
+//!
+//! NOTE: the `TxoSID` of an output is not a part of its transaction,
+//! so a verified UTXO proves that its record was created by a committed
+//! transaction and that the `TxoSID` has the given spent status.
+//!
+//! Disable the default `std` feature to use it in `no_std` environments.
+//!
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![deny(warnings)]
+#![deny(missing_docs)]
+
+extern crate alloc;
+
+pub mod bitmap;
+mod merkle;
+
+#[cfg(test)]
+mod test;
+
+pub use {
+    bitmap::PartialBitMap,
+    merkle::{hash_partial, MerklePath},
+};
+
+use {
+    alloc::{string::String, vec::Vec},
+    core::fmt,
+    serde::{Deserialize, Serialize},
+    serde_json::Value,
+    sha2::{Digest, Sha256},
+};
+
+/// Size of all hash values in byte.
+pub const HASH_SIZE: usize = 32;
+
+/// A sha256 digest, such as a state commitment or a merkle node.
+pub type Hash = [u8; HASH_SIZE];
+
+#[inline(always)]
+pub(crate) fn sha256(data: &[u8]) -> Hash {
+    let mut hash = [0_u8; HASH_SIZE];
+    hash.copy_from_slice(&Sha256::digest(data));
+    hash
+}
+
+/// Reasons of a failed verification.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum VerifyError {
+    /// The state commitment data does not hash to the trusted commitment.
+    StateCommitment,
+    /// Some JSON in the proof is invalid.
+    Malformed,
+    /// A transaction is not in the transaction merkle tree.
+    TxnInclusion,
+    /// The UTXO bitmap is missing or does not match the state commitment.
+    UtxoMap,
+    /// The record is not an output of the transaction.
+    UtxoOutput,
+    /// The UTXO does not exist or its status is not in the bitmap.
+    UtxoStatus,
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let msg = match self {
+            VerifyError::StateCommitment => "state commitment mismatch",
+            VerifyError::Malformed => "malformed proof",
+            VerifyError::TxnInclusion => "invalid transaction merkle proof",
+            VerifyError::UtxoMap => "invalid utxo bitmap",
+            VerifyError::UtxoOutput => "invalid utxo record pointer",
+            VerifyError::UtxoStatus => "unknown utxo status",
+        };
+        f.write_str(msg)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for VerifyError {}
+
+/// Proof that a transaction is in the transaction merkle tree.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TxnProof {
+    /// JSON of `(TxnSID, Transaction)`, the leaf of the merkle tree.
+    pub txn: String,
+    #[allow(missing_docs)]
+    pub merkle_path: MerklePath,
+}
+
+impl TxnProof {
+    fn verify(&self, root: &Hash) -> Result<VerifiedTxn, VerifyError> {
+        if self.merkle_path.root != *root
+            || !self.merkle_path.verify(&sha256(self.txn.as_bytes()))
+        {
+            return Err(VerifyError::TxnInclusion);
+        }
+
+        let txn = serde_json::from_str::<Value>(&self.txn)
+            .map_err(|_| VerifyError::Malformed)?;
+        let txn_sid = txn
+            .get(0)
+            .and_then(Value::as_u64)
+            .ok_or(VerifyError::Malformed)?;
+
+        Ok(VerifiedTxn { txn_sid, txn })
+    }
+}
+
+/// Proof that a UTXO is an output of a committed transaction.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct UtxoProof {
+    #[allow(missing_docs)]
+    pub utxo_sid: u64,
+    /// JSON pointer to the output record in `txn`,
+    /// eg.. "/1/body/operations/0/TransferAsset/body/outputs/1/record".
+    pub record_pointer: String,
+    /// The transaction which created this UTXO.
+    pub txn: TxnProof,
+}
+
+impl UtxoProof {
+    fn verify(
+        &self,
+        data: &CommitmentFields,
+        utxo_map: &PartialBitMap,
+    ) -> Result<VerifiedUtxo, VerifyError> {
+        if self.utxo_sid >= data.txo_count {
+            return Err(VerifyError::UtxoStatus);
+        }
+
+        // the bit of an unspent utxo is set
+        let spent = !utxo_map
+            .query(self.utxo_sid)
+            .ok_or(VerifyError::UtxoStatus)?;
+
+        if !is_output_pointer(&self.record_pointer) {
+            return Err(VerifyError::UtxoOutput);
+        }
+
+        let txn = self.txn.verify(&data.transaction_merkle_commitment.hash)?;
+        let record = txn
+            .txn
+            .pointer(&self.record_pointer)
+            .cloned()
+            .ok_or(VerifyError::UtxoOutput)?;
+
+        Ok(VerifiedUtxo {
+            utxo_sid: self.utxo_sid,
+            txn_sid: txn.txn_sid,
+            record,
+            spent,
+        })
+    }
+}
+
+// Only the outputs that can become UTXOs are acceptable,
+// same as `FinalizedTransaction::set_txo_id`.
+fn is_output_pointer(pointer: &str) -> bool {
+    let parts = pointer.split('/').collect::<Vec<_>>();
+    match parts.as_slice() {
+        ["", "1", "body", "operations", i, "TransferAsset", "body", "outputs", j, "record"]
+        | ["", "1", "body", "operations", i, "IssueAsset", "body", "records", j, "0", "record"]
+        | ["", "1", "body", "operations", i, "MintFra", "entries", j, "utxo", "record"] => {
+            is_index(i) && is_index(j)
+        }
+        _ => false,
+    }
+}
+
+#[inline(always)]
+fn is_index(s: &str) -> bool {
+    !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// Proofs of transactions and UTXOs against one state commitment.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct LightProof {
+    /// JSON of `StateCommitmentData`, hashes to the state commitment.
+    pub state_commitment_data: String,
+    /// Partially serialized bitmap of UTXOs, needed by `utxos`.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub utxo_map: Option<Vec<u8>>,
+    #[allow(missing_docs)]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub txns: Vec<TxnProof>,
+    #[allow(missing_docs)]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub utxos: Vec<UtxoProof>,
+}
+
+impl LightProof {
+    /// Verify all the proofs against a trusted state commitment,
+    /// return the verified contents if all of them are valid.
+    pub fn verify(&self, state_commitment: &Hash) -> Result<VerifiedState, VerifyError> {
+        if sha256(self.state_commitment_data.as_bytes()) != *state_commitment {
+            return Err(VerifyError::StateCommitment);
+        }
+
+        let data = serde_json::from_str::<CommitmentFields>(&self.state_commitment_data)
+            .map_err(|_| VerifyError::Malformed)?;
+
+        let txns = self
+            .txns
+            .iter()
+            .map(|t| t.verify(&data.transaction_merkle_commitment.hash))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let utxos = if self.utxos.is_empty() {
+            Vec::new()
+        } else {
+            let utxo_map = self
+                .utxo_map
+                .as_deref()
+                .ok_or(VerifyError::UtxoMap)
+                .and_then(PartialBitMap::new)?;
+            if !utxo_map.validate(&data.bitmap) {
+                return Err(VerifyError::UtxoMap);
+            }
+
+            self.utxos
+                .iter()
+                .map(|u| u.verify(&data, &utxo_map))
+                .collect::<Result<Vec<_>, _>>()?
+        };
+
+        Ok(VerifiedState {
+            txo_count: data.txo_count,
+            txns,
+            utxos,
+        })
+    }
+}
+
+// The fields of `StateCommitmentData` needed by the verification,
+// others are ignored when parsing.
+#[derive(Deserialize)]
+struct CommitmentFields {
+    bitmap: Hash,
+    transaction_merkle_commitment: HashValue,
+    txo_count: u64,
+}
+
+// Same as the `HashValue` of the `cryptohash` crate.
+#[derive(Deserialize)]
+struct HashValue {
+    hash: Hash,
+}
+
+/// A transaction that has been verified.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct VerifiedTxn {
+    #[allow(missing_docs)]
+    pub txn_sid: u64,
+    /// JSON value of `(TxnSID, Transaction)`.
+    pub txn: Value,
+}
+
+/// A UTXO that has been verified.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct VerifiedUtxo {
+    #[allow(missing_docs)]
+    pub utxo_sid: u64,
+    /// The transaction which created this UTXO.
+    pub txn_sid: u64,
+    /// JSON value of the `BlindAssetRecord`.
+    pub record: Value,
+    #[allow(missing_docs)]
+    pub spent: bool,
+}
+
+/// Contents of a `LightProof` that can be trusted.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct VerifiedState {
+    /// Number of transaction outputs of the ledger.
+    pub txo_count: u64,
+    #[allow(missing_docs)]
+    pub txns: Vec<VerifiedTxn>,
+    #[allow(missing_docs)]
+    pub utxos: Vec<VerifiedUtxo>,
+}
@@ -0,0 +1,65 @@
+//!
+//! # Merkle Paths
+//!
+//! Same algorithm as `cryptohash::Proof::is_valid_proof`,
+//! which hashes its nodes with `hash_partial`.
+//!
+
+use {
+    super::{Hash, HASH_SIZE},
+    alloc::vec::Vec,
+    serde::{Deserialize, Serialize},
+    sha2::{Digest, Sha256},
+};
+
+/// The path from a leaf to the root of a merkle tree.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct MerklePath {
+    /// Index of the leaf in the tree.
+    pub leaf_index: u64,
+    #[allow(missing_docs)]
+    pub root: Hash,
+    /// Hashes of siblings from the bottom up.
+    pub siblings: Vec<Hash>,
+}
+
+impl MerklePath {
+    /// Check if the path leads `leaf` to the root.
+    pub fn verify(&self, leaf: &Hash) -> bool {
+        let mut result = *leaf;
+        let mut id = self.leaf_index;
+
+        for sibling in self.siblings.iter() {
+            result = if id & 1 == 0 {
+                hash_partial(&result, sibling)
+            } else {
+                hash_partial(sibling, &result)
+            };
+            id /= 2;
+        }
+
+        result == self.root
+    }
+}
+
+/// Hash two children into their parent node.
+///
+/// An empty node is filled with zero bytes,
+/// and the right-hand side might not exist.
+pub fn hash_partial(left: &Hash, right: &Hash) -> Hash {
+    let empty = [0_u8; HASH_SIZE];
+
+    let mut hasher = Sha256::new();
+    if *left == empty {
+        return empty;
+    } else if *right == empty {
+        hasher.update(left);
+    } else {
+        hasher.update(left);
+        hasher.update(right);
+    }
+
+    let mut result = empty;
+    result.copy_from_slice(&hasher.finalize());
+    result
+}
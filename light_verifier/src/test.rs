@@ -0,0 +1,214 @@
+#![allow(missing_docs)]
+
+use {
+    super::*,
+    ::bitmap::{BitMap, BITMAP_BLOCK_SIZE},
+    cryptohash::{sha256, HashValue},
+    merkle_tree::AppendOnlyMerkle,
+    serde_json::json,
+    std::{fs, fs::OpenOptions, path::PathBuf},
+};
+
+fn tmp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "light_verifier_{}_{}",
+        name,
+        std::process::id()
+    ));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn new_bitmap(dir: &PathBuf) -> BitMap {
+    let file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create_new(true)
+        .open(dir.join("bitmap"))
+        .unwrap();
+    BitMap::create(file).unwrap()
+}
+
+fn to_merkle_path(proof: &cryptohash::Proof) -> MerklePath {
+    MerklePath {
+        leaf_index: proof.tx_id,
+        root: proof.root_hash.hash,
+        siblings: proof.hash_array.iter().map(|h| h.hash).collect(),
+    }
+}
+
+#[test]
+fn test_merkle_path() {
+    let dir = tmp_dir("merkle");
+    let mut tree = AppendOnlyMerkle::create(dir.join("tree").to_str().unwrap()).unwrap();
+
+    let leaves = (0..300_u64)
+        .map(|i| sha256::hash(&i.to_le_bytes()).0)
+        .collect::<Vec<_>>();
+    leaves.iter().for_each(|leaf| {
+        tree.append_hash(&HashValue { hash: *leaf }).unwrap();
+    });
+
+    for (i, leaf) in leaves.iter().enumerate() {
+        let proof = tree.get_proof(i as u64, 0).unwrap();
+        let path = to_merkle_path(&proof);
+
+        assert!(proof.is_valid_proof(HashValue { hash: *leaf }));
+        assert!(path.verify(leaf));
+        assert!(!path.verify(&leaves[(i + 1) % leaves.len()]));
+    }
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_partial_bitmap() {
+    assert_eq!(BITMAP_BLOCK_SIZE, crate::bitmap::BLOCK_BITS);
+
+    let dir = tmp_dir("bitmap");
+    let mut map = new_bitmap(&dir);
+
+    let size = BITMAP_BLOCK_SIZE * 2 + 100;
+    for i in 0..size {
+        map.set(i).unwrap();
+    }
+    for i in (0..size).step_by(7) {
+        map.clear(i).unwrap();
+    }
+    let checksum = map.compute_checksum().0;
+
+    // only the second block is included
+    let bytes = map.serialize_partial(vec![BITMAP_BLOCK_SIZE + 3], 1);
+    let partial = PartialBitMap::new(&bytes).unwrap();
+    assert!(partial.validate(&checksum));
+    assert!(!partial.validate(&[0; HASH_SIZE]));
+
+    for i in [
+        BITMAP_BLOCK_SIZE,
+        BITMAP_BLOCK_SIZE + 3,
+        2 * BITMAP_BLOCK_SIZE - 1,
+    ] {
+        assert_eq!(Some(map.query(i).unwrap()), partial.query(i as u64));
+    }
+    assert!(partial.query(3).is_none());
+    assert!(partial.query(size as u64).is_none());
+
+    // the full map
+    let bytes = map.serialize(0);
+    let full = PartialBitMap::new(&bytes).unwrap();
+    assert!(full.validate(&checksum));
+    for i in (0..size).step_by(997) {
+        assert_eq!(Some(map.query(i).unwrap()), full.query(i as u64));
+    }
+
+    // tampered
+    let mut bytes = map.serialize_partial(vec![7], 1);
+    let last = bytes.len() - 1;
+    bytes[last] ^= 1;
+    assert!(!PartialBitMap::new(&bytes).map_or(false, |m| m.validate(&checksum)));
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_light_proof() {
+    let dir = tmp_dir("light");
+
+    // two transactions, the second one creates txo 0 and 1
+    let record = json!({"amount": {"NonConfidential": "100"}});
+    let txns = [
+        json!([0, {"body": {"operations": []}}]).to_string(),
+        json!([1, {"body": {"operations": [{"TransferAsset": {"body": {
+            "inputs": [],
+            "outputs": [
+                {"id": null, "record": {"amount": {"NonConfidential": "1"}}},
+                {"id": null, "record": record},
+            ]
+        }}}]}}])
+        .to_string(),
+    ];
+
+    let mut tree = AppendOnlyMerkle::create(dir.join("tree").to_str().unwrap()).unwrap();
+    txns.iter().for_each(|txn| {
+        tree.append_hash(&sha256::hash(txn.as_bytes()).into())
+            .unwrap();
+    });
+
+    let mut map = new_bitmap(&dir);
+    map.set(0).unwrap();
+    map.set(1).unwrap();
+    map.clear(0).unwrap();
+    let bitmap = map.compute_checksum().0;
+    let block_merkle = [0_u8; HASH_SIZE];
+
+    let state_commitment_data = json!({
+        "bitmap": bitmap,
+        "block_merkle": {"hash": block_merkle},
+        "transaction_merkle_commitment": {"hash": tree.get_root_hash().hash},
+        "txo_count": 2,
+    })
+    .to_string();
+    let state_commitment = sha256(state_commitment_data.as_bytes());
+
+    let txn_proof = |i: usize| TxnProof {
+        txn: txns[i].clone(),
+        merkle_path: to_merkle_path(&tree.get_proof(i as u64, 0).unwrap()),
+    };
+    let utxo_proof = |sid: u64, output: usize| UtxoProof {
+        utxo_sid: sid,
+        record_pointer: format!(
+            "/1/body/operations/0/TransferAsset/body/outputs/{output}/record"
+        ),
+        txn: txn_proof(1),
+    };
+
+    let proof = LightProof {
+        state_commitment_data: state_commitment_data.clone(),
+        utxo_map: Some(map.serialize_partial(vec![0, 1], 0)),
+        txns: vec![txn_proof(0)],
+        utxos: vec![utxo_proof(1, 1), utxo_proof(0, 0)],
+    };
+
+    let verified = proof.verify(&state_commitment).unwrap();
+    assert_eq!(2, verified.txo_count);
+    assert_eq!(0, verified.txns[0].txn_sid);
+    assert_eq!(
+        VerifiedUtxo {
+            utxo_sid: 1,
+            txn_sid: 1,
+            record,
+            spent: false,
+        },
+        verified.utxos[0]
+    );
+    assert!(verified.utxos[1].spent);
+
+    // an untrusted state commitment
+    assert_eq!(
+        Err(VerifyError::StateCommitment),
+        proof.verify(&[0; HASH_SIZE])
+    );
+
+    // a transaction that is not committed
+    let mut p = proof.clone();
+    p.txns[0].txn = json!([0, {"body": {"operations": [1]}}]).to_string();
+    assert_eq!(Err(VerifyError::TxnInclusion), p.verify(&state_commitment));
+
+    // a record that is not an output
+    let mut p = proof.clone();
+    p.utxos[0].record_pointer = "/1/body/operations/0/TransferAsset/body".to_string();
+    assert_eq!(Err(VerifyError::UtxoOutput), p.verify(&state_commitment));
+
+    // a utxo that does not exist
+    let mut p = proof.clone();
+    p.utxos[0].utxo_sid = 2;
+    assert_eq!(Err(VerifyError::UtxoStatus), p.verify(&state_commitment));
+
+    // the bitmap is missing
+    let mut p = proof;
+    p.utxo_map = None;
+    assert_eq!(Err(VerifyError::UtxoMap), p.verify(&state_commitment));
+
+    let _ = fs::remove_dir_all(&dir);
+}
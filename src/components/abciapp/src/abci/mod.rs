@@ -5,7 +5,7 @@
 #![deny(warnings)]
 #![allow(clippy::needless_borrow)]
 
-mod server;
+pub(crate) mod server;
 pub mod staking;
 
 use {
@@ -15,13 +15,16 @@ use {
     config::abci::{global_cfg::CFG, ABCIConfig},
     futures::executor::ThreadPool,
     lazy_static::lazy_static,
+    ledger::store::{snapshot, LedgerState},
     ruc::*,
     std::{
         env, fs, mem,
         net::SocketAddr,
+        path::Path,
         sync::{atomic::AtomicBool, Arc},
         thread,
     },
+    tracing::{info, warn},
 };
 
 lazy_static! {
@@ -46,15 +49,43 @@ pub fn run() -> Result<()> {
 
     env::set_var("BNC_DATA_DIR", format!("{}/__bnc__", &config.ledger_dir));
 
+    if let Some(archive) = CFG.ledger_snapshot_export.as_deref() {
+        return export_ledger_snapshot(archive).c(d!());
+    }
+    if let Some(archive) = CFG.ledger_snapshot_import.as_deref() {
+        import_ledger_snapshot(archive).c(d!())?;
+    }
+
     if CFG.enable_query_service {
         env::set_var("FINDORAD_KEEP_HIST", "1");
     }
 
+    let addr_str = format!("{}:{}", config.abci_host, config.abci_port);
+    let addr = addr_str.parse::<SocketAddr>().c(d!())?;
+
+    // nothing can be opened before the data is restored
+    if CFG.state_sync_restore {
+        if fs::read_dir(&CFG.ledger_dir).c(d!())?.next().is_none() {
+            abci::run(addr, server::state_sync::Restorer::new(config));
+            return Ok(());
+        }
+        warn!(target: "abciapp", "The ledger dir is not empty, state sync is skipped");
+    }
+
     let app = server::ABCISubmissionServer::new(
         basedir,
         format!("{}:{}", config.tendermint_host, config.tendermint_port),
     )?;
 
+    start_services(&app, &config);
+
+    abci::run(addr, app);
+
+    Ok(())
+}
+
+/// Start the query, submission and web3 services of the app, if enabled.
+pub(crate) fn start_services(app: &server::ABCISubmissionServer, config: &ABCIConfig) {
     if CFG.enable_query_service {
         let submission_service_hdr = Arc::clone(&app.la);
 
@@ -96,13 +127,66 @@ pub fn run() -> Result<()> {
             evm_ws,
             tendermint_rpc,
             base_app,
+            CFG.enable_eth_debug_api,
         ));
     }
+}
 
-    let addr_str = format!("{}:{}", config.abci_host, config.abci_port);
-    let addr = addr_str.parse::<SocketAddr>().c(d!())?;
+// Export all the data at the last committed height, the node must be stopped.
+fn export_ledger_snapshot(archive: &str) -> Result<()> {
+    let archive = Path::new(archive);
+    let basedir = Path::new(&CFG.ledger_dir);
+    if archive.starts_with(basedir) {
+        return Err(eg!("the archive can not be placed in the ledger dir"));
+    }
 
-    abci::run(addr, app);
+    let manifest = LedgerState::load_or_init(&CFG.ledger_dir)
+        .c(d!())?
+        .export_snapshot(basedir, archive)
+        .c(d!())?;
+
+    info!(
+        target: "abciapp",
+        "Ledger snapshot exported at height {}, state commitment: {}",
+        manifest.height,
+        serde_json::to_string(&manifest.state_commitment).c(d!())?
+    );
+
+    Ok(())
+}
+
+// Import an archive into the empty ledger dir,
+// tendermint must be bootstrapped to the same height separately.
+fn import_ledger_snapshot(archive: &str) -> Result<()> {
+    let trusted = CFG
+        .ledger_snapshot_commitment
+        .as_deref()
+        .map(|c| serde_json::from_str(c).c(d!("invalid state commitment")))
+        .transpose()?;
+    if trusted.is_none() {
+        if !CFG.ledger_snapshot_insecure {
+            return Err(eg!(
+                "a trusted `--ledger-snapshot-commitment` is required, or pass `--ledger-snapshot-insecure` explicitly"
+            ));
+        }
+        warn!(
+            target: "abciapp",
+            "No trusted state commitment, only the archive itself is verified!"
+        );
+    }
+
+    let manifest = snapshot::import(
+        Path::new(archive),
+        Path::new(&CFG.ledger_dir),
+        trusted.as_ref(),
+    )
+    .c(d!())?;
+
+    info!(
+        target: "abciapp",
+        "Ledger snapshot imported at height {}",
+        manifest.height
+    );
 
     Ok(())
 }
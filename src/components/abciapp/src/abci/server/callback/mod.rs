@@ -6,27 +6,39 @@ mod utils;
 
 use {
     crate::{
-        abci::{server::ABCISubmissionServer, staking, IN_SAFE_ITV, IS_EXITING, POOL},
+        abci::{
+            server::{fee_market, state_sync, ABCISubmissionServer},
+            staking, IN_SAFE_ITV, IS_EXITING, POOL,
+        },
         api::{
             query_server::BLOCK_CREATED,
             submission_server::{convert_tx, try_tx_catalog, TxCatalog},
         },
     },
     abci::{
-        CheckTxType, RequestBeginBlock, RequestCheckTx, RequestCommit, RequestDeliverTx,
-        RequestEndBlock, RequestInfo, RequestInitChain, RequestQuery,
+        CheckTxType, RequestApplySnapshotChunk, RequestBeginBlock, RequestCheckTx,
+        RequestCommit, RequestDeliverTx, RequestEndBlock, RequestInfo, RequestInitChain,
+        RequestListSnapshots, RequestLoadSnapshotChunk, RequestOfferSnapshot,
+        RequestQuery, ResponseApplySnapshotChunk, ResponseApplySnapshotChunk_Result,
         ResponseBeginBlock, ResponseCheckTx, ResponseCommit, ResponseDeliverTx,
-        ResponseEndBlock, ResponseInfo, ResponseInitChain, ResponseQuery,
+        ResponseEndBlock, ResponseInfo, ResponseInitChain, ResponseListSnapshots,
+        ResponseLoadSnapshotChunk, ResponseOfferSnapshot, ResponseOfferSnapshot_Result,
+        ResponseQuery,
+    },
+    config::abci::{
+        global_cfg::CFG,
+        upgrade::{self, is_active, Feature},
     },
-    config::abci::global_cfg::CFG,
     fp_storage::hash::{Sha256, StorageHasher},
+    fp_utils::timestamp_converter,
     lazy_static::lazy_static,
     ledger::{
         converter::is_convert_account,
         data_model::Operation,
+        error::{ErrorDetail, LedgerError},
         staking::KEEP_HIST,
         store::{
-            api_cache,
+            api_cache, events,
             fbnc::{new_mapx, Mapx},
         },
     },
@@ -52,6 +64,17 @@ use enterprise_web3::{
 
 pub(crate) static TENDERMINT_BLOCK_HEIGHT: AtomicI64 = AtomicI64::new(0);
 
+// Reject a utxo transaction with the typed code of `msg`, and its JSON detail in `info`,
+// DeliverTx keeps the legacy code `1` until `TypedErrorCode` is active.
+macro_rules! reject {
+    ($resp: expr, $msg: expr, $typed: expr) => {{
+        let detail = ErrorDetail::new($msg);
+        $resp.code = alt!($typed, detail.code, 1);
+        $resp.info = serde_json::to_string(&detail).unwrap_or_default();
+        $resp.log = detail.message;
+    }};
+}
+
 lazy_static! {
     // save the request parameters from the begin_block for use in the end_block
     static ref REQ_BEGIN_BLOCK: Arc<Mutex<RequestBeginBlock>> =
@@ -82,13 +105,17 @@ pub fn info(s: &mut ABCISubmissionServer, req: &RequestInfo) -> ResponseInfo {
     let commitment = state.get_state_commitment();
     let la_hash = commitment.0.as_ref().to_vec();
 
+    upgrade::sync_schedule(state.get_staking().upgrade_schedule());
+
+    // fail on a broken `WEB3_SINK` at startup, instead of the first block
+    #[cfg(feature = "web3_service")]
+    lazy_static::initialize(&enterprise_web3::WEB3_SERVICE_START_HEIGHT);
+
     let h = state.get_tendermint_height() as i64;
     TENDERMINT_BLOCK_HEIGHT.swap(h, Ordering::Relaxed);
     resp.set_last_block_height(h);
     if 0 < h {
-        if CFG.checkpoint.disable_evm_block_height < h
-            && h < CFG.checkpoint.enable_frc20_height
-        {
+        if evm_disabled(h) {
             resp.set_last_block_app_hash(la_hash);
         } else {
             let cs_hash = s.account_base_app.write().info(req).last_block_app_hash;
@@ -130,14 +157,18 @@ pub fn check_tx(s: &mut ABCISubmissionServer, req: &RequestCheckTx) -> ResponseC
         TxCatalog::FindoraTx => {
             if matches!(req.field_type, CheckTxType::New) {
                 if let Ok(tx) = convert_tx(req.get_tx()) {
-                    if td_height > CFG.checkpoint.check_signatures_num {
+                    if is_active(Feature::CheckSignaturesNum, td_height as u64) {
                         for op in tx.body.operations.iter() {
                             if let Operation::TransferAsset(op) = op {
                                 let mut body_signatures = op.body_signatures.clone();
                                 body_signatures.dedup();
                                 if body_signatures.len() > 1 {
-                                    resp.log = "too many body_signatures".to_owned();
-                                    resp.code = 1;
+                                    reject!(
+                                        resp,
+                                        LedgerError::TooManySignatures
+                                            .with("too many body_signatures"),
+                                        true
+                                    );
                                     return resp;
                                 }
                             }
@@ -145,34 +176,59 @@ pub fn check_tx(s: &mut ABCISubmissionServer, req: &RequestCheckTx) -> ResponseC
                         let mut signatures = tx.signatures.clone();
                         signatures.dedup();
                         if signatures.len() > 1 {
-                            resp.log = "Too many signatures".to_owned();
-                            resp.code = 1;
+                            reject!(
+                                resp,
+                                LedgerError::TooManySignatures
+                                    .with("Too many signatures"),
+                                true
+                            );
                             return resp;
                         }
 
                         if tx.pubkey_sign_map.len() > 1 {
-                            resp.log = "too many pubkey_sign_map".to_owned();
-                            resp.code = 1;
+                            reject!(
+                                resp,
+                                LedgerError::TooManySignatures
+                                    .with("too many pubkey_sign_map"),
+                                true
+                            );
                             return resp;
                         }
-                    } else if !tx.valid_in_abci() {
-                        resp.log = "Should not appear in ABCI".to_owned();
-                        resp.code = 1;
+                    } else if let Err(e) = tx.check_in_abci() {
+                        reject!(resp, e.to_string(), true);
                     } else if TX_HISTORY.read().contains_key(&tx.hash_tm_rawbytes()) {
-                        resp.log = "Historical transaction".to_owned();
-                        resp.code = 1;
+                        reject!(
+                            resp,
+                            LedgerError::HistoricalTx.with("Historical transaction"),
+                            true
+                        );
+                    }
+
+                    // the dynamic minimum fee is only a policy of the local mempool
+                    if 0 == resp.code && !tx.is_fee_exempt() {
+                        let min_fee = fee_market::min_fee();
+                        if tx.fee_amount() < min_fee {
+                            reject!(
+                                resp,
+                                LedgerError::InsufficientFee
+                                    .with(format!("the minimum is {min_fee}")),
+                                true
+                            );
+                        }
                     }
+                    resp.set_priority(tx.fee_priority(req.get_tx().len()));
                 } else {
-                    resp.log = "Invalid format".to_owned();
-                    resp.code = 1;
+                    reject!(
+                        resp,
+                        LedgerError::InvalidFormat.with("Invalid format"),
+                        true
+                    );
                 }
             }
             resp
         }
         TxCatalog::EvmTx => {
-            if CFG.checkpoint.disable_evm_block_height < td_height
-                && td_height < CFG.checkpoint.enable_frc20_height
-            {
+            if evm_disabled(td_height) {
                 resp.code = 2;
                 resp.log = "EVM is disabled".to_owned();
                 resp
@@ -228,11 +284,16 @@ pub fn begin_block(
 
     let mut la = s.la.write();
 
-    // set height first
-    la.get_committed_state()
-        .write()
-        .get_staking_mut()
-        .set_custom_block_height(header.height as u64);
+    // set height and time first
+    {
+        let mut state = la.get_committed_state().write();
+        state
+            .get_staking_mut()
+            .set_custom_block_height(header.height as u64);
+        state.set_block_time(timestamp_converter(
+            header.time.clone().unwrap_or_default(),
+        ));
+    }
 
     // then create new block or update simulator
     if la.all_commited() {
@@ -241,12 +302,15 @@ pub fn begin_block(
         pnk!(la.update_staking_simulator());
     }
 
-    if CFG.checkpoint.disable_evm_block_height < header.height
-        && header.height < CFG.checkpoint.enable_frc20_height
-    {
+    if evm_disabled(header.height) {
         ResponseBeginBlock::default()
     } else {
-        s.account_base_app.write().begin_block(req)
+        let validators = staking::evm_staking_validators(
+            la.get_committed_state().read().get_staking(),
+        );
+        let mut account_base_app = s.account_base_app.write();
+        account_base_app.set_staking_validators(validators);
+        account_base_app.begin_block(req)
     }
 }
 
@@ -261,15 +325,21 @@ pub fn deliver_tx(
 
     match tx_catalog {
         TxCatalog::FindoraTx => {
+            let typed = is_active(Feature::TypedErrorCode, td_height as u64);
+            fee_market::count_tx();
             if let Ok(tx) = convert_tx(req.get_tx()) {
-                if td_height > CFG.checkpoint.check_signatures_num {
+                if is_active(Feature::CheckSignaturesNum, td_height as u64) {
                     for op in tx.body.operations.iter() {
                         if let Operation::TransferAsset(op) = op {
                             let mut body_signatures = op.body_signatures.clone();
                             body_signatures.dedup();
                             if body_signatures.len() > 1 {
-                                resp.log = "too many body_signatures".to_owned();
-                                resp.code = 1;
+                                reject!(
+                                    resp,
+                                    LedgerError::TooManySignatures
+                                        .with("too many body_signatures"),
+                                    typed
+                                );
                                 return resp;
                             }
                         }
@@ -277,14 +347,21 @@ pub fn deliver_tx(
                     let mut signatures = tx.signatures.clone();
                     signatures.dedup();
                     if signatures.len() > 1 {
-                        resp.log = "Too many signatures".to_owned();
-                        resp.code = 1;
+                        reject!(
+                            resp,
+                            LedgerError::TooManySignatures.with("Too many signatures"),
+                            typed
+                        );
                         return resp;
                     }
 
                     if tx.pubkey_sign_map.len() > 1 {
-                        resp.log = "too many pubkey_sign_map".to_owned();
-                        resp.code = 1;
+                        reject!(
+                            resp,
+                            LedgerError::TooManySignatures
+                                .with("too many pubkey_sign_map"),
+                            typed
+                        );
                         return resp;
                     }
                 }
@@ -293,9 +370,11 @@ pub fn deliver_tx(
                     TX_HISTORY.write().set_value(txhash, Default::default());
                 });
 
-                if tx.valid_in_abci() {
+                if let Err(e) = tx.check_in_abci() {
+                    reject!(resp, e.to_string(), typed);
+                } else {
                     // Log print for monitor purpose
-                    if td_height < CFG.checkpoint.evm_first_block_height {
+                    if !is_active(Feature::EvmFirstBlock, td_height as u64) {
                         info!(target: "abciapp",
                             "EVM transaction(FindoraTx) detected at early height {}: {:?}",
                             td_height, tx
@@ -310,16 +389,13 @@ pub fn deliver_tx(
                         }
                     }
 
-                    if CFG.checkpoint.disable_evm_block_height < td_height
-                        && td_height < CFG.checkpoint.enable_frc20_height
-                    {
+                    if evm_disabled(td_height) {
                         if is_convert_account(&tx) {
                             resp.code = 2;
                             resp.log = "EVM is disabled".to_owned();
                             return resp;
                         } else if let Err(e) = s.la.write().cache_transaction(tx) {
-                            resp.code = 1;
-                            resp.log = e.to_string();
+                            reject!(resp, e.to_string(), typed);
                         }
                     } else if is_convert_account(&tx) {
                         let hash = sha256::hash(req.get_tx());
@@ -348,7 +424,7 @@ pub fn deliver_tx(
                                 .write()
                                 .commit_session();
                             return resp;
-                        } else if td_height > CFG.checkpoint.fix_exec_code {
+                        } else if is_active(Feature::FixExecCode, td_height as u64) {
                             resp.code = 1;
                             resp.log = "cache_transaction failed".to_owned();
                         }
@@ -365,44 +441,39 @@ pub fn deliver_tx(
                             .db
                             .write()
                             .discard_session();
-                    } else if CFG.checkpoint.utxo_checktx_height < td_height {
+                    } else if is_active(Feature::UtxoCheckTx, td_height as u64) {
                         match tx.check_tx() {
                             Ok(_) => {
                                 if let Err(e) = s.la.write().cache_transaction(tx) {
-                                    resp.code = 1;
-                                    resp.log = e.to_string();
+                                    reject!(resp, e.to_string(), typed);
                                 }
                             }
                             Err(e) => {
-                                resp.code = 1;
-                                resp.log = e.to_string();
+                                reject!(resp, e.to_string(), typed);
                             }
                         }
                     } else if let Err(e) = s.la.write().cache_transaction(tx) {
-                        resp.code = 1;
-                        resp.log = e.to_string();
+                        reject!(resp, e.to_string(), typed);
                     }
-                } else {
-                    resp.code = 1;
-                    resp.log = "Should not appear in ABCI".to_owned();
                 }
             } else {
-                resp.code = 1;
-                resp.log = "Invalid format".to_owned();
+                reject!(
+                    resp,
+                    LedgerError::InvalidFormat.with("Invalid format"),
+                    typed
+                );
             }
 
             resp
         }
         TxCatalog::EvmTx => {
-            if CFG.checkpoint.disable_evm_block_height < td_height
-                && td_height < CFG.checkpoint.enable_frc20_height
-            {
+            if evm_disabled(td_height) {
                 resp.code = 2;
                 resp.log = "EVM is disabled".to_owned();
                 resp
             } else {
                 // Log print for monitor purpose
-                if td_height < CFG.checkpoint.evm_first_block_height {
+                if !is_active(Feature::EvmFirstBlock, td_height as u64) {
                     info!(
                         target:
                         "abciapp",
@@ -414,7 +485,7 @@ pub fn deliver_tx(
                 let (mut resp, non_confidential_outputs) =
                     s.account_base_app.write().deliver_tx(req);
 
-                if td_height > CFG.checkpoint.prismxx_inital_height && 0 == resp.code {
+                if is_active(Feature::PrismMint, td_height as u64) && 0 == resp.code {
                     for non_confidential_output in non_confidential_outputs.iter() {
                         let mut la = s.la.write();
                         let mut laa = la.get_committed_state().write();
@@ -483,6 +554,14 @@ pub fn end_block(
         pnk!(la.end_block());
     }
 
+    // delegations of the evm accounts, on the committed staking like `system_ops`
+    if !evm_disabled(td_height) {
+        staking::system_evm_staking(
+            la.get_committed_state().write().get_staking_mut(),
+            &mut s.account_base_app.write(),
+        );
+    }
+
     if let Ok(Some(vs)) = ruc::info!(staking::get_validators(
         la.get_committed_state().read().get_staking().deref(),
         begin_block_req.last_commit_info.as_ref()
@@ -497,9 +576,7 @@ pub fn end_block(
         &begin_block_req.byzantine_validators.as_slice(),
     );
 
-    if td_height <= CFG.checkpoint.disable_evm_block_height
-        || td_height >= CFG.checkpoint.enable_frc20_height
-    {
+    if !evm_disabled(td_height) {
         let _ = s.account_base_app.write().end_block(req);
     }
 
@@ -523,32 +600,42 @@ pub fn commit(s: &mut ABCISubmissionServer, req: &RequestCommit) -> ResponseComm
         .c(d!())
         .and_then(|s| fs::write(&path, s).c(d!(path))));
 
+    // new schedules take effect from the next block
+    upgrade::sync_schedule(state.get_staking().upgrade_schedule());
+
+    // measure the fullness for the minimum fee of the mempool
+    fee_market::end_block();
+
     let mut r = ResponseCommit::new();
     let la_hash = state.get_state_commitment().0.as_ref().to_vec();
     let cs_hash = s.account_base_app.write().commit(req).data;
 
-    if CFG.checkpoint.disable_evm_block_height < td_height
-        && td_height < CFG.checkpoint.enable_frc20_height
-    {
+    if evm_disabled(td_height) {
         r.set_data(la_hash);
     } else {
         r.set_data(app_hash("commit", td_height, la_hash, cs_hash));
     }
 
+    // the events of the block and the system penalties in `end_block`
+    events::flush();
+
+    // both the ledger and the evm state are committed
+    if state_sync::snapshot_due(td_height as u64) {
+        info_omit!(state_sync::take_snapshot(&state));
+    }
+
     IN_SAFE_ITV.store(false, Ordering::Release);
 
     #[cfg(feature = "web3_service")]
     {
-        use enterprise_web3::{Setter, REDIS_CLIENT, WEB3_SERVICE_START_HEIGHT};
+        use enterprise_web3::{WEB3_SERVICE_START_HEIGHT, WEB3_SINK};
         use std::collections::HashMap;
         use std::mem::replace;
         use tracing::error;
 
         let height = state.get_tendermint_height() as u32;
         if height as u64 > *WEB3_SERVICE_START_HEIGHT {
-            let redis_pool = REDIS_CLIENT.lock().expect("REDIS_CLIENT error");
-            let mut conn = redis_pool.get().expect("get redis connect");
-            let mut setter = Setter::new(&mut *conn, "evm".to_string());
+            let mut setter = WEB3_SINK.lock().expect("WEB3_SINK error");
 
             let nonce_map = if let Ok(mut nonce_map) = NONCE_MAP.lock() {
                 replace(&mut *nonce_map, HashMap::new())
@@ -634,12 +721,7 @@ pub fn commit(s: &mut ABCISubmissionServer, req: &RequestCommit) -> ResponseComm
 
                 for state in state_list.iter() {
                     setter
-                        .set_state(
-                            height,
-                            state.address.clone(),
-                            state.index.clone(),
-                            state.value.clone(),
-                        )
+                        .set_state(height, state.address, state.index, state.value)
                         .map_err(|e| error!("{:?}", e))
                         .unwrap_or(());
                 }
@@ -650,6 +732,8 @@ pub fn commit(s: &mut ABCISubmissionServer, req: &RequestCommit) -> ResponseComm
                         .map_err(|e| error!("{:?}", e))
                         .unwrap_or(());
                 }
+
+                setter.flush().map_err(|e| error!("{:?}", e)).unwrap_or(());
             }
         }
     }
@@ -657,6 +741,47 @@ pub fn commit(s: &mut ABCISubmissionServer, req: &RequestCommit) -> ResponseComm
     r
 }
 
+pub fn list_snapshots(
+    _s: &mut ABCISubmissionServer,
+    req: &RequestListSnapshots,
+) -> ResponseListSnapshots {
+    state_sync::list_snapshots(req)
+}
+
+pub fn load_snapshot_chunk(
+    _s: &mut ABCISubmissionServer,
+    req: &RequestLoadSnapshotChunk,
+) -> ResponseLoadSnapshotChunk {
+    state_sync::load_snapshot_chunk(req)
+}
+
+/// The data of a running node can not be replaced,
+/// snapshots are restored by the nodes started with `--state-sync-restore`.
+pub fn offer_snapshot(
+    _s: &mut ABCISubmissionServer,
+    _req: &RequestOfferSnapshot,
+) -> ResponseOfferSnapshot {
+    let mut resp = ResponseOfferSnapshot::new();
+    resp.set_result(ResponseOfferSnapshot_Result::REJECT);
+    resp
+}
+
+pub fn apply_snapshot_chunk(
+    _s: &mut ABCISubmissionServer,
+    _req: &RequestApplySnapshotChunk,
+) -> ResponseApplySnapshotChunk {
+    let mut resp = ResponseApplySnapshotChunk::new();
+    resp.set_result(ResponseApplySnapshotChunk_Result::ABORT);
+    resp
+}
+
+/// EVM is disabled between `DisableEvm` and `EnableFrc20`
+#[inline(always)]
+pub(crate) fn evm_disabled(td_height: i64) -> bool {
+    is_active(Feature::DisableEvm, td_height as u64)
+        && !is_active(Feature::EnableFrc20, td_height as u64)
+}
+
 /// Combines ledger state hash and EVM chain state hash
 /// and print app hashes for debugging
 fn app_hash(
@@ -104,7 +104,7 @@ fn gen_tendermint_attr_addr(tx: &Transaction) -> (Vec<TagAttr>, Vec<TagAttr>) {
                         base.$idx.push(attr);
                     });
                 };
-                // define\issue\AIR\memo
+                // define\issue\AIR\memo\freeze\clawback
                 ($data: expr) => {
                     let mut attr = TagAttr::default();
                     attr.addr = globutils::wallet::public_key_to_bech32(&$data.pubkey);
@@ -126,6 +126,12 @@ fn gen_tendermint_attr_addr(tx: &Transaction) -> (Vec<TagAttr>, Vec<TagAttr>) {
                 Operation::UpdateMemo(d) => {
                     append_attr!(d);
                 }
+                Operation::FreezeAsset(d) => {
+                    append_attr!(d);
+                }
+                Operation::ClawbackAsset(d) => {
+                    append_attr!(d);
+                }
                 _ => {}
             }
 
@@ -0,0 +1,112 @@
+//!
+//! # Dynamic Minimum Fee
+//!
+//! Measure the fullness of recent blocks by the number of UTXO transactions,
+//! and raise the minimum fee of the local mempool when they are fuller than
+//! `min_fee_target_txs`, so that spam can not squeeze out normal transactions.
+//!
+//! This is a policy of the mempool, `TX_FEE_MIN` is still the consensus rule.
+//!
+
+use {
+    config::abci::global_cfg::CFG,
+    lazy_static::lazy_static,
+    ledger::data_model::{FeeInfo, TX_FEE_MIN},
+    parking_lot::RwLock,
+    std::collections::VecDeque,
+};
+
+/// The minimum fee can rise up to this multiple of `TX_FEE_MIN`.
+pub const MAX_FEE_MULTIPLE: u64 = 100;
+
+lazy_static! {
+    static ref FEE_MARKET: RwLock<FeeMarket> = RwLock::new(FeeMarket::default());
+}
+
+#[derive(Default)]
+struct FeeMarket {
+    // the numbers of transactions in recent blocks
+    window: VecDeque<u64>,
+    // the number of transactions in the current block
+    cur: u64,
+}
+
+/// Count a UTXO transaction delivered in the current block.
+#[inline(always)]
+pub fn count_tx() {
+    FEE_MARKET.write().cur += 1;
+}
+
+/// Move to the next block, should be called at commit.
+pub fn end_block() {
+    let mut fm = FEE_MARKET.write();
+    let cur = std::mem::take(&mut fm.cur);
+    fm.window.push_back(cur);
+    while fm.window.len() > CFG.min_fee_window.max(1) {
+        fm.window.pop_front();
+    }
+}
+
+/// The current fees, see `FeeInfo`.
+pub fn fee_info() -> FeeInfo {
+    let fm = FEE_MARKET.read();
+    fee_info_of(fm.window.iter().copied(), CFG.min_fee_target_txs)
+}
+
+/// Transactions paying less are rejected by `check_tx`.
+#[inline(always)]
+pub fn min_fee() -> u64 {
+    fee_info().min_fee
+}
+
+// The minimum fee follows the average fullness of the window,
+// and the suggested one follows the fullest block in it.
+fn fee_info_of(window: impl Iterator<Item = u64> + Clone, target: u64) -> FeeInfo {
+    if 0 == target {
+        return FeeInfo::default();
+    }
+
+    let (sum, cnt) = window
+        .clone()
+        .fold((0u64, 0u64), |(s, c), n| (s.saturating_add(n), c + 1));
+    let avg = sum.checked_div(cnt).unwrap_or(0);
+    let max = window.max().unwrap_or(0);
+
+    FeeInfo {
+        min_fee: fee_of(avg, target),
+        suggested_fee: fee_of(max, target),
+    }
+}
+
+// `TX_FEE_MIN` until `target`, then linearly with the number of transactions.
+fn fee_of(txs: u64, target: u64) -> u64 {
+    if txs <= target {
+        return TX_FEE_MIN;
+    }
+    let fee = (txs as u128 * TX_FEE_MIN as u128)
+        .div_ceil(target as u128)
+        .min((MAX_FEE_MULTIPLE * TX_FEE_MIN) as u128);
+    fee as u64
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn fee_by_fullness() {
+        let fee = |w: &[u64], target| fee_info_of(w.iter().copied(), target);
+
+        assert_eq!(FeeInfo::default(), fee(&[1000, 1000], 0));
+        assert_eq!(FeeInfo::default(), fee(&[], 10));
+        assert_eq!(FeeInfo::default(), fee(&[10, 0, 5], 10));
+
+        let f = fee(&[0, 40, 20], 10);
+        assert_eq!(2 * TX_FEE_MIN, f.min_fee);
+        assert_eq!(4 * TX_FEE_MIN, f.suggested_fee);
+
+        let f = fee(&[u64::MAX, u64::MAX], 1);
+        assert_eq!(MAX_FEE_MULTIPLE * TX_FEE_MIN, f.min_fee);
+        assert_eq!(MAX_FEE_MULTIPLE * TX_FEE_MIN, f.suggested_fee);
+    }
+}
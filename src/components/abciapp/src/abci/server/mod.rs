@@ -8,10 +8,13 @@ use {
         api::submission_server::SubmissionServer,
     },
     abci::{
-        RequestBeginBlock, RequestCheckTx, RequestCommit, RequestDeliverTx,
-        RequestEndBlock, RequestInfo, RequestInitChain, RequestQuery,
-        ResponseBeginBlock, ResponseCheckTx, ResponseCommit, ResponseDeliverTx,
-        ResponseEndBlock, ResponseInfo, ResponseInitChain, ResponseQuery,
+        RequestApplySnapshotChunk, RequestBeginBlock, RequestCheckTx, RequestCommit,
+        RequestDeliverTx, RequestEndBlock, RequestInfo, RequestInitChain,
+        RequestListSnapshots, RequestLoadSnapshotChunk, RequestOfferSnapshot,
+        RequestQuery, ResponseApplySnapshotChunk, ResponseBeginBlock, ResponseCheckTx,
+        ResponseCommit, ResponseDeliverTx, ResponseEndBlock, ResponseInfo,
+        ResponseInitChain, ResponseListSnapshots, ResponseLoadSnapshotChunk,
+        ResponseOfferSnapshot, ResponseQuery,
     },
     baseapp::BaseApp as AccountBaseAPP,
     config::abci::global_cfg::CFG,
@@ -30,6 +33,8 @@ use {
 pub use tx_sender::forward_txn_with_mode;
 
 pub mod callback;
+pub mod fee_market;
+pub mod state_sync;
 pub mod tx_sender;
 
 /// findora impl of tendermint abci
@@ -126,4 +131,30 @@ impl abci::Application for ABCISubmissionServer {
     fn commit(&mut self, req: &RequestCommit) -> ResponseCommit {
         callback::commit(self, req)
     }
+
+    #[inline(always)]
+    fn list_snapshots(&mut self, req: &RequestListSnapshots) -> ResponseListSnapshots {
+        callback::list_snapshots(self, req)
+    }
+
+    #[inline(always)]
+    fn offer_snapshot(&mut self, req: &RequestOfferSnapshot) -> ResponseOfferSnapshot {
+        callback::offer_snapshot(self, req)
+    }
+
+    #[inline(always)]
+    fn load_snapshot_chunk(
+        &mut self,
+        req: &RequestLoadSnapshotChunk,
+    ) -> ResponseLoadSnapshotChunk {
+        callback::load_snapshot_chunk(self, req)
+    }
+
+    #[inline(always)]
+    fn apply_snapshot_chunk(
+        &mut self,
+        req: &RequestApplySnapshotChunk,
+    ) -> ResponseApplySnapshotChunk {
+        callback::apply_snapshot_chunk(self, req)
+    }
 }
@@ -0,0 +1,521 @@
+//!
+//! # Tendermint State Sync
+//!
+//! Serve the snapshots of the ledger and EVM data to other nodes chunk by chunk,
+//! and bootstrap an empty node from the snapshots offered by tendermint.
+//!
+//! A snapshot is an archive of `ledger::store::snapshot`, taken every
+//! `state_sync_snapshot_itv` blocks at commit: the immutable table files of
+//! the databases are hard linked while the ledger is locked, as a RocksDB
+//! checkpoint does, only the other files are copied, then the archive is
+//! packed in the background.
+//!
+
+use {
+    super::{callback::evm_disabled, ABCISubmissionServer},
+    crate::abci::{start_services, POOL},
+    abci::{
+        RequestApplySnapshotChunk, RequestListSnapshots, RequestLoadSnapshotChunk,
+        RequestOfferSnapshot, ResponseApplySnapshotChunk,
+        ResponseApplySnapshotChunk_Result, ResponseListSnapshots,
+        ResponseLoadSnapshotChunk, ResponseOfferSnapshot, ResponseOfferSnapshot_Result,
+        Snapshot,
+    },
+    config::abci::{global_cfg::CFG, ABCIConfig},
+    globutils::HashOf,
+    ledger::{
+        data_model::StateCommitmentData,
+        store::{
+            fbnc::flush_data,
+            snapshot::{self, SNAPSHOT_VERSION},
+            LedgerState,
+        },
+    },
+    protobuf::RepeatedField,
+    ruc::*,
+    serde::{Deserialize, Serialize},
+    std::{
+        collections::BTreeSet,
+        fs::{self, File},
+        io::{self, Read, Seek, SeekFrom},
+        path::{Path, PathBuf},
+    },
+};
+
+/// The max size of a chunk, tendermint limits it to 16MB.
+const CHUNK_SIZE: u64 = 10 * 1024 * 1024;
+
+const ARCHIVE: &str = "archive.tar";
+const META: &str = "meta.json";
+const PENDING: &str = ".pending";
+
+type Commitment = HashOf<Option<StateCommitmentData>>;
+
+// `${ledger_dir}.state_sync`, must be out of the ledger dir
+fn root() -> PathBuf {
+    Path::new(&CFG.ledger_dir).with_extension("state_sync")
+}
+
+fn snapshots_dir() -> PathBuf {
+    root().join("snapshots")
+}
+
+fn restoring_dir() -> PathBuf {
+    root().join("restoring")
+}
+
+/// Everything of a snapshot except its data,
+/// it is written after the archive, so a snapshot without it is incomplete.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct SnapshotMeta {
+    height: u64,
+    format: u32,
+    chunks: u32,
+    // sha256 of the archive, in hex
+    hash: String,
+    // the state commitment of the ledger, in json
+    metadata: String,
+}
+
+impl SnapshotMeta {
+    fn to_abci(&self) -> Result<Snapshot> {
+        let mut s = Snapshot::new();
+        s.set_height(self.height);
+        s.set_format(self.format);
+        s.set_chunks(self.chunks);
+        s.set_hash(hex::decode(&self.hash).c(d!())?);
+        s.set_metadata(self.metadata.clone().into_bytes());
+        Ok(s)
+    }
+}
+
+/// Whether a snapshot should be taken at this height.
+#[inline(always)]
+pub fn snapshot_due(height: u64) -> bool {
+    0 < CFG.state_sync_snapshot_itv && 0 == height % CFG.state_sync_snapshot_itv
+}
+
+/// Take a snapshot of the committed data,
+/// must be called at the end of `commit` with the ledger locked.
+///
+/// Nothing is packed here, the snapshot holds hard links to the table files,
+/// which are never modified, and copies of the logs and manifests of the
+/// databases, the utxo bitmap and the merkle trees.
+pub fn take_snapshot(state: &LedgerState) -> Result<()> {
+    let height = state.get_tendermint_height();
+    let commitment = state.get_state_commitment().0;
+
+    let pending = snapshots_dir().join(format!("{height}{PENDING}"));
+    let _ = fs::remove_dir_all(&pending);
+
+    flush_data();
+    link_dir(Path::new(&CFG.ledger_dir), &pending).c(d!())?;
+
+    POOL.spawn_ok(async move {
+        ruc::info_omit!(pack(height, commitment, &pending));
+        let _ = fs::remove_dir_all(&pending);
+    });
+
+    Ok(())
+}
+
+fn pack(height: u64, commitment: Commitment, data: &Path) -> Result<()> {
+    let dir = snapshots_dir().join(height.to_string());
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).c(d!())?;
+
+    let archive = dir.join(ARCHIVE);
+    snapshot::export(data, &archive, height, commitment.clone()).c(d!())?;
+    let (size, hash) = snapshot::hash_file(&archive).c(d!())?;
+
+    let meta = SnapshotMeta {
+        height,
+        format: SNAPSHOT_VERSION,
+        chunks: chunks_of(size).c(d!())?,
+        hash,
+        metadata: serde_json::to_string(&commitment).c(d!())?,
+    };
+    fs::write(dir.join(META), serde_json::to_vec(&meta).c(d!())?).c(d!())?;
+
+    prune();
+
+    Ok(())
+}
+
+// Remove everything older than the recent `state_sync_snapshot_keep` snapshots,
+// including the ones left incomplete by a crash.
+fn prune() {
+    let snapshots = list();
+    let oldest = match snapshots
+        .len()
+        .checked_sub(CFG.state_sync_snapshot_keep.max(1))
+    {
+        Some(idx) => snapshots[idx].height,
+        None => return,
+    };
+
+    if let Ok(entries) = fs::read_dir(snapshots_dir()) {
+        for entry in entries.flatten() {
+            let h = entry
+                .file_name()
+                .to_str()
+                .and_then(|n| n.trim_end_matches(PENDING).parse::<u64>().ok());
+            if matches!(h, Some(h) if h < oldest) {
+                let _ = fs::remove_dir_all(entry.path());
+            }
+        }
+    }
+}
+
+// All the complete snapshots, in ascending order of height.
+fn list() -> Vec<SnapshotMeta> {
+    let mut res = fs::read_dir(snapshots_dir())
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|e| fs::read(e.path().join(META)).ok())
+                .filter_map(|m| serde_json::from_slice::<SnapshotMeta>(&m).ok())
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    res.sort_by_key(|m| m.height);
+    res
+}
+
+#[inline(always)]
+fn chunks_of(size: u64) -> Result<u32> {
+    u32::try_from(size.div_ceil(CHUNK_SIZE)).c(d!())
+}
+
+#[inline(always)]
+fn read_chunk(height: u64, index: u32) -> Result<Vec<u8>> {
+    let path = snapshots_dir().join(height.to_string()).join(ARCHIVE);
+    read_chunk_of(&path, index).c(d!())
+}
+
+fn read_chunk_of(archive: &Path, index: u32) -> Result<Vec<u8>> {
+    let mut f = File::open(archive).c(d!())?;
+    f.seek(SeekFrom::Start(index as u64 * CHUNK_SIZE)).c(d!())?;
+
+    let mut chunk = vec![];
+    f.take(CHUNK_SIZE).read_to_end(&mut chunk).c(d!())?;
+    if chunk.is_empty() {
+        return Err(eg!("chunk not found"));
+    }
+
+    Ok(chunk)
+}
+
+// Mirror all the regular files under `src` into `dst`,
+// the table files of RocksDB are immutable, so they are hard linked,
+// and copied only if `dst` is on another file system.
+fn link_dir(src: &Path, dst: &Path) -> Result<()> {
+    fs::create_dir_all(dst).c(d!())?;
+    for entry in fs::read_dir(src).c(d!())? {
+        let entry = entry.c(d!())?;
+        let ft = entry.file_type().c(d!())?;
+        let (from, to) = (entry.path(), dst.join(entry.file_name()));
+        if ft.is_dir() {
+            link_dir(&from, &to).c(d!())?;
+        } else if ft.is_file() {
+            let immutable = matches!(
+                from.extension().and_then(|e| e.to_str()),
+                Some("sst") | Some("blob")
+            );
+            if !immutable || fs::hard_link(&from, &to).is_err() {
+                fs::copy(&from, &to).c(d!())?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[inline(always)]
+fn save_chunk(dir: &Path, index: u32, chunk: &[u8]) -> Result<()> {
+    fs::write(dir.join(format!("chunk-{index}")), chunk).c(d!())
+}
+
+// Join the saved chunks in the order of their indexes,
+// and check the archive against the hash of the snapshot.
+fn assemble(dir: &Path, chunks: u32, hash: &[u8]) -> Result<PathBuf> {
+    let archive = dir.join(ARCHIVE);
+    let mut f = File::create(&archive).c(d!())?;
+    for i in 0..chunks {
+        let chunk = dir.join(format!("chunk-{i}"));
+        io::copy(&mut File::open(&chunk).c(d!())?, &mut f).c(d!())?;
+        fs::remove_file(chunk).c(d!())?;
+    }
+    f.sync_all().c(d!())?;
+
+    let (_, h) = snapshot::hash_file(&archive).c(d!())?;
+    if hex::encode(hash) != h {
+        return Err(eg!("invalid snapshot hash"));
+    }
+
+    Ok(archive)
+}
+
+/// Serve the complete snapshots.
+pub fn list_snapshots(_req: &RequestListSnapshots) -> ResponseListSnapshots {
+    let snapshots = list()
+        .iter()
+        .filter_map(|m| m.to_abci().ok())
+        .collect::<Vec<_>>();
+
+    let mut resp = ResponseListSnapshots::new();
+    resp.set_snapshots(RepeatedField::from_vec(snapshots));
+    resp
+}
+
+/// Serve a chunk of a snapshot, an empty one means not found.
+pub fn load_snapshot_chunk(req: &RequestLoadSnapshotChunk) -> ResponseLoadSnapshotChunk {
+    let mut resp = ResponseLoadSnapshotChunk::new();
+    if SNAPSHOT_VERSION == req.get_format() {
+        if let Ok(chunk) = ruc::info!(read_chunk(req.get_height(), req.get_chunk())) {
+            resp.set_chunk(chunk);
+        }
+    }
+    resp
+}
+
+/// The ABCI application of a node started with `--state-sync-restore`,
+/// it opens nothing until a snapshot is restored into the empty ledger dir,
+/// then loads the restored data and works as a normal node.
+pub struct Restorer {
+    config: ABCIConfig,
+    pending: Option<(Snapshot, Commitment)>,
+    // indexes of the chunks saved for the pending snapshot
+    received: BTreeSet<u32>,
+    app: Option<ABCISubmissionServer>,
+}
+
+impl Restorer {
+    #[allow(missing_docs)]
+    pub fn new(config: ABCIConfig) -> Self {
+        Restorer {
+            config,
+            pending: None,
+            received: BTreeSet::new(),
+            app: None,
+        }
+    }
+
+    fn offer(&mut self, req: &RequestOfferSnapshot) -> ResponseOfferSnapshot_Result {
+        let snapshot = req.get_snapshot();
+        if SNAPSHOT_VERSION != snapshot.get_format() {
+            return ResponseOfferSnapshot_Result::REJECT_FORMAT;
+        }
+
+        let commitment =
+            match serde_json::from_slice::<Commitment>(snapshot.get_metadata()) {
+                Ok(c) => c,
+                Err(_) => return ResponseOfferSnapshot_Result::REJECT,
+            };
+
+        // the app hash is the ledger commitment itself when evm is disabled,
+        // otherwise it will be checked by tendermint after the restoration
+        if evm_disabled(snapshot.get_height() as i64)
+            && commitment.as_ref() != req.get_app_hash()
+        {
+            return ResponseOfferSnapshot_Result::REJECT;
+        }
+
+        let _ = fs::remove_dir_all(restoring_dir());
+        if ruc::info!(fs::create_dir_all(restoring_dir())).is_err() {
+            return ResponseOfferSnapshot_Result::ABORT;
+        }
+
+        self.pending = Some((snapshot.clone(), commitment));
+        self.received.clear();
+        ResponseOfferSnapshot_Result::ACCEPT
+    }
+
+    // Save the chunk, and restore the snapshot after all the chunks
+    // have been saved, no matter in which order they are applied.
+    fn apply(
+        &mut self,
+        req: &RequestApplySnapshotChunk,
+    ) -> Result<ResponseApplySnapshotChunk_Result> {
+        let chunks = match self.pending.as_ref() {
+            Some((snapshot, _)) => snapshot.get_chunks(),
+            None => return Ok(ResponseApplySnapshotChunk_Result::ABORT),
+        };
+
+        let index = req.get_index();
+        if index >= chunks {
+            return Err(eg!("invalid chunk index"));
+        }
+
+        let dir = restoring_dir();
+        save_chunk(&dir, index, req.get_chunk()).c(d!())?;
+        self.received.insert(index);
+        if (self.received.len() as u32) < chunks {
+            return Ok(ResponseApplySnapshotChunk_Result::ACCEPT);
+        }
+
+        let (snapshot, commitment) = self.pending.take().c(d!())?;
+        let archive = assemble(&dir, chunks, snapshot.get_hash()).c(d!())?;
+
+        // the ledger dir is still empty if the import fails
+        let manifest =
+            snapshot::import(&archive, Path::new(&CFG.ledger_dir), Some(&commitment))
+                .c(d!())?;
+        let _ = fs::remove_dir_all(&dir);
+
+        // the ledger dir is not empty any more, no other snapshot can be tried
+        if manifest.height != snapshot.get_height() || ruc::info!(self.load()).is_err() {
+            return Ok(ResponseApplySnapshotChunk_Result::ABORT);
+        }
+
+        Ok(ResponseApplySnapshotChunk_Result::ACCEPT)
+    }
+
+    fn load(&mut self) -> Result<()> {
+        let app = ABCISubmissionServer::new(
+            Some(&CFG.ledger_dir),
+            format!(
+                "{}:{}",
+                self.config.tendermint_host, self.config.tendermint_port
+            ),
+        )
+        .c(d!())?;
+        start_services(&app, &self.config);
+        self.app = Some(app);
+        Ok(())
+    }
+}
+
+macro_rules! delegate {
+    ($($method: ident($req: ty) -> $resp: ty),+ $(,)?) => {
+        $(
+            #[inline(always)]
+            fn $method(&mut self, req: &$req) -> $resp {
+                match self.app.as_mut() {
+                    Some(app) => abci::Application::$method(app, req),
+                    None => <$resp>::new(),
+                }
+            }
+        )+
+    };
+}
+
+impl abci::Application for Restorer {
+    delegate! {
+        info(abci::RequestInfo) -> abci::ResponseInfo,
+        query(abci::RequestQuery) -> abci::ResponseQuery,
+        check_tx(abci::RequestCheckTx) -> abci::ResponseCheckTx,
+        init_chain(abci::RequestInitChain) -> abci::ResponseInitChain,
+        begin_block(abci::RequestBeginBlock) -> abci::ResponseBeginBlock,
+        deliver_tx(abci::RequestDeliverTx) -> abci::ResponseDeliverTx,
+        end_block(abci::RequestEndBlock) -> abci::ResponseEndBlock,
+        commit(abci::RequestCommit) -> abci::ResponseCommit,
+        list_snapshots(RequestListSnapshots) -> ResponseListSnapshots,
+        load_snapshot_chunk(RequestLoadSnapshotChunk) -> ResponseLoadSnapshotChunk,
+    }
+
+    fn offer_snapshot(&mut self, req: &RequestOfferSnapshot) -> ResponseOfferSnapshot {
+        if let Some(app) = self.app.as_mut() {
+            return abci::Application::offer_snapshot(app, req);
+        }
+
+        let mut resp = ResponseOfferSnapshot::new();
+        resp.set_result(self.offer(req));
+        resp
+    }
+
+    fn apply_snapshot_chunk(
+        &mut self,
+        req: &RequestApplySnapshotChunk,
+    ) -> ResponseApplySnapshotChunk {
+        if let Some(app) = self.app.as_mut() {
+            return abci::Application::apply_snapshot_chunk(app, req);
+        }
+
+        let result = self.apply(req).c(d!()).unwrap_or_else(|e| {
+            e.print(None);
+            self.pending = None;
+            self.received.clear();
+            let _ = fs::remove_dir_all(restoring_dir());
+            ResponseApplySnapshotChunk_Result::REJECT_SNAPSHOT
+        });
+
+        let mut resp = ResponseApplySnapshotChunk::new();
+        resp.set_result(result);
+        resp
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn snapshot_chunks() {
+        let dir = globutils::fresh_tmp_dir();
+        let archive = dir.join(ARCHIVE);
+        let data = (0..2 * CHUNK_SIZE + 10)
+            .map(|i| (i % 251) as u8)
+            .collect::<Vec<_>>();
+        pnk!(fs::write(&archive, &data));
+        let (size, hash) = pnk!(snapshot::hash_file(&archive));
+
+        let chunks = pnk!(chunks_of(size));
+        assert_eq!(chunks, 3);
+        assert_eq!(pnk!(chunks_of(0)), 0);
+        assert_eq!(pnk!(chunks_of(CHUNK_SIZE)), 1);
+
+        let all = (0..chunks)
+            .map(|i| pnk!(read_chunk_of(&archive, i)))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            all.iter().map(|c| c.len() as u64).collect::<Vec<_>>(),
+            vec![CHUNK_SIZE, CHUNK_SIZE, 10]
+        );
+        assert!(read_chunk_of(&archive, chunks).is_err());
+
+        // the chunks are joined by their indexes, not by the order of arrival
+        let restoring = dir.join("restoring");
+        pnk!(fs::create_dir_all(&restoring));
+        [2, 0, 1].into_iter().for_each(|i| {
+            pnk!(save_chunk(&restoring, i, &all[i as usize]));
+        });
+        let restored = pnk!(assemble(&restoring, chunks, &pnk!(hex::decode(&hash))));
+        assert_eq!(pnk!(fs::read(restored)), data);
+
+        // a chunk is missing
+        pnk!(save_chunk(&restoring, 0, &all[0]));
+        assert!(assemble(&restoring, chunks, &pnk!(hex::decode(&hash))).is_err());
+
+        // the data does not match the hash of the snapshot
+        [0, 2, 1].into_iter().for_each(|i| {
+            pnk!(save_chunk(&restoring, i, &all[(2 - i) as usize]));
+        });
+        assert!(assemble(&restoring, chunks, &pnk!(hex::decode(&hash))).is_err());
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn snapshot_links() {
+        let dir = globutils::fresh_tmp_dir();
+        let (src, dst) = (dir.join("src"), dir.join("dst"));
+        pnk!(fs::create_dir_all(src.join("db")));
+        pnk!(fs::write(src.join("db/000001.sst"), b"table"));
+        pnk!(fs::write(src.join("db/MANIFEST-000001"), b"manifest"));
+        pnk!(fs::write(src.join("utxo_map"), b"bitmap"));
+
+        pnk!(link_dir(&src, &dst));
+
+        // later changes of the mutable files are not seen by the snapshot
+        pnk!(fs::write(src.join("db/MANIFEST-000001"), b"changed"));
+        pnk!(fs::write(src.join("utxo_map"), b"changed"));
+        // the table files are removed by compactions, but never changed
+        pnk!(fs::remove_file(src.join("db/000001.sst")));
+
+        assert_eq!(pnk!(fs::read(dst.join("db/000001.sst"))), b"table");
+        assert_eq!(pnk!(fs::read(dst.join("db/MANIFEST-000001"))), b"manifest");
+        assert_eq!(pnk!(fs::read(dst.join("utxo_map"))), b"bitmap");
+
+        let _ = fs::remove_dir_all(dir);
+    }
+}
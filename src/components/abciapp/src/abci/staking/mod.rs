@@ -17,8 +17,10 @@ use {
     crate::abci::server::callback::TENDERMINT_BLOCK_HEIGHT,
     abci::{Evidence, Header, LastCommitInfo, PubKey, ValidatorUpdate},
     baseapp::BaseApp as AccountBaseApp,
-    config::abci::global_cfg::CFG,
-    fp_types::actions::xhub::NonConfidentialOutput,
+    config::abci::upgrade::{is_active, Feature},
+    fp_types::actions::xhub::{
+        StakingFailure, StakingRequest, StakingValidator, UtxoOutput,
+    },
     lazy_static::lazy_static,
     ledger::{
         data_model::{Operation, Transaction, ASSET_TYPE_FRA},
@@ -27,10 +29,14 @@ use {
                 governance::{governance_penalty_tendermint_auto, ByzantineKind},
                 mint_fra::{MintEntry, MintFraOps, MintKind},
             },
-            td_addr_to_string, Staking, VALIDATOR_UPDATE_BLOCK_ITV,
+            td_addr_to_string, PartialUnDelegation, Staking, VALIDATOR_UPDATE_BLOCK_ITV,
+        },
+        store::{
+            events::{self, LedgerEvent, StakingEvent},
+            LedgerState,
         },
-        store::LedgerState,
     },
+    primitive_types::H160,
     ruc::*,
     serde::Serialize,
     std::{
@@ -130,12 +136,12 @@ pub fn get_validators(
     // reverse sort
     vs.sort_by(|a, b| b.1.cmp(&a.1));
 
-    let validator_limit =
-        if CFG.checkpoint.validators_limit_v2_height > staking.cur_height() {
-            VALIDATOR_LIMIT
-        } else {
-            VALIDATOR_LIMIT_V2
-        };
+    let validator_limit = if is_active(Feature::ValidatorsLimitV2, staking.cur_height())
+    {
+        VALIDATOR_LIMIT_V2
+    } else {
+        VALIDATOR_LIMIT
+    };
 
     // set the power of every extra validators to zero,
     // then tendermint can remove them from consensus logic.
@@ -172,12 +178,19 @@ pub fn system_ops(
     // trigger system staking process
     la.get_staking_mut().delegation_process();
     la.get_staking_mut().validator_apply_current();
-
-    ruc::info_omit!(set_rewards(
-        la,
-        &header.proposer_address,
-        last_commit_info.map(get_last_vote_percent)
-    ));
+    la.get_staking_mut().proposal_process();
+
+    // jail the byzantine validators before paying the rewards,
+    // so that a proposer is not paid for the block reporting itself
+    let jail_first = is_active(Feature::ValidatorJail, la.get_staking().cur_height());
+
+    if !jail_first {
+        ruc::info_omit!(set_rewards(
+            la,
+            &header.proposer_address,
+            last_commit_info.map(get_last_vote_percent)
+        ));
+    }
 
     // tendermint primary governances
     evs.iter()
@@ -192,6 +205,14 @@ pub fn system_ops(
             ruc::info_omit!(system_governance(la.get_staking_mut().deref_mut(), &bz));
         });
 
+    if jail_first {
+        ruc::info_omit!(set_rewards(
+            la,
+            &header.proposer_address,
+            last_commit_info.map(get_last_vote_percent)
+        ));
+    }
+
     // application custom governances
     if let Some(lci) = last_commit_info {
         let online_list = lci
@@ -213,6 +234,23 @@ pub fn system_ops(
             });
         }
 
+        // keep the signing records in a rolling window,
+        // they change the staking state, so only after `UptimePenalty`
+        if is_active(Feature::UptimePenalty, la.get_staking().cur_height()) {
+            let votes = lci
+                .votes
+                .iter()
+                .flat_map(|info| {
+                    let signed = info.signed_last_block;
+                    info.validator
+                        .as_ref()
+                        .map(|v| (td_addr_to_string(&v.address), signed))
+                })
+                .collect::<Vec<_>>();
+            let proposer = td_addr_to_string(&header.proposer_address);
+            la.get_staking_mut().uptime_record(&votes, &proposer);
+        }
+
         if online_list.len() != lci.votes.len() {
             if let Ok(pl) = ruc::info!(gen_offline_punish_list(
                 la.get_staking().deref(),
@@ -281,13 +319,26 @@ fn system_governance(staking: &mut Staking, bz: &ByzantineInfo) -> Result<()> {
         "UNKNOWN" => ByzantineKind::Unknown,
         _ => return Err(eg!()),
     };
-    governance_penalty_tendermint_auto(staking, bz.addr, &kind).c(d!())
+    let validator_id = staking.validator_td_addr_to_app_pk(bz.addr).ok();
+    governance_penalty_tendermint_auto(staking, bz.addr, &kind)
+        .c(d!())
+        .map(|_| {
+            events::notify([LedgerEvent::Staking {
+                height: staking.cur_height(),
+                txn_sid: None,
+                event: StakingEvent::Penalty {
+                    validator: bz.addr.to_owned(),
+                    validator_id,
+                    kind,
+                },
+            }]);
+        })
 }
 
 /// Pay for freed 'Delegations' and 'FraDistributions'.
 pub fn system_prism_mint_pay(
     la: &mut LedgerState,
-    mint: &NonConfidentialOutput,
+    mint: &UtxoOutput,
 ) -> Option<Transaction> {
     let mut mints = Vec::new();
 
@@ -321,10 +372,7 @@ pub fn system_prism_mint_pay(
         la.insert_asset_type(atc, at);
     }
 
-    let mint_entry =
-        MintEntry::new(MintKind::Other, mint.target, None, mint.amount, mint.asset);
-
-    mints.push(mint_entry);
+    mints.push(utxo_mint_entry(mint));
 
     let mint_ops =
         Operation::MintFra(MintFraOps::new(la.get_staking().cur_height(), mints));
@@ -354,12 +402,13 @@ pub fn system_mint_pay(
         })
         .chain(staking.delegation_get_global_rewards().into_iter().map(
             |(k, (n, receiver_pk))| {
-                let pk = if td_height
-                    <= CFG.checkpoint.fix_undelegation_missing_reward_height
-                {
-                    Some(k)
-                } else {
+                let pk = if is_active(
+                    Feature::FixUndelegationMissingReward,
+                    td_height as u64,
+                ) {
                     receiver_pk
+                } else {
+                    Some(k)
                 };
                 MintEntry::new(MintKind::Claim, k, pk, n, ASSET_TYPE_FRA)
             },
@@ -380,19 +429,39 @@ pub fn system_mint_pay(
         .take(NUM_TO_PAY)
         .collect::<Vec<_>>();
 
+    // the proceeds of the keys bound to evm accounts are credited to the accounts,
+    // and minted to the black hole for the bookkeeping of the staking,
+    // unless they have been given to another receiver
+    if is_active(Feature::EvmStaking, td_height as u64) {
+        for entry in mint_entries
+            .iter_mut()
+            .filter(|e| e.utxo.record.public_key == e.target_pk)
+        {
+            if let Some(owner) = account_base_app.staking_owner_of(&entry.target_pk) {
+                match account_base_app
+                    .credit_staking(&owner, entry.amount)
+                    .c(d!())
+                {
+                    Ok(_) => {
+                        *entry = MintEntry::new(
+                            entry.kind.clone(),
+                            entry.target_pk,
+                            Some(*BLACK_HOLE_PUBKEY_STAKING),
+                            entry.amount,
+                            ASSET_TYPE_FRA,
+                        );
+                    }
+                    Err(e) => e.print(None),
+                }
+            }
+        }
+    }
+
     // add account mint_entries.
     let mut mints = if let Some(account_mint) = account_base_app.consume_mint() {
         account_mint
             .iter()
-            .map(|mint| {
-                MintEntry::new(
-                    MintKind::Other,
-                    mint.target,
-                    None,
-                    mint.amount,
-                    mint.asset,
-                )
-            })
+            .map(utxo_mint_entry)
             .collect::<Vec<MintEntry>>()
     } else {
         Vec::new()
@@ -412,6 +481,104 @@ pub fn system_mint_pay(
     }
 }
 
+/// The mint entry of an output from the evm side.
+///
+/// A confidential one mints the record built by the sender, which is checked
+/// against the burned amount when the output is queued, with its owner memo.
+fn utxo_mint_entry(mint: &UtxoOutput) -> MintEntry {
+    match mint.transfer.as_deref() {
+        Some(transfer) => MintEntry::new_confidential(
+            MintKind::Other,
+            mint.target,
+            transfer.outputs[0].clone(),
+            transfer.owners_memos[0].clone(),
+        ),
+        None => {
+            MintEntry::new(MintKind::Other, mint.target, None, mint.amount, mint.asset)
+        }
+    }
+}
+
+/// Apply the staking requests of the evm accounts in the order of execution,
+/// a failed one is recorded for its owner to see,
+/// and the balance of a failed delegation will be given back to its owner.
+pub fn system_evm_staking(staking: &mut Staking, account_base_app: &mut AccountBaseApp) {
+    for req in account_base_app
+        .consume_staking_requests()
+        .unwrap_or_default()
+    {
+        if let Err(e) = apply_evm_staking_request(staking, &req).c(d!()) {
+            e.print(None);
+            if let StakingRequest::Delegate { owner, amount, .. } = &req {
+                info_omit!(account_base_app.credit_staking(owner, *amount));
+            }
+            info_omit!(account_base_app.record_staking_failure(&StakingFailure {
+                height: staking.cur_height(),
+                request: req,
+                reason: e.to_string(),
+            }));
+        }
+    }
+}
+
+fn apply_evm_staking_request(staking: &mut Staking, req: &StakingRequest) -> Result<()> {
+    match req {
+        StakingRequest::Delegate {
+            delegator,
+            validator,
+            amount,
+            ..
+        } => staking
+            .delegate(
+                *delegator,
+                &td_addr_to_string(validator.as_bytes()),
+                *amount,
+            )
+            .c(d!()),
+        StakingRequest::UnDelegate {
+            delegator,
+            validator,
+            amount: Some(am),
+            ..
+        } => {
+            let pu = PartialUnDelegation::new(
+                *am,
+                staking.gen_tmp_delegator_id(),
+                validator.as_bytes().to_vec(),
+            );
+            staking.undelegate(delegator, Some(&pu)).c(d!())
+        }
+        StakingRequest::UnDelegate {
+            delegator,
+            amount: None,
+            ..
+        } => staking.undelegate(delegator, None).c(d!()),
+        StakingRequest::Claim {
+            delegator, amount, ..
+        } => staking.claim(*delegator, *amount).c(d!()),
+    }
+}
+
+/// The current validators visible to the staking precompile of the evm.
+pub fn evm_staking_validators(staking: &Staking) -> Vec<StakingValidator> {
+    staking
+        .validator_get_current()
+        .map(|vd| {
+            vd.body
+                .values()
+                .filter(|v| v.td_addr.len() == H160::len_bytes())
+                .map(|v| StakingValidator {
+                    td_addr: H160::from_slice(&v.td_addr),
+                    power: v.td_power,
+                    commission_rate: v.commission_rate,
+                    delegated: v.delegators.values().sum(),
+                    jailed: v.is_jailed(),
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
 /// filtering online lists from staking's validators
 fn gen_offline_punish_list(
     staking: &Staking,
@@ -431,20 +598,31 @@ fn gen_offline_punish_list(
         .collect::<Vec<_>>();
     vs.sort_by(|a, b| b.1.cmp(&a.1));
 
-    let validator_limit =
-        if CFG.checkpoint.validators_limit_v2_height > staking.cur_height() {
-            VALIDATOR_LIMIT
-        } else {
-            VALIDATOR_LIMIT_V2
-        };
+    let validator_limit = if is_active(Feature::ValidatorsLimitV2, staking.cur_height())
+    {
+        VALIDATOR_LIMIT_V2
+    } else {
+        VALIDATOR_LIMIT
+    };
 
     vs.iter_mut().skip(validator_limit).for_each(|(_, power)| {
         *power = 0;
     });
 
+    // tolerate occasional missed blocks,
+    // only punish those who have missed too many blocks in the window
+    let by_uptime = is_active(Feature::UptimePenalty, last_height as u64);
+
     Ok(vs
         .into_iter()
         .filter(|v| 0 < v.1 && !online_list.contains(&v.0))
+        .filter(|v| {
+            !by_uptime
+                || staking
+                    .uptime_get(&td_addr_to_string(v.0))
+                    .map(|si| si.missed_too_many())
+                    .unwrap_or(false)
+        })
         .map(|(id, _)| id.clone())
         .collect())
 }
@@ -0,0 +1,82 @@
+//!
+//! # Ledger Event Streaming
+//!
+//! Push the events of committed blocks to clients as server-sent events,
+//! every message is a `LedgerEvent` in JSON form.
+//!
+
+use {
+    actix_web::{error, web, HttpResponse},
+    baseapp::notify::Notifications,
+    futures::{future, StreamExt},
+    globutils::wallet,
+    lazy_static::lazy_static,
+    ledger::store::events::{self, LedgerEvent},
+    ruc::*,
+    serde::Deserialize,
+    std::{collections::HashSet, sync::Arc, thread},
+};
+
+lazy_static! {
+    static ref LEDGER_EVENTS: Notifications<Arc<LedgerEvent>> = Notifications::new();
+}
+
+/// Forward the events of the ledger to all the connected clients,
+/// should be called only once.
+pub(crate) fn start_event_forwarder() {
+    let evs = events::subscribe();
+    thread::spawn(move || {
+        evs.iter().for_each(|ev| {
+            ruc::info_omit!(LEDGER_EVENTS.notify(Arc::new(ev)));
+        })
+    });
+}
+
+#[allow(missing_docs)]
+#[derive(Deserialize, Debug)]
+pub struct LedgerEventQueryParams {
+    /// only push the events related to this address
+    address: Option<String>,
+    /// comma separated kinds of events, eg.. "block,utxo_created"
+    kinds: Option<String>,
+}
+
+/// Subscribe the events of committed blocks,
+/// the connection is kept open until the client closes it,
+/// or falls behind by more than `NOTIFICATION_BUFFER` events.
+pub async fn subscribe_ledger_events(
+    web::Query(info): web::Query<LedgerEventQueryParams>,
+) -> actix_web::Result<HttpResponse> {
+    let address = info
+        .address
+        .as_deref()
+        .map(wallet::public_key_from_base64)
+        .transpose()
+        .c(d!())
+        .map_err(|e| error::ErrorBadRequest(e.to_string()))?;
+
+    let kinds = info.kinds.map(|ks| {
+        ks.split(',')
+            .map(|k| k.trim().to_owned())
+            .collect::<HashSet<_>>()
+    });
+
+    let stream = LEDGER_EVENTS
+        .notification_stream()
+        .filter(move |ev| {
+            future::ready(
+                kinds.as_ref().map_or(true, |ks| ks.contains(ev.kind()))
+                    && address.as_ref().map_or(true, |pk| ev.is_related_to(pk)),
+            )
+        })
+        .map(|ev| {
+            serde_json::to_string(&*ev)
+                .map(|ev| web::Bytes::from(format!("data: {ev}\n\n")))
+                .map_err(error::ErrorInternalServerError)
+        });
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .header("Cache-Control", "no-cache")
+        .streaming(stream))
+}
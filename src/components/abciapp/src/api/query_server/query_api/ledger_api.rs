@@ -4,23 +4,29 @@
 
 use {
     super::server::QueryServer,
+    crate::abci::server::fee_market,
     actix_web::{error, web},
-    config::abci::global_cfg::CFG,
+    config::abci::{
+        global_cfg::CFG,
+        upgrade::{activation_height, is_active, Feature},
+    },
     finutils::api::{
-        DelegationInfo, DelegatorInfo, DelegatorList, NetworkRoute, Validator,
-        ValidatorDetail, ValidatorList,
+        DelegationInfo, DelegatorInfo, DelegatorList, NetworkRoute, ProposalDetail,
+        ProposalList, Validator, ValidatorDetail, ValidatorList,
     },
     globutils::HashOf,
     ledger::{
         data_model::{
-            AssetType, AssetTypeCode, AuthenticatedUtxo, StateCommitmentData, TxnSID,
-            TxoSID, UnAuthenticatedUtxo, Utxo,
+            AssetType, AssetTypeCode, AuthenticatedUtxo, FeeInfo, StateCommitmentData,
+            TxnSID, TxoSID, UnAuthenticatedUtxo, Utxo,
         },
         staking::{
-            DelegationRwdDetail, DelegationState, Staking, TendermintAddr,
-            TendermintAddrRef,
+            proposal::ProposalId, uptime::UptimeStats, BlockHeight, DelegationRwdDetail,
+            DelegationState, Staking, TendermintAddr, TendermintAddrRef,
         },
+        store::api_cache::{DelegationSnapshot, ValidatorSnapshot},
     },
+    light_verifier::LightProof,
     parking_lot::RwLock,
     ruc::*,
     serde::{Deserialize, Serialize},
@@ -125,6 +131,63 @@ pub async fn query_utxos(
     Ok(web::Json(ledger.get_utxos(sid_list.as_slice())))
 }
 
+/// Query the proof of a transaction for light clients,
+/// it can be verified against a trusted state commitment by `light_verifier`.
+pub async fn query_txn_light_proof(
+    data: web::Data<Arc<RwLock<QueryServer>>>,
+    info: web::Path<String>,
+) -> actix_web::Result<web::Json<LightProof>> {
+    let txn_sid = info
+        .parse::<usize>()
+        .map_err(|_| error::ErrorBadRequest("Invalid txn sid encoding"))?;
+
+    let qs = data.read();
+    qs.ledger_cloned
+        .get_txn_light_proof(TxnSID(txn_sid))
+        .map(web::Json)
+        .map_err(|e| error::ErrorNotFound(e.to_string()))
+}
+
+/// Query the proofs of a list of utxos(spent or not) for light clients,
+/// the list is separated by commas, eg.. "1,2,3".
+pub async fn query_utxo_light_proof(
+    data: web::Data<Arc<RwLock<QueryServer>>>,
+    info: web::Path<String>,
+) -> actix_web::Result<web::Json<LightProof>> {
+    let sid_list = info
+        .as_ref()
+        .split(',')
+        .map(|i| i.parse::<u64>().map(TxoSID).map_err(error::ErrorBadRequest))
+        .collect::<actix_web::Result<Vec<_>, actix_web::error::Error>>()?;
+
+    if sid_list.len() > 10 || sid_list.is_empty() {
+        return Err(error::ErrorBadRequest("Invalid Query List"));
+    }
+
+    let qs = data.read();
+    qs.ledger_cloned
+        .get_utxo_light_proof(&sid_list)
+        .map(web::Json)
+        .map_err(|e| error::ErrorNotFound(e.to_string()))
+}
+
+/// Query the proofs of all the utxos owned by an address,
+/// so that light clients can verify its balance.
+pub async fn query_owned_utxo_light_proof(
+    data: web::Data<Arc<RwLock<QueryServer>>>,
+    owner: web::Path<String>,
+) -> actix_web::Result<web::Json<LightProof>> {
+    let pk = globutils::wallet::public_key_from_base64(owner.as_str())
+        .c(d!())
+        .map_err(|e| error::ErrorBadRequest(e.to_string()))?;
+
+    let qs = data.read();
+    qs.ledger_cloned
+        .get_owned_utxo_light_proof(&pk)
+        .map(web::Json)
+        .map_err(|e| error::ErrorInternalServerError(e.to_string()))
+}
+
 /// query asset according to `AssetType`
 pub async fn query_asset(
     data: web::Data<Arc<RwLock<QueryServer>>>,
@@ -249,13 +312,16 @@ pub async fn query_validators(
                             staking.delegation_has_addr(&pk),
                             &v,
                         )
+                        .with_uptime(
+                            staking.uptime_get(tendermint_addr).map(|si| si.stats()),
+                        )
                     })
             })
             .collect();
-        return Ok(web::Json(ValidatorList::new(
-            staking.cur_height(),
-            validators_list,
-        )));
+        return Ok(web::Json(
+            ValidatorList::new(staking.cur_height(), validators_list)
+                .with_threshold(staking.params().max_power_percent_per_validator),
+        ));
     };
 
     Ok(web::Json(ValidatorList::new(0, vec![])))
@@ -556,6 +622,7 @@ pub async fn query_validator_detail(
                     .ok_or_else(|| error::ErrorBadRequest("not exists"))?;
                 fra_rewards += delegation.rwd_amount;
             }
+            let uptime = staking.uptime_get(addr.as_ref()).map(|si| si.stats());
             let resp = ValidatorDetail {
                 addr: addr.into_inner(),
                 is_online: v.signed_last_block,
@@ -577,6 +644,8 @@ pub async fn query_validator_detail(
                 validator_realtime_apy,
                 kind: v.kind(),
                 delegator_cnt: v.delegators.len() as u64,
+                uptime,
+                jailed_until: v.jailed_until,
             };
             return Ok(web::Json(resp));
         }
@@ -585,6 +654,81 @@ pub async fn query_validator_detail(
     Err(error::ErrorNotFound("not exists"))
 }
 
+/// query the signing statistics of a validator in the rolling window
+pub async fn query_validator_uptime(
+    data: web::Data<Arc<RwLock<QueryServer>>>,
+    addr: web::Path<TendermintAddr>,
+) -> actix_web::Result<web::Json<UptimeStats>> {
+    let qs = data.read();
+    qs.ledger_cloned
+        .get_staking()
+        .uptime_get(addr.as_ref())
+        .map(|si| web::Json(si.stats()))
+        .ok_or_else(|| error::ErrorNotFound("not exists"))
+}
+
+#[allow(missing_docs)]
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct UpgradeInfo {
+    pub feature: Feature,
+    pub activation_height: BlockHeight,
+    /// Whether the height is scheduled on chain.
+    pub scheduled: bool,
+    pub active: bool,
+}
+
+/// query the activation heights of all the protocol features
+pub async fn query_upgrade_schedule(
+    data: web::Data<Arc<RwLock<QueryServer>>>,
+) -> actix_web::Result<web::Json<Vec<UpgradeInfo>>> {
+    let qs = data.read();
+    let ledger = &qs.ledger_cloned;
+    let schedule = ledger.get_staking().upgrade_schedule();
+    let h = ledger.get_tendermint_height();
+
+    let res = Feature::ALL
+        .iter()
+        .map(|f| UpgradeInfo {
+            feature: *f,
+            activation_height: activation_height(*f),
+            scheduled: schedule.contains_key(f),
+            active: is_active(*f, h),
+        })
+        .collect();
+
+    Ok(web::Json(res))
+}
+
+/// query the minimum and suggested fees of UTXO transactions
+pub async fn query_suggested_fee() -> web::Json<FeeInfo> {
+    web::Json(fee_market::fee_info())
+}
+
+#[allow(missing_docs)]
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct FrozenAssetInfo {
+    /// The single outputs frozen by the issuer
+    pub txos: Vec<TxoSID>,
+    /// The addresses whose outputs are all frozen by the issuer
+    pub addresses: Vec<XfrPublicKey>,
+}
+
+/// query the frozen outputs of a freezable asset according to `AssetTypeCode`
+pub async fn query_asset_frozen(
+    data: web::Data<Arc<RwLock<QueryServer>>>,
+    info: web::Path<String>,
+) -> actix_web::Result<web::Json<FrozenAssetInfo>> {
+    let code = AssetTypeCode::new_from_base64(&info)
+        .map_err(|_| error::ErrorBadRequest("Invalid asset definition encoding."))?;
+
+    let qs = data.read();
+    let ledger = &qs.ledger_cloned;
+    Ok(web::Json(FrozenAssetInfo {
+        txos: ledger.get_frozen_txos(&code),
+        addresses: ledger.get_frozen_addresses(&code),
+    }))
+}
+
 /// query delegation info according to `public_key`
 pub async fn query_delegation_info(
     data: web::Data<Arc<RwLock<QueryServer>>>,
@@ -689,6 +833,147 @@ pub async fn query_owned_utxos(
         .map(|pk| web::Json(pnk!(ledger.get_owned_utxos(&pk))))
 }
 
+/// Query all parameter proposals with their tallies,
+/// and the staking parameters in effect.
+pub async fn query_proposal_list(
+    data: web::Data<Arc<RwLock<QueryServer>>>,
+) -> actix_web::Result<web::Json<ProposalList>> {
+    let qs = data.read();
+    let staking = qs.ledger_cloned.get_staking();
+
+    let proposals = staking
+        .proposal_get_all()
+        .map(|p| {
+            let tally = staking.proposal_tally(p.id).unwrap_or_default();
+            ProposalDetail::new(p.clone(), tally)
+        })
+        .collect();
+
+    Ok(web::Json(ProposalList::new(
+        staking.cur_height(),
+        staking.params().clone(),
+        proposals,
+    )))
+}
+
+/// Query a parameter proposal with its tally according to `ProposalId`.
+pub async fn query_proposal(
+    data: web::Data<Arc<RwLock<QueryServer>>>,
+    id: web::Path<ProposalId>,
+) -> actix_web::Result<web::Json<ProposalDetail>> {
+    let qs = data.read();
+    let staking = qs.ledger_cloned.get_staking();
+
+    staking
+        .proposal_get(*id)
+        .zip(staking.proposal_tally(*id))
+        .map(|(p, tally)| web::Json(ProposalDetail::new(p.clone(), tally)))
+        .c(d!())
+        .map_err(|e| error::ErrorNotFound(e.to_string()))
+}
+
+#[allow(missing_docs)]
+#[derive(Deserialize, Debug)]
+pub struct AtHeightQueryParams {
+    address: String,
+    height: Option<BlockHeight>,
+}
+
+#[allow(missing_docs)]
+#[derive(Deserialize, Debug)]
+pub struct HeightQueryParams {
+    height: Option<BlockHeight>,
+}
+
+#[allow(missing_docs)]
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct BalanceAtHeight {
+    height: BlockHeight,
+    balance: u64,
+}
+
+#[allow(missing_docs)]
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct DelegationAtHeight {
+    height: BlockHeight,
+    #[serde(flatten)]
+    delegation: DelegationSnapshot,
+}
+
+#[allow(missing_docs)]
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ValidatorsAtHeight {
+    height: BlockHeight,
+    validators: Vec<ValidatorSnapshot>,
+}
+
+// the latest height will be used if `height` is missing,
+// heights that have not been reached are rejected
+#[inline(always)]
+fn resolve_height(
+    qs: &QueryServer,
+    height: Option<BlockHeight>,
+) -> actix_web::Result<BlockHeight> {
+    let h = qs.ledger_cloned.get_tendermint_height();
+    match height {
+        Some(req_h) if req_h > h => Err(error::ErrorBadRequest(format!(
+            "height {req_h} is above the latest height {h}"
+        ))),
+        Some(req_h) => Ok(req_h),
+        None => Ok(h),
+    }
+}
+
+/// Query the non-confidential balance of an address at the end of a block.
+pub async fn get_balance_at_height(
+    data: web::Data<Arc<RwLock<QueryServer>>>,
+    web::Query(info): web::Query<AtHeightQueryParams>,
+) -> actix_web::Result<web::Json<BalanceAtHeight>> {
+    let pk = globutils::wallet::public_key_from_base64(&info.address)
+        .c(d!())
+        .map_err(|e| error::ErrorBadRequest(e.to_string()))?;
+
+    let qs = data.read();
+    let height = resolve_height(&qs, info.height)?;
+
+    qs.ledger_cloned
+        .get_nonconfidential_balance_at_height(&pk, height)
+        .map(|balance| web::Json(BalanceAtHeight { height, balance }))
+        .ok_or_else(|| error::ErrorNotFound("no history"))
+}
+
+/// Query the delegation principal of an address at the end of a block.
+pub async fn get_delegation_at_height(
+    data: web::Data<Arc<RwLock<QueryServer>>>,
+    web::Query(info): web::Query<AtHeightQueryParams>,
+) -> actix_web::Result<web::Json<DelegationAtHeight>> {
+    let pk = globutils::wallet::public_key_from_base64(&info.address)
+        .c(d!())
+        .map_err(|e| error::ErrorBadRequest(e.to_string()))?;
+
+    let qs = data.read();
+    let height = resolve_height(&qs, info.height)?;
+
+    qs.ledger_cloned
+        .get_delegation_at_height(&pk, height)
+        .map(|delegation| web::Json(DelegationAtHeight { height, delegation }))
+        .ok_or_else(|| error::ErrorNotFound("not exists"))
+}
+
+/// Query the validators and their vote powers at the end of a block.
+pub async fn get_validators_at_height(
+    data: web::Data<Arc<RwLock<QueryServer>>>,
+    web::Query(info): web::Query<HeightQueryParams>,
+) -> actix_web::Result<web::Json<ValidatorsAtHeight>> {
+    let qs = data.read();
+    let height = resolve_height(&qs, info.height)?;
+
+    qs.ledger_cloned
+        .get_validators_at_height(height)
+        .map(|validators| web::Json(ValidatorsAtHeight { height, validators }))
+        .ok_or_else(|| error::ErrorNotFound("no history"))
+}
+
 #[allow(missing_docs)]
 pub enum ApiRoutes {
     UtxoSid,
@@ -705,6 +990,15 @@ pub enum ApiRoutes {
     DelegationInfo,
     DelegatorList,
     ValidatorDetail,
+    ProposalList,
+    Proposal,
+    TxnLightProof,
+    UtxoLightProof,
+    OwnedUtxoLightProof,
+    ValidatorUptime,
+    UpgradeSchedule,
+    SuggestedFee,
+    AssetFrozen,
 }
 
 impl NetworkRoute for ApiRoutes {
@@ -724,6 +1018,15 @@ impl NetworkRoute for ApiRoutes {
             ApiRoutes::DelegationInfo => "delegation_info",
             ApiRoutes::DelegatorList => "delegator_list",
             ApiRoutes::ValidatorDetail => "validator_detail",
+            ApiRoutes::ProposalList => "proposal_list",
+            ApiRoutes::Proposal => "proposal",
+            ApiRoutes::TxnLightProof => "txn_light_proof",
+            ApiRoutes::UtxoLightProof => "utxo_light_proof",
+            ApiRoutes::OwnedUtxoLightProof => "owned_utxo_light_proof",
+            ApiRoutes::ValidatorUptime => "validator_uptime",
+            ApiRoutes::UpgradeSchedule => "upgrade_schedule",
+            ApiRoutes::SuggestedFee => "suggested_fee",
+            ApiRoutes::AssetFrozen => "asset_frozen",
         };
         "/".to_owned() + endpoint
     }
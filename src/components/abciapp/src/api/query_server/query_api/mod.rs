@@ -3,6 +3,7 @@
 //!
 
 // pub it for doc
+pub mod event_api;
 pub mod ledger_api;
 
 pub mod server;
@@ -12,6 +13,7 @@ use {
     actix_cors::Cors,
     actix_web::{error, middleware, web, App, HttpServer},
     config::abci::{global_cfg::CFG, CheckPointConfig},
+    event_api::subscribe_ledger_events,
     finutils::api::NetworkRoute,
     globutils::wallet,
     ledger::{
@@ -20,8 +22,10 @@ use {
             TxnIDHash, TxnSID, TxoSID, XfrAddress, BLACK_HOLE_PUBKEY,
         },
         staking::{
-            ops::mint_fra::MintEntry, FF_PK_EXTRA_120_0000, FRA, FRA_TOTAL_AMOUNT,
+            ops::mint_fra::MintEntry, BlockHeight, FF_PK_EXTRA_120_0000, FRA,
+            FRA_TOTAL_AMOUNT,
         },
+        store::api_cache::{TxnDirection, TxnHistFilter, TxnHistPage},
     },
     ledger_api::*,
     parking_lot::RwLock,
@@ -400,6 +404,68 @@ pub async fn get_related_txns(
     Ok(web::Json(records.unwrap_or_default()))
 }
 
+/// The default number of transactions in one page of `TxnHistQueryParams`.
+const TXN_HIST_PAGE_SIZE: usize = 20;
+
+/// The max number of transactions in one page of `TxnHistQueryParams`.
+const TXN_HIST_PAGE_SIZE_MAX: usize = 100;
+
+#[allow(missing_docs)]
+#[derive(Debug, Deserialize)]
+pub struct TxnHistQueryParams {
+    address: String,
+    cursor: Option<usize>,
+    limit: Option<usize>,
+    order: Option<OrderOption>,
+    kind: Option<String>,
+    asset: Option<String>,
+    direction: Option<TxnDirection>,
+    from_height: Option<BlockHeight>,
+    to_height: Option<BlockHeight>,
+}
+
+/// Returns one page of the transactions related to a given ledger address,
+/// the newest transactions come first by default.
+pub async fn get_txn_history(
+    data: web::Data<Arc<RwLock<QueryServer>>>,
+    web::Query(info): web::Query<TxnHistQueryParams>,
+) -> actix_web::Result<web::Json<TxnHistPage>> {
+    // Convert from base64 representation
+    let key: XfrPublicKey = wallet::public_key_from_base64(&info.address)
+        .c(d!())
+        .map_err(|e| error::ErrorBadRequest(e.to_string()))?;
+
+    let asset = info
+        .asset
+        .as_deref()
+        .map(AssetTypeCode::new_from_base64)
+        .transpose()
+        .c(d!())
+        .map_err(|e| error::ErrorBadRequest(e.to_string()))?;
+
+    let filter = TxnHistFilter {
+        kind: info.kind,
+        asset,
+        direction: info.direction,
+        from_height: info.from_height,
+        to_height: info.to_height,
+    };
+
+    let limit = info
+        .limit
+        .unwrap_or(TXN_HIST_PAGE_SIZE)
+        .clamp(1, TXN_HIST_PAGE_SIZE_MAX);
+
+    let server = data.read();
+    Ok(web::Json(server.get_related_transactions_page(
+        &XfrAddress { key },
+        &filter,
+        info.cursor.map(TxnSID),
+        limit,
+        info.order != Some(OrderOption::Asc),
+    )))
+}
+
 /// Returns the list of transfer transations associated with a given asset
 pub async fn get_related_xfrs(
     data: web::Data<Arc<RwLock<QueryServer>>>,
@@ -538,6 +604,13 @@ impl QueryApi {
                 .service(
                     web::resource("claim_history").route(web::get().to(get_claim_txns)),
                 )
+                .service(
+                    web::resource("txn_history").route(web::get().to(get_txn_history)),
+                )
+                .service(
+                    web::resource("ledger_events")
+                        .route(web::get().to(subscribe_ledger_events)),
+                )
                 .service(
                     web::resource("coinbase_history")
                         .route(web::get().to(get_coinbase_oper_list)),
@@ -616,6 +689,18 @@ impl QueryApi {
                     &ApiRoutes::OwnedUtxos.with_arg_template("owner"),
                     web::get().to(query_owned_utxos),
                 )
+                .route(
+                    &ApiRoutes::TxnLightProof.with_arg_template("sid"),
+                    web::get().to(query_txn_light_proof),
+                )
+                .route(
+                    &ApiRoutes::UtxoLightProof.with_arg_template("sid_list"),
+                    web::get().to(query_utxo_light_proof),
+                )
+                .route(
+                    &ApiRoutes::OwnedUtxoLightProof.with_arg_template("owner"),
+                    web::get().to(query_owned_utxo_light_proof),
+                )
                 .route(
                     &ApiRoutes::ValidatorList.route(),
                     web::get().to(query_validators),
@@ -640,10 +725,46 @@ impl QueryApi {
                     web::resource("/validator_delegation")
                         .route(web::get().to(get_validator_delegation_history)),
                 )
+                .service(
+                    web::resource("/balance_at_height")
+                        .route(web::get().to(get_balance_at_height)),
+                )
+                .service(
+                    web::resource("/delegation_at_height")
+                        .route(web::get().to(get_delegation_at_height)),
+                )
+                .service(
+                    web::resource("/validators_at_height")
+                        .route(web::get().to(get_validators_at_height)),
+                )
                 .route(
                     &ApiRoutes::ValidatorDetail.with_arg_template("NodeAddress"),
                     web::get().to(query_validator_detail),
                 )
+                .route(
+                    &ApiRoutes::ValidatorUptime.with_arg_template("NodeAddress"),
+                    web::get().to(query_validator_uptime),
+                )
+                .route(
+                    &ApiRoutes::ProposalList.route(),
+                    web::get().to(query_proposal_list),
+                )
+                .route(
+                    &ApiRoutes::Proposal.with_arg_template("id"),
+                    web::get().to(query_proposal),
+                )
+                .route(
+                    &ApiRoutes::UpgradeSchedule.route(),
+                    web::get().to(query_upgrade_schedule),
+                )
+                .route(
+                    &ApiRoutes::SuggestedFee.route(),
+                    web::get().to(query_suggested_fee),
+                )
+                .route(
+                    &ApiRoutes::AssetFrozen.with_arg_template("code"),
+                    web::get().to(query_asset_frozen),
+                )
                 .service(
                     web::resource("/display_checkpoint")
                         .route(web::get().to(get_checkpoint)),
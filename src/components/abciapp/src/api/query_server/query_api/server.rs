@@ -10,7 +10,10 @@ use {
             TxnIDHash, TxnSID, TxoSID, XfrAddress,
         },
         staking::{ops::mint_fra::MintEntry, BlockHeight},
-        store::LedgerState,
+        store::{
+            api_cache::{TxnHistFilter, TxnHistPage},
+            LedgerState,
+        },
     },
     parking_lot::{Condvar, Mutex, RwLock},
     ruc::*,
@@ -223,6 +226,24 @@ impl QueryServer {
             .map(|d| d.iter().map(|(k, _)| k).collect())
     }
 
+    /// Returns one page of the transactions related to a given ledger address,
+    /// see `get_related_transactions` for the relationship.
+    #[inline(always)]
+    pub fn get_related_transactions_page(
+        &self,
+        address: &XfrAddress,
+        filter: &TxnHistFilter,
+        cursor: Option<TxnSID>,
+        limit: usize,
+        order_desc: bool,
+    ) -> TxnHistPage {
+        self.ledger_cloned
+            .api_cache
+            .as_ref()
+            .unwrap()
+            .get_related_txns_page(address, filter, cursor, limit, order_desc)
+    }
+
     /// Returns the set of transfer transactions that are associated with a given asset.
     /// The asset type must be nonconfidential.
     #[inline(always)]
@@ -4,6 +4,7 @@
 
 use {
     super::{
+        event_api::start_event_forwarder,
         server::{QueryServer, BLOCK_CREATED},
         QueryApi,
     },
@@ -22,6 +23,7 @@ pub(crate) fn start_query_server(
     let qs2 = Arc::clone(&qs);
 
     QueryApi::create(qs1, addrs).c(d!()).map(|_| {
+        start_event_forwarder();
         thread::spawn(move || loop {
             let mut created = BLOCK_CREATED.0.lock();
             if !*created {
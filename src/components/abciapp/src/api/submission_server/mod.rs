@@ -8,7 +8,8 @@ use {
     fp_utils::tx::EVM_TX_TAG,
     ledger::{
         data_model::{BlockEffect, Transaction, TxnEffect, TxnSID, TxnTempSID, TxoSID},
-        store::LedgerState,
+        error::ErrorDetail,
+        store::{simulate::TxnSimulation, LedgerState},
     },
     parking_lot::RwLock,
     rand_core::{CryptoRng, RngCore},
@@ -38,6 +39,8 @@ impl fmt::Display for TxnHandle {
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 #[allow(missing_docs)]
 pub enum TxnStatus {
+    /// The typed code of the reason is kept by the server separately,
+    /// see `SubmissionServer::get_txn_error_detail`.
     Rejected(String),
     Committed((TxnSID, Vec<TxoSID>)),
     Pending,
@@ -71,6 +74,7 @@ where
     block: Option<BlockEffect>,
     pending_txns: Vec<(TxnTempSID, TxnHandle, Transaction)>,
     txn_status: HashMap<TxnHandle, TxnStatus>,
+    txn_error_details: HashMap<TxnHandle, ErrorDetail>,
     block_capacity: usize,
     prng: RNG,
     commit_mode: CommitMode,
@@ -93,6 +97,7 @@ where
             committed_state: ledger_state,
             block: None,
             txn_status: HashMap::new(),
+            txn_error_details: HashMap::new(),
             pending_txns: vec![],
             prng,
             block_capacity,
@@ -111,6 +116,7 @@ where
             committed_state: ledger_state,
             block: None,
             txn_status: HashMap::new(),
+            txn_error_details: HashMap::new(),
             pending_txns: vec![],
             prng,
             block_capacity: 0,
@@ -124,6 +130,11 @@ where
         self.txn_status.get(txn_handle).cloned()
     }
 
+    /// The typed reason of a rejected transaction
+    pub fn get_txn_error_detail(&self, txn_handle: &TxnHandle) -> Option<ErrorDetail> {
+        self.txn_error_details.get(txn_handle).cloned()
+    }
+
     /// Determine if block is empty
     pub fn all_commited(&self) -> bool {
         self.block.is_none()
@@ -229,13 +240,23 @@ where
                 Ok(handle)
             }
             Err(e) => {
+                let detail = ErrorDetail::new(e.to_string());
                 self.txn_status
-                    .insert(handle, TxnStatus::Rejected(e.to_string()));
+                    .insert(handle.clone(), TxnStatus::Rejected(e.to_string()));
+                self.txn_error_details.insert(handle, detail);
                 Err(e)
             }
         }
     }
 
+    /// Check a transaction against the committed state and the block in progress,
+    /// nothing will be changed.
+    pub fn simulate_transaction(&self, txn: Transaction) -> TxnSimulation {
+        self.committed_state
+            .read()
+            .simulate_transaction(self.block.as_ref(), txn)
+    }
+
     /// Handle the whole process when there's a new transaction
     pub fn handle_transaction(&mut self, txn: Transaction) -> Result<TxnHandle> {
         let txn_handle = TxnHandle::new(&txn);
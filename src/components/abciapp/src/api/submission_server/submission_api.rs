@@ -6,8 +6,11 @@ use {
     super::{SubmissionServer, TxnForward, TxnHandle},
     actix_cors::Cors,
     actix_web::{error, middleware, web, App, HttpServer},
+    config::abci::global_cfg::CFG,
     finutils::api::NetworkRoute,
-    ledger::data_model::Transaction,
+    ledger::{
+        data_model::Transaction, error::ErrorDetail, store::simulate::TxnSimulation,
+    },
     parking_lot::RwLock,
     rand_core::{CryptoRng, RngCore},
     ruc::*,
@@ -53,6 +56,21 @@ where
         })
 }
 
+/// Checks a transaction without submitting it,
+/// returns the report of validity, errors, outputs and fee.
+///
+/// Only served with `--enable-tx-simulation`.
+pub async fn simulate_transaction<RNG, TF>(
+    data: web::Data<Arc<RwLock<SubmissionServer<RNG, TF>>>>,
+    body: web::Json<Transaction>,
+) -> web::Json<TxnSimulation>
+where
+    RNG: RngCore + CryptoRng,
+    TF: TxnForward + Sync + Send,
+{
+    web::Json(data.read().simulate_transaction(body.into_inner()))
+}
+
 /// Queries the status of a transaction by its handle. Returns either a not committed message or a
 /// serialized TxnStatus.
 pub async fn txn_status<RNG, TF>(
@@ -77,6 +95,26 @@ where
     Ok(res)
 }
 
+/// Queries the typed reason of a rejected transaction by its handle.
+pub async fn txn_error_detail<RNG, TF>(
+    data: web::Data<Arc<RwLock<SubmissionServer<RNG, TF>>>>,
+    info: web::Path<String>,
+) -> StdResult<web::Json<ErrorDetail>, actix_web::error::Error>
+where
+    RNG: RngCore + CryptoRng,
+    TF: TxnForward + Sync + Send,
+{
+    data.read()
+        .get_txn_error_detail(&TxnHandle(info.clone()))
+        .map(web::Json)
+        .ok_or_else(|| {
+            error::ErrorNotFound(format!(
+                "No rejected transaction with handle {} found.",
+                &info
+            ))
+        })
+}
+
 /// Structures exposed to the outside world
 pub struct SubmissionApi;
 
@@ -84,7 +122,9 @@ pub struct SubmissionApi;
 #[allow(missing_docs)]
 pub enum SubmissionRoutes {
     SubmitTransaction,
+    SimulateTransaction,
     TxnStatus,
+    TxnErrorDetail,
     Ping,
     Version,
 }
@@ -93,7 +133,9 @@ impl NetworkRoute for SubmissionRoutes {
     fn route(&self) -> String {
         let endpoint = match *self {
             SubmissionRoutes::SubmitTransaction => "submit_transaction",
+            SubmissionRoutes::SimulateTransaction => "simulate_transaction",
             SubmissionRoutes::TxnStatus => "txn_status",
+            SubmissionRoutes::TxnErrorDetail => "txn_error_detail",
             SubmissionRoutes::Ping => "ping",
             SubmissionRoutes::Version => "version",
         };
@@ -114,7 +156,7 @@ impl SubmissionApi {
         let _ = actix_rt::System::new("findora API");
 
         HttpServer::new(move || {
-            App::new()
+            let app = App::new()
                 .wrap(middleware::Logger::default())
                 .wrap(Cors::permissive().supports_credentials())
                 .data(web::JsonConfig::default().limit(2048 * 1024))
@@ -122,13 +164,28 @@ impl SubmissionApi {
                 .route(
                     &SubmissionRoutes::SubmitTransaction.route(),
                     web::post().to(submit_transaction::<RNG, TF>),
+                );
+
+            // every simulation runs the checks of a whole transaction
+            let app = if CFG.enable_tx_simulation {
+                app.route(
+                    &SubmissionRoutes::SimulateTransaction.route(),
+                    web::post().to(simulate_transaction::<RNG, TF>),
                 )
-                .route(&SubmissionRoutes::Ping.route(), web::get().to(ping))
+            } else {
+                app
+            };
+
+            app.route(&SubmissionRoutes::Ping.route(), web::get().to(ping))
                 .route(&SubmissionRoutes::Version.route(), web::get().to(version))
                 .route(
                     &SubmissionRoutes::TxnStatus.with_arg_template("handle"),
                     web::get().to(txn_status::<RNG, TF>),
                 )
+                .route(
+                    &SubmissionRoutes::TxnErrorDetail.with_arg_template("handle"),
+                    web::get().to(txn_error_detail::<RNG, TF>),
+                )
         })
         .bind(&format!("{host}:{port}"))
         .c(d!())?
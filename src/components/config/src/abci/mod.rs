@@ -4,6 +4,7 @@ use {
     ruc::*,
     serde::{Deserialize, Serialize},
     std::{
+        collections::BTreeMap,
         convert::TryFrom,
         env,
         fs::{self, File},
@@ -13,6 +14,8 @@ use {
     toml,
 };
 
+pub mod upgrade;
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[allow(missing_docs)]
 pub struct CheckPointConfig {
@@ -114,6 +117,16 @@ pub struct CheckPointConfig {
 
     #[serde(default = "def_fix_check_replay")]
     pub fix_check_replay: u64,
+
+    // feature name => activation height, of the features declared
+    // without a height in `upgrade::features!`.
+    #[serde(default)]
+    pub upgrade_heights: BTreeMap<String, u64>,
+
+    // base64 asset code => the max amount bridged in a block, either way,
+    // the assets not listed are not limited, the tables are kept the last ones.
+    #[serde(default)]
+    pub bridge_asset_limits: BTreeMap<String, u64>,
 }
 
 fn def_fix_check_replay() -> u64 {
@@ -175,7 +188,9 @@ lazy_static! {
         prismxx_inital_height: 128,
         prism_bridge_address: "0x5f9552fEd754F20B636C996DaDB32806554Bb995".to_owned(),
         remove_fake_staking_hash: 0,
-        fix_check_replay: 0
+        fix_check_replay: 0,
+        upgrade_heights: BTreeMap::new(),
+        bridge_asset_limits: BTreeMap::new()
     };
 }
 
@@ -210,7 +225,9 @@ lazy_static! {
         prismxx_inital_height: 4033522,
         prism_bridge_address: "0x4672372fDB139B7295Fc59b55b43EC5fF2761A0b".to_owned(),
         remove_fake_staking_hash: 4033522,
-        fix_check_replay: 4033522
+        fix_check_replay: 4033522,
+        upgrade_heights: BTreeMap::new(),
+        bridge_asset_limits: BTreeMap::new()
     };
 }
 
@@ -243,6 +260,13 @@ impl CheckPointConfig {
         let config: CheckPointConfig = toml::from_str(content.as_str())
             .or_else(|_| serde_json::from_str(content.as_str()))
             .unwrap();
+        if let Some(name) = config
+            .upgrade_heights
+            .keys()
+            .find(|name| name.parse::<upgrade::Feature>().is_err())
+        {
+            panic!("unknown feature in upgrade_heights: {name}");
+        }
         Some(config)
     }
 }
@@ -368,13 +392,24 @@ pub mod global_cfg {
         pub submission_service_port: u16,
         pub ledger_service_port: u16,
         pub enable_query_service: bool,
+        pub enable_tx_simulation: bool,
         pub disable_eth_empty_blocks: bool,
         pub enable_eth_api_service: bool,
+        pub enable_eth_debug_api: bool,
         pub evm_http_port: u16,
         pub evm_ws_port: u16,
         pub tendermint_node_self_addr: Option<String>,
         pub tendermint_node_key_config_path: Option<String>,
         pub ledger_dir: String,
+        pub ledger_snapshot_export: Option<String>,
+        pub ledger_snapshot_import: Option<String>,
+        pub ledger_snapshot_commitment: Option<String>,
+        pub ledger_snapshot_insecure: bool,
+        pub state_sync_snapshot_itv: u64,
+        pub state_sync_snapshot_keep: usize,
+        pub state_sync_restore: bool,
+        pub min_fee_target_txs: u64,
+        pub min_fee_window: usize,
         #[cfg(target_os = "linux")]
         pub btmcfg: BtmCfg,
         pub checkpoint: CheckPointConfig,
@@ -403,14 +438,25 @@ pub mod global_cfg {
             .arg_from_usage("--submission-service-port=[Submission Service Port]")
             .arg_from_usage("--ledger-service-port=[Ledger Service Port]")
             .arg_from_usage("-q, --enable-query-service")
+            .arg_from_usage("--enable-tx-simulation 'serve `/simulate_transaction` in the submission service, disabled by default'")
             .arg_from_usage("--disable-eth-empty-blocks 'not generate empty ethereum blocks when no evm transaction'")
             .arg_from_usage("--enable-eth-api-service")
+            .arg_from_usage("--enable-eth-debug-api 'serve the `debug_*` methods in the web3 service, which replay transactions and are costly, disabled by default'")
             .arg_from_usage("--evm-http-port=[EVM Web3 Http Port]")
             .arg_from_usage("--evm-ws-port=[EVM Web3 WS Port]")
             .arg_from_usage("--tendermint-node-self-addr=[Address] 'the address of your tendermint node, in upper-hex format'")
             .arg_from_usage("--tendermint-node-key-config-path=[Path] 'such as: ${HOME}/.tendermint/config/priv_validator_key.json'")
             .arg_from_usage("-d, --ledger-dir=[Path]")
             .arg_from_usage("--checkpoint-file=[Path]")
+            .arg_from_usage("--export-ledger-snapshot=[Path] 'export all the data in the ledger dir to a new archive, then exit'")
+            .arg_from_usage("--import-ledger-snapshot=[Path] 'import an archive into the empty ledger dir before starting'")
+            .arg_from_usage("--state-sync-snapshot-itv=[Interval] 'interval between adjacent state-sync snapshots, 0(default) to disable'")
+            .arg_from_usage("--state-sync-snapshot-keep=[Capacity] 'the number of recent state-sync snapshots to keep, default to 2'")
+            .arg_from_usage("--state-sync-restore 'bootstrap an empty node from the snapshots offered by tendermint state sync'")
+            .arg_from_usage("--min-fee-target-txs=[Count] 'the expected number of utxo transactions in a block, the minimum fee of the mempool rises when recent blocks are fuller, 0(default) to disable'")
+            .arg_from_usage("--min-fee-window=[Blocks] 'the number of recent blocks used to measure the fullness, default to 20'")
+            .arg_from_usage("--ledger-snapshot-commitment=[JSON] 'the state commitment of the imported height from a trusted node, as returned by `global_state_version`, required by `--import-ledger-snapshot`, it only covers part of the ledger, the archive must also come from a trusted source'")
+            .arg_from_usage("--ledger-snapshot-insecure 'import a ledger snapshot without a trusted state commitment, only the archive itself is verified'")
             .arg_from_usage("--enable-snapshot 'global switch for enabling snapshot functions'")
             .arg_from_usage("--snapshot-list 'list all available snapshots in the form of block height'")
             .arg_from_usage("--snapshot-target=[TargetPath] 'a data volume containing both ledger data and tendermint data'")
@@ -502,6 +548,8 @@ pub mod global_cfg {
             .c(d!())?;
         let eqs = m.is_present("enable-query-service")
             || env::var("ENABLE_QUERY_SERVICE").is_ok();
+        let ets = m.is_present("enable-tx-simulation")
+            || env::var("ENABLE_TX_SIMULATION").is_ok();
         let tnsa = m
             .value_of("tendermint-node-self-addr")
             .map(|v| v.to_owned())
@@ -522,6 +570,8 @@ pub mod global_cfg {
             || env::var("DISABLE_ETH_EMPTY_BLOCKS").is_ok();
         let eas = m.is_present("enable-eth-api-service")
             || env::var("ENABLE_ETH_API_SERVICE").is_ok();
+        let eda = m.is_present("enable-eth-debug-api")
+            || env::var("ENABLE_ETH_DEBUG_API").is_ok();
         let ehp = m
             .value_of("evm-http-port")
             .map(|v| v.to_owned())
@@ -536,6 +586,42 @@ pub mod global_cfg {
             .unwrap_or_else(|| "8546".to_owned())
             .parse::<u16>()
             .c(d!())?;
+        let lse = m.value_of("export-ledger-snapshot").map(|v| v.to_owned());
+        let lsi = m.value_of("import-ledger-snapshot").map(|v| v.to_owned());
+        let lsc = m
+            .value_of("ledger-snapshot-commitment")
+            .map(|v| v.to_owned());
+        let lsis = m.is_present("ledger-snapshot-insecure");
+        let sssi = m
+            .value_of("state-sync-snapshot-itv")
+            .map(|v| v.to_owned())
+            .or_else(|| env::var("STATE_SYNC_SNAPSHOT_ITV").ok())
+            .unwrap_or_else(|| "0".to_owned())
+            .parse::<u64>()
+            .c(d!())?;
+        let sssk = m
+            .value_of("state-sync-snapshot-keep")
+            .map(|v| v.to_owned())
+            .or_else(|| env::var("STATE_SYNC_SNAPSHOT_KEEP").ok())
+            .unwrap_or_else(|| "2".to_owned())
+            .parse::<usize>()
+            .c(d!())?;
+        let ssr =
+            m.is_present("state-sync-restore") || env::var("STATE_SYNC_RESTORE").is_ok();
+        let mftt = m
+            .value_of("min-fee-target-txs")
+            .map(|v| v.to_owned())
+            .or_else(|| env::var("MIN_FEE_TARGET_TXS").ok())
+            .unwrap_or_else(|| "0".to_owned())
+            .parse::<u64>()
+            .c(d!())?;
+        let mfw = m
+            .value_of("min-fee-window")
+            .map(|v| v.to_owned())
+            .or_else(|| env::var("MIN_FEE_WINDOW").ok())
+            .unwrap_or_else(|| "20".to_owned())
+            .parse::<usize>()
+            .c(d!())?;
         let checkpoint_path = m
             .value_of("checkpoint-file")
             .map(|v| v.to_owned())
@@ -551,13 +637,24 @@ pub mod global_cfg {
             submission_service_port: ssp,
             ledger_service_port: lsp,
             enable_query_service: eqs,
+            enable_tx_simulation: ets,
             disable_eth_empty_blocks: eeb,
             enable_eth_api_service: eas,
+            enable_eth_debug_api: eda,
             evm_http_port: ehp,
             evm_ws_port: ewp,
             tendermint_node_self_addr: tnsa,
             tendermint_node_key_config_path: tnkcp,
             ledger_dir: ld,
+            ledger_snapshot_export: lse,
+            ledger_snapshot_import: lsi,
+            ledger_snapshot_commitment: lsc,
+            ledger_snapshot_insecure: lsis,
+            state_sync_snapshot_itv: sssi,
+            state_sync_snapshot_keep: sssk,
+            state_sync_restore: ssr,
+            min_fee_target_txs: mftt,
+            min_fee_window: mfw,
             #[cfg(target_os = "linux")]
             btmcfg: parse_btmcfg(&m).c(d!())?,
             checkpoint: CheckPointConfig::from_file(&checkpoint_path).unwrap(),
@@ -0,0 +1,224 @@
+//!
+//! # Protocol Upgrades
+//!
+//! Every protocol change that takes effect at some block height is
+//! declared once in the `features!` registry below, its default activation
+//! height comes from the `CheckPointConfig`, and can be overridden by an
+//! upgrade schedule voted on chain by the validators.
+//!
+//! The features declared without a height are named nowhere else, their
+//! heights are configured by name in `CheckPointConfig::upgrade_heights`,
+//! and they are active by default only in the `debug_env` builds.
+//!
+//! The ledger keeps the on-chain schedule, and syncs it here after each
+//! commit, so any module can call `is_active(feature, height)`.
+//!
+
+use {
+    super::global_cfg::CFG,
+    lazy_static::lazy_static,
+    ruc::*,
+    serde::{Deserialize, Serialize},
+    std::{collections::BTreeMap, fmt, str::FromStr, sync::RwLock},
+};
+
+/// Activation heights of the features scheduled on chain.
+pub type UpgradeSchedule = BTreeMap<Feature, u64>;
+
+lazy_static! {
+    static ref SCHEDULE: RwLock<UpgradeSchedule> = RwLock::new(BTreeMap::new());
+    static ref CONFIGURED: BTreeMap<Feature, u64> = CFG
+        .checkpoint
+        .upgrade_heights
+        .iter()
+        .filter_map(|(name, h)| name.parse().ok().map(|f| (f, *h)))
+        .collect();
+}
+
+macro_rules! features {
+    (@height $feature: expr) => { configured($feature) };
+    (@height $feature: expr, $height: expr) => { $height };
+    ($($(#[$attr:meta])* $feature: ident $(=> $height: expr)?),+ $(,)?) => {
+        /// All the height-activated protocol changes.
+        #[derive(
+            Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize,
+        )]
+        #[serde(rename_all = "snake_case")]
+        pub enum Feature {
+            $($(#[$attr])* $feature,)+
+        }
+
+        impl Feature {
+            /// All the declared features.
+            pub const ALL: &'static [Feature] = &[$(Feature::$feature,)+];
+
+            /// The first height at which the feature is active,
+            /// according to the local `CheckPointConfig`.
+            pub fn default_height(self) -> u64 {
+                match self {
+                    $(Feature::$feature => features!(@height self $(, $height)?),)+
+                }
+            }
+        }
+    };
+}
+
+features! {
+    /// Enable evm substate.
+    EvmSubstate => at(CFG.checkpoint.evm_substate_height),
+    /// Enable evm substate v2.
+    EvmSubstateV2 => at(CFG.checkpoint.evm_substate_v2_height),
+    /// Disable evm, until `EnableFrc20` is active.
+    DisableEvm => after(CFG.checkpoint.disable_evm_block_height),
+    /// Enable evm again along with FRC20.
+    EnableFrc20 => at(CFG.checkpoint.enable_frc20_height),
+    /// Revert evm transactions on errors.
+    TxRevertOnError => at(CFG.checkpoint.tx_revert_on_error_height),
+    /// The first block that may contain evm transactions.
+    EvmFirstBlock => at(CFG.checkpoint.evm_first_block_height),
+    /// Fix a BUG in the calculation of commission.
+    ZeroAmountFix => after(CFG.checkpoint.zero_amount_fix_height),
+    /// Fix the calculation of APY.
+    ApyFix => after(CFG.checkpoint.apy_fix_height),
+    /// Fix delegation rewards overflow using bigint.
+    OverflowFix => after(CFG.checkpoint.overflow_fix_height),
+    /// Sync APY v7 upgrade block height.
+    SecondFix => after(CFG.checkpoint.second_fix_height),
+    /// Change APY modifier based on v7 rate.
+    ApyV7Upgrade => after(CFG.checkpoint.apy_v7_upgrade_height),
+    /// Add an extra `reserved` address.
+    FfAddrExtraFix => after(CFG.checkpoint.ff_addr_extra_fix_height),
+    /// Fix incorrect calculations about nonconfidential balances.
+    NonconfidentialBalanceFix => after(CFG.checkpoint.nonconfidential_balance_fix_height),
+    /// Fix unpaid delegation.
+    FixUnpaidDelegation => at(CFG.checkpoint.fix_unpaid_delegation_height),
+    /// Fix missing rewards within 21 days after undelegation.
+    FixUndelegationMissingReward => after(CFG.checkpoint.fix_undelegation_missing_reward_height),
+    /// Increment nonce for CheckTx without executing evm transactions.
+    EvmCheckTxNonce => at(CFG.checkpoint.evm_checktx_nonce),
+    /// Check utxo transactions before delivering them.
+    UtxoCheckTx => after(CFG.checkpoint.utxo_checktx_height),
+    /// Check utxo signatures by the `pubkey_sign_map`,
+    /// it shares the config with `UtxoCheckTx`, but is active one block earlier.
+    UtxoSignatureMap => at(CFG.checkpoint.utxo_checktx_height),
+    /// Fix the amount of delegators when punishing validators.
+    FixDelegatorsAm => after(CFG.checkpoint.fix_delegators_am_height),
+    /// The v2 limits of the validator set.
+    ValidatorsLimitV2 => at(CFG.checkpoint.validators_limit_v2_height),
+    /// Disable delegating calls of the FRC20 precompile.
+    DisableDelegateFrc20 => after(CFG.checkpoint.disable_delegate_frc20),
+    /// Return an error code when converting accounts fails.
+    FixExecCode => after(CFG.checkpoint.fix_exec_code),
+    /// Reject transactions with duplicated signatures.
+    CheckSignaturesNum => after(CFG.checkpoint.check_signatures_num),
+    /// Fix the missing receipts of reverted evm transactions.
+    FixDeliverTxRevertNonce => at(CFG.checkpoint.fix_deliver_tx_revert_nonce_height),
+    /// Prefix the asset codes of utxo transactions.
+    UtxoAssetPrefix => at(CFG.checkpoint.utxo_asset_prefix_height),
+    /// Mint utxo assets from the prism bridge.
+    PrismMint => after(CFG.checkpoint.prismxx_inital_height),
+    /// Remove staking from the state commitment.
+    RemoveFakeStakingHash => at(CFG.checkpoint.remove_fake_staking_hash),
+    /// Check replayed transactions in the same block.
+    FixCheckReplay => after(CFG.checkpoint.fix_check_replay),
+    /// Punish offline validators according to their uptime.
+    UptimePenalty,
+    /// Jail the validators punished by rules with a jail period.
+    ValidatorJail,
+    /// Return typed error codes of rejected utxo transactions in DeliverTx.
+    TypedErrorCode,
+    /// Allow the outputs locked until a height or a time.
+    TxoLock,
+    /// Allow the issuers to freeze and reclaim the outputs of custom assets.
+    AssetFreeze,
+    /// Allow the evm accounts to delegate through the staking precompile.
+    EvmStaking,
+    /// Accept the EIP-2718 typed evm transactions, with a base fee per block.
+    TypedTransaction,
+    /// Allow confidential outputs and per-asset limits across the utxo and evm bridge.
+    ConfidentialBridge,
+    /// Log the conversions between the utxos and the evm accounts in the evm receipts.
+    UtxoBridgeLogs,
+    /// Allow the validators to update the byzantine penalty rules on chain.
+    PenaltyRuleUpdate,
+    /// Allow the validators to change the staking parameters by proposals.
+    StakingProposal,
+    /// Allow the validators to schedule the activation of the features on chain.
+    ScheduleUpgrade,
+}
+
+/// The default height of the features declared without one.
+#[cfg(feature = "debug_env")]
+const DEFAULT_HEIGHT: u64 = 0;
+#[cfg(not(feature = "debug_env"))]
+const DEFAULT_HEIGHT: u64 = u64::MAX;
+
+// configured by the name of the feature
+#[inline(always)]
+fn configured(feature: Feature) -> u64 {
+    CONFIGURED.get(&feature).copied().unwrap_or(DEFAULT_HEIGHT)
+}
+
+// active since `h`
+#[inline(always)]
+fn at(h: impl Into<i128>) -> u64 {
+    h.into().clamp(0, u64::MAX as i128) as u64
+}
+
+// active since `h + 1`
+#[inline(always)]
+fn after(h: impl Into<i128>) -> u64 {
+    at(h.into() + 1)
+}
+
+impl Feature {
+    /// The name used in configurations and queries.
+    #[inline(always)]
+    pub fn name(self) -> String {
+        pnk!(serde_json::to_value(self))
+            .as_str()
+            .unwrap_or_default()
+            .to_owned()
+    }
+}
+
+impl fmt::Display for Feature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Feature {
+    type Err = Box<dyn RucError>;
+
+    fn from_str(s: &str) -> Result<Self> {
+        serde_json::from_value(serde_json::Value::String(s.to_owned()))
+            .c(d!(format!("unknown feature: {s}")))
+    }
+}
+
+/// The first height at which the feature is active,
+/// the on-chain schedule takes precedence over the local configuration.
+#[inline(always)]
+pub fn activation_height(feature: Feature) -> u64 {
+    SCHEDULE
+        .read()
+        .unwrap()
+        .get(&feature)
+        .copied()
+        .unwrap_or_else(|| feature.default_height())
+}
+
+/// Whether the feature is active at the given height.
+#[inline(always)]
+pub fn is_active(feature: Feature, height: u64) -> bool {
+    height >= activation_height(feature)
+}
+
+/// Replace the local copy of the on-chain schedule,
+/// should be called after the ledger state is loaded or committed.
+pub fn sync_schedule(schedule: &UpgradeSchedule) {
+    if *SCHEDULE.read().unwrap() != *schedule {
+        *SCHEDULE.write().unwrap() = schedule.clone();
+    }
+}
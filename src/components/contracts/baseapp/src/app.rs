@@ -1,14 +1,11 @@
-use crate::extensions::SignedExtra;
+use crate::{extensions::SignedExtra, modules::prism_deposits};
 use abci::*;
-use config::abci::global_cfg::CFG;
+use config::abci::upgrade::{is_active, Feature};
 use fp_core::context::RunTxMode;
 use fp_evm::BlockId;
-use fp_types::{
-    actions::xhub::NonConfidentialOutput, assemble::convert_unchecked_transaction,
-};
-use fp_utils::tx::EvmRawTxWrapper;
-use module_evm::utils::{deposit_asset_event_topic_str, parse_deposit_asset_event};
-use primitive_types::U256;
+use fp_types::{actions::xhub::UtxoOutput, assemble::convert_unchecked_transaction};
+use fp_utils::{hashing::sha2_256, tx::EvmRawTxWrapper};
+use primitive_types::{H256, U256};
 use ruc::*;
 use tracing::{debug, error, info};
 
@@ -92,8 +89,7 @@ impl crate::BaseApp {
                         #[cfg(feature = "enterprise-web3")]
                         {
                             use enterprise_web3::{
-                                Setter, PENDING_CODE_MAP, PENDING_STATE_UPDATE_LIST,
-                                REDIS_CLIENT,
+                                PENDING_CODE_MAP, PENDING_STATE_UPDATE_LIST, WEB3_SINK,
                             };
                             use std::{collections::HashMap, mem::replace};
                             let code_map =
@@ -112,16 +108,9 @@ impl crate::BaseApp {
                                 Default::default()
                             };
                             if 0 == ar.code {
-                                if let fp_types::actions::Action::Ethereum(
-                                    fp_types::actions::ethereum::Action::Transact(tx),
-                                ) = tmp_tx.function
-                                {
-                                    let redis_pool =
-                                        REDIS_CLIENT.lock().expect("REDIS_CLIENT error");
-                                    let mut conn =
-                                        redis_pool.get().expect("get redis connect");
+                                if let Some(tx) = ethereum_transaction(tmp_tx.function) {
                                     let mut setter =
-                                        Setter::new(&mut *conn, "evm".to_string());
+                                        WEB3_SINK.lock().expect("WEB3_SINK error");
 
                                     setter
                                         .set_pending_tx(tx)
@@ -136,9 +125,9 @@ impl crate::BaseApp {
                                     for state in state_list.iter() {
                                         setter
                                             .set_pending_state(
-                                                state.address.clone(),
-                                                state.index.clone(),
-                                                state.value.clone(),
+                                                state.address,
+                                                state.index,
+                                                state.value,
                                             )
                                             .map_err(|e| error!("{e:?}"))
                                             .unwrap_or(());
@@ -205,9 +194,9 @@ impl crate::BaseApp {
     pub fn deliver_tx(
         &mut self,
         req: &RequestDeliverTx,
-    ) -> (ResponseDeliverTx, Vec<NonConfidentialOutput>) {
+    ) -> (ResponseDeliverTx, Vec<UtxoOutput>) {
         let mut resp = ResponseDeliverTx::new();
-        let mut non_confidential_outputs = Vec::new();
+        let mut mint_outputs = Vec::new();
 
         let raw_tx = if let Ok(tx) = EvmRawTxWrapper::unwrap(req.get_tx()) {
             tx
@@ -215,21 +204,40 @@ impl crate::BaseApp {
             info!(target: "baseapp", "Transaction deliver tx unwrap evm tag failed");
             resp.code = 1;
             resp.log = String::from("Transaction deliver tx unwrap evm tag failed");
-            return (resp, non_confidential_outputs);
+            return (resp, mint_outputs);
         };
 
         if let Ok(tx) = convert_unchecked_transaction::<SignedExtra>(raw_tx) {
             let ctx = self.retrieve_context(RunTxMode::Deliver).clone();
             #[cfg(feature = "enterprise-web3")]
             let tmp_tx = tx.clone();
+            let td_height = self.deliver_state.block_header().height;
+            let hash = H256::from(sha2_256(req.get_tx()));
+            // the logs are built before the execution, so that a failure of them
+            // rejects the transaction instead of being left out of a committed one
+            let xhub_logs = match &tx.function {
+                fp_types::actions::Action::XHub(action)
+                    if is_active(Feature::UtxoBridgeLogs, td_height as u64) =>
+                {
+                    match crate::modules::ModuleManager::xhub_logs(action, hash) {
+                        Ok(logs) => Some(logs),
+                        Err(e) => {
+                            resp.code = 1;
+                            resp.log = format!("xhub logs error: {e}");
+                            return (resp, mint_outputs);
+                        }
+                    }
+                }
+                _ => None,
+            };
             let ret = self.modules.process_tx::<SignedExtra>(ctx, tx);
             match ret {
                 Ok(ar) => {
                     #[cfg(feature = "enterprise-web3")]
                     {
                         use enterprise_web3::{
-                            Setter, REDIS_CLIENT, REMOVE_PENDING_CODE_MAP,
-                            REMOVE_PENDING_STATE_UPDATE_LIST,
+                            REMOVE_PENDING_CODE_MAP, REMOVE_PENDING_STATE_UPDATE_LIST,
+                            WEB3_SINK,
                         };
                         use std::{mem::replace, ops::DerefMut};
                         let code_map =
@@ -252,16 +260,9 @@ impl crate::BaseApp {
                             Default::default()
                         };
                         if 0 == ar.code {
-                            if let fp_types::actions::Action::Ethereum(
-                                fp_types::actions::ethereum::Action::Transact(tx),
-                            ) = tmp_tx.function
-                            {
-                                let redis_pool =
-                                    REDIS_CLIENT.lock().expect("REDIS_CLIENT error");
-                                let mut conn =
-                                    redis_pool.get().expect("get redis connect");
+                            if let Some(tx) = ethereum_transaction(tmp_tx.function) {
                                 let mut setter =
-                                    Setter::new(&mut *conn, "evm".to_string());
+                                    WEB3_SINK.lock().expect("WEB3_SINK error");
 
                                 setter
                                     .remove_pending_tx(tx)
@@ -277,10 +278,7 @@ impl crate::BaseApp {
 
                                 for (address, index) in state_list.iter() {
                                     setter
-                                        .remove_pending_state(
-                                            address.clone(),
-                                            index.clone(),
-                                        )
+                                        .remove_pending_state(*address, *index)
                                         .map_err(|e| error!("{:?}", e))
                                         .unwrap_or(());
                                 }
@@ -299,80 +297,46 @@ impl crate::BaseApp {
                     resp.gas_wanted = ar.gas_wanted as i64;
                     resp.gas_used = ar.gas_used as i64;
                     resp.events = protobuf::RepeatedField::from_vec(ar.events);
-                    let td_height = self.deliver_state.block_header().height;
-                    if td_height > CFG.checkpoint.prismxx_inital_height && 0 == resp.code
+                    if let Some(logs) = xhub_logs.filter(|_| 0 == resp.code) {
+                        if let Err(e) = self.modules.process_xhub_logs(
+                            &self.deliver_state,
+                            logs,
+                            hash,
+                        ) {
+                            // the transfer is committed, but its result must not
+                            // hide that the receipt of it is missing
+                            error!(target: "baseapp", "xhub logs error: {e}");
+                            resp.code = 1;
+                            resp.log = format!("xhub logs error: {e}");
+                        }
+                    }
+                    if is_active(Feature::PrismMint, td_height as u64) && 0 == resp.code
                     {
-                        let deposit_asset_topic = deposit_asset_event_topic_str();
-
-                        for evt in resp.events.iter() {
-                            if evt.field_type == *"ethereum_ContractLog" {
-                                let mut bridge_contract_found = false;
-                                let mut deposit_asset_foud = false;
-
-                                for pair in evt.attributes.iter() {
-                                    let key = String::from_utf8(pair.key.clone())
-                                        .unwrap_or_default();
-                                    if key == *"address" {
-                                        let addr = String::from_utf8(pair.value.clone())
-                                            .unwrap_or_default();
-                                        if addr
-                                            == CFG
-                                                .checkpoint
-                                                .prism_bridge_address
-                                                .to_lowercase()
-                                        {
-                                            bridge_contract_found = true
-                                        }
-                                    }
-                                    if key == *"topics" {
-                                        let topic =
-                                            String::from_utf8(pair.value.clone())
-                                                .unwrap_or_default();
-                                        if topic == deposit_asset_topic {
-                                            deposit_asset_foud = true
-                                        }
-                                    }
-                                    if key == *"data"
-                                        && bridge_contract_found
-                                        && deposit_asset_foud
-                                    {
-                                        let data = String::from_utf8(pair.value.clone())
-                                            .unwrap_or_default();
-
-                                        let data_vec =
-                                            serde_json::from_str(&data).unwrap();
-
-                                        let deposit_asset =
-                                            parse_deposit_asset_event(data_vec);
-
-                                        match deposit_asset {
-                                            Ok(deposit) => {
-                                                non_confidential_outputs.push(deposit)
-                                            }
-                                            Err(e) => {
-                                                resp.code = 1;
-                                                resp.log = e.to_string();
-                                            }
-                                        }
-                                    }
+                        // the limits are checked before the transaction is committed
+                        for deposit in prism_deposits(&resp.events) {
+                            match deposit {
+                                Ok(deposit) => mint_outputs.push(deposit),
+                                Err(e) => {
+                                    resp.code = 1;
+                                    resp.log = e.to_string();
                                 }
                             }
                         }
                     }
 
-                    (resp, non_confidential_outputs)
+                    (resp, mint_outputs)
                 }
                 Err(e) => {
                     error!(target: "baseapp", "Ethereum transaction deliver error: {e}");
                     resp.code = 1;
                     resp.log = format!("Ethereum transaction deliver error: {e}");
-                    (resp, non_confidential_outputs)
+                    (resp, mint_outputs)
                 }
             }
         } else {
             resp.code = 1;
             resp.log = String::from("Failed to convert transaction when deliver tx!");
-            (resp, non_confidential_outputs)
+            (resp, mint_outputs)
         }
     }
 
@@ -426,3 +390,18 @@ impl crate::BaseApp {
         res
     }
 }
+
+/// The ethereum transaction of an action, legacy or typed.
+#[cfg(feature = "enterprise-web3")]
+fn ethereum_transaction(
+    function: fp_types::actions::Action,
+) -> Option<ethereum::TransactionV2> {
+    use fp_types::actions::{ethereum::Action as EthereumAction, Action};
+    match function {
+        Action::Ethereum(EthereumAction::Transact(tx)) => {
+            Some(ethereum::TransactionV2::Legacy(tx))
+        }
+        Action::Ethereum(EthereumAction::TransactV2(tx)) => Some(tx),
+        _ => None,
+    }
+}
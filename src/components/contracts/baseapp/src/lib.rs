@@ -8,11 +8,11 @@
 mod app;
 pub mod extensions;
 mod modules;
-mod notify;
+pub mod notify;
 
 use crate::modules::ModuleManager;
 use abci::Header;
-use ethereum::BlockV0 as Block;
+use ethereum::BlockV2 as Block;
 use evm_precompile::{self, FindoraPrecompiles};
 use fin_db::{FinDB, RocksDB};
 use fp_core::context::Context as Context2;
@@ -26,9 +26,13 @@ use fp_evm::BlockId;
 use fp_traits::{
     account::{AccountAsset, FeeCalculator},
     base::BaseProvider,
-    evm::{EthereumAddressMapping, EthereumDecimalsMapping},
+    evm::{DecimalsMapping, EthereumAddressMapping, EthereumDecimalsMapping},
+};
+use fp_types::{
+    actions::xhub::{StakingFailure, StakingRequest, StakingValidator, UtxoOutput},
+    actions::Action,
+    crypto::Address,
 };
-use fp_types::{actions::xhub::NonConfidentialOutput, actions::Action, crypto::Address};
 use lazy_static::lazy_static;
 use ledger::data_model::Transaction as FindoraTransaction;
 use notify::*;
@@ -38,6 +42,7 @@ use ruc::{eg, Result};
 use std::{borrow::BorrowMut, path::Path, sync::Arc};
 use storage::state::{ChainState, ChainStateOpts};
 use tracing::info;
+use zei::xfr::sig::XfrPublicKey;
 
 lazy_static! {
     /// An identifier that distinguishes different EVM chains.
@@ -133,6 +138,7 @@ impl module_evm::Config for BaseApp {
     type ChainId = ChainId;
     type DecimalsMapping = EthereumDecimalsMapping;
     type FeeCalculator = ();
+    type BaseFeeCalculator = module_ethereum::App<Self>;
     type Precompiles = (
         evm_precompile_basic::ECRecover,
         evm_precompile_basic::Sha256,
@@ -151,6 +157,7 @@ impl module_evm::Config for BaseApp {
 impl module_xhub::Config for BaseApp {
     type AccountAsset = module_account::App<Self>;
     type DecimalsMapping = EthereumDecimalsMapping;
+    type AssetBridge = module_evm::App<Self>;
 }
 
 impl BaseApp {
@@ -359,9 +366,35 @@ impl BaseApp {
             .process_findora_tx(&self.deliver_state, tx, H256::from_slice(hash))
     }
 
-    pub fn consume_mint(&mut self) -> Option<Vec<NonConfidentialOutput>> {
+    pub fn consume_mint(&mut self) -> Option<Vec<UtxoOutput>> {
         module_xhub::App::<BaseApp>::consume_mint(&self.deliver_state)
     }
+
+    pub fn consume_staking_requests(&mut self) -> Option<Vec<StakingRequest>> {
+        module_xhub::staking::consume_staking_requests(&self.deliver_state)
+    }
+
+    /// Credit `amount` FRA to `owner`, the balance of a failed delegation,
+    /// or the proceeds of the delegations of the findora key bound to it.
+    pub fn credit_staking(&mut self, owner: &Address, amount: u64) -> Result<()> {
+        let balance = EthereumDecimalsMapping::from_native_token(U256::from(amount))
+            .ok_or_else(|| eg!("the credit amount is too large"))?;
+        module_account::App::<BaseApp>::mint(&self.deliver_state, owner, balance)
+    }
+
+    /// The evm account bound to the findora key `delegator`, if any.
+    pub fn staking_owner_of(&self, delegator: &XfrPublicKey) -> Option<Address> {
+        module_xhub::staking::owner_of(&self.deliver_state, delegator)
+    }
+
+    /// Keep a request failed in the ledger, visible to its owner in the evm.
+    pub fn record_staking_failure(&mut self, failure: &StakingFailure) -> Result<()> {
+        module_xhub::staking::record_failure(&self.deliver_state, failure)
+    }
+
+    pub fn set_staking_validators(&self, validators: Vec<StakingValidator>) {
+        module_xhub::staking::set_validators(validators)
+    }
 }
 
 impl BaseProvider for BaseApp {
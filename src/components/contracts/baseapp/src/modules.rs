@@ -1,6 +1,9 @@
 use super::*;
 use abci::*;
-use config::abci::global_cfg::CFG;
+use config::abci::{
+    global_cfg::CFG,
+    upgrade::{is_active, Feature},
+};
 use fp_core::{
     context::Context,
     module::AppModule,
@@ -10,7 +13,7 @@ use fp_core::{
 };
 use fp_traits::evm::DecimalsMapping;
 use fp_types::{
-    actions,
+    actions::{self, xhub::UtxoOutput},
     assemble::{convert_unsigned_transaction, CheckedTransaction, UncheckedTransaction},
     crypto::{Address, HA256},
 };
@@ -19,6 +22,10 @@ use ledger::{
     data_model::{Transaction as FindoraTransaction, ASSET_TYPE_FRA},
 };
 use module_ethereum::storage::{TransactionIndex, DELIVER_PENDING_TRANSACTIONS};
+use module_evm::utils::{
+    deposit_asset_event_topic_str, parse_deposit_asset_event, utxo_deposit_log,
+    utxo_withdraw_log,
+};
 use ruc::*;
 use serde::Serialize;
 
@@ -101,11 +108,11 @@ impl ModuleManager {
     ) -> Result<ActionResult> {
         let checked = tx.clone().check()?;
         match tx.function.clone() {
-            actions::Action::Ethereum(action) => Self::dispatch::<
-                actions::ethereum::Action,
-                module_ethereum::App<BaseApp>,
-                Extra,
-            >(&ctx, action, checked),
+            actions::Action::Ethereum(action) => {
+                Self::dispatch::<actions::ethereum::Action, EthereumBridge, Extra>(
+                    &ctx, action, checked,
+                )
+            }
             _ => Self::dispatch::<actions::Action, BaseApp, Extra>(
                 &ctx,
                 tx.function,
@@ -122,14 +129,15 @@ impl ModuleManager {
     ) -> Result<()> {
         let (from, owner, amount, asset, lowlevel) =
             check_convert_account(tx, ctx.header.height)?;
+        module_xhub::App::<BaseApp>::check_bridge_limit(ctx, asset, amount)?;
 
-        if CFG.checkpoint.prismxx_inital_height < ctx.header.height {
+        if is_active(Feature::PrismMint, ctx.header.height as u64) {
             let mut pending_txs = DELIVER_PENDING_TRANSACTIONS.lock().c(d!())?;
             // if let Some(pending_txs)
             // let transaction_index = pending_txs.as_ref().unwrap_or_default().len() as u32;
             let transaction_index = pending_txs.len() as u32;
 
-            let (tx, tx_status, receipt) = if asset == ASSET_TYPE_FRA {
+            let (tx, mut tx_status, mut receipt) = if asset == ASSET_TYPE_FRA {
                 let balance =
                     EthereumDecimalsMapping::from_native_token(U256::from(amount))
                         .ok_or_else(|| {
@@ -168,13 +176,24 @@ impl ModuleManager {
                 )?
             };
 
+            if is_active(Feature::UtxoBridgeLogs, ctx.header.height as u64) {
+                let value = bridge_value(asset == ASSET_TYPE_FRA, amount)
+                    .ok_or_else(|| eg!("The transfer to account amount is too large"))?;
+                let log = utxo_deposit_log(hash, owner, asset, value);
+                module_evm::App::<BaseApp>::append_logs(
+                    &mut tx_status,
+                    &mut receipt,
+                    vec![log],
+                );
+            }
+
             TransactionIndex::insert(
                 &mut *ctx.db.write(),
                 &HA256::new(tx_status.transaction_hash),
                 &(U256::from(ctx.header.height), tx_status.transaction_index),
             )?;
 
-            pending_txs.push((tx, tx_status, receipt));
+            pending_txs.push((ethereum::TransactionV2::Legacy(tx), tx_status, receipt));
 
             Ok(())
         } else {
@@ -183,6 +202,77 @@ impl ModuleManager {
             module_account::App::<BaseApp>::mint(ctx, &Address::from(owner), balance)
         }
     }
+
+    /// The logs of the transfers to utxo of an xhub action,
+    /// `hash` is the hash of the xhub transaction.
+    pub fn xhub_logs(
+        action: &actions::xhub::Action,
+        hash: H256,
+    ) -> Result<Vec<ethereum::Log>> {
+        let outputs = match action {
+            actions::xhub::Action::NonConfidentialTransfer(t) => t
+                .outputs
+                .iter()
+                .map(|o| (o.target, o.asset, o.amount))
+                .collect::<Vec<_>>(),
+            actions::xhub::Action::Transfer(t) => t
+                .outputs
+                .iter()
+                .map(|o| (o.target, o.asset, o.amount))
+                .collect(),
+        };
+
+        outputs
+            .iter()
+            .map(|(target, asset, amount)| {
+                bridge_value(*asset == ASSET_TYPE_FRA, *amount)
+                    .map(|value| utxo_withdraw_log(hash, target, *asset, value))
+                    .ok_or_else(|| eg!("the transfer to UTXO amount is too large"))
+            })
+            .collect()
+    }
+
+    /// Put the logs of an xhub transaction in a system transaction,
+    /// `hash` is the hash of the xhub transaction.
+    pub fn process_xhub_logs(
+        &self,
+        ctx: &Context,
+        logs: Vec<ethereum::Log>,
+        hash: H256,
+    ) -> Result<()> {
+        if logs.is_empty() {
+            return Ok(());
+        }
+
+        let mut pending_txs = DELIVER_PENDING_TRANSACTIONS.lock().c(d!())?;
+        let transaction_index = pending_txs.len() as u32;
+        let (tx, tx_status, receipt) =
+            module_evm::App::<BaseApp>::utxo_bridge_transaction(
+                hash,
+                transaction_index,
+                logs,
+            );
+
+        TransactionIndex::insert(
+            &mut *ctx.db.write(),
+            &HA256::new(hash),
+            &(U256::from(ctx.header.height), transaction_index),
+        )?;
+
+        pending_txs.push((ethereum::TransactionV2::Legacy(tx), tx_status, receipt));
+
+        Ok(())
+    }
+}
+
+/// An amount on the utxo side in the unit of the evm side,
+/// only FRA has different decimals.
+fn bridge_value(fra: bool, amount: u64) -> Option<U256> {
+    if fra {
+        EthereumDecimalsMapping::from_native_token(U256::from(amount))
+    } else {
+        Some(U256::from(amount))
+    }
 }
 
 impl ModuleManager {
@@ -206,3 +296,112 @@ impl ModuleManager {
         Ok(ActionResult::default())
     }
 }
+
+/// The deposits to utxo in the logs of the prism bridge, by the events of
+/// an ethereum transaction.
+pub(crate) fn prism_deposits(events: &[Event]) -> Vec<Result<UtxoOutput>> {
+    let deposit_asset_topic = deposit_asset_event_topic_str();
+    let mut deposits = Vec::new();
+
+    for evt in events.iter() {
+        if evt.field_type == *"ethereum_ContractLog" {
+            let mut bridge_contract_found = false;
+            let mut deposit_asset_foud = false;
+
+            for pair in evt.attributes.iter() {
+                let key = String::from_utf8(pair.key.clone()).unwrap_or_default();
+                if key == *"address" {
+                    let addr = String::from_utf8(pair.value.clone()).unwrap_or_default();
+                    if addr == CFG.checkpoint.prism_bridge_address.to_lowercase() {
+                        bridge_contract_found = true
+                    }
+                }
+                if key == *"topics" {
+                    let topic =
+                        String::from_utf8(pair.value.clone()).unwrap_or_default();
+                    if topic == deposit_asset_topic {
+                        deposit_asset_foud = true
+                    }
+                }
+                if key == *"data" && bridge_contract_found && deposit_asset_foud {
+                    let data = String::from_utf8(pair.value.clone()).unwrap_or_default();
+
+                    let data_vec = serde_json::from_str(&data).unwrap();
+
+                    deposits.push(parse_deposit_asset_event(data_vec));
+                }
+            }
+        }
+    }
+
+    deposits
+}
+
+/// The ethereum module, with the deposits of the prism bridge checked against
+/// the bridge limits, a transaction exceeding them fails before its state
+/// is committed.
+struct EthereumBridge;
+
+impl ValidateUnsigned for EthereumBridge {
+    type Call = actions::ethereum::Action;
+
+    fn pre_execute(ctx: &Context, call: &Self::Call) -> Result<()> {
+        module_ethereum::App::<BaseApp>::pre_execute(ctx, call)
+    }
+
+    fn validate_unsigned(ctx: &Context, call: &Self::Call) -> Result<()> {
+        module_ethereum::App::<BaseApp>::validate_unsigned(ctx, call)
+    }
+
+    fn post_execute(ctx: &Context, result: &ActionResult) -> Result<()> {
+        module_ethereum::App::<BaseApp>::post_execute(ctx, result)
+    }
+}
+
+impl Executable for EthereumBridge {
+    type Origin = Address;
+    type Call = actions::ethereum::Action;
+
+    fn execute(
+        origin: Option<Self::Origin>,
+        call: Self::Call,
+        ctx: &Context,
+    ) -> Result<ActionResult> {
+        let mut ar = module_ethereum::App::<BaseApp>::execute(origin, call, ctx)?;
+
+        let height = ctx.header.height as u64;
+        if 0 != ar.code || !is_active(Feature::ConfidentialBridge, height) {
+            return Ok(ar);
+        }
+
+        let checked = prism_deposits(&ar.events)
+            .into_iter()
+            .try_for_each(|deposit| {
+                deposit.and_then(|deposit| {
+                    module_xhub::App::<BaseApp>::check_bridge_limit(
+                        ctx,
+                        deposit.asset,
+                        deposit.amount,
+                    )
+                })
+            });
+        if let Err(e) = checked {
+            ar.code = 1;
+            ar.log = e.to_string();
+
+            // the state is discarded, so are the logs in the receipt
+            if RunTxMode::Deliver == ctx.run_mode {
+                let mut pending_txs = DELIVER_PENDING_TRANSACTIONS.lock().c(d!())?;
+                if let Some((_, status, receipt)) = pending_txs.last_mut() {
+                    status.logs.clear();
+                    status.logs_bloom = Default::default();
+                    receipt.state_root = H256::zero();
+                    receipt.logs.clear();
+                    receipt.logs_bloom = Default::default();
+                }
+            }
+        }
+
+        Ok(ar)
+    }
+}
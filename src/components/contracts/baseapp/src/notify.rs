@@ -1,8 +1,12 @@
-use futures::channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender};
+use futures::channel::mpsc::{channel, Receiver, Sender};
 use parking_lot::Mutex;
 use ruc::*;
 
-type NotificationSinks<T> = Mutex<Vec<UnboundedSender<T>>>;
+/// The number of notifications buffered for a subscriber,
+/// a subscriber falling behind by more than this is dropped.
+pub const NOTIFICATION_BUFFER: usize = 1024;
+
+type NotificationSinks<T> = Mutex<Vec<Sender<T>>>;
 
 pub struct Notifications<T> {
     sinks: NotificationSinks<T>,
@@ -25,16 +29,43 @@ impl<T: Clone> Notifications<T> {
         &self.sinks
     }
 
-    pub fn notification_stream(&self) -> UnboundedReceiver<T> {
-        let (sink, stream) = unbounded();
+    pub fn notification_stream(&self) -> Receiver<T> {
+        let (sink, stream) = channel(NOTIFICATION_BUFFER);
         self.sinks.lock().push(sink);
         stream
     }
 
+    /// Send `data` to all the subscribers, the closed ones and
+    /// the ones whose buffer is full are dropped, never blocks.
     pub fn notify(&self, data: T) -> Result<()> {
         self.sinks
             .lock()
-            .retain(|sink| sink.unbounded_send(data.clone()).is_ok());
+            .retain_mut(|sink| sink.try_send(data.clone()).is_ok());
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+
+    #[test]
+    fn slow_subscriber_is_dropped() {
+        let notifications = Notifications::new();
+        let mut fast = notifications.notification_stream();
+        let _slow = notifications.notification_stream();
+
+        for i in 0..NOTIFICATION_BUFFER {
+            pnk!(notifications.notify(i));
+            assert_eq!(Some(i), futures::executor::block_on(fast.next()),);
+        }
+        assert_eq!(2, notifications.notification_sinks().lock().len());
+
+        // the buffer of `slow` is full
+        for i in 0..2 {
+            pnk!(notifications.notify(i));
+        }
+        assert_eq!(1, notifications.notification_sinks().lock().len());
+    }
+}
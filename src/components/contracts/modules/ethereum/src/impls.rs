@@ -1,9 +1,9 @@
 use crate::storage::*;
-use crate::{App, Config, ContractLog, TransactionExecuted};
-use config::abci::global_cfg::CFG;
+use crate::{App, Config, ContractLog, TransactionData, TransactionExecuted};
+use config::abci::upgrade::{activation_height, is_active, Feature};
 use ethereum::{
-    BlockV0 as Block, LegacyTransactionMessage, ReceiptV0 as Receipt,
-    TransactionV0 as Transaction,
+    BlockV0 as Block, BlockV2, EIP1559TransactionMessage, EIP2930TransactionMessage,
+    LegacyTransactionMessage, ReceiptV0 as Receipt, TransactionV2 as Transaction,
 };
 use ethereum_types::{Bloom, BloomInput, H160, H256, H64, U256};
 use evm::{ExitFatal, ExitReason};
@@ -16,6 +16,7 @@ use fp_core::{
 use fp_events::Event;
 use fp_evm::{BlockId, CallOrCreateInfo, Runner, TransactionStatus};
 use fp_storage::{Borrow, BorrowMut};
+use fp_traits::evm::{BaseFeeCalculator, FeeCalculator};
 use fp_types::crypto::Address;
 use fp_types::{
     actions::evm as EvmAction,
@@ -29,13 +30,19 @@ use tracing::{debug, info};
 #[cfg(feature = "web3_service")]
 use enterprise_web3::{TxState, BLOCK, RECEIPTS, TXS, WEB3_SERVICE_START_HEIGHT};
 
+impl<C: Config> BaseFeeCalculator for App<C> {
+    fn base_fee(ctx: &Context) -> U256 {
+        BaseFeePerGas::get(ctx.state.read().borrow())
+            .unwrap_or_else(C::FeeCalculator::min_gas_price)
+    }
+}
+
 impl<C: Config> App<C> {
     pub fn recover_signer_fast(
         ctx: &Context,
         transaction: &Transaction,
     ) -> Option<H160> {
-        let transaction_hash =
-            H256::from_slice(Keccak256::digest(&rlp::encode(transaction)).as_slice());
+        let transaction_hash = transaction.hash();
 
         // Check historical cache first for Deliver Context, while holding the read lock
         if ctx.run_mode == RunTxMode::Deliver {
@@ -66,12 +73,32 @@ impl<C: Config> App<C> {
     pub fn recover_signer(transaction: &Transaction) -> Option<H160> {
         let mut sig = [0u8; 65];
         let mut msg = [0u8; 32];
-        sig[0..32].copy_from_slice(&transaction.signature.r()[..]);
-        sig[32..64].copy_from_slice(&transaction.signature.s()[..]);
-        sig[64] = transaction.signature.standard_v();
-        msg.copy_from_slice(
-            &LegacyTransactionMessage::from(transaction.clone()).hash()[..],
-        );
+        match transaction {
+            Transaction::Legacy(t) => {
+                sig[0..32].copy_from_slice(&t.signature.r()[..]);
+                sig[32..64].copy_from_slice(&t.signature.s()[..]);
+                sig[64] = t.signature.standard_v();
+                msg.copy_from_slice(
+                    &LegacyTransactionMessage::from(t.clone()).hash()[..],
+                );
+            }
+            Transaction::EIP2930(t) => {
+                sig[0..32].copy_from_slice(&t.r[..]);
+                sig[32..64].copy_from_slice(&t.s[..]);
+                sig[64] = t.odd_y_parity as u8;
+                msg.copy_from_slice(
+                    &EIP2930TransactionMessage::from(t.clone()).hash()[..],
+                );
+            }
+            Transaction::EIP1559(t) => {
+                sig[0..32].copy_from_slice(&t.r[..]);
+                sig[32..64].copy_from_slice(&t.s[..]);
+                sig[64] = t.odd_y_parity as u8;
+                msg.copy_from_slice(
+                    &EIP1559TransactionMessage::from(t.clone()).hash()[..],
+                );
+            }
+        }
 
         let pubkey = secp256k1_ecdsa_recover(&sig, &msg).ok()?;
         Some(H160::from(H256::from_slice(
@@ -91,7 +118,7 @@ impl<C: Config> App<C> {
             std::mem::take(&mut *txns)
         };
 
-        if block_number < U256::from(CFG.checkpoint.evm_first_block_height)
+        if block_number < U256::from(activation_height(Feature::EvmFirstBlock))
             || (pending_txs.is_empty() && self.disable_eth_empty_blocks)
         {
             is_store_block = false;
@@ -138,8 +165,13 @@ impl<C: Config> App<C> {
             mix_hash: H256::default(),
             nonce: H64::default(),
         };
-        let block = Block::new(partial_header, transactions, ommers);
+        let block = BlockV2::new(partial_header, transactions, ommers);
         let block_hash = HA256::new(block.header.hash());
+        let typed = is_active(Feature::TypedTransaction, block_number.as_u64());
+
+        if typed {
+            Self::update_base_fee(ctx, block.header.gas_used)?;
+        }
 
         CurrentBlockNumber::put(ctx.db.write().borrow_mut(), &block_number)?;
         BlockHash::insert(ctx.db.write().borrow_mut(), &block_number, &block_hash)?;
@@ -154,7 +186,20 @@ impl<C: Config> App<C> {
                 &block_hash,
                 &statuses,
             )?;
-            CurrentBlock::insert(ctx.db.write().borrow_mut(), &block_hash, &block)?;
+            if typed {
+                CurrentBlockV2::insert(
+                    ctx.db.write().borrow_mut(),
+                    &block_hash,
+                    &block,
+                )?;
+            } else {
+                // Only the legacy transactions before the activation.
+                CurrentBlock::insert(
+                    ctx.db.write().borrow_mut(),
+                    &block_hash,
+                    &Self::legacy_block(block.clone()),
+                )?;
+            }
 
             #[cfg(feature = "web3_service")]
             {
@@ -214,8 +259,8 @@ impl<C: Config> App<C> {
         let source = Self::recover_signer_fast(ctx, &transaction)
             .ok_or_else(|| eg!("ExecuteTransaction: InvalidSignature"))?;
 
-        let transaction_hash =
-            H256::from_slice(Keccak256::digest(&rlp::encode(&transaction)).as_slice());
+        let transaction_hash = transaction.hash();
+        let transaction_data = TransactionData::from(&transaction);
 
         let transaction_index = if just_check {
             0
@@ -224,24 +269,26 @@ impl<C: Config> App<C> {
             txns.len() as u32
         };
 
-        let gas_limit = transaction.gas_limit;
+        let gas_limit = transaction_data.gas_limit;
 
         let execute_ret = Self::execute_transaction(
             ctx,
             source,
-            transaction.input.clone(),
-            transaction.value,
-            transaction.gas_limit,
-            Some(transaction.gas_price),
-            Some(transaction.nonce),
-            transaction.action,
+            transaction_data.input,
+            transaction_data.value,
+            transaction_data.gas_limit,
+            Some(transaction_data.gas_price),
+            Some(transaction_data.nonce),
+            transaction_data.max_priority_fee_per_gas,
+            transaction_data.access_list,
+            transaction_data.action,
         );
 
         let (ar_code, info, to, contract_address, reason, data, status, used_gas) =
             match execute_ret {
                 Err(e) => {
                     let to = if let ethereum::TransactionAction::Call(target) =
-                        transaction.action
+                        transaction_data.action
                     {
                         Some(target)
                     } else {
@@ -368,7 +415,7 @@ impl<C: Config> App<C> {
             info!(target: "ethereum", "evm execute result: reason {:?} status {:?} used_gas {}", reason, status, used_gas);
         }
 
-        if ctx.header.height < CFG.checkpoint.tx_revert_on_error_height {
+        if !is_active(Feature::TxRevertOnError, ctx.header.height as u64) {
             code = 0;
         }
 
@@ -390,7 +437,7 @@ impl<C: Config> App<C> {
                 pending_txs.push((transaction, status, receipt));
             }
 
-            if ctx.header.height < CFG.checkpoint.tx_revert_on_error_height {
+            if !is_active(Feature::TxRevertOnError, ctx.header.height as u64) {
                 TransactionIndex::insert(
                     ctx.state.write().borrow_mut(),
                     &HA256::new(transaction_hash),
@@ -440,6 +487,8 @@ impl<C: Config> App<C> {
         gas_limit: U256,
         gas_price: Option<U256>,
         nonce: Option<U256>,
+        max_priority_fee_per_gas: Option<U256>,
+        access_list: Vec<(H160, Vec<H256>)>,
         action: ethereum::TransactionAction,
     ) -> Result<(Option<H160>, Option<H160>, CallOrCreateInfo)> {
         match action {
@@ -454,6 +503,8 @@ impl<C: Config> App<C> {
                         gas_limit: gas_limit.low_u64(),
                         gas_price,
                         nonce,
+                        max_priority_fee_per_gas,
+                        access_list,
                     },
                     C::config(),
                 )?;
@@ -470,6 +521,8 @@ impl<C: Config> App<C> {
                         gas_limit: gas_limit.low_u64(),
                         gas_price,
                         nonce,
+                        max_priority_fee_per_gas,
+                        access_list,
                     },
                     C::config(),
                 )?;
@@ -490,9 +543,65 @@ impl<C: Config> App<C> {
     }
 
     /// Get the block with given block id.
-    pub fn current_block(&self, ctx: &Context, id: Option<BlockId>) -> Option<Block> {
+    pub fn current_block(&self, ctx: &Context, id: Option<BlockId>) -> Option<BlockV2> {
         let hash = HA256::new(Self::block_hash(ctx, id).unwrap_or_default());
-        CurrentBlock::get(ctx.db.read().borrow(), &hash)
+        CurrentBlockV2::get(ctx.db.read().borrow(), &hash).or_else(|| {
+            CurrentBlock::get(ctx.db.read().borrow(), &hash).map(|block| BlockV2 {
+                header: block.header,
+                transactions: block
+                    .transactions
+                    .into_iter()
+                    .map(Transaction::Legacy)
+                    .collect(),
+                ommers: block.ommers,
+            })
+        })
+    }
+
+    fn legacy_block(block: BlockV2) -> Block {
+        Block {
+            header: block.header,
+            transactions: block
+                .transactions
+                .into_iter()
+                .filter_map(|t| match t {
+                    Transaction::Legacy(t) => Some(t),
+                    _ => None,
+                })
+                .collect(),
+            ommers: block.ommers,
+        }
+    }
+
+    /// Adjust the base fee of the next block by the gas used, see EIP-1559.
+    pub fn update_base_fee(ctx: &Context, gas_used: U256) -> Result<()> {
+        // The target is a half of the block gas limit.
+        const ELASTICITY_MULTIPLIER: u64 = 2;
+        // The base fee changes 1/8 at most per block.
+        const BASE_FEE_MAX_CHANGE_DENOMINATOR: u64 = 8;
+
+        let base_fee = Self::base_fee(ctx);
+        let target = C::BlockGasLimit::get() / ELASTICITY_MULTIPLIER;
+        if target.is_zero() {
+            return Ok(());
+        }
+
+        let next = if gas_used > target {
+            let delta = base_fee * (gas_used - target)
+                / target
+                / BASE_FEE_MAX_CHANGE_DENOMINATOR;
+            base_fee.saturating_add(delta.max(U256::one()))
+        } else {
+            let delta = base_fee * (target - gas_used)
+                / target
+                / BASE_FEE_MAX_CHANGE_DENOMINATOR;
+            base_fee.saturating_sub(delta)
+        };
+
+        BaseFeePerGas::put(
+            ctx.state.write().borrow_mut(),
+            &next.max(C::FeeCalculator::min_gas_price()),
+        )
     }
 
     /// Get receipts with given block id.
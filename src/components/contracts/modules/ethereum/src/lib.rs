@@ -3,9 +3,11 @@
 
 mod basic;
 mod impls;
+mod transaction;
 
 use abci::{RequestEndBlock, ResponseEndBlock};
-use config::abci::global_cfg::CFG;
+use config::abci::upgrade::{is_active, Feature};
+use ethereum::TransactionV2;
 use ethereum_types::{H160, H256, U256};
 use evm::Config as EvmConfig;
 use fp_core::context::RunTxMode;
@@ -20,13 +22,18 @@ use fp_events::*;
 use fp_evm::{BlockId, Runner};
 use fp_traits::{
     account::AccountAsset,
-    evm::{AddressMapping, BlockHashMapping, DecimalsMapping, FeeCalculator},
+    evm::{
+        AddressMapping, BaseFeeCalculator, BlockHashMapping, DecimalsMapping,
+        FeeCalculator,
+    },
 };
 use fp_types::{actions::ethereum::Action, crypto::Address};
 use ruc::*;
 use std::marker::PhantomData;
 use tracing::debug;
 
+pub use transaction::TransactionData;
+
 pub const MODULE_NAME: &str = "ethereum";
 
 static ISTANBUL_CONFIG: EvmConfig = EvmConfig::istanbul();
@@ -55,9 +62,7 @@ pub trait Config {
 }
 
 pub mod storage {
-    use ethereum::{
-        BlockV0 as Block, ReceiptV0 as Receipt, TransactionV0 as Transaction,
-    };
+    use ethereum::{BlockV0 as Block, BlockV2, ReceiptV0 as Receipt, TransactionV2};
     use ethereum_types::U256;
     use fp_evm::TransactionStatus;
     use fp_storage::*;
@@ -74,14 +79,18 @@ pub mod storage {
     generate_storage!(Ethereum, BlockHash => Map<U256, HA256>);
     // The ethereum history blocks with block number.
     generate_storage!(Ethereum, CurrentBlock => Map<HA256, Block>);
+    // The typed ethereum blocks, stored since `Feature::TypedTransaction`.
+    generate_storage!(Ethereum, CurrentBlockV2 => Map<HA256, BlockV2>);
     // The ethereum history receipts with block number.
     generate_storage!(Ethereum, CurrentReceipts => Map<HA256, Vec<Receipt>>);
     // The ethereum history transaction statuses with block number.
     generate_storage!(Ethereum, CurrentTransactionStatuses => Map<HA256, Vec<TransactionStatus>>);
+    // The base fee per gas of the next block, see EIP-1559.
+    generate_storage!(Ethereum, BaseFeePerGas => Value<U256>);
 
     // The following data is stored in in-memory array
     // Current building block's transactions and receipts.
-    type PendingTransactions = Mutex<Vec<(Transaction, TransactionStatus, Receipt)>>;
+    type PendingTransactions = Mutex<Vec<(TransactionV2, TransactionStatus, Receipt)>>;
 
     lazy_static! {
         pub static ref DELIVER_PENDING_TRANSACTIONS: PendingTransactions =
@@ -152,20 +161,34 @@ impl<C: Config> Executable for App<C> {
         ensure!(origin.is_none(), "InvalidTransaction: IllegalOrigin");
 
         match call {
-            Action::Transact(tx) => Self::do_transact(ctx, tx),
+            Action::Transact(tx) => Self::do_transact(ctx, TransactionV2::Legacy(tx)),
+            Action::TransactV2(tx) => {
+                ensure!(
+                    is_active(Feature::TypedTransaction, ctx.header.height as u64),
+                    "InvalidTransaction: typed transaction is not supported"
+                );
+                Self::do_transact(ctx, tx)
+            }
         }
     }
 }
 
+fn typed_transaction(call: &Action) -> TransactionV2 {
+    match call {
+        Action::Transact(tx) => TransactionV2::Legacy(tx.clone()),
+        Action::TransactV2(tx) => tx.clone(),
+    }
+}
+
 impl<C: Config> ValidateUnsigned for App<C> {
     type Call = Action;
 
     fn pre_execute(ctx: &Context, call: &Self::Call) -> Result<()> {
-        if ctx.header.height >= CFG.checkpoint.evm_checktx_nonce
+        if is_active(Feature::EvmCheckTxNonce, ctx.header.height as u64)
             && ctx.run_mode == RunTxMode::Check
         {
-            let Action::Transact(transaction) = call;
-            let origin = Self::recover_signer_fast(ctx, transaction)
+            let transaction = typed_transaction(call);
+            let origin = Self::recover_signer_fast(ctx, &transaction)
                 .ok_or_else(|| eg!("ExecuteTransaction: InvalidSignature"))?;
             let account_id = C::AddressMapping::convert_to_account_id(origin);
             C::AccountAsset::inc_nonce(ctx, &account_id)?;
@@ -175,8 +198,17 @@ impl<C: Config> ValidateUnsigned for App<C> {
     }
 
     fn validate_unsigned(ctx: &Context, call: &Self::Call) -> Result<()> {
-        let Action::Transact(transaction) = call;
-        if let Some(chain_id) = transaction.signature.chain_id() {
+        if let Action::TransactV2(_) = call {
+            if !is_active(Feature::TypedTransaction, ctx.header.height as u64) {
+                return Err(eg!(
+                    "InvalidTransaction: typed transaction is not supported"
+                ));
+            }
+        }
+
+        let transaction = typed_transaction(call);
+        let transaction_data = TransactionData::from(&transaction);
+        if let Some(chain_id) = transaction_data.chain_id {
             if chain_id != C::ChainId::get() {
                 return Err(eg!(format!(
                     "InvalidChainId, got {}, but expected {}",
@@ -188,24 +220,47 @@ impl<C: Config> ValidateUnsigned for App<C> {
             return Err(eg!("Must provide chainId".to_string()));
         }
 
-        let origin = Self::recover_signer_fast(ctx, transaction)
+        let origin = Self::recover_signer_fast(ctx, &transaction)
             .ok_or_else(|| eg!("ExecuteTransaction: InvalidSignature"))?;
 
         // Same as go ethereum, Min gas limit is 21000.
-        if transaction.gas_limit < U256::from(21000)
-            || transaction.gas_limit > C::BlockGasLimit::get()
+        if transaction_data.gas_limit < U256::from(21000)
+            || transaction_data.gas_limit > C::BlockGasLimit::get()
         {
             return Err(eg!(format!(
                 "InvalidGasLimit: got {}, the gas limit must be in range [21000, {}]",
-                transaction.gas_limit,
+                transaction_data.gas_limit,
                 C::BlockGasLimit::get()
             )));
         }
 
-        if transaction.gas_price < C::FeeCalculator::min_gas_price() {
+        if let Some(max_priority_fee) = transaction_data.max_priority_fee_per_gas {
+            let base_fee = Self::base_fee(ctx);
+            if transaction_data.gas_price < base_fee {
+                return Err(eg!(format!(
+                    "InvalidGasPrice: got max fee per gas {}, but the base fee is {}",
+                    transaction_data.gas_price, base_fee
+                )));
+            }
+            if max_priority_fee > transaction_data.gas_price {
+                return Err(eg!(format!(
+                    "InvalidPriorityFee: got {}, but the max fee per gas is {}",
+                    max_priority_fee, transaction_data.gas_price
+                )));
+            }
+        } else if is_active(Feature::TypedTransaction, ctx.header.height as u64) {
+            // legacy and EIP-2930 transactions pay the base fee as well
+            let base_fee = Self::base_fee(ctx);
+            if transaction_data.gas_price < base_fee {
+                return Err(eg!(format!(
+                    "InvalidGasPrice: got {}, but the base fee is {}",
+                    transaction_data.gas_price, base_fee
+                )));
+            }
+        } else if transaction_data.gas_price < C::FeeCalculator::min_gas_price() {
             return Err(eg!(format!(
                 "InvalidGasPrice: got {}, but the minimum gas price is {}",
-                transaction.gas_price,
+                transaction_data.gas_price,
                 C::FeeCalculator::min_gas_price()
             )));
         }
@@ -216,16 +271,18 @@ impl<C: Config> ValidateUnsigned for App<C> {
         let nonce = account.nonce;
         let balance = account.balance;
 
-        if transaction.nonce < nonce {
+        if transaction_data.nonce < nonce {
             #[cfg(not(feature = "benchmark"))]
             return Err(eg!(format!(
                 "InvalidNonce: origin: {:?}, got {}, but expected {}",
-                origin, transaction.nonce, nonce
+                origin, transaction_data.nonce, nonce
             )));
         }
 
-        let fee = transaction.gas_price.saturating_mul(transaction.gas_limit);
-        let total_payment = transaction.value.saturating_add(fee);
+        let fee = transaction_data
+            .gas_price
+            .saturating_mul(transaction_data.gas_limit);
+        let total_payment = transaction_data.value.saturating_add(fee);
         if balance < total_payment {
             return Err(eg!(format!(
                 "InsufficientBalance, origin: {origin:?}, actual balance {balance}, but expected payment {total_payment}",
@@ -237,7 +294,7 @@ impl<C: Config> ValidateUnsigned for App<C> {
 
     fn post_execute(ctx: &Context, result: &ActionResult) -> Result<()> {
         if result.code != 0
-            && ctx.header.height >= CFG.checkpoint.fix_deliver_tx_revert_nonce_height
+            && is_active(Feature::FixDeliverTxRevertNonce, ctx.header.height as u64)
         {
             let prev = result
                 .source
@@ -0,0 +1,77 @@
+use ethereum::{TransactionAction, TransactionV2 as Transaction};
+use ethereum_types::{H160, H256, U256};
+
+/// The fields of a transaction used in the execution, whatever its type.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TransactionData {
+    pub action: TransactionAction,
+    pub input: Vec<u8>,
+    pub nonce: U256,
+    pub gas_limit: U256,
+    /// The gas price of a legacy or an EIP-2930 transaction,
+    /// or the max fee per gas of an EIP-1559 transaction.
+    pub gas_price: U256,
+    /// The tip per gas of an EIP-1559 transaction.
+    pub max_priority_fee_per_gas: Option<U256>,
+    pub value: U256,
+    pub chain_id: Option<u64>,
+    pub access_list: Vec<(H160, Vec<H256>)>,
+}
+
+impl TransactionData {
+    /// The gas price paid with the base fee of the block.
+    pub fn effective_gas_price(&self, base_fee: U256) -> U256 {
+        match self.max_priority_fee_per_gas {
+            Some(tip) => self.gas_price.min(base_fee.saturating_add(tip)),
+            None => self.gas_price,
+        }
+    }
+}
+
+impl From<&Transaction> for TransactionData {
+    fn from(transaction: &Transaction) -> Self {
+        match transaction {
+            Transaction::Legacy(t) => TransactionData {
+                action: t.action,
+                input: t.input.clone(),
+                nonce: t.nonce,
+                gas_limit: t.gas_limit,
+                gas_price: t.gas_price,
+                max_priority_fee_per_gas: None,
+                value: t.value,
+                chain_id: t.signature.chain_id(),
+                access_list: Vec::new(),
+            },
+            Transaction::EIP2930(t) => TransactionData {
+                action: t.action,
+                input: t.input.clone(),
+                nonce: t.nonce,
+                gas_limit: t.gas_limit,
+                gas_price: t.gas_price,
+                max_priority_fee_per_gas: None,
+                value: t.value,
+                chain_id: Some(t.chain_id),
+                access_list: t
+                    .access_list
+                    .iter()
+                    .map(|item| (item.address, item.slots.clone()))
+                    .collect(),
+            },
+            Transaction::EIP1559(t) => TransactionData {
+                action: t.action,
+                input: t.input.clone(),
+                nonce: t.nonce,
+                gas_limit: t.gas_limit,
+                gas_price: t.max_fee_per_gas,
+                max_priority_fee_per_gas: Some(t.max_priority_fee_per_gas),
+                value: t.value,
+                chain_id: Some(t.chain_id),
+                access_list: t
+                    .access_list
+                    .iter()
+                    .map(|item| (item.address, item.slots.clone()))
+                    .collect(),
+            },
+        }
+    }
+}
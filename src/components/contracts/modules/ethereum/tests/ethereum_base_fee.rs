@@ -0,0 +1,59 @@
+//! Tests of the EIP-1559 base fee.
+
+use baseapp::BaseApp;
+use ethereum_types::U256;
+use fp_core::{context::Context, macros::Get};
+use fp_mocks::*;
+use fp_storage::BorrowMut;
+use fp_traits::evm::{BaseFeeCalculator, FeeCalculator};
+use module_ethereum::storage::BaseFeePerGas;
+
+type Ethereum = module_ethereum::App<BaseApp>;
+
+fn min_gas_price() -> U256 {
+    <BaseApp as module_ethereum::Config>::FeeCalculator::min_gas_price()
+}
+
+fn target() -> U256 {
+    <BaseApp as module_ethereum::Config>::BlockGasLimit::get() / 2
+}
+
+// The base fee of the next block, from `base_fee` and `gas_used` of this one.
+fn next_base_fee(ctx: &Context, base_fee: U256, gas_used: U256) -> U256 {
+    BaseFeePerGas::put(ctx.state.write().borrow_mut(), &base_fee).unwrap();
+    Ethereum::update_base_fee(ctx, gas_used).unwrap();
+    Ethereum::base_fee(ctx)
+}
+
+#[test]
+fn update_base_fee() {
+    let ctx = BASE_APP.lock().unwrap().deliver_state.clone();
+    let base_fee = min_gas_price() * 8;
+
+    // full blocks raise it by 1/8
+    assert_eq!(
+        next_base_fee(&ctx, base_fee, target() * 2),
+        min_gas_price() * 9
+    );
+    // any usage above the target raises it
+    assert!(next_base_fee(&ctx, base_fee, target() + 1) > base_fee);
+    // unchanged at the target
+    assert_eq!(next_base_fee(&ctx, base_fee, target()), base_fee);
+    // below the target, lowered in proportion
+    let next = next_base_fee(&ctx, base_fee, target() / 2);
+    assert!(next < base_fee && next > min_gas_price() * 7);
+    // empty blocks lower it by 1/8
+    assert_eq!(
+        next_base_fee(&ctx, base_fee, U256::zero()),
+        min_gas_price() * 7
+    );
+    // but never below the minimum gas price
+    assert_eq!(
+        next_base_fee(&ctx, min_gas_price(), U256::zero()),
+        min_gas_price()
+    );
+    assert_eq!(
+        next_base_fee(&ctx, min_gas_price() * 8 / 7, U256::zero()),
+        min_gas_price()
+    );
+}
@@ -1,7 +1,7 @@
 #[cfg(test)]
 mod tests;
 
-use config::abci::global_cfg::CFG;
+use config::abci::upgrade::{is_active, Feature};
 use core::marker::PhantomData;
 use ethereum_types::{H160, U256};
 use evm::{
@@ -88,7 +88,7 @@ impl<C: Config> Precompile for FRC20<C> {
         context: &Context,
         state: &FinState,
     ) -> PrecompileResult {
-        if CFG.checkpoint.disable_delegate_frc20 < state.header.height {
+        if is_active(Feature::DisableDelegateFrc20, state.header.height as u64) {
             let addr = context.address;
             if addr != H160::from_low_u64_be(Self::contract_id()) {
                 return Err(PrecompileFailure::Error {
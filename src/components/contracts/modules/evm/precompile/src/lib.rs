@@ -1,3 +1,4 @@
+use config::abci::upgrade::{is_active, Feature};
 use ethereum_types::H160;
 use evm::{executor::stack::PrecompileSet, Context};
 use module_evm::precompile::{Precompile, PrecompileResult};
@@ -8,6 +9,7 @@ use evm_precompile_basic::{ECRecover, ECRecoverPublicKey, Identity, Ripemd160, S
 use evm_precompile_frc20::FRC20;
 use evm_precompile_modexp::Modexp;
 use evm_precompile_sha3fips::{Sha3FIPS256, Sha3FIPS512};
+use evm_precompile_staking::Staking;
 use fp_core::context::Context as Context2;
 use module_evm::precompile::PrecompileId;
 use module_evm::Config;
@@ -76,6 +78,11 @@ where
             a if a == H160::from_low_u64_be(Anemoi::contract_id()) => {
                 Some(Anemoi::execute(input, target_gas, context, ctx))
             }
+            a if a == H160::from_low_u64_be(Staking::<C>::contract_id())
+                && is_active(Feature::EvmStaking, ctx.header.height as u64) =>
+            {
+                Some(Staking::<C>::execute(input, target_gas, context, ctx))
+            }
             // a if a == H160::from_low_u64_be(EthPairing::contract_id()) => {
             //     Some(EthPairing::execute(handle, ctx))
             // }
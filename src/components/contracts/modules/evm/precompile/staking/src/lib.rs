@@ -0,0 +1,459 @@
+#[cfg(test)]
+mod tests;
+
+use core::marker::PhantomData;
+use ethereum_types::{H160, H256, U256};
+use evm::{
+    executor::stack::{PrecompileFailure, PrecompileOutput},
+    Context, ExitSucceed,
+};
+use evm_precompile_utils::{
+    error, Address, EvmDataReader, EvmDataWriter, EvmResult, Gasometer, LogsBuilder,
+};
+use fp_core::macros::Get;
+use fp_traits::{
+    account::AccountAsset,
+    evm::{AddressMapping, DecimalsMapping},
+};
+use fp_types::actions::xhub::StakingRequest;
+use module_evm::{
+    precompile::{FinState, Precompile, PrecompileId, PrecompileResult},
+    Config,
+};
+use module_xhub::staking;
+use slices::u8_slice;
+use tracing::debug;
+use zei::{
+    serialization::ZeiFromToBytes,
+    xfr::sig::{XfrPublicKey, XfrSignature},
+};
+
+/// Staking bind event selector, Keccak256("Bind(address,bytes32)")
+///
+/// event Bind(address indexed account, bytes32 indexed delegator);
+pub const BIND_EVENT_SELECTOR: &[u8; 32] =
+    u8_slice!("0x8906c1f266f9d28356a1d9dbcd3460a5f0170289993ee185410f81bbb6c24c94");
+
+/// Staking delegate event selector, Keccak256("Delegate(address,address,uint256)")
+///
+/// event Delegate(address indexed account, address indexed validator, uint256 amount);
+pub const DELEGATE_EVENT_SELECTOR: &[u8; 32] =
+    u8_slice!("0x510b11bb3f3c799b11307c01ab7db0d335683ef5b2da98f7697de744f465eacc");
+
+/// Staking undelegate event selector, Keccak256("Undelegate(address,address,uint256)")
+///
+/// event Undelegate(address indexed account, address indexed validator, uint256 amount);
+pub const UNDELEGATE_EVENT_SELECTOR: &[u8; 32] =
+    u8_slice!("0xbda8c0e95802a0e6788c3e9027292382d5a41b86556015f846b03a9874b2b827");
+
+/// Staking claim event selector, Keccak256("Claim(address,uint256)")
+///
+/// event Claim(address indexed account, uint256 amount);
+pub const CLAIM_EVENT_SELECTOR: &[u8; 32] =
+    u8_slice!("0x47cee97cb7acd717b3c0aa1435d004cd5b3c8c57d70dbceb4e4458bbd60e39d4");
+
+/// The prefix of the message signed by the findora key to bind an account,
+/// followed by the big-endian chain id and the address of the account.
+pub const BIND_DOMAIN: &[u8] = b"Findora staking precompile bind:";
+
+/// The message to be signed by the findora key to bind `account` on `chain_id`.
+pub fn bind_message(chain_id: u64, account: H160) -> Vec<u8> {
+    [BIND_DOMAIN, &chain_id.to_be_bytes(), account.as_bytes()].concat()
+}
+
+// Reads cost about the same as the FRC20 ones,
+// writes are charged for the storage and the work of the ledger.
+const GAS_VALIDATOR_INFO: u64 = 1600;
+const GAS_DELEGATOR_OF: u64 = 1350;
+const GAS_LAST_FAILURE: u64 = 1350;
+const GAS_BIND: u64 = 30000;
+const GAS_DELEGATE: u64 = 45000;
+const GAS_UNDELEGATE: u64 = 35000;
+const GAS_CLAIM: u64 = 25000;
+
+/// Delegations of the evm accounts.
+///
+/// An account binds itself to a findora key with a signature of that key
+/// on the `bind_message` of the account, a key is bound to one account at most,
+/// the key is the delegator in the `Staking` of the ledger, and the unbonded
+/// principals and the claimed rewards of it are credited to the account.
+/// The requests are applied by the ledger at the end of the block,
+/// a failed one is kept as the `lastFailure` of the account,
+/// and a failed delegation is refunded to the account.
+pub struct Staking<C> {
+    _marker: PhantomData<C>,
+}
+
+impl<C: Config> PrecompileId for Staking<C> {
+    fn contract_id() -> u64 {
+        0x1001
+    }
+}
+
+#[evm_precompile_utils::generate_function_selector]
+#[derive(Debug, PartialEq, Eq, num_enum::TryFromPrimitive, num_enum::IntoPrimitive)]
+pub enum Call {
+    ValidatorInfo = "validatorInfo(address)",
+    DelegatorOf = "delegatorOf(address)",
+    Bind = "bind(bytes32,bytes32,bytes32)",
+    Delegate = "delegate(address,uint256)",
+    Undelegate = "undelegate(address,uint256)",
+    Claim = "claim(uint256)",
+    LastFailure = "lastFailure(address)",
+}
+
+impl<C: Config> Precompile for Staking<C> {
+    fn execute(
+        input: &[u8],
+        target_gas: Option<u64>,
+        context: &Context,
+        state: &FinState,
+    ) -> PrecompileResult {
+        // the caller is the owner of the delegation
+        if context.address != H160::from_low_u64_be(Self::contract_id()) {
+            return Err(PrecompileFailure::Error {
+                exit_status: error("No delegatecall support"),
+            });
+        }
+
+        let mut input = EvmDataReader::new(input);
+        let selector = match input.read_selector::<Call>() {
+            Ok(v) => v,
+            Err(e) => {
+                return Err(PrecompileFailure::Error { exit_status: e });
+            }
+        };
+
+        match &selector {
+            Call::ValidatorInfo => Self::validator_info(input, target_gas),
+            Call::DelegatorOf => Self::delegator_of(state, input, target_gas),
+            Call::Bind => Self::bind(state, input, target_gas, context),
+            Call::Delegate => Self::delegate(state, input, target_gas, context),
+            Call::Undelegate => Self::undelegate(state, input, target_gas, context),
+            Call::Claim => Self::claim(state, input, target_gas, context),
+            Call::LastFailure => Self::last_failure(state, input, target_gas),
+        }
+        .map_err(|e| PrecompileFailure::Error { exit_status: e })
+    }
+}
+
+impl<C: Config> Staking<C> {
+    /// Returns the power, the delegated amount, the commission rate
+    /// and the jail state of a validator by its tendermint address.
+    fn validator_info(
+        mut input: EvmDataReader,
+        target_gas: Option<u64>,
+    ) -> EvmResult<PrecompileOutput> {
+        let mut gasometer = Gasometer::new(target_gas);
+        gasometer.record_cost(GAS_VALIDATOR_INFO)?;
+
+        input.expect_arguments(1)?;
+
+        let td_addr: H160 = input.read::<Address>()?.into();
+        let v = staking::get_validator(&td_addr)
+            .ok_or_else(|| error("Staking: validator not found"))?;
+        debug!(target: "evm", "Staking#validator_info: {:?}", v);
+
+        Ok(PrecompileOutput {
+            exit_status: ExitSucceed::Returned,
+            cost: gasometer.used_gas(),
+            output: EvmDataWriter::new()
+                .write(Self::from_native(v.power)?)
+                .write(Self::from_native(v.delegated)?)
+                .write(U256::from(v.commission_rate[0]))
+                .write(U256::from(v.commission_rate[1]))
+                .write(v.jailed)
+                .build(),
+            logs: vec![],
+        })
+    }
+
+    /// Returns the findora key bound to `account`, zero if none.
+    fn delegator_of(
+        state: &FinState,
+        mut input: EvmDataReader,
+        target_gas: Option<u64>,
+    ) -> EvmResult<PrecompileOutput> {
+        let mut gasometer = Gasometer::new(target_gas);
+        gasometer.record_cost(GAS_DELEGATOR_OF)?;
+
+        input.expect_arguments(1)?;
+
+        let account: H160 = input.read::<Address>()?.into();
+        let account_id = C::AddressMapping::convert_to_account_id(account);
+        let delegator = staking::delegator_of(state, &account_id)
+            .map(|pk| H256::from_slice(&pk.zei_to_bytes()))
+            .unwrap_or_default();
+        debug!(target: "evm", "Staking#delegator_of: {:?}, {:?}", account, delegator);
+
+        Ok(PrecompileOutput {
+            exit_status: ExitSucceed::Returned,
+            cost: gasometer.used_gas(),
+            output: EvmDataWriter::new().write(delegator).build(),
+            logs: vec![],
+        })
+    }
+
+    /// Returns the height and the kind of the last request of `account`
+    /// the ledger failed to apply, zeros if none,
+    /// the kind is 1 for `delegate`, 2 for `undelegate` and 3 for `claim`.
+    fn last_failure(
+        state: &FinState,
+        mut input: EvmDataReader,
+        target_gas: Option<u64>,
+    ) -> EvmResult<PrecompileOutput> {
+        let mut gasometer = Gasometer::new(target_gas);
+        gasometer.record_cost(GAS_LAST_FAILURE)?;
+
+        input.expect_arguments(1)?;
+
+        let account: H160 = input.read::<Address>()?.into();
+        let account_id = C::AddressMapping::convert_to_account_id(account);
+        let (height, kind) = staking::last_failure(state, &account_id)
+            .map(|f| (f.height, f.request.kind()))
+            .unwrap_or_default();
+        debug!(target: "evm", "Staking#last_failure: {:?}, {}, {}", account, height, kind);
+
+        Ok(PrecompileOutput {
+            exit_status: ExitSucceed::Returned,
+            cost: gasometer.used_gas(),
+            output: EvmDataWriter::new()
+                .write(U256::from(height))
+                .write(kind)
+                .build(),
+            logs: vec![],
+        })
+    }
+
+    /// Binds the caller to a findora key, with the signature of the key
+    /// on the `bind_message` of the caller, which can not be replayed
+    /// on another chain or for another purpose.
+    fn bind(
+        state: &FinState,
+        mut input: EvmDataReader,
+        target_gas: Option<u64>,
+        context: &Context,
+    ) -> EvmResult<PrecompileOutput> {
+        let mut gasometer = Gasometer::new(target_gas);
+        gasometer.record_cost(GAS_BIND)?;
+        gasometer.record_log_costs_manual(3, 0)?;
+
+        input.expect_arguments(3)?;
+
+        let delegator: H256 = input.read()?;
+        let sig_r: H256 = input.read()?;
+        let sig_s: H256 = input.read()?;
+
+        let pk = XfrPublicKey::zei_from_bytes(delegator.as_bytes())
+            .map_err(|_| error("Staking: invalid delegator"))?;
+        let sig = XfrSignature::zei_from_bytes(&[sig_r.0, sig_s.0].concat())
+            .map_err(|_| error("Staking: invalid signature"))?;
+        pk.verify(&bind_message(C::ChainId::get(), context.caller), &sig)
+            .map_err(|_| error("Staking: invalid signature"))?;
+
+        let caller = C::AddressMapping::convert_to_account_id(context.caller);
+        if staking::delegator_of(state, &caller).is_some() {
+            return Err(error("Staking: already bound"));
+        }
+        if staking::owner_of(state, &pk).is_some() {
+            return Err(error("Staking: delegator already bound"));
+        }
+        debug!(target: "evm", "Staking#bind: {:?}, {:?}", context.caller, delegator);
+
+        staking::bind_delegator(state, &caller, &pk)
+            .map_err(|e| error(format!("{e:?}")))?;
+
+        Ok(PrecompileOutput {
+            exit_status: ExitSucceed::Returned,
+            cost: gasometer.used_gas(),
+            output: EvmDataWriter::new().write(true).build(),
+            logs: LogsBuilder::new(context.address)
+                .log3(BIND_EVENT_SELECTOR, context.caller, delegator, vec![])
+                .build(),
+        })
+    }
+
+    /// Delegates `amount` of the caller's balance to a validator,
+    /// the balance is burned and becomes the principal in the `Staking`.
+    fn delegate(
+        state: &FinState,
+        mut input: EvmDataReader,
+        target_gas: Option<u64>,
+        context: &Context,
+    ) -> EvmResult<PrecompileOutput> {
+        let mut gasometer = Gasometer::new(target_gas);
+        gasometer.record_cost(GAS_DELEGATE)?;
+        gasometer.record_log_costs_manual(3, 32)?;
+
+        input.expect_arguments(2)?;
+
+        let validator: H160 = input.read::<Address>()?.into();
+        let amount: U256 = input.read()?;
+
+        let caller = C::AddressMapping::convert_to_account_id(context.caller);
+        let delegator = staking::delegator_of(state, &caller)
+            .ok_or_else(|| error("Staking: caller not bound"))?;
+        let v = staking::get_validator(&validator)
+            .ok_or_else(|| error("Staking: validator not found"))?;
+        if v.jailed {
+            return Err(error("Staking: validator jailed"));
+        }
+
+        let native = Self::to_native(amount)?;
+        if native == 0 {
+            return Err(error("Staking: zero amount"));
+        }
+        // the dust below the native decimals stays in the account
+        let amount = Self::from_native(native)?;
+        if C::AccountAsset::balance(state, &caller) < amount {
+            return Err(error("Staking: insufficient balance"));
+        }
+        debug!(target: "evm",
+            "Staking#delegate: sender: {:?}, validator: {:?}, amount: {:?}",
+            context.caller, validator, amount
+        );
+
+        C::AccountAsset::burn(state, &caller, amount)
+            .map_err(|e| error(format!("{e:?}")))?;
+        staking::add_staking_request(
+            state,
+            StakingRequest::Delegate {
+                owner: caller,
+                delegator,
+                validator,
+                amount: native,
+            },
+        )
+        .map_err(|e| error(format!("{e:?}")))?;
+
+        Ok(PrecompileOutput {
+            exit_status: ExitSucceed::Returned,
+            cost: gasometer.used_gas(),
+            output: EvmDataWriter::new().write(true).build(),
+            logs: LogsBuilder::new(context.address)
+                .log3(
+                    DELEGATE_EVENT_SELECTOR,
+                    context.caller,
+                    validator,
+                    EvmDataWriter::new().write(amount).build(),
+                )
+                .build(),
+        })
+    }
+
+    /// Undelegates `amount` from a validator, or all of the delegations
+    /// of the caller if `amount` is zero.
+    fn undelegate(
+        state: &FinState,
+        mut input: EvmDataReader,
+        target_gas: Option<u64>,
+        context: &Context,
+    ) -> EvmResult<PrecompileOutput> {
+        let mut gasometer = Gasometer::new(target_gas);
+        gasometer.record_cost(GAS_UNDELEGATE)?;
+        gasometer.record_log_costs_manual(3, 32)?;
+
+        input.expect_arguments(2)?;
+
+        let validator: H160 = input.read::<Address>()?.into();
+        let amount: U256 = input.read()?;
+
+        let caller = C::AddressMapping::convert_to_account_id(context.caller);
+        let delegator = staking::delegator_of(state, &caller)
+            .ok_or_else(|| error("Staking: caller not bound"))?;
+        if staking::get_validator(&validator).is_none() {
+            return Err(error("Staking: validator not found"));
+        }
+        let native = Self::to_native(amount)?;
+        debug!(target: "evm",
+            "Staking#undelegate: sender: {:?}, validator: {:?}, amount: {:?}",
+            context.caller, validator, amount
+        );
+
+        staking::add_staking_request(
+            state,
+            StakingRequest::UnDelegate {
+                owner: caller,
+                delegator,
+                validator,
+                amount: Some(native).filter(|am| *am != 0),
+            },
+        )
+        .map_err(|e| error(format!("{e:?}")))?;
+
+        Ok(PrecompileOutput {
+            exit_status: ExitSucceed::Returned,
+            cost: gasometer.used_gas(),
+            output: EvmDataWriter::new().write(true).build(),
+            logs: LogsBuilder::new(context.address)
+                .log3(
+                    UNDELEGATE_EVENT_SELECTOR,
+                    context.caller,
+                    validator,
+                    EvmDataWriter::new().write(amount).build(),
+                )
+                .build(),
+        })
+    }
+
+    /// Claims `amount` of the rewards, or all of them if `amount` is zero.
+    fn claim(
+        state: &FinState,
+        mut input: EvmDataReader,
+        target_gas: Option<u64>,
+        context: &Context,
+    ) -> EvmResult<PrecompileOutput> {
+        let mut gasometer = Gasometer::new(target_gas);
+        gasometer.record_cost(GAS_CLAIM)?;
+        gasometer.record_log_costs_manual(2, 32)?;
+
+        input.expect_arguments(1)?;
+
+        let amount: U256 = input.read()?;
+
+        let caller = C::AddressMapping::convert_to_account_id(context.caller);
+        let delegator = staking::delegator_of(state, &caller)
+            .ok_or_else(|| error("Staking: caller not bound"))?;
+        let native = Self::to_native(amount)?;
+        debug!(target: "evm",
+            "Staking#claim: sender: {:?}, amount: {:?}",
+            context.caller, amount
+        );
+
+        staking::add_staking_request(
+            state,
+            StakingRequest::Claim {
+                owner: caller,
+                delegator,
+                amount: Some(native).filter(|am| *am != 0),
+            },
+        )
+        .map_err(|e| error(format!("{e:?}")))?;
+
+        Ok(PrecompileOutput {
+            exit_status: ExitSucceed::Returned,
+            cost: gasometer.used_gas(),
+            output: EvmDataWriter::new().write(true).build(),
+            logs: LogsBuilder::new(context.address)
+                .log2(
+                    CLAIM_EVENT_SELECTOR,
+                    context.caller,
+                    EvmDataWriter::new().write(amount).build(),
+                )
+                .build(),
+        })
+    }
+
+    fn to_native(amount: U256) -> EvmResult<u64> {
+        let native = C::DecimalsMapping::convert_to_native_token(amount);
+        if native > U256::from(u64::MAX) {
+            return Err(error("Staking: amount too large"));
+        }
+        Ok(native.as_u64())
+    }
+
+    fn from_native(amount: u64) -> EvmResult<U256> {
+        C::DecimalsMapping::from_native_token(U256::from(amount))
+            .ok_or_else(|| error("Staking: amount too large"))
+    }
+}
@@ -0,0 +1,420 @@
+use crate::*;
+use baseapp::BaseApp;
+use fp_mocks::*;
+use fp_types::actions::xhub::{StakingFailure, StakingValidator};
+
+use evm_precompile_utils::{error, EvmDataWriter};
+use module_evm::precompile::Precompile;
+use sha3::{Digest, Keccak256};
+use zei::xfr::sig::XfrKeyPair;
+
+pub const STAKING_PRECOMPILE_ADDRESS: u64 = 0x1001;
+
+fn context(caller: H160) -> evm::Context {
+    evm::Context {
+        address: H160::from_low_u64_be(STAKING_PRECOMPILE_ADDRESS),
+        caller,
+        apparent_value: From::from(0),
+    }
+}
+
+fn td_addr() -> H160 {
+    H160::from_low_u64_be(0xff)
+}
+
+#[test]
+fn selectors() {
+    assert_eq!(Call::ValidatorInfo as u32, 0x4f1811dd);
+    assert_eq!(Call::DelegatorOf as u32, 0x2222ef9f);
+    assert_eq!(Call::Bind as u32, 0xf2c2ca17);
+    assert_eq!(Call::Delegate as u32, 0x026e402b);
+    assert_eq!(Call::Undelegate as u32, 0x4d99dd16);
+    assert_eq!(Call::Claim as u32, 0x379607f5);
+    assert_eq!(Call::LastFailure as u32, 0xbe691abc);
+
+    assert_eq!(
+        BIND_EVENT_SELECTOR,
+        &Keccak256::digest(b"Bind(address,bytes32)")[..]
+    );
+    assert_eq!(
+        DELEGATE_EVENT_SELECTOR,
+        &Keccak256::digest(b"Delegate(address,address,uint256)")[..]
+    );
+    assert_eq!(
+        UNDELEGATE_EVENT_SELECTOR,
+        &Keccak256::digest(b"Undelegate(address,address,uint256)")[..]
+    );
+    assert_eq!(
+        CLAIM_EVENT_SELECTOR,
+        &Keccak256::digest(b"Claim(address,uint256)")[..]
+    );
+}
+
+#[test]
+fn no_delegatecall() {
+    assert_eq!(
+        Staking::<BaseApp>::execute(
+            &EvmDataWriter::new().write_selector(Call::Claim).build(),
+            None,
+            &evm::Context {
+                address: H160::from_low_u64_be(9),
+                caller: ALICE_ECDSA.address,
+                apparent_value: From::from(0),
+            },
+            &BASE_APP.lock().unwrap().deliver_state,
+        ),
+        Err(PrecompileFailure::Error {
+            exit_status: error("No delegatecall support")
+        })
+    );
+}
+
+#[test]
+fn staking_works() {
+    test_mint_balance(
+        &ALICE_ECDSA.account_id,
+        U256::from(10_000_000_000_000_000_000_u128),
+        1,
+    );
+    module_xhub::staking::set_validators(vec![StakingValidator {
+        td_addr: td_addr(),
+        power: 100_000_000,
+        commission_rate: [1, 100],
+        delegated: 0,
+        jailed: false,
+    }]);
+
+    not_bound_fails();
+    bind_works();
+    validator_info_works();
+    delegate_works();
+    reverted_delegate_is_discarded();
+    undelegate_and_claim_works();
+    last_failure_works();
+}
+
+fn not_bound_fails() {
+    assert_eq!(
+        Staking::<BaseApp>::execute(
+            &EvmDataWriter::new()
+                .write_selector(Call::Delegate)
+                .write(Address(td_addr()))
+                .write(U256::from(1))
+                .build(),
+            None,
+            &context(ALICE_ECDSA.address),
+            &BASE_APP.lock().unwrap().deliver_state,
+        ),
+        Err(PrecompileFailure::Error {
+            exit_status: error("Staking: caller not bound")
+        })
+    );
+}
+
+fn bind_works() {
+    let delegator = H256::from_slice(&ALICE_XFR.get_pk().zei_to_bytes());
+    let chain_id = <BaseApp as Config>::ChainId::get();
+    let bind = |signer: &XfrKeyPair, msg: &[u8]| {
+        let sig = signer.sign(msg).zei_to_bytes();
+        Staking::<BaseApp>::execute(
+            &EvmDataWriter::new()
+                .write_selector(Call::Bind)
+                .write(delegator)
+                .write(H256::from_slice(&sig[..32]))
+                .write(H256::from_slice(&sig[32..]))
+                .build(),
+            None,
+            &context(ALICE_ECDSA.address),
+            &BASE_APP.lock().unwrap().deliver_state,
+        )
+    };
+
+    let msg = bind_message(chain_id, ALICE_ECDSA.address);
+
+    // signed by another key
+    assert_eq!(
+        bind(&BOB_XFR, &msg),
+        Err(PrecompileFailure::Error {
+            exit_status: error("Staking: invalid signature")
+        })
+    );
+
+    // signed without the domain, or for another chain
+    for msg in [
+        ALICE_ECDSA.address.as_bytes().to_vec(),
+        bind_message(chain_id + 1, ALICE_ECDSA.address),
+    ] {
+        assert_eq!(
+            bind(&ALICE_XFR, &msg),
+            Err(PrecompileFailure::Error {
+                exit_status: error("Staking: invalid signature")
+            })
+        );
+    }
+
+    assert_eq!(
+        bind(&ALICE_XFR, &msg),
+        Ok(PrecompileOutput {
+            exit_status: ExitSucceed::Returned,
+            output: EvmDataWriter::new().write(true).build(),
+            cost: GAS_BIND + 1500,
+            logs: LogsBuilder::new(H160::from_low_u64_be(STAKING_PRECOMPILE_ADDRESS))
+                .log3(BIND_EVENT_SELECTOR, ALICE_ECDSA.address, delegator, vec![])
+                .build(),
+        })
+    );
+
+    assert_eq!(
+        bind(&ALICE_XFR, &msg),
+        Err(PrecompileFailure::Error {
+            exit_status: error("Staking: already bound")
+        })
+    );
+
+    // the key is bound to one account at most
+    let msg = bind_message(chain_id, BOB_ECDSA.address);
+    let sig = ALICE_XFR.sign(&msg).zei_to_bytes();
+    assert_eq!(
+        Staking::<BaseApp>::execute(
+            &EvmDataWriter::new()
+                .write_selector(Call::Bind)
+                .write(delegator)
+                .write(H256::from_slice(&sig[..32]))
+                .write(H256::from_slice(&sig[32..]))
+                .build(),
+            None,
+            &context(BOB_ECDSA.address),
+            &BASE_APP.lock().unwrap().deliver_state,
+        ),
+        Err(PrecompileFailure::Error {
+            exit_status: error("Staking: delegator already bound")
+        })
+    );
+    assert_eq!(
+        staking::owner_of(&BASE_APP.lock().unwrap().deliver_state, &ALICE_XFR.get_pk()),
+        Some(ALICE_ECDSA.account_id.clone())
+    );
+
+    assert_eq!(
+        Staking::<BaseApp>::execute(
+            &EvmDataWriter::new()
+                .write_selector(Call::DelegatorOf)
+                .write(Address(ALICE_ECDSA.address))
+                .build(),
+            None,
+            &context(BOB_ECDSA.address),
+            &BASE_APP.lock().unwrap().deliver_state,
+        ),
+        Ok(PrecompileOutput {
+            exit_status: ExitSucceed::Returned,
+            output: EvmDataWriter::new().write(delegator).build(),
+            cost: GAS_DELEGATOR_OF,
+            logs: Default::default(),
+        })
+    );
+}
+
+fn validator_info_works() {
+    assert_eq!(
+        Staking::<BaseApp>::execute(
+            &EvmDataWriter::new()
+                .write_selector(Call::ValidatorInfo)
+                .write(Address(td_addr()))
+                .build(),
+            None,
+            &context(ALICE_ECDSA.address),
+            &BASE_APP.lock().unwrap().deliver_state,
+        ),
+        Ok(PrecompileOutput {
+            exit_status: ExitSucceed::Returned,
+            output: EvmDataWriter::new()
+                .write(U256::from(100_000_000_000_000_000_000_u128))
+                .write(U256::zero())
+                .write(U256::from(1))
+                .write(U256::from(100))
+                .write(false)
+                .build(),
+            cost: GAS_VALIDATOR_INFO,
+            logs: Default::default(),
+        })
+    );
+
+    assert_eq!(
+        Staking::<BaseApp>::execute(
+            &EvmDataWriter::new()
+                .write_selector(Call::ValidatorInfo)
+                .write(Address(H160::zero()))
+                .build(),
+            None,
+            &context(ALICE_ECDSA.address),
+            &BASE_APP.lock().unwrap().deliver_state,
+        ),
+        Err(PrecompileFailure::Error {
+            exit_status: error("Staking: validator not found")
+        })
+    );
+}
+
+fn delegate_works() {
+    // 1 FRA and some dust below the native decimals
+    let amount = U256::from(1_000_000_000_000_000_001_u128);
+    let burned = U256::from(1_000_000_000_000_000_000_u128);
+
+    assert_eq!(
+        Staking::<BaseApp>::execute(
+            &EvmDataWriter::new()
+                .write_selector(Call::Delegate)
+                .write(Address(td_addr()))
+                .write(amount)
+                .build(),
+            None,
+            &context(ALICE_ECDSA.address),
+            &BASE_APP.lock().unwrap().deliver_state,
+        ),
+        Ok(PrecompileOutput {
+            exit_status: ExitSucceed::Returned,
+            output: EvmDataWriter::new().write(true).build(),
+            cost: GAS_DELEGATE + 1756,
+            logs: LogsBuilder::new(H160::from_low_u64_be(STAKING_PRECOMPILE_ADDRESS))
+                .log3(
+                    DELEGATE_EVENT_SELECTOR,
+                    ALICE_ECDSA.address,
+                    td_addr(),
+                    EvmDataWriter::new().write(burned).build(),
+                )
+                .build(),
+        })
+    );
+
+    let base_app = BASE_APP.lock().unwrap();
+    assert_eq!(
+        module_account::App::<BaseApp>::balance(
+            &base_app.deliver_state,
+            &ALICE_ECDSA.account_id
+        ),
+        U256::from(9_000_000_000_000_000_000_u128)
+    );
+    assert_eq!(
+        staking::consume_staking_requests(&base_app.deliver_state),
+        Some(vec![StakingRequest::Delegate {
+            owner: ALICE_ECDSA.account_id.clone(),
+            delegator: ALICE_XFR.get_pk(),
+            validator: td_addr(),
+            amount: 1_000_000,
+        }])
+    );
+}
+
+fn reverted_delegate_is_discarded() {
+    let base_app = BASE_APP.lock().unwrap();
+    let ctx = &base_app.deliver_state;
+    let balance = module_account::App::<BaseApp>::balance(ctx, &ALICE_ECDSA.account_id);
+
+    // the frame of a call reverted by its caller
+    ctx.state.write().stack_push();
+    assert!(Staking::<BaseApp>::execute(
+        &EvmDataWriter::new()
+            .write_selector(Call::Delegate)
+            .write(Address(td_addr()))
+            .write(U256::from(1_000_000_000_000_000_000_u128))
+            .build(),
+        None,
+        &context(ALICE_ECDSA.address),
+        ctx,
+    )
+    .is_ok());
+    ctx.state.write().stack_discard();
+
+    assert_eq!(
+        module_account::App::<BaseApp>::balance(ctx, &ALICE_ECDSA.account_id),
+        balance
+    );
+    assert_eq!(staking::consume_staking_requests(ctx), None);
+}
+
+fn undelegate_and_claim_works() {
+    let call = |input: Vec<u8>| {
+        Staking::<BaseApp>::execute(
+            &input,
+            None,
+            &context(ALICE_ECDSA.address),
+            &BASE_APP.lock().unwrap().deliver_state,
+        )
+    };
+
+    assert!(call(
+        EvmDataWriter::new()
+            .write_selector(Call::Undelegate)
+            .write(Address(td_addr()))
+            .write(U256::zero())
+            .build()
+    )
+    .is_ok());
+    assert!(call(
+        EvmDataWriter::new()
+            .write_selector(Call::Claim)
+            .write(U256::from(2_000_000_000_000_u64))
+            .build()
+    )
+    .is_ok());
+
+    assert_eq!(
+        staking::consume_staking_requests(&BASE_APP.lock().unwrap().deliver_state),
+        Some(vec![
+            StakingRequest::UnDelegate {
+                owner: ALICE_ECDSA.account_id.clone(),
+                delegator: ALICE_XFR.get_pk(),
+                validator: td_addr(),
+                amount: None,
+            },
+            StakingRequest::Claim {
+                owner: ALICE_ECDSA.account_id.clone(),
+                delegator: ALICE_XFR.get_pk(),
+                amount: Some(2),
+            },
+        ])
+    );
+}
+
+fn last_failure_works() {
+    let call = || {
+        Staking::<BaseApp>::execute(
+            &EvmDataWriter::new()
+                .write_selector(Call::LastFailure)
+                .write(Address(ALICE_ECDSA.address))
+                .build(),
+            None,
+            &context(BOB_ECDSA.address),
+            &BASE_APP.lock().unwrap().deliver_state,
+        )
+    };
+    let output = |height: u64, kind: u8| {
+        Ok(PrecompileOutput {
+            exit_status: ExitSucceed::Returned,
+            output: EvmDataWriter::new()
+                .write(U256::from(height))
+                .write(kind)
+                .build(),
+            cost: GAS_LAST_FAILURE,
+            logs: Default::default(),
+        })
+    };
+
+    assert_eq!(call(), output(0, 0));
+
+    // recorded by the ledger
+    staking::record_failure(
+        &BASE_APP.lock().unwrap().deliver_state,
+        &StakingFailure {
+            height: 9,
+            request: StakingRequest::Claim {
+                owner: ALICE_ECDSA.account_id.clone(),
+                delegator: ALICE_XFR.get_pk(),
+                amount: Some(2),
+            },
+            reason: "claim amount exceed total rewards".to_owned(),
+        },
+    )
+    .unwrap();
+    assert_eq!(call(), output(9, 3));
+}
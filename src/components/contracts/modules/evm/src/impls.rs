@@ -1,16 +1,20 @@
+use crate::runtime::runner::ActionRunner;
 use crate::storage::*;
+use crate::system_contracts::{SystemContracts, SYSTEM_ADDR};
 use crate::{App, Config};
+use ethabi::{ParamType, Token};
 use ethereum_types::{H160, H256, U256};
 use fp_core::context::Context;
 use fp_evm::Account;
 use fp_storage::{Borrow, BorrowMut};
 use fp_traits::{
     account::AccountAsset,
-    evm::{AddressMapping, OnChargeEVMTransaction},
+    evm::{AddressMapping, AssetBridge, OnChargeEVMTransaction},
 };
 use fp_types::crypto::{HA160, HA256};
 use fp_utils::proposer_converter;
-use ruc::Result;
+use ruc::*;
+use std::str::FromStr;
 
 impl<C: Config> App<C> {
     /// Check whether an account is empty.
@@ -108,4 +112,77 @@ impl<C: Config> OnChargeEVMTransaction for App<C> {
         C::AccountAsset::refund(ctx, &account_id, already_withdrawn)?;
         C::AccountAsset::burn(ctx, &account_id, corrected_fee)
     }
+
+    fn pay_priority_fee(ctx: &Context, tip: U256) -> Result<()> {
+        if tip.is_zero() {
+            return Ok(());
+        }
+        let author = C::AddressMapping::convert_to_account_id(Self::find_proposer(ctx));
+        C::AccountAsset::mint(ctx, &author, tip)
+    }
+}
+
+/// The gas limit of the calls of the bridge to the FRC20 contracts.
+const ASSET_BRIDGE_GAS_LIMIT: u64 = 9999999;
+
+/// Lock the FRC20 tokens in the prism bridge, as its `depositFRC20` does,
+/// they are released by its `withdrawAsset` when converted back.
+impl<C: Config> AssetBridge for App<C> {
+    fn lock_asset(
+        ctx: &Context,
+        who: H160,
+        token: H160,
+        asset: [u8; 32],
+        amount: U256,
+    ) -> Result<()> {
+        let contracts = SystemContracts::new()?;
+        let system = H160::from_str(SYSTEM_ADDR).c(d!())?;
+
+        let function = contracts.bridge.function("computeERC20AssetType").c(d!())?;
+        let input = function.encode_input(&[Token::Address(token)]).c(d!())?;
+        let (data, _, _) = ActionRunner::<C>::execute_systemc_contract(
+            ctx,
+            input,
+            system,
+            ASSET_BRIDGE_GAS_LIMIT,
+            contracts.bridge_address,
+            U256::zero(),
+        )?;
+        let mapped = function
+            .decode_output(&data)
+            .c(d!())?
+            .pop()
+            .and_then(Token::into_fixed_bytes)
+            .c(d!())?;
+        if mapped != asset {
+            return Err(eg!("the FRC20 token is not mapped to the asset"));
+        }
+
+        let params = [ParamType::Address, ParamType::Uint(256)];
+        let mut input = ethabi::short_signature("transfer", &params).to_vec();
+        input.extend(ethabi::encode(&[
+            Token::Address(contracts.bridge_address),
+            Token::Uint(amount),
+        ]));
+        let (data, _, _) = ActionRunner::<C>::execute_systemc_contract(
+            ctx,
+            input,
+            who,
+            ASSET_BRIDGE_GAS_LIMIT,
+            token,
+            U256::zero(),
+        )?;
+        // some tokens return nothing on success
+        let succeed = data.is_empty()
+            || ethabi::decode(&[ParamType::Bool], &data)
+                .ok()
+                .and_then(|mut r| r.pop())
+                .and_then(Token::into_bool)
+                .unwrap_or(false);
+        if !succeed {
+            return Err(eg!("failed to lock the FRC20 token in the bridge"));
+        }
+
+        Ok(())
+    }
 }
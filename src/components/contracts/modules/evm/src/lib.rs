@@ -29,7 +29,10 @@ use fp_evm::TransactionStatus;
 use fp_storage::Borrow;
 use fp_traits::{
     account::AccountAsset,
-    evm::{AddressMapping, BlockHashMapping, DecimalsMapping, FeeCalculator},
+    evm::{
+        AddressMapping, BaseFeeCalculator, BlockHashMapping, DecimalsMapping,
+        FeeCalculator,
+    },
 };
 use fp_types::{
     actions::evm::Action,
@@ -41,7 +44,7 @@ use runtime::runner::ActionRunner;
 pub use runtime::*;
 use std::marker::PhantomData;
 use std::str::FromStr;
-use system_contracts::{SystemContracts, SYSTEM_ADDR};
+use system_contracts::{SystemContracts, SYSTEM_ADDR, UTXO_BRIDGE_ADDR};
 use zei::xfr::sig::XfrPublicKey;
 
 pub const MODULE_NAME: &str = "evm";
@@ -61,6 +64,8 @@ pub trait Config {
     type DecimalsMapping: DecimalsMapping;
     /// Calculator for current gas price.
     type FeeCalculator: FeeCalculator;
+    /// Calculator for the base fee of current block.
+    type BaseFeeCalculator: BaseFeeCalculator;
     /// Precompiles associated with this EVM engine.
     type Precompiles: PrecompileSet;
     type PrecompilesType: EvmPrecompileSet;
@@ -202,6 +207,42 @@ impl<C: Config> App<C> {
         ))
     }
 
+    /// A system transaction of `UTXO_BRIDGE_ADDR` carrying the logs of the
+    /// conversions between the utxos and the evm accounts.
+    pub fn utxo_bridge_transaction(
+        transaction_hash: H256,
+        transaction_index: u32,
+        logs: Vec<Log>,
+    ) -> (TransactionV0, TransactionStatus, Receipt) {
+        let from = H160::from_str(SYSTEM_ADDR).unwrap();
+        let to = H160::from_str(UTXO_BRIDGE_ADDR).unwrap();
+        Self::system_transaction(
+            transaction_hash,
+            Vec::new(),
+            U256::zero(),
+            TransactionAction::Call(to),
+            U256::zero(),
+            U256::one(),
+            U256::zero(),
+            transaction_index,
+            from,
+            to,
+            logs,
+        )
+    }
+
+    /// Append `logs` to a system transaction.
+    pub fn append_logs(
+        tx_status: &mut TransactionStatus,
+        receipt: &mut Receipt,
+        mut logs: Vec<Log>,
+    ) {
+        Self::logs_bloom(&logs, &mut tx_status.logs_bloom);
+        tx_status.logs.append(&mut logs);
+        receipt.logs_bloom = tx_status.logs_bloom;
+        receipt.logs = tx_status.logs.clone();
+    }
+
     fn logs_bloom(logs: &[ethereum::Log], bloom: &mut Bloom) {
         for log in logs {
             bloom.accrue(BloomInput::Raw(&log.address[..]));
@@ -1,6 +1,7 @@
 use super::stack::FindoraStackState;
 // use crate::precompile::PrecompileSet;
 use crate::{App, Config};
+use config::abci::upgrade::{is_active, Feature};
 use ethereum_types::{H160, H256, U256};
 use evm::{
     executor::stack::{StackExecutor, StackSubstateMetadata},
@@ -8,7 +9,7 @@ use evm::{
 };
 use fp_core::{context::Context, ensure, macros::Get2};
 use fp_evm::*;
-use fp_traits::evm::{FeeCalculator, OnChargeEVMTransaction};
+use fp_traits::evm::{BaseFeeCalculator, FeeCalculator, OnChargeEVMTransaction};
 use fp_types::actions::evm::*;
 use ruc::*;
 use sha3::{Digest, Keccak256};
@@ -29,6 +30,7 @@ impl<C: Config> ActionRunner<C> {
         value: U256,
         gas_limit: u64,
         gas_price: Option<U256>,
+        max_priority_fee_per_gas: Option<U256>,
         nonce: Option<U256>,
         config: &'config evm::Config,
         precompiles: &'precompiles C::PrecompilesType,
@@ -45,15 +47,33 @@ impl<C: Config> ActionRunner<C> {
         ) -> (ExitReason, R),
     {
         // Gas price check is skipped when performing a gas estimation.
-        let gas_price = match gas_price {
-            Some(gas_price) => {
+        let (gas_price, priority_fee) = match (gas_price, max_priority_fee_per_gas) {
+            // EIP-1559, `gas_price` is the max fee per gas,
+            // pays the base fee and a tip no more than it.
+            (Some(max_fee), Some(max_priority_fee)) => {
+                let base_fee = C::BaseFeeCalculator::base_fee(ctx);
+                ensure!(max_fee >= base_fee, "GasPriceTooLow");
+                ensure!(max_fee >= max_priority_fee, "PriorityFeeTooHigh");
+                let priority_fee = max_priority_fee.min(max_fee - base_fee);
+                (base_fee + priority_fee, priority_fee)
+            }
+            // Legacy and EIP-2930, since the base fee is introduced,
+            // pays the base fee and the rest of the gas price as a tip.
+            (Some(gas_price), None)
+                if is_active(Feature::TypedTransaction, ctx.header.height as u64) =>
+            {
+                let base_fee = C::BaseFeeCalculator::base_fee(ctx);
+                ensure!(gas_price >= base_fee, "GasPriceTooLow");
+                (gas_price, gas_price - base_fee)
+            }
+            (Some(gas_price), None) => {
                 ensure!(
                     gas_price >= C::FeeCalculator::min_gas_price(),
                     "GasPriceTooLow"
                 );
-                gas_price
+                (gas_price, U256::zero())
             }
-            None => Default::default(),
+            (None, _) => Default::default(),
         };
 
         let vicinity = Vicinity {
@@ -111,6 +131,8 @@ impl<C: Config> ActionRunner<C> {
         if !config.estimate {
             // Refund fees to the `source` account if deducted more before,
             App::<C>::correct_and_deposit_fee(ctx, &source, actual_fee, total_fee)?;
+            // The fee is burnt except the tip, which goes to the block author.
+            App::<C>::pay_priority_fee(ctx, priority_fee.saturating_mul(used_gas))?;
         }
 
         let state = executor.into_state();
@@ -207,7 +229,7 @@ impl<C: Config> ActionRunner<C> {
 impl<C: Config> Runner for ActionRunner<C> {
     fn call(ctx: &Context, args: Call, config: &evm::Config) -> Result<CallInfo> {
         let precompiles = C::PrecompilesValue::get(ctx.clone());
-        let access_list = Vec::new();
+        let access_list = args.access_list;
 
         Self::execute(
             ctx,
@@ -215,6 +237,7 @@ impl<C: Config> Runner for ActionRunner<C> {
             args.value,
             args.gas_limit,
             args.gas_price,
+            args.max_priority_fee_per_gas,
             args.nonce,
             config,
             &precompiles,
@@ -233,7 +256,7 @@ impl<C: Config> Runner for ActionRunner<C> {
 
     fn create(ctx: &Context, args: Create, config: &evm::Config) -> Result<CreateInfo> {
         let precompiles = C::PrecompilesValue::get(ctx.clone());
-        let access_list = Vec::new();
+        let access_list = args.access_list;
 
         Self::execute(
             ctx,
@@ -241,6 +264,7 @@ impl<C: Config> Runner for ActionRunner<C> {
             args.value,
             args.gas_limit,
             args.gas_price,
+            args.max_priority_fee_per_gas,
             args.nonce,
             config,
             &precompiles,
@@ -272,7 +296,7 @@ impl<C: Config> Runner for ActionRunner<C> {
     ) -> Result<CreateInfo> {
         let code_hash = H256::from_slice(Keccak256::digest(&args.init).as_slice());
         let precompiles = C::PrecompilesValue::get(ctx.clone());
-        let access_list = Vec::new();
+        let access_list = args.access_list;
 
         Self::execute(
             ctx,
@@ -280,6 +304,7 @@ impl<C: Config> Runner for ActionRunner<C> {
             args.value,
             args.gas_limit,
             args.gas_price,
+            args.max_priority_fee_per_gas,
             args.nonce,
             config,
             &precompiles,
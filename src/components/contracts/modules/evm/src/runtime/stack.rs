@@ -1,5 +1,5 @@
 use crate::{storage::*, AddressMapping, App, Config};
-use config::abci::global_cfg::CFG;
+use config::abci::upgrade::{is_active, Feature};
 use ethereum_types::{H160, H256, U256};
 use evm::{
     backend::Backend,
@@ -39,11 +39,11 @@ impl<'context, 'config> FindoraStackSubstate<'context, 'config> {
 
     pub fn enter(&mut self, gas_limit: u64, is_static: bool) {
         let mut substate = None;
-        if self.ctx.header.height < CFG.checkpoint.tx_revert_on_error_height {
+        if !is_active(Feature::TxRevertOnError, self.ctx.header.height as u64) {
             self.ctx.state.write().commit_session(); // before substate
-        } else if self.ctx.header.height >= CFG.checkpoint.evm_substate_v2_height {
+        } else if is_active(Feature::EvmSubstateV2, self.ctx.header.height as u64) {
             self.ctx.state.write().stack_push(); // substate v2
-        } else if self.ctx.header.height >= CFG.checkpoint.evm_substate_height {
+        } else if is_active(Feature::EvmSubstate, self.ctx.header.height as u64) {
             substate = Some((*self.ctx.state.read()).substate()); // substate v1
         } else {
             // else does nothing
@@ -70,9 +70,9 @@ impl<'context, 'config> FindoraStackSubstate<'context, 'config> {
         self.logs.append(&mut exited.logs);
         self.deletes.append(&mut exited.deletes);
 
-        if self.ctx.header.height < CFG.checkpoint.tx_revert_on_error_height {
+        if !is_active(Feature::TxRevertOnError, self.ctx.header.height as u64) {
             self.ctx.state.write().commit_session(); // before substate
-        } else if self.ctx.header.height >= CFG.checkpoint.evm_substate_v2_height {
+        } else if is_active(Feature::EvmSubstateV2, self.ctx.header.height as u64) {
             self.ctx.state.write().stack_commit(); // substate v2
         } else {
             // substate v1 and else do nothing
@@ -86,11 +86,11 @@ impl<'context, 'config> FindoraStackSubstate<'context, 'config> {
         mem::swap(&mut exited, self);
         self.metadata.swallow_revert(exited.metadata)?;
 
-        if self.ctx.header.height < CFG.checkpoint.tx_revert_on_error_height {
+        if !is_active(Feature::TxRevertOnError, self.ctx.header.height as u64) {
             self.ctx.state.write().discard_session(); // before substate
-        } else if self.ctx.header.height >= CFG.checkpoint.evm_substate_v2_height {
+        } else if is_active(Feature::EvmSubstateV2, self.ctx.header.height as u64) {
             self.ctx.state.write().stack_discard(); // substate v2
-        } else if self.ctx.header.height >= CFG.checkpoint.evm_substate_height {
+        } else if is_active(Feature::EvmSubstate, self.ctx.header.height as u64) {
             let _ = mem::replace(
                 self.ctx.state.write().deref_mut(),
                 exited.substate.unwrap(),
@@ -107,11 +107,11 @@ impl<'context, 'config> FindoraStackSubstate<'context, 'config> {
         mem::swap(&mut exited, self);
         self.metadata.swallow_discard(exited.metadata)?;
 
-        if self.ctx.header.height < CFG.checkpoint.tx_revert_on_error_height {
+        if !is_active(Feature::TxRevertOnError, self.ctx.header.height as u64) {
             self.ctx.state.write().discard_session(); // before substate
-        } else if self.ctx.header.height >= CFG.checkpoint.evm_substate_v2_height {
+        } else if is_active(Feature::EvmSubstateV2, self.ctx.header.height as u64) {
             self.ctx.state.write().stack_discard(); // substate v2
-        } else if self.ctx.header.height >= CFG.checkpoint.evm_substate_height {
+        } else if is_active(Feature::EvmSubstate, self.ctx.header.height as u64) {
             let _ = mem::replace(
                 self.ctx.state.write().deref_mut(),
                 exited.substate.unwrap(),
@@ -180,9 +180,8 @@ impl<'context, 'vicinity, 'config, C: Config>
     ) -> Self {
         // two versions of EVM substate implementation
         let mut substate = None;
-        if ctx.header.height >= CFG.checkpoint.evm_substate_height
-            && ctx.header.height < CFG.checkpoint.evm_substate_v2_height
-        {
+        let h = ctx.header.height as u64;
+        if is_active(Feature::EvmSubstate, h) && !is_active(Feature::EvmSubstateV2, h) {
             substate = Some((*ctx.state.read()).substate());
         }
 
@@ -8,6 +8,10 @@ use serde::{Deserialize, Serialize};
 
 pub static SYSTEM_ADDR: &str = "0x0000000000000000000000000000000000002000";
 
+/// The reserved address of the logs of the conversions
+/// between the utxos and the evm accounts.
+pub static UTXO_BRIDGE_ADDR: &str = "0x0000000000000000000000000000000000002003";
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct SystemContracts {
     pub bridge: Contract,
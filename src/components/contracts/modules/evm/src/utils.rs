@@ -1,8 +1,12 @@
-use ethabi::{Event, EventParam, ParamType, RawLog};
+use crate::system_contracts::UTXO_BRIDGE_ADDR;
+use ethabi::{Event, EventParam, ParamType, RawLog, Token};
+use ethereum::Log;
+use ethereum_types::{H160, H256, U256};
 use fp_traits::evm::{DecimalsMapping, EthereumDecimalsMapping};
-use fp_types::actions::xhub::NonConfidentialOutput;
+use fp_types::actions::xhub::UtxoOutput;
 use ledger::data_model::ASSET_TYPE_FRA;
 use ruc::*;
+use std::str::FromStr;
 use zei::{
     serialization::ZeiFromToBytes,
     xfr::{
@@ -51,7 +55,7 @@ pub fn deposit_asset_event_topic_str() -> String {
     "[0x".to_owned() + &*temp + &*"]".to_owned()
 }
 
-pub fn parse_deposit_asset_event(data: Vec<u8>) -> Result<NonConfidentialOutput> {
+pub fn parse_deposit_asset_event(data: Vec<u8>) -> Result<UtxoOutput> {
     let event = deposit_asset_event();
     let log = RawLog {
         topics: vec![event.signature()],
@@ -82,11 +86,95 @@ pub fn parse_deposit_asset_event(data: Vec<u8>) -> Result<NonConfidentialOutput>
     let decimal = result.params[3].value.clone().into_uint().c(d!())?;
     let max_supply = result.params[4].value.clone().into_uint().c(d!())?;
 
-    Ok(NonConfidentialOutput {
+    Ok(UtxoOutput {
         asset: asset_type,
         amount,
         target,
+        transfer: None,
+        token: None,
         decimal: decimal.as_u64() as u8,
         max_supply: max_supply.as_u64(),
     })
 }
+
+fn utxo_bridge_event(name: &str, target: ParamType) -> Event {
+    Event {
+        name: name.to_owned(),
+        inputs: vec![
+            EventParam {
+                name: "source".to_owned(),
+                kind: ParamType::FixedBytes(32),
+                indexed: true,
+            },
+            EventParam {
+                name: "target".to_owned(),
+                kind: target,
+                indexed: true,
+            },
+            EventParam {
+                name: "asset".to_owned(),
+                kind: ParamType::FixedBytes(32),
+                indexed: true,
+            },
+            EventParam {
+                name: "amount".to_owned(),
+                kind: ParamType::Uint(256),
+                indexed: false,
+            },
+        ],
+        anonymous: false,
+    }
+}
+
+/// `UtxoDeposit(bytes32 indexed source, address indexed target, bytes32 indexed asset, uint256 amount)`,
+/// the utxos of the transaction `source` converted to the evm account `target`.
+pub fn utxo_deposit_event() -> Event {
+    utxo_bridge_event("UtxoDeposit", ParamType::Address)
+}
+
+/// `UtxoWithdraw(bytes32 indexed source, bytes32 indexed target, bytes32 indexed asset, uint256 amount)`,
+/// the balance burned by the transaction `source` to mint an utxo to the findora key `target`.
+pub fn utxo_withdraw_event() -> Event {
+    utxo_bridge_event("UtxoWithdraw", ParamType::FixedBytes(32))
+}
+
+fn utxo_bridge_log(
+    event: Event,
+    source: H256,
+    target: H256,
+    asset: AssetType,
+    amount: U256,
+) -> Log {
+    Log {
+        address: H160::from_str(UTXO_BRIDGE_ADDR).unwrap_or_default(),
+        topics: vec![event.signature(), source, target, H256(asset.0)],
+        data: ethabi::encode(&[Token::Uint(amount)]),
+    }
+}
+
+/// The log of `UtxoDeposit`, `amount` is in the unit of the evm side.
+pub fn utxo_deposit_log(
+    source: H256,
+    target: H160,
+    asset: AssetType,
+    amount: U256,
+) -> Log {
+    utxo_bridge_log(
+        utxo_deposit_event(),
+        source,
+        H256::from(target),
+        asset,
+        amount,
+    )
+}
+
+/// The log of `UtxoWithdraw`, `amount` is in the unit of the evm side.
+pub fn utxo_withdraw_log(
+    source: H256,
+    target: &XfrPublicKey,
+    asset: AssetType,
+    amount: U256,
+) -> Log {
+    let target = H256::from_slice(&target.zei_to_bytes());
+    utxo_bridge_log(utxo_withdraw_event(), source, target, asset, amount)
+}
@@ -288,6 +288,8 @@ fn test_balance_of_with_eth_call(contract: ERC20, who: H160) -> U256 {
         gas_limit: DEFAULT_GAS_LIMIT,
         gas_price: None,
         nonce: None,
+        max_priority_fee_per_gas: None,
+        access_list: Vec::new(),
     };
 
     let info = <BaseApp as module_ethereum::Config>::Runner::call(
@@ -0,0 +1,74 @@
+//! Decoding of the logs of the conversions between the utxos and the evm accounts.
+
+use ethabi::{RawLog, Token};
+use ethereum_types::{H160, H256, U256};
+use fp_mocks::*;
+use module_evm::{
+    system_contracts::UTXO_BRIDGE_ADDR,
+    utils::{
+        utxo_deposit_event, utxo_deposit_log, utxo_withdraw_event, utxo_withdraw_log,
+    },
+};
+use std::str::FromStr;
+use zei::{serialization::ZeiFromToBytes, xfr::structs::AssetType};
+
+fn decode(event: &ethabi::Event, log: ethereum::Log) -> Vec<Token> {
+    assert_eq!(log.address, H160::from_str(UTXO_BRIDGE_ADDR).unwrap());
+    assert_eq!(log.topics[0], event.signature());
+
+    event
+        .parse_log(RawLog {
+            topics: log.topics,
+            data: log.data,
+        })
+        .unwrap()
+        .params
+        .into_iter()
+        .map(|p| p.value)
+        .collect()
+}
+
+#[test]
+fn decode_utxo_deposit() {
+    let source = H256::repeat_byte(1);
+    let asset = AssetType([2; 32]);
+    let amount = U256::from(10).pow(U256::from(18));
+
+    let log = utxo_deposit_log(source, ALICE_ECDSA.address, asset, amount);
+    assert_eq!(
+        decode(&utxo_deposit_event(), log),
+        vec![
+            Token::FixedBytes(source.as_bytes().to_vec()),
+            Token::Address(ALICE_ECDSA.address),
+            Token::FixedBytes(asset.0.to_vec()),
+            Token::Uint(amount),
+        ]
+    );
+}
+
+#[test]
+fn decode_utxo_withdraw() {
+    let source = H256::repeat_byte(3);
+    let asset = AssetType([4; 32]);
+    let amount = U256::from(100);
+
+    let log = utxo_withdraw_log(source, &BOB_XFR.pub_key, asset, amount);
+    assert_eq!(
+        decode(&utxo_withdraw_event(), log),
+        vec![
+            Token::FixedBytes(source.as_bytes().to_vec()),
+            Token::FixedBytes(BOB_XFR.pub_key.zei_to_bytes()),
+            Token::FixedBytes(asset.0.to_vec()),
+            Token::Uint(amount),
+        ]
+    );
+
+    // not a deposit
+    let log = utxo_withdraw_log(source, &BOB_XFR.pub_key, asset, amount);
+    assert!(utxo_deposit_event()
+        .parse_log(RawLog {
+            topics: log.topics,
+            data: log.data,
+        })
+        .is_err());
+}
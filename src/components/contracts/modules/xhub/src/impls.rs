@@ -1,14 +1,26 @@
 use crate::storage::*;
 use crate::{App, Config};
+use config::abci::{
+    global_cfg::CFG,
+    upgrade::{is_active, Feature},
+};
 use fp_core::{context::Context, ensure, transaction::ActionResult};
-use fp_storage::{Borrow, BorrowMut};
 use fp_traits::{account::AccountAsset, evm::DecimalsMapping};
-use fp_types::actions::xhub::NonConfidentialTransfer;
-use fp_types::{actions::xhub::NonConfidentialOutput, crypto::Address};
-use ledger::data_model::ASSET_TYPE_FRA;
-use primitive_types::U256;
+use fp_types::{
+    actions::xhub::{
+        MintStatus, NonConfidentialTransfer, PendingMint, Transfer, UtxoOutput,
+    },
+    crypto::Address,
+};
+use ledger::{
+    converter::check_utxo_transfer,
+    data_model::{AssetTypeCode, ASSET_TYPE_FRA},
+};
+use primitive_types::{H160, U256};
 use ruc::*;
+use std::convert::TryFrom;
 use tracing::debug;
+use zei::xfr::structs::AssetType;
 
 impl<C: Config> App<C> {
     pub fn transfer_to_nonconfidential_utxo(
@@ -16,19 +28,58 @@ impl<C: Config> App<C> {
         sender: Address,
         call: NonConfidentialTransfer,
     ) -> Result<ActionResult> {
+        let outputs = call.outputs.into_iter().map(UtxoOutput::from).collect();
+        Self::burn_to_utxo(ctx, sender, call.input_value, outputs)
+    }
+
+    pub fn transfer_to_utxo(
+        ctx: &Context,
+        sender: Address,
+        call: Transfer,
+    ) -> Result<ActionResult> {
+        ensure!(
+            is_active(Feature::ConfidentialBridge, ctx.header.height as u64),
+            "Transfer to confidential UTXO is not enabled"
+        );
+        Self::burn_to_utxo(ctx, sender, call.input_value, call.outputs)
+    }
+
+    fn burn_to_utxo(
+        ctx: &Context,
+        sender: Address,
+        input_value: u64,
+        outputs: Vec<UtxoOutput>,
+    ) -> Result<ActionResult> {
+        let multi_asset =
+            is_active(Feature::ConfidentialBridge, ctx.header.height as u64);
+
         let mut transfer_amount = 0;
-        for output in &call.outputs {
-            ensure!(
-                output.asset == ASSET_TYPE_FRA,
-                "Invalid asset type only support FRA"
-            );
-            transfer_amount += output.amount;
+        for output in &outputs {
+            if output.asset == ASSET_TYPE_FRA {
+                transfer_amount += output.amount;
+            } else {
+                ensure!(multi_asset, "Invalid asset type only support FRA");
+                ensure!(
+                    output.token.is_some(),
+                    "the FRC20 token of the asset is missing"
+                );
+            }
+            if let Some(transfer) = &output.transfer {
+                ensure!(multi_asset, "Transfer to confidential UTXO is not enabled");
+                check_utxo_transfer(
+                    transfer,
+                    &output.target,
+                    output.amount,
+                    output.asset,
+                )
+                .c(d!("the confidential output mismatch the burned asset"))?;
+            }
         }
 
         debug!(target: "xhub", "transfer to UTXO {} FRA", transfer_amount);
 
         ensure!(
-            call.input_value == transfer_amount,
+            input_value == transfer_amount,
             "Input value mismatch utxo output"
         );
 
@@ -43,26 +94,129 @@ impl<C: Config> App<C> {
 
         if !amount.is_zero() {
             C::AccountAsset::burn(ctx, &sender, amount)?;
-            Self::add_mint(ctx, call.outputs)?;
+            Self::check_bridge_limit(ctx, ASSET_TYPE_FRA, transfer_amount)?;
+        }
+
+        for output in outputs.iter().filter(|o| o.asset != ASSET_TYPE_FRA) {
+            let who = H160::try_from(&sender)
+                .map_err(|_| eg!("only the evm accounts can transfer FRC20 assets"))?;
+            let token = output.token.c(d!())?;
+            C::AssetBridge::lock_asset(
+                ctx,
+                who,
+                token,
+                output.asset.0,
+                U256::from(output.amount),
+            )?;
+            Self::check_bridge_limit(ctx, output.asset, output.amount)?;
+        }
+
+        if !amount.is_zero() || outputs.iter().any(|o| o.asset != ASSET_TYPE_FRA) {
+            Self::add_mint(ctx, outputs)?;
         }
         Ok(ActionResult::default())
     }
 
-    pub(crate) fn add_mint(
+    /// Count `amount` of `asset` in the amount bridged in the current block,
+    /// either way, fails if it exceeds the limit of the asset in the checkpoint.
+    pub fn check_bridge_limit(
         ctx: &Context,
-        mut outputs: Vec<NonConfidentialOutput>,
+        asset: AssetType,
+        amount: u64,
     ) -> Result<()> {
-        let ops =
-            if let Some(mut ori_outputs) = PendingUTXOs::get(ctx.db.read().borrow()) {
-                ori_outputs.append(&mut outputs);
-                ori_outputs
-            } else {
-                outputs
+        let height = ctx.header.height as u64;
+        if !is_active(Feature::ConfidentialBridge, height) {
+            return Ok(());
+        }
+
+        let limit = CFG
+            .checkpoint
+            .bridge_asset_limits
+            .iter()
+            .find(|(code, _)| {
+                AssetTypeCode::new_from_base64(code)
+                    .map(|code| code.val == asset)
+                    .unwrap_or(false)
+            })
+            .map(|(_, limit)| *limit);
+        let limit = if let Some(limit) = limit {
+            limit
+        } else {
+            return Ok(());
+        };
+
+        let mut state = ctx.state.write();
+        let mut amounts = match BridgedAmounts::get(&*state) {
+            Some((h, amounts)) if h == height => amounts,
+            _ => Vec::new(),
+        };
+        let idx = match amounts.iter().position(|(ty, _)| *ty == asset) {
+            Some(idx) => idx,
+            None => {
+                amounts.push((asset, 0));
+                amounts.len() - 1
+            }
+        };
+        amounts[idx].1 = amounts[idx]
+            .1
+            .checked_add(amount)
+            .filter(|total| *total <= limit)
+            .c(d!(
+                "the bridged amount of the asset exceeds the limit of the block"
+            ))?;
+
+        BridgedAmounts::put(&mut *state, &(height, amounts))
+    }
+
+    pub(crate) fn add_mint(ctx: &Context, outputs: Vec<UtxoOutput>) -> Result<()> {
+        let height = ctx.header.height as u64;
+        let mut db = ctx.db.write();
+        let mut id = NextMintId::get(&*db).unwrap_or_default();
+        let mut ids = PendingUTXOs::get(&*db).unwrap_or_default();
+        for output in outputs {
+            let mint = PendingMint {
+                id,
+                height,
+                output,
+                status: MintStatus::Pending,
             };
-        PendingUTXOs::put(ctx.db.write().borrow_mut(), &ops)
+            PendingMints::insert(&mut *db, &id, &mint)?;
+            ids.push(id);
+            id += 1;
+        }
+        NextMintId::put(&mut *db, &id)?;
+        PendingUTXOs::put(&mut *db, &ids)
+    }
+
+    /// Take the outputs waiting to be mint in the order of the queue,
+    /// they are marked as minted at the current height.
+    pub fn consume_mint(ctx: &Context) -> Option<Vec<UtxoOutput>> {
+        let height = ctx.header.height as u64;
+        let mut db = ctx.db.write();
+        let ids = PendingUTXOs::take(&mut *db)?;
+        let outputs = ids
+            .iter()
+            .filter_map(|id| {
+                let mut mint = PendingMints::get(&*db, id)?;
+                mint.status = MintStatus::Minted(height);
+                info_omit!(PendingMints::insert(&mut *db, id, &mint));
+                Some(mint.output)
+            })
+            .collect();
+        Some(outputs)
+    }
+
+    pub fn pending_mint(ctx: &Context, id: u64) -> Option<PendingMint> {
+        PendingMints::get(&*ctx.db.read(), &id)
     }
 
-    pub fn consume_mint(ctx: &Context) -> Option<Vec<NonConfidentialOutput>> {
-        PendingUTXOs::take(ctx.db.write().borrow_mut())
+    /// The outputs still waiting to be mint, in the order of the queue.
+    pub fn pending_mints(ctx: &Context) -> Vec<PendingMint> {
+        let db = ctx.db.read();
+        PendingUTXOs::get(&*db)
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|id| PendingMints::get(&*db, id))
+            .collect()
     }
 }
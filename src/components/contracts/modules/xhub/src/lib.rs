@@ -3,13 +3,18 @@
 
 mod basic;
 mod impls;
+pub mod staking;
 
+use abci::{RequestQuery, ResponseQuery};
 use fp_core::{
     context::Context,
     module::AppModule,
     transaction::{ActionResult, Executable},
 };
-use fp_traits::{account::AccountAsset, evm::DecimalsMapping};
+use fp_traits::{
+    account::AccountAsset,
+    evm::{AssetBridge, DecimalsMapping},
+};
 use fp_types::{actions::xhub::Action, crypto::Address};
 use ruc::*;
 use std::marker::PhantomData;
@@ -21,16 +26,40 @@ pub trait Config {
     type AccountAsset: AccountAsset<Address>;
     /// Mapping from eth decimals to native token decimals.
     type DecimalsMapping: DecimalsMapping;
+    /// Lock the FRC20 tokens transferred to utxo in the bridge.
+    type AssetBridge: AssetBridge;
 }
 
 mod storage {
-    use fp_types::actions::xhub::NonConfidentialOutput;
+    use fp_types::{
+        actions::xhub::{PendingMint, StakingFailure, StakingRequest},
+        crypto::Address,
+    };
+    use zei::xfr::{sig::XfrPublicKey, structs::AssetType};
 
     use fp_storage::*;
 
     // The following data is stored in non-state rocksdb
-    // account balance transfer to utxo waiting to be mint.
-    generate_storage!(XHub, PendingUTXOs => Value<Vec<NonConfidentialOutput>>);
+    // the id of the next account balance transfer to utxo.
+    generate_storage!(XHub, NextMintId => Value<u64>);
+    // ids of the account balance transfers to utxo waiting to be mint.
+    generate_storage!(XHub, PendingUTXOs => Value<Vec<u64>>);
+    // id => the account balance transfer to utxo and its status.
+    generate_storage!(XHub, PendingMints => Map<u64, PendingMint>);
+
+    // The following data is stored in state rocksdb
+    // evm account => the findora key bound to delegate.
+    generate_storage!(XHub, StakingDelegators => Map<Address, XfrPublicKey>);
+    // the address of a bound findora key => the evm account it's bound to.
+    generate_storage!(XHub, StakingOwners => Map<Address, Address>);
+    // evm account => its last staking request failed in the ledger.
+    generate_storage!(XHub, StakingFailures => Map<Address, StakingFailure>);
+    // staking requests of evm accounts waiting to be applied to the ledger,
+    // discarded with the state of a reverted call.
+    generate_storage!(XHub, PendingStaking => Value<Vec<StakingRequest>>);
+    // the amounts of the assets bridged in a block, by the block height,
+    // discarded with the state of a failed transaction.
+    generate_storage!(XHub, BridgedAmounts => Value<(u64, Vec<(AssetType, u64)>)>);
 }
 
 #[derive(Clone)]
@@ -46,7 +75,47 @@ impl<C: Config> Default for App<C> {
     }
 }
 
-impl<C: Config> AppModule for App<C> {}
+impl<C: Config> AppModule for App<C> {
+    fn query_route(
+        &self,
+        ctx: Context,
+        path: Vec<&str>,
+        req: &RequestQuery,
+    ) -> ResponseQuery {
+        let mut resp: ResponseQuery = Default::default();
+        if path.len() != 1 {
+            resp.code = 1;
+            resp.log = String::from("xhub: invalid query path");
+            return resp;
+        }
+        let value = match path[0] {
+            "pending_mint" => {
+                let id = serde_json::from_slice::<u64>(req.data.as_slice());
+                if id.is_err() {
+                    resp.code = 1;
+                    resp.log =
+                        String::from("xhub: query pending mint with invalid params");
+                    return resp;
+                }
+                serde_json::to_vec(&Self::pending_mint(&ctx, id.unwrap()))
+            }
+            "pending_mints" => serde_json::to_vec(&Self::pending_mints(&ctx)),
+            _ => {
+                resp.code = 1;
+                resp.log = String::from("xhub: invalid query path");
+                return resp;
+            }
+        };
+
+        if let Ok(value) = value {
+            resp.value = value;
+        } else {
+            resp.code = 1;
+            resp.log = String::from("xhub: failed to serialize pending mints");
+        }
+        resp
+    }
+}
 
 impl<C: Config> Executable for App<C> {
     type Origin = Address;
@@ -65,6 +134,13 @@ impl<C: Config> Executable for App<C> {
                     Err(eg!("invalid transaction origin"))
                 }
             }
+            Action::Transfer(action) => {
+                if let Some(sender) = origin {
+                    Self::transfer_to_utxo(ctx, sender, action)
+                } else {
+                    Err(eg!("invalid transaction origin"))
+                }
+            }
         }
     }
 }
@@ -0,0 +1,97 @@
+//!
+//! The bridge between the evm accounts and the `Staking` of the ledger.
+//!
+//! The staking precompile binds an evm account to a findora key,
+//! which is the delegator in the `Staking`, and queues the requests here,
+//! the ledger applies them at the end of the block.
+//! The queue is a part of the state, so the requests of a reverted call
+//! are discarded together with its burned balance.
+//!
+//! A key is bound to one account at most, the ledger credits the proceeds
+//! of the key to that account, and records the requests it failed to apply.
+//!
+
+use crate::storage::*;
+use fp_core::context::{Context, RwLock};
+use fp_storage::{Borrow, BorrowMut};
+use fp_types::{
+    actions::xhub::{StakingFailure, StakingRequest, StakingValidator},
+    crypto::Address,
+};
+use lazy_static::lazy_static;
+use primitive_types::H160;
+use ruc::*;
+use std::collections::BTreeMap;
+use zei::xfr::sig::XfrPublicKey;
+
+lazy_static! {
+    // A snapshot of the committed validators,
+    // updated by the ledger at the beginning of each block.
+    static ref VALIDATORS: RwLock<BTreeMap<H160, StakingValidator>> =
+        RwLock::new(BTreeMap::new());
+}
+
+/// Replace the validators visible to the evm.
+pub fn set_validators(validators: Vec<StakingValidator>) {
+    *VALIDATORS.write() = validators.into_iter().map(|v| (v.td_addr, v)).collect();
+}
+
+/// Get a validator by its tendermint address.
+pub fn get_validator(td_addr: &H160) -> Option<StakingValidator> {
+    VALIDATORS.read().get(td_addr).cloned()
+}
+
+/// The findora key bound to `who`, if any.
+pub fn delegator_of(ctx: &Context, who: &Address) -> Option<XfrPublicKey> {
+    StakingDelegators::get(ctx.state.read().borrow(), who)
+}
+
+/// The evm account bound to `delegator`, if any.
+pub fn owner_of(ctx: &Context, delegator: &XfrPublicKey) -> Option<Address> {
+    StakingOwners::get(ctx.state.read().borrow(), &Address::from(*delegator))
+}
+
+/// Bind `who` to `delegator`, the caller should verify the ownership of the key,
+/// and that neither of them is bound.
+pub fn bind_delegator(
+    ctx: &Context,
+    who: &Address,
+    delegator: &XfrPublicKey,
+) -> Result<()> {
+    StakingDelegators::insert(ctx.state.write().borrow_mut(), who, delegator).and_then(
+        |_| {
+            StakingOwners::insert(
+                ctx.state.write().borrow_mut(),
+                &Address::from(*delegator),
+                who,
+            )
+        },
+    )
+}
+
+/// Queue a request to be applied at the end of the block.
+pub fn add_staking_request(ctx: &Context, request: StakingRequest) -> Result<()> {
+    let mut requests =
+        PendingStaking::get(ctx.state.read().borrow()).unwrap_or_default();
+    requests.push(request);
+    PendingStaking::put(ctx.state.write().borrow_mut(), &requests)
+}
+
+/// Take all the requests of the block, in the order of execution.
+pub fn consume_staking_requests(ctx: &Context) -> Option<Vec<StakingRequest>> {
+    PendingStaking::take(ctx.state.write().borrow_mut())
+}
+
+/// Keep the failure of a request as the last one of its owner.
+pub fn record_failure(ctx: &Context, failure: &StakingFailure) -> Result<()> {
+    StakingFailures::insert(
+        ctx.state.write().borrow_mut(),
+        failure.request.owner(),
+        failure,
+    )
+}
+
+/// The last failed request of `who`, if any.
+pub fn last_failure(ctx: &Context, who: &Address) -> Option<StakingFailure> {
+    StakingFailures::get(ctx.state.read().borrow(), who)
+}
@@ -0,0 +1,79 @@
+#![allow(clippy::field_reassign_with_default)]
+
+//! Xhub module integration tests.
+
+use abci::RequestQuery;
+use fp_core::{context::Context, module::AppModule, transaction::Executable};
+use fp_mocks::*;
+use fp_traits::account::AccountAsset;
+use fp_types::{
+    actions::xhub::{
+        Action as XHubAction, MintStatus, NonConfidentialOutput,
+        NonConfidentialTransfer, PendingMint, UtxoOutput,
+    },
+    crypto::Address,
+    U256,
+};
+use ledger::data_model::ASSET_TYPE_FRA;
+
+type XHub = module_xhub::App<BaseApp>;
+
+fn query<T: serde::de::DeserializeOwned>(ctx: &Context, path: &str, data: &[u8]) -> T {
+    let mut req = RequestQuery::default();
+    req.data = data.to_vec();
+    let resp = XHub::default().query_route(ctx.clone(), vec![path], &req);
+    assert_eq!(resp.code, 0, "query {path} failed: {}", resp.log);
+    serde_json::from_slice(&resp.value).unwrap()
+}
+
+#[test]
+fn pending_mint_queue() {
+    let ctx = BASE_APP.lock().unwrap().deliver_state.clone();
+    let alice: Address = ALICE_XFR.pub_key.into();
+    <BaseApp as module_xhub::Config>::AccountAsset::mint(
+        &ctx,
+        &alice,
+        U256::from(10_u64.pow(18)),
+    )
+    .unwrap();
+
+    let output = NonConfidentialOutput {
+        asset: ASSET_TYPE_FRA,
+        amount: 100,
+        target: BOB_XFR.pub_key,
+        decimal: 0,
+        max_supply: 0,
+    };
+    let action = XHubAction::NonConfidentialTransfer(NonConfidentialTransfer {
+        input_value: 200,
+        outputs: vec![output.clone(), output.clone()],
+    });
+    XHub::execute(Some(alice), action, &ctx).unwrap();
+
+    let pending: Vec<PendingMint> = query(&ctx, "pending_mints", &[]);
+    assert_eq!(pending.len(), 2);
+    assert_eq!(pending[0].id + 1, pending[1].id);
+    for mint in &pending {
+        assert_eq!(mint.output, UtxoOutput::from(output.clone()));
+        assert_eq!(mint.status, MintStatus::Pending);
+    }
+
+    let id = serde_json::to_vec(&pending[1].id).unwrap();
+    let mint: Option<PendingMint> = query(&ctx, "pending_mint", &id);
+    assert_eq!(mint.as_ref(), Some(&pending[1]));
+
+    let mut req = RequestQuery::default();
+    req.data = b"not an id".to_vec();
+    let resp = XHub::default().query_route(ctx.clone(), vec!["pending_mint"], &req);
+    assert_eq!(resp.code, 1);
+
+    // minted in the order of the queue, and kept with the height
+    assert_eq!(XHub::consume_mint(&ctx).unwrap().len(), 2);
+    let pending: Vec<PendingMint> = query(&ctx, "pending_mints", &[]);
+    assert!(pending.is_empty());
+    let mint: Option<PendingMint> = query(&ctx, "pending_mint", &id);
+    assert_eq!(
+        mint.unwrap().status,
+        MintStatus::Minted(ctx.header.height as u64)
+    );
+}
@@ -1,6 +1,6 @@
 use crate::context::{Context, RunTxMode};
 use abci::Event;
-use config::abci::global_cfg::CFG;
+use config::abci::upgrade::{is_active, Feature};
 use fp_types::{crypto::Address, transaction::CheckedTransaction};
 use impl_trait_for_tuples::impl_for_tuples;
 use ruc::*;
@@ -159,7 +159,7 @@ where
 
         ctx.state.write().commit_session();
 
-        if ctx.header.height >= CFG.checkpoint.evm_checktx_nonce {
+        if is_active(Feature::EvmCheckTxNonce, ctx.header.height as u64) {
             // The transaction is only executed if the run mode is Deliver
             if ctx.run_mode == RunTxMode::Check {
                 return Ok(ActionResult::default());
@@ -173,9 +173,10 @@ where
                     res.log = String::from("ctx state is not good to commit");
 
                     ctx.state.write().discard_session();
-                } else if CFG.checkpoint.fix_deliver_tx_revert_nonce_height
-                    > ctx.block_header().height
-                    || signed_tx
+                } else if !is_active(
+                    Feature::FixDeliverTxRevertNonce,
+                    ctx.block_header().height as u64,
+                ) || signed_tx
                 {
                     if res.code == 0 {
                         Extra::post_execute(ctx, pre, &res)?;
@@ -1,15 +1,18 @@
+mod sink;
+
+pub use sink::{DiskSink, MemorySink, NoopSink, RedisSink, SinkConfig, Web3StateSink};
+
 use evm_exporter::{
-    Block as EnterpriseBlock, Getter, Receipt as EnterpriseReceipt,
-    State as EnterpriseState, TransactionStatus as EnterpriseTxState,
+    Block as EnterpriseBlock, Receipt as EnterpriseReceipt, State as EnterpriseState,
+    TransactionStatus as EnterpriseTxState,
 };
 use lazy_static::lazy_static;
 use primitive_types::{H160, H256, U256};
-use redis::Client;
 use ruc::*;
 use std::collections::HashMap;
+use std::env;
 use std::sync::{Arc, Mutex};
-
-pub use evm_exporter::Setter;
+use tracing::warn;
 
 pub type State = EnterpriseState;
 pub type Block = EnterpriseBlock;
@@ -28,8 +31,8 @@ lazy_static! {
     pub static ref BLOCK: Arc<Mutex<Option<Block>>> = Arc::new(Mutex::new(None));
     pub static ref RECEIPTS: Arc<Mutex<Vec<Receipt>>> = Arc::new(Mutex::new(vec![]));
     pub static ref TXS: Arc<Mutex<Vec<TxState>>> = Arc::new(Mutex::new(vec![]));
-    pub static ref REDIS_CLIENT: Arc<Mutex<r2d2::Pool<Client>>> =
-        Arc::new(Mutex::new(gen_redis_client()));
+    pub static ref WEB3_SINK: Arc<Mutex<Box<dyn Web3StateSink>>> =
+        Arc::new(Mutex::new(open_sink()));
     pub static ref WEB3_SERVICE_START_HEIGHT: u64 = load_start_height();
     pub static ref PENDING_CODE_MAP: Arc<Mutex<HashMap<H160, Vec<u8>>>> =
         Arc::new(Mutex::new(HashMap::new()));
@@ -41,17 +44,29 @@ lazy_static! {
         Arc::new(Mutex::new(vec![]));
 }
 
-fn gen_redis_client() -> r2d2::Pool<Client> {
-    let redis_addr = std::env::var("REDIS_ADDR").unwrap();
-    let client = Client::open(redis_addr).unwrap();
-    let pool = pnk!(r2d2::Pool::builder().max_size(50).build(client));
-    pool
+// A sink chosen by `WEB3_SINK` must be usable, the node refuses to start
+// otherwise, only the default one falls back to `NoopSink`.
+fn open_sink() -> Box<dyn Web3StateSink> {
+    let sink = SinkConfig::from_env().and_then(|cfg| cfg.open());
+    if env::var_os("WEB3_SINK").is_some() {
+        pnk!(sink.c(d!("WEB3_SINK is unavailable")))
+    } else {
+        or_noop(sink)
+    }
+}
+
+// A node without a usable sink keeps running, without exporting the state.
+fn or_noop(sink: Result<Box<dyn Web3StateSink>>) -> Box<dyn Web3StateSink> {
+    sink.unwrap_or_else(|e| {
+        warn!(target: "enterprise_web3", "web3 sink unavailable, the evm state will not be exported: {e}");
+        Box::new(NoopSink)
+    })
 }
 
 fn load_start_height() -> u64 {
-    let redis_pool = REDIS_CLIENT.lock().unwrap();
-    let mut conn = redis_pool.get().expect("get redis connect");
-    let mut getter = Getter::new(&mut *conn, "evm".to_string());
-    let last_height = getter.latest_height().expect("redis latest_height error");
-    last_height as u64
+    let mut sink = WEB3_SINK.lock().expect("WEB3_SINK error");
+    sink.latest_height().expect("web3 sink latest_height error")
 }
+
+#[cfg(test)]
+mod tests;
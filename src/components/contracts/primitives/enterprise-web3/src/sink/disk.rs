@@ -0,0 +1,198 @@
+use super::Web3StateSink;
+use crate::{Block, Receipt, TxState};
+use ethereum::TransactionV2 as Transaction;
+use fin_db::RocksDB;
+use primitive_types::{H160, H256, U256};
+use ruc::*;
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::Path,
+};
+use storage::{db::IterOrder, state::ChainState};
+
+/// A sink in an embedded db, the state of a block is written at `flush`.
+///
+/// The keys are suffixed with the height, so a read at some height
+/// finds the last write at or below it. The pending state of the mempool
+/// stays in memory.
+pub struct DiskSink {
+    db: ChainState<RocksDB>,
+    height: u32,
+    batch: BTreeMap<Vec<u8>, Vec<u8>>,
+    pending_txs: Vec<Transaction>,
+    pending_codes: HashMap<H160, Vec<u8>>,
+    pending_states: HashMap<(H160, H256), H256>,
+}
+
+fn key(kind: &str, id: &[u8], height: u32) -> Vec<u8> {
+    format!("{}:{}:{:010}", kind, hex::encode(id), height).into_bytes()
+}
+
+impl DiskSink {
+    pub fn open(dir: &Path) -> Result<Self> {
+        let rdb = RocksDB::open(dir).c(d!())?;
+        Ok(DiskSink {
+            db: ChainState::new(rdb, "web3_sink".to_owned(), 0),
+            height: 0,
+            batch: BTreeMap::new(),
+            pending_txs: vec![],
+            pending_codes: HashMap::new(),
+            pending_states: HashMap::new(),
+        })
+    }
+
+    fn put(&mut self, kind: &str, id: &[u8], height: u32, value: Vec<u8>) {
+        self.batch.insert(key(kind, id, height), value);
+    }
+
+    // The last value of `id` written at or below `height`.
+    fn get(&self, kind: &str, id: &[u8], height: u32) -> Option<Vec<u8>> {
+        let lower = key(kind, id, 0);
+        let upper = key(kind, id, height.saturating_add(1));
+        let mut value = None;
+        self.db
+            .iterate(&lower, &upper, IterOrder::Desc, &mut |(_, v)| {
+                value = Some(v);
+                // stop at the first one
+                true
+            });
+        value
+    }
+}
+
+impl Web3StateSink for DiskSink {
+    fn latest_height(&mut self) -> Result<u64> {
+        self.db.height().c(d!())
+    }
+
+    fn set_height(&mut self, height: u32) -> Result<()> {
+        self.height = height;
+        Ok(())
+    }
+
+    fn set_balance(&mut self, height: u32, address: H160, balance: U256) -> Result<()> {
+        self.put(
+            "balance",
+            address.as_bytes(),
+            height,
+            <[u8; 32]>::from(balance).to_vec(),
+        );
+        Ok(())
+    }
+
+    fn set_nonce(&mut self, height: u32, address: H160, nonce: U256) -> Result<()> {
+        self.put(
+            "nonce",
+            address.as_bytes(),
+            height,
+            <[u8; 32]>::from(nonce).to_vec(),
+        );
+        Ok(())
+    }
+
+    fn set_byte_code(
+        &mut self,
+        height: u32,
+        address: H160,
+        code: Vec<u8>,
+    ) -> Result<()> {
+        self.put("code", address.as_bytes(), height, code);
+        Ok(())
+    }
+
+    fn set_state(
+        &mut self,
+        height: u32,
+        address: H160,
+        index: H256,
+        value: H256,
+    ) -> Result<()> {
+        let id = [address.as_bytes(), index.as_bytes()].concat();
+        self.put("state", &id, height, value.as_bytes().to_vec());
+        Ok(())
+    }
+
+    fn set_block_info(
+        &mut self,
+        block: Block,
+        receipts: Vec<Receipt>,
+        txs: Vec<TxState>,
+    ) -> Result<()> {
+        let value = serde_json::to_vec(&(block, receipts, txs)).c(d!())?;
+        let height = self.height;
+        self.put("block", &[], height, value);
+        Ok(())
+    }
+
+    fn set_pending_tx(&mut self, tx: Transaction) -> Result<()> {
+        self.pending_txs.push(tx);
+        Ok(())
+    }
+
+    fn set_pending_code(&mut self, address: H160, code: Vec<u8>) -> Result<()> {
+        self.pending_codes.insert(address, code);
+        Ok(())
+    }
+
+    fn set_pending_state(
+        &mut self,
+        address: H160,
+        index: H256,
+        value: H256,
+    ) -> Result<()> {
+        self.pending_states.insert((address, index), value);
+        Ok(())
+    }
+
+    fn remove_pending_tx(&mut self, tx: Transaction) -> Result<()> {
+        self.pending_txs.retain(|t| t != &tx);
+        Ok(())
+    }
+
+    fn remove_pending_code(&mut self, address: H160) -> Result<()> {
+        self.pending_codes.remove(&address);
+        Ok(())
+    }
+
+    fn remove_pending_state(&mut self, address: H160, index: H256) -> Result<()> {
+        self.pending_states.remove(&(address, index));
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        let batch = std::mem::take(&mut self.batch)
+            .into_iter()
+            .map(|(k, v)| (k, Some(v)))
+            .collect();
+        self.db.commit(batch, self.height as u64, true).c(d!())?;
+        Ok(())
+    }
+
+    fn balance(&mut self, height: u32, address: H160) -> Result<U256> {
+        Ok(self
+            .get("balance", address.as_bytes(), height)
+            .map(|v| U256::from_big_endian(&v))
+            .unwrap_or_default())
+    }
+
+    fn nonce(&mut self, height: u32, address: H160) -> Result<U256> {
+        Ok(self
+            .get("nonce", address.as_bytes(), height)
+            .map(|v| U256::from_big_endian(&v))
+            .unwrap_or_default())
+    }
+
+    fn byte_code(&mut self, height: u32, address: H160) -> Result<Vec<u8>> {
+        Ok(self
+            .get("code", address.as_bytes(), height)
+            .unwrap_or_default())
+    }
+
+    fn state(&mut self, height: u32, address: H160, index: H256) -> Result<H256> {
+        let id = [address.as_bytes(), index.as_bytes()].concat();
+        Ok(self
+            .get("state", &id, height)
+            .map(|v| H256::from_slice(&v))
+            .unwrap_or_default())
+    }
+}
@@ -0,0 +1,135 @@
+use super::Web3StateSink;
+use crate::{Block, Receipt, TxState};
+use ethereum::TransactionV2 as Transaction;
+use primitive_types::{H160, H256, U256};
+use ruc::*;
+use std::collections::{BTreeMap, HashMap};
+
+/// A sink in the memory of the process, for the tests of the exporter.
+#[derive(Default)]
+pub struct MemorySink {
+    pub height: u64,
+    balances: BTreeMap<(H160, u32), U256>,
+    nonces: BTreeMap<(H160, u32), U256>,
+    codes: BTreeMap<(H160, u32), Vec<u8>>,
+    states: BTreeMap<((H160, H256), u32), H256>,
+    pub blocks: Vec<(Block, Vec<Receipt>, Vec<TxState>)>,
+    pub pending_txs: Vec<Transaction>,
+    pub pending_codes: HashMap<H160, Vec<u8>>,
+    pub pending_states: HashMap<(H160, H256), H256>,
+}
+
+// The last value of `key` written at or below `height`.
+fn at_height<K: Ord + Clone, V: Clone + Default>(
+    map: &BTreeMap<(K, u32), V>,
+    key: K,
+    height: u32,
+) -> V {
+    map.range((key.clone(), 0)..=(key, height))
+        .next_back()
+        .map(|(_, v)| v.clone())
+        .unwrap_or_default()
+}
+
+impl Web3StateSink for MemorySink {
+    fn latest_height(&mut self) -> Result<u64> {
+        Ok(self.height)
+    }
+
+    fn set_height(&mut self, height: u32) -> Result<()> {
+        self.height = height as u64;
+        Ok(())
+    }
+
+    fn set_balance(&mut self, height: u32, address: H160, balance: U256) -> Result<()> {
+        self.balances.insert((address, height), balance);
+        Ok(())
+    }
+
+    fn set_nonce(&mut self, height: u32, address: H160, nonce: U256) -> Result<()> {
+        self.nonces.insert((address, height), nonce);
+        Ok(())
+    }
+
+    fn set_byte_code(
+        &mut self,
+        height: u32,
+        address: H160,
+        code: Vec<u8>,
+    ) -> Result<()> {
+        self.codes.insert((address, height), code);
+        Ok(())
+    }
+
+    fn set_state(
+        &mut self,
+        height: u32,
+        address: H160,
+        index: H256,
+        value: H256,
+    ) -> Result<()> {
+        self.states.insert(((address, index), height), value);
+        Ok(())
+    }
+
+    fn set_block_info(
+        &mut self,
+        block: Block,
+        receipts: Vec<Receipt>,
+        txs: Vec<TxState>,
+    ) -> Result<()> {
+        self.blocks.push((block, receipts, txs));
+        Ok(())
+    }
+
+    fn set_pending_tx(&mut self, tx: Transaction) -> Result<()> {
+        self.pending_txs.push(tx);
+        Ok(())
+    }
+
+    fn set_pending_code(&mut self, address: H160, code: Vec<u8>) -> Result<()> {
+        self.pending_codes.insert(address, code);
+        Ok(())
+    }
+
+    fn set_pending_state(
+        &mut self,
+        address: H160,
+        index: H256,
+        value: H256,
+    ) -> Result<()> {
+        self.pending_states.insert((address, index), value);
+        Ok(())
+    }
+
+    fn remove_pending_tx(&mut self, tx: Transaction) -> Result<()> {
+        self.pending_txs.retain(|t| t != &tx);
+        Ok(())
+    }
+
+    fn remove_pending_code(&mut self, address: H160) -> Result<()> {
+        self.pending_codes.remove(&address);
+        Ok(())
+    }
+
+    fn remove_pending_state(&mut self, address: H160, index: H256) -> Result<()> {
+        self.pending_states.remove(&(address, index));
+        Ok(())
+    }
+
+    fn balance(&mut self, height: u32, address: H160) -> Result<U256> {
+        Ok(at_height(&self.balances, address, height))
+    }
+
+    fn nonce(&mut self, height: u32, address: H160) -> Result<U256> {
+        Ok(at_height(&self.nonces, address, height))
+    }
+
+    fn byte_code(&mut self, height: u32, address: H160) -> Result<Vec<u8>> {
+        Ok(at_height(&self.codes, address, height))
+    }
+
+    fn state(&mut self, height: u32, address: H160, index: H256) -> Result<H256> {
+        Ok(at_height(&self.states, (address, index), height))
+    }
+}
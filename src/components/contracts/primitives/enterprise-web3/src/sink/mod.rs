@@ -0,0 +1,134 @@
+//!
+//! The destinations of the evm state exported for the enterprise web3 service.
+//!
+//! `WEB3_SINK` selects the sink of a node:
+//! - `redis`, the default, writes to the redis at `REDIS_ADDR`
+//! - `disk` writes to an embedded db under `WEB3_SINK_DIR`
+//! - `memory` keeps everything in the process, for tests
+//! - `none` exports nothing, also used when the selected sink can not be opened
+//!
+
+mod disk;
+mod memory;
+mod noop;
+mod redis;
+
+pub use self::disk::DiskSink;
+pub use self::memory::MemorySink;
+pub use self::noop::NoopSink;
+pub use self::redis::RedisSink;
+
+use crate::{Block, Receipt, TxState};
+use ethereum::TransactionV2 as Transaction;
+use primitive_types::{H160, H256, U256};
+use ruc::*;
+use std::{env, path::PathBuf};
+
+/// A store of the evm state, written at the commit of each block,
+/// and of the pending state of the mempool.
+pub trait Web3StateSink: Send {
+    /// The height of the last exported block.
+    fn latest_height(&mut self) -> Result<u64>;
+
+    /// Start exporting the block at `height`.
+    fn set_height(&mut self, height: u32) -> Result<()>;
+
+    fn set_balance(&mut self, height: u32, address: H160, balance: U256) -> Result<()>;
+
+    fn set_nonce(&mut self, height: u32, address: H160, nonce: U256) -> Result<()>;
+
+    fn set_byte_code(&mut self, height: u32, address: H160, code: Vec<u8>)
+        -> Result<()>;
+
+    fn set_state(
+        &mut self,
+        height: u32,
+        address: H160,
+        index: H256,
+        value: H256,
+    ) -> Result<()>;
+
+    fn set_block_info(
+        &mut self,
+        block: Block,
+        receipts: Vec<Receipt>,
+        txs: Vec<TxState>,
+    ) -> Result<()>;
+
+    /// Both the legacy and the typed transactions are accepted.
+    fn set_pending_tx(&mut self, tx: Transaction) -> Result<()>;
+
+    fn set_pending_code(&mut self, address: H160, code: Vec<u8>) -> Result<()>;
+
+    fn set_pending_state(
+        &mut self,
+        address: H160,
+        index: H256,
+        value: H256,
+    ) -> Result<()>;
+
+    fn remove_pending_tx(&mut self, tx: Transaction) -> Result<()>;
+
+    fn remove_pending_code(&mut self, address: H160) -> Result<()>;
+
+    fn remove_pending_state(&mut self, address: H160, index: H256) -> Result<()>;
+
+    /// Persist the writes of the block, called after all the setters of a block.
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    /// The balance of `address` at `height`.
+    fn balance(&mut self, height: u32, address: H160) -> Result<U256>;
+
+    /// The nonce of `address` at `height`.
+    fn nonce(&mut self, height: u32, address: H160) -> Result<U256>;
+
+    /// The code of `address` at `height`.
+    fn byte_code(&mut self, height: u32, address: H160) -> Result<Vec<u8>>;
+
+    /// The storage of `address` at `index` at `height`.
+    fn state(&mut self, height: u32, address: H160, index: H256) -> Result<H256>;
+}
+
+/// The sink selected for a node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SinkConfig {
+    /// The address of the redis server.
+    Redis(String),
+    /// The directory of the embedded db.
+    Disk(PathBuf),
+    Memory,
+    Noop,
+}
+
+impl SinkConfig {
+    /// Load the config from `WEB3_SINK`, `REDIS_ADDR` and `WEB3_SINK_DIR`.
+    pub fn from_env() -> Result<Self> {
+        match env::var("WEB3_SINK").as_deref().unwrap_or("redis") {
+            "redis" => env::var("REDIS_ADDR")
+                .c(d!("REDIS_ADDR is required by the redis sink"))
+                .map(SinkConfig::Redis),
+            "disk" => env::var("WEB3_SINK_DIR")
+                .c(d!("WEB3_SINK_DIR is required by the disk sink"))
+                .map(|dir| SinkConfig::Disk(PathBuf::from(dir))),
+            "memory" => Ok(SinkConfig::Memory),
+            "none" => Ok(SinkConfig::Noop),
+            other => Err(eg!(format!("unknown web3 sink: {other}"))),
+        }
+    }
+
+    /// Open the sink.
+    pub fn open(&self) -> Result<Box<dyn Web3StateSink>> {
+        match self {
+            SinkConfig::Redis(addr) => {
+                RedisSink::open(addr).map(|s| Box::new(s) as Box<dyn Web3StateSink>)
+            }
+            SinkConfig::Disk(dir) => {
+                DiskSink::open(dir).map(|s| Box::new(s) as Box<dyn Web3StateSink>)
+            }
+            SinkConfig::Memory => Ok(Box::new(MemorySink::default())),
+            SinkConfig::Noop => Ok(Box::new(NoopSink)),
+        }
+    }
+}
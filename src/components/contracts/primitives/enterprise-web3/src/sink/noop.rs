@@ -0,0 +1,104 @@
+use super::Web3StateSink;
+use crate::{Block, Receipt, TxState};
+use ethereum::TransactionV2 as Transaction;
+use primitive_types::{H160, H256, U256};
+use ruc::*;
+
+/// A sink that drops all the writes, the reads return the defaults.
+pub struct NoopSink;
+
+impl Web3StateSink for NoopSink {
+    fn latest_height(&mut self) -> Result<u64> {
+        Ok(0)
+    }
+
+    fn set_height(&mut self, _height: u32) -> Result<()> {
+        Ok(())
+    }
+
+    fn set_balance(
+        &mut self,
+        _height: u32,
+        _address: H160,
+        _balance: U256,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn set_nonce(&mut self, _height: u32, _address: H160, _nonce: U256) -> Result<()> {
+        Ok(())
+    }
+
+    fn set_byte_code(
+        &mut self,
+        _height: u32,
+        _address: H160,
+        _code: Vec<u8>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn set_state(
+        &mut self,
+        _height: u32,
+        _address: H160,
+        _index: H256,
+        _value: H256,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn set_block_info(
+        &mut self,
+        _block: Block,
+        _receipts: Vec<Receipt>,
+        _txs: Vec<TxState>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn set_pending_tx(&mut self, _tx: Transaction) -> Result<()> {
+        Ok(())
+    }
+
+    fn set_pending_code(&mut self, _address: H160, _code: Vec<u8>) -> Result<()> {
+        Ok(())
+    }
+
+    fn set_pending_state(
+        &mut self,
+        _address: H160,
+        _index: H256,
+        _value: H256,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn remove_pending_tx(&mut self, _tx: Transaction) -> Result<()> {
+        Ok(())
+    }
+
+    fn remove_pending_code(&mut self, _address: H160) -> Result<()> {
+        Ok(())
+    }
+
+    fn remove_pending_state(&mut self, _address: H160, _index: H256) -> Result<()> {
+        Ok(())
+    }
+
+    fn balance(&mut self, _height: u32, _address: H160) -> Result<U256> {
+        Ok(U256::zero())
+    }
+
+    fn nonce(&mut self, _height: u32, _address: H160) -> Result<U256> {
+        Ok(U256::zero())
+    }
+
+    fn byte_code(&mut self, _height: u32, _address: H160) -> Result<Vec<u8>> {
+        Ok(vec![])
+    }
+
+    fn state(&mut self, _height: u32, _address: H160, _index: H256) -> Result<H256> {
+        Ok(H256::zero())
+    }
+}
@@ -0,0 +1,136 @@
+use super::Web3StateSink;
+use crate::{Block, Receipt, TxState};
+use ethereum::{TransactionV0, TransactionV2 as Transaction};
+use evm_exporter::{Getter, Setter};
+use primitive_types::{H160, H256, U256};
+use redis::Client;
+use ruc::*;
+
+// The prefix of all the keys written by the exporter.
+const PREFIX: &str = "evm";
+
+// The exporter only stores the legacy transactions.
+fn legacy(tx: Transaction) -> Result<TransactionV0> {
+    match tx {
+        Transaction::Legacy(tx) => Ok(tx),
+        _ => Err(eg!(
+            "typed transactions are not supported by the redis exporter"
+        )),
+    }
+}
+
+// Run `$method` of a `Setter` or a `Getter` on a pooled connection.
+macro_rules! exec {
+    ($sink: ident, $ty: ident, $method: ident($($arg: expr),*)) => {{
+        let mut conn = $sink.pool.get().c(d!())?;
+        $ty::new(&mut *conn, PREFIX.to_string())
+            .$method($($arg),*)
+            .map_err(|e| eg!(format!("{e:?}")))
+    }};
+}
+
+/// The sink of the redis read by the enterprise web3 service.
+pub struct RedisSink {
+    pool: r2d2::Pool<Client>,
+}
+
+impl RedisSink {
+    pub fn open(addr: &str) -> Result<Self> {
+        let client = Client::open(addr).c(d!())?;
+        let pool = r2d2::Pool::builder().max_size(50).build(client).c(d!())?;
+        Ok(RedisSink { pool })
+    }
+}
+
+impl Web3StateSink for RedisSink {
+    fn latest_height(&mut self) -> Result<u64> {
+        exec!(self, Getter, latest_height()).map(|h| h as u64)
+    }
+
+    fn set_height(&mut self, height: u32) -> Result<()> {
+        exec!(self, Setter, set_height(height))
+    }
+
+    fn set_balance(&mut self, height: u32, address: H160, balance: U256) -> Result<()> {
+        exec!(self, Setter, set_balance(height, address, balance))
+    }
+
+    fn set_nonce(&mut self, height: u32, address: H160, nonce: U256) -> Result<()> {
+        exec!(self, Setter, set_nonce(height, address, nonce))
+    }
+
+    fn set_byte_code(
+        &mut self,
+        height: u32,
+        address: H160,
+        code: Vec<u8>,
+    ) -> Result<()> {
+        exec!(self, Setter, set_byte_code(height, address, code))
+    }
+
+    fn set_state(
+        &mut self,
+        height: u32,
+        address: H160,
+        index: H256,
+        value: H256,
+    ) -> Result<()> {
+        exec!(self, Setter, set_state(height, address, index, value))
+    }
+
+    fn set_block_info(
+        &mut self,
+        block: Block,
+        receipts: Vec<Receipt>,
+        txs: Vec<TxState>,
+    ) -> Result<()> {
+        exec!(self, Setter, set_block_info(block, receipts, txs))
+    }
+
+    fn set_pending_tx(&mut self, tx: Transaction) -> Result<()> {
+        let tx = legacy(tx).c(d!())?;
+        exec!(self, Setter, set_pending_tx(tx))
+    }
+
+    fn set_pending_code(&mut self, address: H160, code: Vec<u8>) -> Result<()> {
+        exec!(self, Setter, set_pending_code(address, code))
+    }
+
+    fn set_pending_state(
+        &mut self,
+        address: H160,
+        index: H256,
+        value: H256,
+    ) -> Result<()> {
+        exec!(self, Setter, set_pending_state(address, index, value))
+    }
+
+    fn remove_pending_tx(&mut self, tx: Transaction) -> Result<()> {
+        let tx = legacy(tx).c(d!())?;
+        exec!(self, Setter, remove_pending_tx(tx))
+    }
+
+    fn remove_pending_code(&mut self, address: H160) -> Result<()> {
+        exec!(self, Setter, remove_pending_code(address))
+    }
+
+    fn remove_pending_state(&mut self, address: H160, index: H256) -> Result<()> {
+        exec!(self, Setter, remove_pending_state(address, index))
+    }
+
+    fn balance(&mut self, height: u32, address: H160) -> Result<U256> {
+        exec!(self, Getter, get_balance(height, address))
+    }
+
+    fn nonce(&mut self, height: u32, address: H160) -> Result<U256> {
+        exec!(self, Getter, get_nonce(height, address))
+    }
+
+    fn byte_code(&mut self, height: u32, address: H160) -> Result<Vec<u8>> {
+        exec!(self, Getter, get_byte_code(height, address))
+    }
+
+    fn state(&mut self, height: u32, address: H160, index: H256) -> Result<H256> {
+        exec!(self, Getter, get_state(height, address, index))
+    }
+}
@@ -0,0 +1,85 @@
+use crate::*;
+use std::env::temp_dir;
+use std::time::SystemTime;
+
+fn exports_state(sink: &mut dyn Web3StateSink) {
+    let alice = H160::from_low_u64_be(1);
+    let index = H256::from_low_u64_be(2);
+
+    for height in [1, 3] {
+        sink.set_height(height).unwrap();
+        sink.set_balance(height, alice, U256::from(height * 10))
+            .unwrap();
+        sink.set_nonce(height, alice, U256::from(height)).unwrap();
+        sink.set_byte_code(height, alice, vec![height as u8])
+            .unwrap();
+        sink.set_state(height, alice, index, H256::from_low_u64_be(height as u64))
+            .unwrap();
+        sink.flush().unwrap();
+    }
+
+    assert_eq!(sink.latest_height().unwrap(), 3);
+    assert_eq!(sink.balance(0, alice).unwrap(), U256::zero());
+    assert_eq!(sink.balance(2, alice).unwrap(), U256::from(10));
+    assert_eq!(sink.balance(5, alice).unwrap(), U256::from(30));
+    assert_eq!(sink.nonce(3, alice).unwrap(), U256::from(3));
+    assert_eq!(sink.byte_code(2, alice).unwrap(), vec![1]);
+    assert_eq!(
+        sink.state(4, alice, index).unwrap(),
+        H256::from_low_u64_be(3)
+    );
+    assert_eq!(sink.state(4, alice, H256::zero()).unwrap(), H256::zero());
+}
+
+#[test]
+fn memory_sink_works() {
+    let mut sink = MemorySink::default();
+    exports_state(&mut sink);
+
+    let code_owner = H160::from_low_u64_be(9);
+    sink.set_pending_code(code_owner, vec![1, 2]).unwrap();
+    assert_eq!(sink.pending_codes.get(&code_owner), Some(&vec![1, 2]));
+    sink.remove_pending_code(code_owner).unwrap();
+    assert!(sink.pending_codes.is_empty());
+}
+
+#[test]
+fn disk_sink_works() {
+    let time = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let mut path = temp_dir();
+    path.push(format!("temp-web3-sink-{time}"));
+
+    exports_state(&mut DiskSink::open(&path).unwrap());
+
+    // reopened from the disk
+    let mut sink = DiskSink::open(&path).unwrap();
+    assert_eq!(sink.latest_height().unwrap(), 3);
+    assert_eq!(
+        sink.balance(2, H160::from_low_u64_be(1)).unwrap(),
+        U256::from(10)
+    );
+    std::fs::remove_dir_all(path).unwrap();
+}
+
+#[test]
+fn noop_sink_on_error() {
+    let mut sink = or_noop(SinkConfig::Redis("not a redis url".to_owned()).open());
+    assert_eq!(sink.latest_height().unwrap(), 0);
+    sink.set_balance(1, H160::from_low_u64_be(1), U256::one())
+        .unwrap();
+    assert_eq!(
+        sink.balance(1, H160::from_low_u64_be(1)).unwrap(),
+        U256::zero()
+    );
+}
+
+#[test]
+#[should_panic]
+fn explicit_sink_must_be_usable() {
+    // misspelled
+    std::env::set_var("WEB3_SINK", "dsik");
+    open_sink();
+}
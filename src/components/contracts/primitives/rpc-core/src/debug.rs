@@ -0,0 +1,36 @@
+//! Debug rpc interface.
+
+use crate::types::{BlockNumber, CallRequest, Trace, TraceParams};
+use ethereum_types::H256;
+use jsonrpc_core::{BoxFuture, Result};
+use jsonrpc_derive::rpc;
+
+pub use rpc_impl_DebugApi::gen_server::DebugApi as DebugApiServer;
+
+/// Debug rpc interface.
+#[rpc(server)]
+pub trait DebugApi {
+    /// Re-executes a transaction on the state of its block,
+    /// returns the struct logs or the call tree of it.
+    ///
+    /// Only the evm transactions before it in the block are replayed,
+    /// the conversions between the utxos and the evm balances are skipped,
+    /// so the trace may differ from the receipt of a transaction
+    /// spending a balance converted in the same block.
+    #[rpc(name = "debug_traceTransaction")]
+    fn trace_transaction(
+        &self,
+        hash: H256,
+        params: Option<TraceParams>,
+    ) -> BoxFuture<Result<Trace>>;
+
+    /// Executes a call on the state of a block like `eth_call`,
+    /// returns the struct logs or the call tree of it.
+    #[rpc(name = "debug_traceCall")]
+    fn trace_call(
+        &self,
+        request: CallRequest,
+        number: Option<BlockNumber>,
+        params: Option<TraceParams>,
+    ) -> BoxFuture<Result<Trace>>;
+}
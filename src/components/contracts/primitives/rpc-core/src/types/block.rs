@@ -84,6 +84,9 @@ pub struct Block {
     pub total_difficulty: U256,
     /// Seal fields
     pub seal_fields: Vec<Bytes>,
+    /// Base fee per gas, since the activation of EIP-1559
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base_fee_per_gas: Option<U256>,
     /// Uncles' hashes
     pub uncles: Vec<H256>,
     /// Transactions
@@ -129,6 +132,9 @@ pub struct Header {
     pub difficulty: U256,
     /// Seal fields
     pub seal_fields: Vec<Bytes>,
+    /// Base fee per gas, since the activation of EIP-1559
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base_fee_per_gas: Option<U256>,
     /// Size in bytes
     pub size: Option<U256>,
 }
@@ -16,8 +16,8 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-use crate::types::Bytes;
-use ethereum_types::{H160, U256};
+use crate::types::{AccessListItem, Bytes};
+use ethereum_types::{H160, U256, U64};
 use serde::Deserialize;
 
 /// Call request
@@ -39,4 +39,13 @@ pub struct CallRequest {
     pub data: Option<Bytes>,
     /// Nonce
     pub nonce: Option<U256>,
+    /// Max fee per gas of an EIP-1559 transaction
+    pub max_fee_per_gas: Option<U256>,
+    /// Max priority fee per gas of an EIP-1559 transaction
+    pub max_priority_fee_per_gas: Option<U256>,
+    /// Access list
+    pub access_list: Option<Vec<AccessListItem>>,
+    /// The type of the transaction
+    #[serde(rename = "type")]
+    pub transaction_type: Option<U64>,
 }
@@ -0,0 +1,109 @@
+//! Types of the `debug` namespace.
+
+use crate::types::Bytes;
+use ethereum_types::{H160, H256, U256};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Options of a trace, the same as the ones of geth.
+#[derive(Debug, Default, PartialEq, Eq, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceParams {
+    /// Do not return the storage of the struct logs.
+    pub disable_storage: Option<bool>,
+    /// Do not return the memory of the struct logs.
+    pub disable_memory: Option<bool>,
+    /// Do not return the stack of the struct logs.
+    pub disable_stack: Option<bool>,
+    /// `callTracer` for a call tree, struct logs if none.
+    pub tracer: Option<String>,
+}
+
+impl TraceParams {
+    /// The name of the call tracer.
+    pub const CALL_TRACER: &'static str = "callTracer";
+
+    /// Whether a call tree is requested.
+    pub fn is_call_tracer(&self) -> bool {
+        self.tracer.as_deref() == Some(Self::CALL_TRACER)
+    }
+}
+
+/// The result of a trace.
+#[derive(Debug, PartialEq, Eq, Serialize, Clone)]
+#[serde(untagged)]
+pub enum Trace {
+    /// The struct logs of all the executed opcodes.
+    Raw(RawTrace),
+    /// The tree of the calls.
+    CallTree(CallFrame),
+}
+
+/// The opcode trace of an execution.
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RawTrace {
+    /// Used gas
+    pub gas: U256,
+    /// Whether the execution failed
+    pub failed: bool,
+    /// Output of the execution
+    pub return_value: Bytes,
+    /// Executed opcodes
+    pub struct_logs: Vec<StructLog>,
+}
+
+/// An executed opcode.
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct StructLog {
+    /// Program counter
+    pub pc: u64,
+    /// Name of the opcode
+    pub op: String,
+    /// Remaining gas before the opcode
+    pub gas: U256,
+    /// Cost of the opcode
+    pub gas_cost: U256,
+    /// Depth of the call, starts from 1
+    pub depth: u64,
+    /// Stack before the opcode
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stack: Option<Vec<H256>>,
+    /// Memory before the opcode, in words
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory: Option<Vec<H256>>,
+    /// Storage of the contract accessed so far
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub storage: Option<BTreeMap<H256, H256>>,
+}
+
+/// A call in the style of the `callTracer` of geth.
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CallFrame {
+    /// CALL, STATICCALL, DELEGATECALL, CREATE or CREATE2
+    #[serde(rename = "type")]
+    pub call_type: String,
+    /// Caller
+    pub from: H160,
+    /// Callee, or the created contract
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<H160>,
+    /// Transferred value
+    pub value: U256,
+    /// Gas limit of the call
+    pub gas: U256,
+    /// Used gas
+    pub gas_used: U256,
+    /// Input data
+    pub input: Bytes,
+    /// Output data
+    pub output: Bytes,
+    /// The reason of a failed call
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Sub calls, in the order of execution
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub calls: Vec<CallFrame>,
+}
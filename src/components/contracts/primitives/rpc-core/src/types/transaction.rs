@@ -19,7 +19,7 @@
 use crate::types::Bytes;
 use ethereum_types::{H160, H256, H512, U256, U64};
 use serde::ser::SerializeStruct;
-use serde::{Serialize, Serializer};
+use serde::{Deserialize, Serialize, Serializer};
 use std::{
     collections::HashMap,
     sync::{Arc, Mutex},
@@ -67,6 +67,28 @@ pub struct Transaction {
     pub r: U256,
     /// The S field of the signature.
     pub s: U256,
+    /// The type of the transaction, see EIP-2718.
+    #[serde(rename = "type")]
+    pub transaction_type: U64,
+    /// Max fee per gas of an EIP-1559 transaction.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_fee_per_gas: Option<U256>,
+    /// Max priority fee per gas of an EIP-1559 transaction.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_priority_fee_per_gas: Option<U256>,
+    /// The access list of a typed transaction.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub access_list: Option<Vec<AccessListItem>>,
+}
+
+/// An item of the access list, see EIP-2930.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccessListItem {
+    /// Accessed address
+    pub address: H160,
+    /// Accessed storage keys of the address
+    pub storage_keys: Vec<H256>,
 }
 
 /// Local Transaction Status
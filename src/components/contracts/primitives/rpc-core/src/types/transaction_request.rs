@@ -18,8 +18,8 @@
 
 //! `TransactionRequest` type
 
-use crate::types::Bytes;
-use ethereum_types::{H160, U256};
+use crate::types::{AccessListItem, Bytes};
+use ethereum_types::{H160, U256, U64};
 use serde::{Deserialize, Serialize};
 
 /// Transaction request coming from RPC
@@ -41,4 +41,13 @@ pub struct TransactionRequest {
     pub data: Option<Bytes>,
     /// Transaction's nonce
     pub nonce: Option<U256>,
+    /// Max fee per gas of an EIP-1559 transaction
+    pub max_fee_per_gas: Option<U256>,
+    /// Max priority fee per gas of an EIP-1559 transaction
+    pub max_priority_fee_per_gas: Option<U256>,
+    /// Access list
+    pub access_list: Option<Vec<AccessListItem>>,
+    /// The type of the transaction
+    #[serde(rename = "type")]
+    pub transaction_type: Option<U64>,
 }
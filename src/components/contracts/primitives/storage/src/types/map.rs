@@ -1,6 +1,6 @@
 use crate::hash::StorageHasher;
 use crate::*;
-use config::abci::global_cfg::CFG;
+use config::abci::upgrade::{is_active, Feature};
 use ruc::*;
 use std::str::FromStr;
 use storage::db::MerkleDB;
@@ -141,7 +141,7 @@ where
 
     /// Remove the value under a key.
     pub fn remove<D: MerkleDB>(state: &mut State<D>, key: &Key) {
-        if state.height().unwrap() >= CFG.checkpoint.evm_substate_v2_height as u64 {
+        if is_active(Feature::EvmSubstateV2, state.height().unwrap()) {
             Instance::delete(state, Self::build_key_for(key).as_slice()).unwrap()
         } else {
             Instance::delete_v0(state, Self::build_key_for(key).as_slice()).unwrap()
@@ -1,6 +1,6 @@
 use crate::hash::*;
 use crate::*;
-use config::abci::global_cfg::CFG;
+use config::abci::upgrade::{is_active, Feature};
 use storage::db::MerkleDB;
 use storage::state::State;
 use storage::store::Prefix;
@@ -99,7 +99,7 @@ where
 
     /// Take a value from storage, removing it afterwards.
     pub fn delete<D: MerkleDB>(state: &mut State<D>) {
-        if state.height().unwrap() >= CFG.checkpoint.evm_substate_v2_height as u64 {
+        if is_active(Feature::EvmSubstateV2, state.height().unwrap()) {
             Instance::delete(state, <Self as StoragePrefixKey>::store_key().as_ref())
                 .unwrap()
         } else {
@@ -60,6 +60,12 @@ impl FeeCalculator for () {
     }
 }
 
+/// Trait that outputs the base fee of the current block, see EIP-1559.
+pub trait BaseFeeCalculator {
+    /// Return the base fee per gas of the block in `ctx`.
+    fn base_fee(ctx: &Context) -> U256;
+}
+
 /// Handle withdrawing, refunding and depositing of transaction fees.
 pub trait OnChargeEVMTransaction {
     /// Before the transaction is executed the payment of the transaction fees
@@ -74,4 +80,33 @@ pub trait OnChargeEVMTransaction {
         corrected_fee: U256,
         already_withdrawn: U256,
     ) -> Result<()>;
+
+    /// Pay the priority fee of an EIP-1559 transaction to the block author.
+    fn pay_priority_fee(ctx: &Context, tip: U256) -> Result<()>;
+}
+
+/// Lock the FRC20 tokens mapped to the utxo assets in the bridge,
+/// for the transfers of the evm balances to utxo.
+pub trait AssetBridge {
+    /// Lock `amount` of `token` of `who` in the bridge,
+    /// fails if `token` is not mapped to `asset` by the bridge.
+    fn lock_asset(
+        ctx: &Context,
+        who: H160,
+        token: H160,
+        asset: [u8; 32],
+        amount: U256,
+    ) -> Result<()>;
+}
+
+impl AssetBridge for () {
+    fn lock_asset(
+        _ctx: &Context,
+        _who: H160,
+        _token: H160,
+        _asset: [u8; 32],
+        _amount: U256,
+    ) -> Result<()> {
+        Err(ruc::eg!("the FRC20 assets are not supported"))
+    }
 }
@@ -1,7 +1,9 @@
-use ethereum::TransactionV0 as Transaction;
+use ethereum::{TransactionV0 as Transaction, TransactionV2};
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Action {
     Transact(Transaction),
+    /// EIP-2718 typed transactions, accepted since `Feature::TypedTransaction`.
+    TransactV2(TransactionV2),
 }
@@ -17,6 +17,13 @@ pub struct Call {
     pub gas_limit: u64,
     pub gas_price: Option<U256>,
     pub nonce: Option<U256>,
+    /// The tip per gas of an EIP-1559 transaction,
+    /// `gas_price` is the max fee per gas then.
+    #[serde(default)]
+    pub max_priority_fee_per_gas: Option<U256>,
+    /// The EIP-2930 access list.
+    #[serde(default)]
+    pub access_list: Vec<(H160, Vec<H256>)>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -27,6 +34,13 @@ pub struct Create {
     pub gas_limit: u64,
     pub gas_price: Option<U256>,
     pub nonce: Option<U256>,
+    /// The tip per gas of an EIP-1559 transaction,
+    /// `gas_price` is the max fee per gas then.
+    #[serde(default)]
+    pub max_priority_fee_per_gas: Option<U256>,
+    /// The EIP-2930 access list.
+    #[serde(default)]
+    pub access_list: Vec<(H160, Vec<H256>)>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -38,4 +52,11 @@ pub struct Create2 {
     pub gas_limit: u64,
     pub gas_price: Option<U256>,
     pub nonce: Option<U256>,
+    /// The tip per gas of an EIP-1559 transaction,
+    /// `gas_price` is the max fee per gas then.
+    #[serde(default)]
+    pub max_priority_fee_per_gas: Option<U256>,
+    /// The EIP-2930 access list.
+    #[serde(default)]
+    pub access_list: Vec<(H160, Vec<H256>)>,
 }
@@ -1,10 +1,14 @@
+use crate::crypto::Address;
+use primitive_types::H160;
 use serde::{Deserialize, Serialize};
 use zei::xfr::sig::XfrPublicKey;
-use zei::xfr::structs::AssetType;
+use zei::xfr::structs::{AssetType, XfrBody};
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Action {
     NonConfidentialTransfer(NonConfidentialTransfer),
+    /// Since `Feature::ConfidentialBridge`.
+    Transfer(Transfer),
 }
 
 /// Findora evm account balance transfer to NonConfidential utxo.
@@ -25,3 +29,140 @@ pub struct NonConfidentialOutput {
     #[serde(skip)]
     pub max_supply: u64,
 }
+
+/// Findora evm account balance transfer to utxo,
+/// the outputs can be confidential.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Transfer {
+    pub input_value: u64,
+    pub outputs: Vec<UtxoOutput>,
+}
+
+/// Evm account balance convert to utxo, `amount` and `asset` are public
+/// on the evm side, the minted record can hide them on the utxo side.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UtxoOutput {
+    pub asset: AssetType,
+    pub amount: u64,
+    pub target: XfrPublicKey,
+    /// Built by the sender for a confidential record, a transfer from a public
+    /// record of `amount` of `asset` to the record of `target`, so its blinds
+    /// are only known to the sender and the owner memo in it is for `target`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transfer: Option<Box<XfrBody>>,
+    /// The FRC20 token mapped to `asset`, for the assets other than FRA
+    /// transferred from an evm account.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token: Option<H160>,
+    #[serde(skip)]
+    pub decimal: u8,
+    #[serde(skip)]
+    pub max_supply: u64,
+}
+
+impl UtxoOutput {
+    #[inline(always)]
+    pub fn is_confidential(&self) -> bool {
+        self.transfer.is_some()
+    }
+}
+
+impl From<NonConfidentialOutput> for UtxoOutput {
+    fn from(o: NonConfidentialOutput) -> Self {
+        UtxoOutput {
+            asset: o.asset,
+            amount: o.amount,
+            target: o.target,
+            transfer: None,
+            token: None,
+            decimal: o.decimal,
+            max_supply: o.max_supply,
+        }
+    }
+}
+
+/// An output queued by the xhub, waiting to be minted by the ledger.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingMint {
+    pub id: u64,
+    /// The evm height it was queued at.
+    pub height: u64,
+    pub output: UtxoOutput,
+    pub status: MintStatus,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MintStatus {
+    Pending,
+    /// Minted by the ledger at the height.
+    Minted(u64),
+}
+
+/// A staking request from an evm account,
+/// applied to the `Staking` of the ledger at the end of the block.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StakingRequest {
+    /// Delegate the burned balance of `owner` to a validator,
+    /// `owner` will be refunded if the delegation fails.
+    Delegate {
+        owner: Address,
+        delegator: XfrPublicKey,
+        validator: H160,
+        amount: u64,
+    },
+    /// Undelegate from a validator, all of the delegations if `amount` is `None`.
+    UnDelegate {
+        owner: Address,
+        delegator: XfrPublicKey,
+        validator: H160,
+        amount: Option<u64>,
+    },
+    /// Claim the rewards, all of them if `amount` is `None`.
+    Claim {
+        owner: Address,
+        delegator: XfrPublicKey,
+        amount: Option<u64>,
+    },
+}
+
+impl StakingRequest {
+    /// The evm account that sent the request.
+    pub fn owner(&self) -> &Address {
+        match self {
+            StakingRequest::Delegate { owner, .. }
+            | StakingRequest::UnDelegate { owner, .. }
+            | StakingRequest::Claim { owner, .. } => owner,
+        }
+    }
+
+    /// The code of the request kind visible to the evm,
+    /// 1 for `Delegate`, 2 for `UnDelegate` and 3 for `Claim`.
+    pub fn kind(&self) -> u8 {
+        match self {
+            StakingRequest::Delegate { .. } => 1,
+            StakingRequest::UnDelegate { .. } => 2,
+            StakingRequest::Claim { .. } => 3,
+        }
+    }
+}
+
+/// The last failed staking request of an evm account,
+/// the ledger applies the requests after the calls succeed,
+/// so a failure can only be seen afterwards.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StakingFailure {
+    /// The height at which the request was applied.
+    pub height: u64,
+    pub request: StakingRequest,
+    pub reason: String,
+}
+
+/// The state of a validator visible to the evm, by its tendermint address.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StakingValidator {
+    pub td_addr: H160,
+    pub power: u64,
+    pub commission_rate: [u64; 2],
+    pub delegated: u64,
+    pub jailed: bool,
+}
@@ -102,6 +102,18 @@ impl From<H160> for Address32 {
     }
 }
 
+/// The evm address of an account mapped from it.
+impl<'a> TryFrom<&'a Address32> for H160 {
+    type Error = ();
+    fn try_from(x: &'a Address32) -> Result<Self, ()> {
+        if x.0.starts_with(b"evm:") && x.0[24..].iter().all(|b| *b == 0) {
+            Ok(H160::from_slice(&x.0[4..24]))
+        } else {
+            Err(())
+        }
+    }
+}
+
 /// A wrapper of the Hash type defined inf fixed-hash crate.
 #[derive(
     Clone, Eq, PartialEq, Ord, PartialOrd, Default, Hash, Serialize, Deserialize, Debug,
@@ -0,0 +1,232 @@
+use crate::eth::{call_access_list, call_fees, native_block_id, spawn_blocking};
+use crate::internal_err;
+use crate::tracer::Tracer;
+use crate::utils::convert_join_error_to_rpc_error;
+use baseapp::BaseApp;
+use ethereum::TransactionV2 as EthereumTransaction;
+use ethereum_types::{H256, U256};
+use evm::ExitReason;
+use fp_core::context::Context;
+use fp_evm::{BlockId, CallOrCreateInfo, Runner};
+use fp_rpc_core::types::{BlockNumber, CallRequest, Trace, TraceParams};
+use fp_rpc_core::DebugApi;
+use fp_traits::base::BaseProvider;
+use fp_types::actions::evm::{Call, Create};
+use jsonrpc_core::{BoxFuture, Result};
+use module_ethereum::TransactionData;
+use parking_lot::RwLock;
+use std::sync::Arc;
+use tracing::debug;
+
+pub struct DebugApiImpl {
+    account_base_app: Arc<RwLock<BaseApp>>,
+}
+
+impl DebugApiImpl {
+    pub fn new(account_base_app: Arc<RwLock<BaseApp>>) -> Self {
+        Self { account_base_app }
+    }
+}
+
+impl DebugApi for DebugApiImpl {
+    fn trace_transaction(
+        &self,
+        hash: H256,
+        params: Option<TraceParams>,
+    ) -> BoxFuture<Result<Trace>> {
+        debug!(target: "eth_rpc", "trace_transaction, hash:{:?}, params:{:?}", hash, params);
+
+        let account_base_app = self.account_base_app.clone();
+
+        let task = spawn_blocking(move || -> Result<Trace> {
+            let params = params.unwrap_or_default();
+
+            let (number, index) = account_base_app
+                .read()
+                .transaction_index(hash)
+                .ok_or_else(|| internal_err("transaction not found"))?;
+            let block = account_base_app
+                .read()
+                .current_block(Some(BlockId::Number(number)))
+                .ok_or_else(|| internal_err("failed to get block"))?;
+            let transaction = block
+                .transactions
+                .get(index as usize)
+                .ok_or_else(|| internal_err("transaction not found"))?;
+
+            // Replay the block on the state of its parent, only the evm
+            // transactions are in the block, the conversions from and to
+            // the utxos are skipped, see `DebugApi::trace_transaction`.
+            let height = block.header.number.as_u64();
+            let mut ctx = account_base_app
+                .read()
+                .create_context_at(height.saturating_sub(1))
+                .ok_or_else(|| internal_err("failed to create context"))?;
+
+            ctx.header
+                .mut_time()
+                .set_seconds(block.header.timestamp as i64);
+            ctx.header.height = height as i64;
+            ctx.header.proposer_address = Vec::from(block.header.beneficiary.as_bytes());
+
+            for tx in &block.transactions[..index as usize] {
+                if let Err(e) = execute_transaction(&ctx, tx) {
+                    debug!(target: "eth_rpc", "replay transaction error: {:?}", e);
+                }
+            }
+
+            let (res, tracer) =
+                Tracer::trace(&params, || execute_transaction(&ctx, transaction));
+            let (exit_reason, return_value, used_gas) = res?;
+
+            Ok(tracer.into_trace(&exit_reason, return_value, used_gas))
+        });
+
+        Box::pin(async move {
+            match task.await {
+                Ok(r) => r,
+                Err(e) => Err(convert_join_error_to_rpc_error(e)),
+            }
+        })
+    }
+
+    fn trace_call(
+        &self,
+        request: CallRequest,
+        block_number: Option<BlockNumber>,
+        params: Option<TraceParams>,
+    ) -> BoxFuture<Result<Trace>> {
+        debug!(target: "eth_rpc", "trace_call, height {:?}, request:{:?}", block_number, request);
+
+        let account_base_app = self.account_base_app.clone();
+
+        let task = spawn_blocking(move || -> Result<Trace> {
+            let params = params.unwrap_or_default();
+            let CallRequest {
+                from,
+                to,
+                gas_price,
+                gas,
+                value,
+                data,
+                nonce,
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+                access_list,
+                ..
+            } = request;
+            let (gas_price, max_priority_fee_per_gas) =
+                call_fees(gas_price, max_fee_per_gas, max_priority_fee_per_gas);
+            let access_list = call_access_list(access_list);
+
+            let id = native_block_id(block_number);
+            let block = account_base_app
+                .read()
+                .current_block(id)
+                .ok_or_else(|| internal_err("failed to get block"))?;
+
+            // use given gas limit or query current block's limit
+            let gas_limit = gas.unwrap_or(block.header.gas_limit);
+            let data = data.map(|d| d.0).unwrap_or_default();
+
+            let mut config = <BaseApp as module_ethereum::Config>::config().clone();
+            config.estimate = true;
+
+            let mut ctx = account_base_app
+                .read()
+                .create_context_at(block.header.number.as_u64())
+                .ok_or_else(|| internal_err("failed to create context"))?;
+
+            ctx.header
+                .mut_time()
+                .set_seconds(block.header.timestamp as i64);
+            ctx.header.height = block.header.number.as_u64() as i64;
+            ctx.header.proposer_address = Vec::from(block.header.beneficiary.as_bytes());
+
+            let (res, tracer) = Tracer::trace(&params, || match to {
+                Some(to) => {
+                    let call = Call {
+                        source: from.unwrap_or_default(),
+                        target: to,
+                        input: data,
+                        value: value.unwrap_or_default(),
+                        gas_limit: gas_limit.as_u64(),
+                        gas_price,
+                        max_priority_fee_per_gas,
+                        nonce,
+                        access_list,
+                    };
+                    <BaseApp as module_ethereum::Config>::Runner::call(
+                        &ctx, call, &config,
+                    )
+                    .map(CallOrCreateInfo::Call)
+                }
+                None => {
+                    let create = Create {
+                        source: from.unwrap_or_default(),
+                        init: data,
+                        value: value.unwrap_or_default(),
+                        gas_limit: gas_limit.as_u64(),
+                        gas_price,
+                        max_priority_fee_per_gas,
+                        nonce,
+                        access_list,
+                    };
+                    <BaseApp as module_ethereum::Config>::Runner::create(
+                        &ctx, create, &config,
+                    )
+                    .map(CallOrCreateInfo::Create)
+                }
+            });
+            let info = res.map_err(|err| {
+                internal_err(format!("evm runner trace call error: {err:?}"))
+            })?;
+            let (exit_reason, return_value, used_gas) = execution_result(info);
+
+            Ok(tracer.into_trace(&exit_reason, return_value, used_gas))
+        });
+
+        Box::pin(async move {
+            match task.await {
+                Ok(r) => r,
+                Err(e) => Err(convert_join_error_to_rpc_error(e)),
+            }
+        })
+    }
+}
+
+fn execute_transaction(
+    ctx: &Context,
+    transaction: &EthereumTransaction,
+) -> Result<(ExitReason, Vec<u8>, U256)> {
+    let source = module_ethereum::App::<BaseApp>::recover_signer(transaction)
+        .ok_or_else(|| internal_err("failed to recover signer"))?;
+
+    let data = TransactionData::from(transaction);
+    let (_, _, info) = module_ethereum::App::<BaseApp>::execute_transaction(
+        ctx,
+        source,
+        data.input,
+        data.value,
+        data.gas_limit,
+        Some(data.gas_price),
+        Some(data.nonce),
+        data.max_priority_fee_per_gas,
+        data.access_list,
+        data.action,
+    )
+    .map_err(|err| internal_err(format!("evm runner execute error: {err:?}")))?;
+
+    Ok(execution_result(info))
+}
+
+fn execution_result(info: CallOrCreateInfo) -> (ExitReason, Vec<u8>, U256) {
+    match info {
+        CallOrCreateInfo::Call(info) => (info.exit_reason, info.value, info.used_gas),
+        CallOrCreateInfo::Create(info) => (
+            info.exit_reason,
+            info.value.as_bytes().to_vec(),
+            info.used_gas,
+        ),
+    }
+}
@@ -3,23 +3,24 @@ use crate::utils::{
 };
 use crate::{error_on_execution_failure, internal_err};
 use baseapp::{extensions::SignedExtra, BaseApp};
-use config::abci::global_cfg::CFG;
+use config::abci::upgrade::{activation_height, is_active, Feature};
 use ethereum::{
-    BlockV0 as EthereumBlock, LegacyTransactionMessage as EthereumTransactionMessage,
-    TransactionV0 as EthereumTransaction,
+    BlockV2 as EthereumBlock, EIP1559TransactionMessage, EIP2930TransactionMessage,
+    EnvelopedEncodable, LegacyTransactionMessage, TransactionV0,
+    TransactionV2 as EthereumTransaction,
 };
 use ethereum_types::{BigEndianHash, Bloom, H160, H256, H512, H64, U256, U64};
 use evm::{ExitError, ExitReason};
 use fp_evm::{BlockId, Runner, TransactionStatus};
 use fp_rpc_core::types::{
-    Block, BlockNumber, BlockTransactions, Bytes, CallRequest, Filter, FilteredParams,
-    Index, Log, Receipt, Rich, RichBlock, SyncStatus, Transaction, TransactionRequest,
-    Work,
+    AccessListItem, Block, BlockNumber, BlockTransactions, Bytes, CallRequest,
+    FeeHistory, Filter, FilteredParams, Index, Log, Receipt, Rich, RichBlock,
+    SyncStatus, Transaction, TransactionRequest, Work,
 };
 use fp_rpc_core::EthApi;
 use fp_traits::{
     base::BaseProvider,
-    evm::{AddressMapping, EthereumAddressMapping, FeeCalculator},
+    evm::{AddressMapping, BaseFeeCalculator, EthereumAddressMapping, FeeCalculator},
 };
 use fp_types::{
     actions,
@@ -30,6 +31,7 @@ use fp_utils::{ecdsa::SecpPair, tx::EvmRawTxWrapper};
 use hex_literal::hex;
 use jsonrpc_core::{futures::future, BoxFuture, Result};
 use lazy_static::lazy_static;
+use module_ethereum::TransactionData;
 use parking_lot::RwLock;
 use sha3::{Digest, Keccak256};
 use std::{collections::BTreeMap, convert::Into, ops::Range, sync::Arc};
@@ -38,6 +40,9 @@ use tendermint_rpc::{Client, HttpClient};
 use tokio::runtime::{Handle, Runtime};
 use tracing::{debug, warn};
 
+// The max number of blocks in a fee history.
+const MAX_FEE_HISTORY: u64 = 1024;
+
 lazy_static! {
     static ref RT: Runtime =
         Runtime::new().expect("Failed to create thread pool executor");
@@ -53,7 +58,7 @@ lazy_static! {
 //
 // FixMe: Please remove me and initialize tokio runtime properly for both http and websocket when web3 server is booting.
 //
-fn spawn_blocking<F, R>(f: F) -> tokio::task::JoinHandle<R>
+pub(crate) fn spawn_blocking<F, R>(f: F) -> tokio::task::JoinHandle<R>
 where
     F: FnOnce() -> R + Send + 'static,
     R: Send + 'static,
@@ -339,7 +344,14 @@ impl EthApi for EthApiImpl {
                 value,
                 data,
                 nonce,
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+                access_list,
+                ..
             } = request;
+            let (gas_price, max_priority_fee_per_gas) =
+                call_fees(gas_price, max_fee_per_gas, max_priority_fee_per_gas);
+            let access_list = call_access_list(access_list);
 
             let id = native_block_id(block_number);
             let block = account_base_app
@@ -374,7 +386,9 @@ impl EthApi for EthApiImpl {
                         value: value.unwrap_or_default(),
                         gas_limit: gas_limit.as_u64(),
                         gas_price,
+                        max_priority_fee_per_gas,
                         nonce,
+                        access_list,
                     };
 
                     let info = <BaseApp as module_ethereum::Config>::Runner::call(
@@ -396,7 +410,9 @@ impl EthApi for EthApiImpl {
                         value: value.unwrap_or_default(),
                         gas_limit: gas_limit.as_u64(),
                         gas_price,
+                        max_priority_fee_per_gas,
                         nonce,
+                        access_list,
                     };
 
                     let info = <BaseApp as module_ethereum::Config>::Runner::create(
@@ -457,6 +473,108 @@ impl EthApi for EthApiImpl {
         })
     }
 
+    fn fee_history(
+        &self,
+        block_count: U256,
+        newest_block: BlockNumber,
+        reward_percentiles: Option<Vec<f64>>,
+    ) -> BoxFuture<Result<FeeHistory>> {
+        debug!(target: "eth_rpc", "fee_history, block_count:{:?}, newest_block:{:?}, reward_percentiles:{:?}", block_count, newest_block, reward_percentiles);
+
+        let account_base_app = self.account_base_app.clone();
+
+        let task = spawn_blocking(move || -> Result<FeeHistory> {
+            if let Some(percentiles) = reward_percentiles.as_ref() {
+                let sorted = percentiles.windows(2).all(|w| w[0] <= w[1]);
+                if !sorted || percentiles.iter().any(|p| !(0.0..=100.0).contains(p)) {
+                    return Err(internal_err("invalid reward percentiles"));
+                }
+            }
+            // the same cap as geth
+            let block_count = block_count.min(U256::from(MAX_FEE_HISTORY)).as_u64();
+
+            let newest = account_base_app
+                .read()
+                .current_block(native_block_id(Some(newest_block)))
+                .ok_or_else(|| internal_err("failed to get block"))?
+                .header
+                .number
+                .as_u64();
+            let oldest = newest.saturating_sub(block_count.saturating_sub(1));
+            let min_gas_price =
+                <BaseApp as module_evm::Config>::FeeCalculator::min_gas_price();
+
+            let mut fee_history = FeeHistory {
+                oldest_block: U256::from(oldest),
+                reward: reward_percentiles.as_ref().map(|_| vec![]),
+                ..Default::default()
+            };
+            if block_count == 0 {
+                return Ok(fee_history);
+            }
+
+            for height in oldest..=newest {
+                let id = Some(BlockId::Number(U256::from(height)));
+                let block = account_base_app
+                    .read()
+                    .current_block(id.clone())
+                    .ok_or_else(|| internal_err("failed to get block"))?;
+                let base_fee =
+                    block_base_fee(&account_base_app, height).unwrap_or(min_gas_price);
+
+                fee_history.base_fee_per_gas.push(base_fee);
+                fee_history
+                    .gas_used_ratio
+                    .push(if block.header.gas_limit.is_zero() {
+                        0.0
+                    } else {
+                        block.header.gas_used.as_u64() as f64
+                            / block.header.gas_limit.as_u64() as f64
+                    });
+
+                if let (Some(percentiles), Some(reward)) =
+                    (reward_percentiles.as_ref(), fee_history.reward.as_mut())
+                {
+                    let receipts = account_base_app
+                        .read()
+                        .current_receipts(id)
+                        .unwrap_or_default();
+                    reward.push(block_rewards(&block, &receipts, base_fee, percentiles));
+                }
+            }
+
+            // the base fee of the block after the newest one
+            let next_base_fee = if is_active(Feature::TypedTransaction, newest + 1) {
+                account_base_app
+                    .read()
+                    .create_context_at(newest)
+                    .map(|ctx| {
+                        <module_ethereum::App<BaseApp> as BaseFeeCalculator>::base_fee(
+                            &ctx,
+                        )
+                    })
+                    .unwrap_or(min_gas_price)
+            } else {
+                min_gas_price
+            };
+            fee_history.base_fee_per_gas.push(next_base_fee);
+
+            Ok(fee_history)
+        });
+
+        Box::pin(async move {
+            match task.await {
+                Ok(r) => r,
+                Err(e) => Err(convert_join_error_to_rpc_error(e)),
+            }
+        })
+    }
+
+    fn max_priority_fee_per_gas(&self) -> BoxFuture<Result<U256>> {
+        // The blocks are far from full, a transaction is included without any tip.
+        Box::pin(async move { Ok(U256::zero()) })
+    }
+
     fn block_number(&self) -> BoxFuture<Result<U256>> {
         let account_base_app = self.account_base_app.clone();
 
@@ -523,11 +641,14 @@ impl EthApi for EthApiImpl {
 
             match (block, statuses) {
                 (Some(block), Some(statuses)) => {
+                    let base_fee =
+                        block_base_fee(&account_base_app, block.header.number.as_u64());
                     let blk = rich_block_build(
                         block,
                         statuses.into_iter().map(Some).collect(),
                         Some(hash),
                         full,
+                        base_fee,
                     );
 
                     match blk {
@@ -563,7 +684,7 @@ impl EthApi for EthApiImpl {
 
         let task = spawn_blocking(move || -> Result<Option<RichBlock>> {
             if let Some(h) = height {
-                if 0 < h && h < CFG.checkpoint.evm_first_block_height as u64 {
+                if 0 < h && h < activation_height(Feature::EvmFirstBlock) {
                     return Ok(Some(dummy_block(h, full)));
                 }
             }
@@ -576,11 +697,14 @@ impl EthApi for EthApiImpl {
                 (Some(block), Some(statuses)) => {
                     let hash = block.header.hash();
 
+                    let base_fee =
+                        block_base_fee(&account_base_app, block.header.number.as_u64());
                     let blk = rich_block_build(
                         block,
                         statuses.into_iter().map(Some).collect(),
                         Some(hash),
                         full,
+                        base_fee,
                     );
 
                     match blk {
@@ -721,18 +845,35 @@ impl EthApi for EthApiImpl {
     }
 
     fn send_raw_transaction(&self, bytes: Bytes) -> BoxFuture<Result<H256>> {
-        let transaction = match rlp::decode::<EthereumTransaction>(&bytes.0[..]) {
-            Ok(transaction) => transaction,
-            Err(_) => {
-                return Box::pin(future::err(internal_err("decode transaction failed")));
+        let slice = &bytes.0[..];
+        if slice.is_empty() {
+            return Box::pin(future::err(internal_err("transaction data is empty")));
+        }
+        // A legacy transaction is a rlp list, whose first byte is above 0x7f,
+        // the others are typed transactions of EIP-2718.
+        let call = if slice[0] > 0x7f {
+            match rlp::decode::<TransactionV0>(slice) {
+                Ok(transaction) => actions::ethereum::Action::Transact(transaction),
+                Err(_) => {
+                    return Box::pin(future::err(internal_err(
+                        "decode transaction failed",
+                    )));
+                }
+            }
+        } else {
+            match rlp::decode::<EthereumTransaction>(&rlp::encode(&slice)) {
+                Ok(transaction) => actions::ethereum::Action::TransactV2(transaction),
+                Err(_) => {
+                    return Box::pin(future::err(internal_err(
+                        "decode transaction failed",
+                    )));
+                }
             }
         };
-        debug!(target: "eth_rpc", "send_raw_transaction :{:?}", transaction);
+        debug!(target: "eth_rpc", "send_raw_transaction :{:?}", call);
 
-        let transaction_hash =
-            H256::from_slice(Keccak256::digest(&rlp::encode(&transaction)).as_slice());
-        let function =
-            actions::Action::Ethereum(actions::ethereum::Action::Transact(transaction));
+        let transaction_hash = H256::from_slice(Keccak256::digest(slice).as_slice());
+        let function = actions::Action::Ethereum(call);
         let txn = serde_json::to_vec(
             &UncheckedTransaction::<SignedExtra>::new_unsigned(function),
         )
@@ -817,7 +958,10 @@ impl EthApi for EthApiImpl {
 
             // recap gas limit according to account balance
             if let Some(from) = request.from {
-                let gas_price = request.gas_price.unwrap_or_default();
+                let gas_price = request
+                    .max_fee_per_gas
+                    .or(request.gas_price)
+                    .unwrap_or_default();
                 if gas_price > U256::zero() {
                     let balance = Self::_balance(account_base_app.clone(), from, None)
                         .unwrap_or_default();
@@ -867,7 +1011,14 @@ impl EthApi for EthApiImpl {
                     value,
                     data,
                     nonce,
+                    max_fee_per_gas,
+                    max_priority_fee_per_gas,
+                    access_list,
+                    ..
                 } = request;
+                let (gas_price, max_priority_fee_per_gas) =
+                    call_fees(gas_price, max_fee_per_gas, max_priority_fee_per_gas);
+                let access_list = call_access_list(access_list);
 
                 let gas_limit = core::cmp::min(
                     gas.unwrap_or_else(|| U256::from(gas_limit)).low_u64(),
@@ -886,7 +1037,9 @@ impl EthApi for EthApiImpl {
                             value: value.unwrap_or_default(),
                             gas_limit,
                             gas_price,
+                            max_priority_fee_per_gas,
                             nonce,
+                            access_list,
                         };
 
                         let info = <BaseApp as module_ethereum::Config>::Runner::call(
@@ -910,7 +1063,9 @@ impl EthApi for EthApiImpl {
                             value: value.unwrap_or_default(),
                             gas_limit,
                             gas_price,
+                            max_priority_fee_per_gas,
                             nonce,
+                            access_list,
                         };
 
                         let info = <BaseApp as module_ethereum::Config>::Runner::create(
@@ -1006,10 +1161,13 @@ impl EthApi for EthApiImpl {
                         }
                     }
 
+                    let base_fee =
+                        block_base_fee(&account_base_app, block.header.number.as_u64());
                     Ok(Some(transaction_build(
                         block.transactions[index].clone(),
                         Some(block),
                         Some(statuses[index].clone()),
+                        base_fee,
                     )))
                 }
                 _ => Ok(None),
@@ -1047,10 +1205,13 @@ impl EthApi for EthApiImpl {
                         return Ok(None);
                     }
 
+                    let base_fee =
+                        block_base_fee(&account_base_app, block.header.number.as_u64());
                     Ok(Some(transaction_build(
                         block.transactions[index].clone(),
                         Some(block),
                         Some(statuses[index].clone()),
+                        base_fee,
                     )))
                 }
                 _ => Ok(None),
@@ -1085,10 +1246,13 @@ impl EthApi for EthApiImpl {
                         return Ok(None);
                     }
 
+                    let base_fee =
+                        block_base_fee(&account_base_app, block.header.number.as_u64());
                     Ok(Some(transaction_build(
                         block.transactions[index].clone(),
                         Some(block),
                         Some(statuses[index].clone()),
+                        base_fee,
                     )))
                 }
                 _ => Ok(None),
@@ -1140,6 +1304,14 @@ impl EthApi for EthApiImpl {
                     );
                     let receipt = receipts[index].clone();
                     let status = statuses[index].clone();
+                    let transaction = &block.transactions[index];
+                    let effective_gas_price = {
+                        let data = TransactionData::from(transaction);
+                        block_base_fee(&account_base_app, block.header.number.as_u64())
+                            .map_or(data.gas_price, |base_fee| {
+                                data.effective_gas_price(base_fee)
+                            })
+                    };
                     let mut cumulative_receipts = receipts;
                     cumulative_receipts
                         .truncate((status.transaction_index + 1) as usize);
@@ -1197,6 +1369,8 @@ impl EthApi for EthApiImpl {
                         status_code: Some(U64::from(receipt.state_root.to_low_u64_be())),
                         logs_bloom: receipt.logs_bloom,
                         state_root: None,
+                        transaction_type: U64::from(transaction_type(transaction)),
+                        effective_gas_price,
                     }));
                 }
                 _ => Ok(None),
@@ -1342,6 +1516,7 @@ fn rich_block_build(
     statuses: Vec<Option<TransactionStatus>>,
     hash: Option<H256>,
     full_transactions: bool,
+    base_fee: Option<U256>,
 ) -> Result<RichBlock> {
     if block.transactions.len() > statuses.len() {
         return Err(internal_err("block transaction statuses statue error!!!"));
@@ -1373,6 +1548,7 @@ fn rich_block_build(
                 Bytes(block.header.mix_hash.as_bytes().to_vec()),
                 Bytes(block.header.nonce.as_bytes().to_vec()),
             ],
+            base_fee_per_gas: base_fee,
             uncles: vec![],
             transactions: {
                 if full_transactions {
@@ -1386,6 +1562,7 @@ fn rich_block_build(
                                     transaction.clone(),
                                     Some(block.clone()),
                                     Some(statuses[index].clone().unwrap_or_default()),
+                                    base_fee,
                                 )
                             })
                             .collect(),
@@ -1395,14 +1572,7 @@ fn rich_block_build(
                         block
                             .transactions
                             .iter()
-                            .map(|transaction| {
-                                H256::from_slice(
-                                    Keccak256::digest(&rlp::encode(
-                                        &transaction.clone(),
-                                    ))
-                                    .as_slice(),
-                                )
-                            })
+                            .map(|transaction| transaction.hash())
                             .collect(),
                     )
                 }
@@ -1417,15 +1587,17 @@ fn transaction_build(
     transaction: EthereumTransaction,
     block: Option<EthereumBlock>,
     status: Option<TransactionStatus>,
+    base_fee: Option<U256>,
 ) -> Transaction {
     let pubkey = match public_key(&transaction) {
         Ok(p) => Some(p),
         Err(_e) => None,
     };
+    let data = TransactionData::from(&transaction);
 
-    Transaction {
-        hash: H256::from_slice(Keccak256::digest(&rlp::encode(&transaction)).as_slice()),
-        nonce: transaction.nonce,
+    let mut tx = Transaction {
+        hash: transaction.hash(),
+        nonce: data.nonce,
         block_hash: block.as_ref().map(|block| {
             H256::from_slice(Keccak256::digest(&rlp::encode(&block.header)).as_slice())
         }),
@@ -1446,41 +1618,179 @@ fn transaction_build(
         ),
         to: status.as_ref().map_or(
             {
-                match transaction.action {
+                match data.action {
                     ethereum::TransactionAction::Call(to) => Some(to),
                     _ => None,
                 }
             },
             |status| status.to,
         ),
-        value: transaction.value,
-        gas_price: transaction.gas_price,
-        gas: transaction.gas_limit,
-        input: Bytes(transaction.clone().input),
+        value: data.value,
+        // the effective gas price once the transaction is mined
+        gas_price: base_fee.map_or(data.gas_price, |base_fee| {
+            data.effective_gas_price(base_fee)
+        }),
+        gas: data.gas_limit,
+        input: Bytes(data.input.clone()),
         creates: status.as_ref().and_then(|status| status.contract_address),
-        raw: Bytes(rlp::encode(&transaction).to_vec()),
+        raw: Bytes(EnvelopedEncodable::encode(&transaction).to_vec()),
         public_key: pubkey.as_ref().map(H512::from),
-        chain_id: transaction.signature.chain_id().map(U64::from),
-        standard_v: U256::from(transaction.signature.standard_v()),
-        v: U256::from(transaction.signature.v()),
-        r: U256::from(transaction.signature.r().as_bytes()),
-        s: U256::from(transaction.signature.s().as_bytes()),
+        chain_id: data.chain_id.map(U64::from),
+        transaction_type: U64::from(transaction_type(&transaction)),
+        ..Default::default()
+    };
+
+    match &transaction {
+        EthereumTransaction::Legacy(t) => {
+            tx.standard_v = U256::from(t.signature.standard_v());
+            tx.v = U256::from(t.signature.v());
+            tx.r = U256::from(t.signature.r().as_bytes());
+            tx.s = U256::from(t.signature.s().as_bytes());
+        }
+        EthereumTransaction::EIP2930(t) => {
+            tx.standard_v = U256::from(t.odd_y_parity as u8);
+            tx.v = U256::from(t.odd_y_parity as u8);
+            tx.r = U256::from(t.r.as_bytes());
+            tx.s = U256::from(t.s.as_bytes());
+            tx.access_list = Some(rpc_access_list(&data.access_list));
+        }
+        EthereumTransaction::EIP1559(t) => {
+            tx.standard_v = U256::from(t.odd_y_parity as u8);
+            tx.v = U256::from(t.odd_y_parity as u8);
+            tx.r = U256::from(t.r.as_bytes());
+            tx.s = U256::from(t.s.as_bytes());
+            tx.max_fee_per_gas = Some(t.max_fee_per_gas);
+            tx.max_priority_fee_per_gas = Some(t.max_priority_fee_per_gas);
+            tx.access_list = Some(rpc_access_list(&data.access_list));
+        }
     }
+
+    tx
 }
 
 pub fn public_key(transaction: &EthereumTransaction) -> ruc::Result<[u8; 64]> {
     let mut sig = [0u8; 65];
     let mut msg = [0u8; 32];
-    sig[0..32].copy_from_slice(&transaction.signature.r()[..]);
-    sig[32..64].copy_from_slice(&transaction.signature.s()[..]);
-    sig[64] = transaction.signature.standard_v();
-    msg.copy_from_slice(
-        &EthereumTransactionMessage::from(transaction.clone()).hash()[..],
-    );
+    match transaction {
+        EthereumTransaction::Legacy(t) => {
+            sig[0..32].copy_from_slice(&t.signature.r()[..]);
+            sig[32..64].copy_from_slice(&t.signature.s()[..]);
+            sig[64] = t.signature.standard_v();
+            msg.copy_from_slice(&LegacyTransactionMessage::from(t.clone()).hash()[..]);
+        }
+        EthereumTransaction::EIP2930(t) => {
+            sig[0..32].copy_from_slice(&t.r[..]);
+            sig[32..64].copy_from_slice(&t.s[..]);
+            sig[64] = t.odd_y_parity as u8;
+            msg.copy_from_slice(&EIP2930TransactionMessage::from(t.clone()).hash()[..]);
+        }
+        EthereumTransaction::EIP1559(t) => {
+            sig[0..32].copy_from_slice(&t.r[..]);
+            sig[32..64].copy_from_slice(&t.s[..]);
+            sig[64] = t.odd_y_parity as u8;
+            msg.copy_from_slice(&EIP1559TransactionMessage::from(t.clone()).hash()[..]);
+        }
+    }
 
     fp_types::crypto::secp256k1_ecdsa_recover(&sig, &msg)
 }
 
+/// The priority fees per gas of a block at `percentiles`,
+/// weighted by the gas used of each transaction.
+fn block_rewards(
+    block: &EthereumBlock,
+    receipts: &[ethereum::ReceiptV0],
+    base_fee: U256,
+    percentiles: &[f64],
+) -> Vec<U256> {
+    let mut rewards: Vec<(U256, U256)> = block
+        .transactions
+        .iter()
+        .zip(receipts.iter())
+        .map(|(transaction, receipt)| {
+            let effective_gas_price =
+                TransactionData::from(transaction).effective_gas_price(base_fee);
+            (
+                effective_gas_price.saturating_sub(base_fee),
+                receipt.used_gas,
+            )
+        })
+        .collect();
+    if rewards.is_empty() {
+        return vec![U256::zero(); percentiles.len()];
+    }
+    rewards.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let gas_used = block.header.gas_used.as_u64() as f64;
+    let mut index = 0;
+    let mut cumulative_gas = rewards[0].1.as_u64();
+    percentiles
+        .iter()
+        .map(|p| {
+            let threshold = gas_used * p / 100.0;
+            while (cumulative_gas as f64) < threshold && index < rewards.len() - 1 {
+                index += 1;
+                cumulative_gas += rewards[index].1.as_u64();
+            }
+            rewards[index].0
+        })
+        .collect()
+}
+
+/// The type of `transaction`, see EIP-2718.
+fn transaction_type(transaction: &EthereumTransaction) -> u8 {
+    match transaction {
+        EthereumTransaction::Legacy(_) => 0,
+        EthereumTransaction::EIP2930(_) => 1,
+        EthereumTransaction::EIP1559(_) => 2,
+    }
+}
+
+fn rpc_access_list(access_list: &[(H160, Vec<H256>)]) -> Vec<AccessListItem> {
+    access_list
+        .iter()
+        .map(|(address, storage_keys)| AccessListItem {
+            address: *address,
+            storage_keys: storage_keys.clone(),
+        })
+        .collect()
+}
+
+pub(crate) fn call_access_list(
+    access_list: Option<Vec<AccessListItem>>,
+) -> Vec<(H160, Vec<H256>)> {
+    access_list
+        .unwrap_or_default()
+        .into_iter()
+        .map(|item| (item.address, item.storage_keys))
+        .collect()
+}
+
+/// The gas price and the tip of a call, the gas price is the max fee per gas
+/// for an EIP-1559 call.
+pub(crate) fn call_fees(
+    gas_price: Option<U256>,
+    max_fee_per_gas: Option<U256>,
+    max_priority_fee_per_gas: Option<U256>,
+) -> (Option<U256>, Option<U256>) {
+    match max_fee_per_gas {
+        Some(max_fee) => (
+            Some(max_fee),
+            Some(max_priority_fee_per_gas.unwrap_or_default()),
+        ),
+        None => (gas_price, None),
+    }
+}
+
+/// The base fee per gas of the block at `height`, which is decided by its parent.
+pub(crate) fn block_base_fee(app: &Arc<RwLock<BaseApp>>, height: u64) -> Option<U256> {
+    if !is_active(Feature::TypedTransaction, height) {
+        return None;
+    }
+    let ctx = app.read().create_context_at(height.saturating_sub(1))?;
+    Some(<module_ethereum::App<BaseApp> as BaseFeeCalculator>::base_fee(&ctx))
+}
+
 fn filter_range_logs(
     app: Arc<RwLock<BaseApp>>,
     ret: &mut Vec<Log>,
@@ -1599,7 +1909,7 @@ pub fn filter_block_logs<'a>(
     ret
 }
 
-fn native_block_id(number: Option<BlockNumber>) -> Option<BlockId> {
+pub(crate) fn native_block_id(number: Option<BlockNumber>) -> Option<BlockId> {
     match number.unwrap_or(BlockNumber::Latest) {
         BlockNumber::Hash { hash, .. } => Some(BlockId::Hash(hash)),
         BlockNumber::Num(number) => Some(BlockId::Number(number.into())),
@@ -1610,7 +1920,7 @@ fn native_block_id(number: Option<BlockNumber>) -> Option<BlockId> {
 }
 
 fn dummy_block(height: u64, full: bool) -> Rich<Block> {
-    let hash = if height == (CFG.checkpoint.evm_first_block_height as u64) - 1 {
+    let hash = if height == activation_height(Feature::EvmFirstBlock) - 1 {
         H256([0; 32])
     } else {
         H256::from_slice(&sha3::Keccak256::digest(&height.to_le_bytes()))
@@ -1657,6 +1967,7 @@ fn dummy_block(height: u64, full: bool) -> Rich<Block> {
             ),
             Bytes::new(hex!("0000000000000000").to_vec()),
         ],
+        base_fee_per_gas: None,
         uncles: vec![],
         transactions,
         size: Some(U256::from(0x1_u32)),
@@ -1,5 +1,6 @@
+use crate::eth::block_base_fee;
 use baseapp::BaseApp;
-use ethereum::{BlockV0 as EthereumBlock, ReceiptV0 as Receipt};
+use ethereum::{BlockV2 as EthereumBlock, ReceiptV0 as Receipt};
 use ethereum_types::{H256, U256};
 use fp_evm::BlockId;
 use fp_rpc_core::{
@@ -143,14 +144,21 @@ impl EthPubSubApiT for EthPubSubApiImpl {
                             if is_new_block {
                                 let block = app
                                     .read()
-                                    .current_block(Some(block_id));
+                                    .current_block(Some(block_id))
+                                    .map(|block| {
+                                        let base_fee = block_base_fee(
+                                            &app,
+                                            block.header.number.as_u64(),
+                                        );
+                                        (block, base_fee)
+                                    });
                                 futures::future::ready(block)
                             } else {
                                 futures::future::ready(None)
                             }
                         })
-                        .map(|block| {
-                            Ok::<_, ()>(Ok(SubscriptionResult::new().new_heads(block)))
+                        .map(|(block, base_fee)| {
+                            Ok::<_, ()>(Ok(SubscriptionResult::new().new_heads(block, base_fee)))
                         });
                     stream
                         .forward(
@@ -184,7 +192,11 @@ impl SubscriptionResult {
     pub fn new() -> Self {
         SubscriptionResult {}
     }
-    pub fn new_heads(&self, block: EthereumBlock) -> PubSubResult {
+    pub fn new_heads(
+        &self,
+        block: EthereumBlock,
+        base_fee: Option<U256>,
+    ) -> PubSubResult {
         PubSubResult::Header(Box::new(Rich {
             inner: Header {
                 hash: Some(H256::from_slice(
@@ -208,6 +220,7 @@ impl SubscriptionResult {
                     Bytes(block.header.mix_hash.as_bytes().to_vec()),
                     Bytes(block.header.nonce.as_bytes().to_vec()),
                 ],
+                base_fee_per_gas: base_fee,
                 size: Some(U256::from(rlp::encode(&block).len() as u32)),
             },
             extra_info: BTreeMap::new(),
@@ -226,10 +239,7 @@ impl SubscriptionResult {
         let mut log_index: u32 = 0;
         for (receipt_index, receipt) in receipts.into_iter().enumerate() {
             let transaction_hash: Option<H256> = if !receipt.logs.is_empty() {
-                Some(H256::from_slice(
-                    Keccak256::digest(&rlp::encode(&block.transactions[receipt_index]))
-                        .as_slice(),
-                ))
+                Some(block.transactions[receipt_index].hash())
             } else {
                 None
             };
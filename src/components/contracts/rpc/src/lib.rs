@@ -1,10 +1,12 @@
 #![deny(warnings)]
 #![allow(missing_docs)]
 
+mod debug;
 mod eth;
 mod eth_filter;
 mod eth_pubsub;
 mod net;
+mod tracer;
 mod utils;
 mod web3;
 
@@ -13,7 +15,8 @@ use eth::filter_block_logs;
 use evm::{ExitError, ExitReason};
 use fp_rpc_core::types::pubsub::Metadata;
 use fp_rpc_core::{
-    EthApiServer, EthFilterApiServer, EthPubSubApiServer, NetApiServer, Web3ApiServer,
+    DebugApiServer, EthApiServer, EthFilterApiServer, EthPubSubApiServer, NetApiServer,
+    Web3ApiServer,
 };
 use fp_rpc_server::{rpc_handler, start_http, start_ws, RpcHandler, RpcMiddleware};
 use fp_utils::ecdsa::SecpPair;
@@ -32,6 +35,7 @@ pub fn start_web3_service(
     evm_ws: String,
     tendermint_rpc: String,
     app: Arc<RwLock<BaseApp>>,
+    enable_debug: bool,
 ) -> Box<dyn std::any::Any + Send> {
     let app2 = Arc::new(RwLock::new(app.read().derive_app()));
 
@@ -59,6 +63,9 @@ pub fn start_web3_service(
                 net::NetApiImpl::new().to_delegate(),
                 web3::Web3ApiImpl::new().to_delegate(),
                 eth_pubsub::EthPubSubApiImpl::new(app2.clone()).to_delegate(),
+                // replaying transactions is costly, only served on demand
+                enable_debug
+                    .then(|| debug::DebugApiImpl::new(app.clone()).to_delegate()),
             ),
             RpcMiddleware::new(),
         )
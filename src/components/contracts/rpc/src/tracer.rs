@@ -0,0 +1,577 @@
+//!
+//! Collects the struct logs and the call tree of an evm execution
+//! from the tracing events of `evm`, `evm-runtime` and `evm-gasometer`.
+//!
+
+use ethereum_types::{H160, H256, U256};
+use evm::tracing::Event as EvmEvent;
+use evm::{CreateScheme, ExitReason};
+use evm_gasometer::tracing::{Event as GasEvent, Snapshot};
+use evm_runtime::tracing::Event as StepEvent;
+use fp_rpc_core::types::{Bytes, CallFrame, RawTrace, StructLog, Trace, TraceParams};
+use std::{cell::RefCell, collections::BTreeMap, rc::Rc};
+
+#[derive(Default)]
+pub struct Tracer {
+    disable_storage: bool,
+    disable_memory: bool,
+    disable_stack: bool,
+    call_tracer: bool,
+    // the gas limit of the transaction, for the root call
+    gas_limit: Option<U256>,
+    depth: u64,
+    struct_logs: Vec<StructLog>,
+    // whether the last struct log is waiting for its gas
+    pending_gas: bool,
+    storages: BTreeMap<H160, BTreeMap<H256, H256>>,
+    frames: Vec<CallFrame>,
+    root: Option<CallFrame>,
+}
+
+impl Tracer {
+    fn new(params: &TraceParams) -> Self {
+        Tracer {
+            disable_storage: params.disable_storage.unwrap_or_default(),
+            disable_memory: params.disable_memory.unwrap_or_default(),
+            disable_stack: params.disable_stack.unwrap_or_default(),
+            call_tracer: params.is_call_tracer(),
+            ..Default::default()
+        }
+    }
+
+    /// Run `f` with the tracer listening to the evm.
+    pub fn trace<R, F: FnOnce() -> R>(params: &TraceParams, f: F) -> (R, Self) {
+        let tracer = Rc::new(RefCell::new(Tracer::new(params)));
+        let mut evm_listener = Listener(tracer.clone());
+        let mut step_listener = Listener(tracer.clone());
+        let mut gas_listener = Listener(tracer.clone());
+
+        let res = evm::tracing::using(&mut evm_listener, || {
+            evm_runtime::tracing::using(&mut step_listener, || {
+                evm_gasometer::tracing::using(&mut gas_listener, f)
+            })
+        });
+
+        let tracer = tracer.take();
+        (res, tracer)
+    }
+
+    /// Build the result from the outcome of the execution.
+    pub fn into_trace(
+        mut self,
+        exit_reason: &ExitReason,
+        return_value: Vec<u8>,
+        used_gas: U256,
+    ) -> Trace {
+        if self.call_tracer {
+            // fold the calls not exited, if any
+            while !self.frames.is_empty() {
+                self.exit_frame(exit_reason, &return_value);
+            }
+            let mut root = self.root.unwrap_or_default();
+            if let Some(gas_limit) = self.gas_limit {
+                root.gas = gas_limit;
+            }
+            root.gas_used = used_gas;
+            Trace::CallTree(root)
+        } else {
+            Trace::Raw(RawTrace {
+                gas: used_gas,
+                failed: !exit_reason.is_succeed(),
+                return_value: Bytes(return_value),
+                struct_logs: self.struct_logs,
+            })
+        }
+    }
+
+    fn enter_frame(&mut self, frame: CallFrame) {
+        self.depth += 1;
+        if self.call_tracer {
+            self.frames.push(frame);
+        }
+    }
+
+    fn exit_frame(&mut self, reason: &ExitReason, return_value: &[u8]) {
+        self.depth = self.depth.saturating_sub(1);
+        if !self.call_tracer {
+            return;
+        }
+
+        if let Some(mut frame) = self.frames.pop() {
+            frame.output = Bytes(return_value.to_vec());
+            frame.error = match reason {
+                ExitReason::Succeed(_) => None,
+                ExitReason::Revert(_) => Some("execution reverted".to_string()),
+                ExitReason::Error(e) => Some(format!("{e:?}")),
+                ExitReason::Fatal(e) => Some(format!("{e:?}")),
+            };
+            match self.frames.last_mut() {
+                Some(parent) => parent.calls.push(frame),
+                None => self.root = Some(frame),
+            }
+        }
+    }
+
+    fn on_evm(&mut self, event: EvmEvent) {
+        match event {
+            EvmEvent::TransactCall { gas_limit, .. }
+            | EvmEvent::TransactCreate { gas_limit, .. }
+            | EvmEvent::TransactCreate2 { gas_limit, .. } => {
+                self.gas_limit = Some(gas_limit);
+            }
+            EvmEvent::Call {
+                code_address,
+                transfer,
+                input,
+                target_gas,
+                is_static,
+                context,
+            } => {
+                let call_type = if is_static {
+                    "STATICCALL"
+                } else if code_address != context.address {
+                    // the code of `code_address` runs on the storage of `context.address`
+                    if transfer.is_some() {
+                        "CALLCODE"
+                    } else {
+                        "DELEGATECALL"
+                    }
+                } else {
+                    "CALL"
+                };
+                let frame = CallFrame {
+                    call_type: call_type.to_string(),
+                    from: context.caller,
+                    to: Some(code_address),
+                    value: transfer
+                        .as_ref()
+                        .map(|t| t.value)
+                        .unwrap_or(context.apparent_value),
+                    gas: target_gas.map(U256::from).unwrap_or_default(),
+                    input: Bytes(input.to_vec()),
+                    ..Default::default()
+                };
+                self.enter_frame(frame);
+            }
+            EvmEvent::Create {
+                caller,
+                address,
+                scheme,
+                value,
+                init_code,
+                target_gas,
+            } => {
+                let call_type = match scheme {
+                    CreateScheme::Create2 { .. } => "CREATE2",
+                    _ => "CREATE",
+                };
+                let frame = CallFrame {
+                    call_type: call_type.to_string(),
+                    from: caller,
+                    to: Some(address),
+                    value,
+                    gas: target_gas.map(U256::from).unwrap_or_default(),
+                    input: Bytes(init_code.to_vec()),
+                    ..Default::default()
+                };
+                self.enter_frame(frame);
+            }
+            EvmEvent::Exit {
+                reason,
+                return_value,
+            } => {
+                self.exit_frame(reason, return_value);
+            }
+            _ => {}
+        }
+    }
+
+    fn on_step(&mut self, event: StepEvent) {
+        match event {
+            StepEvent::Step {
+                context,
+                opcode,
+                position,
+                stack,
+                memory,
+            } => {
+                if self.call_tracer {
+                    return;
+                }
+                let stack = (!self.disable_stack).then(|| stack.data().clone());
+                let memory = (!self.disable_memory).then(|| {
+                    memory
+                        .data()
+                        .chunks(32)
+                        .map(|word| {
+                            let mut buf = [0u8; 32];
+                            buf[..word.len()].copy_from_slice(word);
+                            H256(buf)
+                        })
+                        .collect()
+                });
+                let storage = (!self.disable_storage).then(|| {
+                    self.storages
+                        .get(&context.address)
+                        .cloned()
+                        .unwrap_or_default()
+                });
+                self.struct_logs.push(StructLog {
+                    pc: position.as_ref().map(|p| *p as u64).unwrap_or_default(),
+                    op: opcode_name(opcode.0),
+                    depth: self.depth,
+                    stack,
+                    memory,
+                    storage,
+                    ..Default::default()
+                });
+                self.pending_gas = true;
+            }
+            StepEvent::SLoad {
+                address,
+                index,
+                value,
+            }
+            | StepEvent::SStore {
+                address,
+                index,
+                value,
+            } => {
+                self.storages
+                    .entry(address)
+                    .or_default()
+                    .insert(index, value);
+                if let Some(storage) = self
+                    .struct_logs
+                    .last_mut()
+                    .and_then(|log| log.storage.as_mut())
+                {
+                    storage.insert(index, value);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn on_gas(&mut self, event: GasEvent) {
+        let (cost, snapshot) = match event {
+            GasEvent::RecordCost { cost, snapshot } => (cost, snapshot),
+            GasEvent::RecordDynamicCost {
+                gas_cost,
+                memory_gas,
+                snapshot,
+                ..
+            } => {
+                let memory_cost = snapshot
+                    .map(|s| memory_gas.saturating_sub(s.memory_gas))
+                    .unwrap_or_default();
+                (gas_cost + memory_cost, snapshot)
+            }
+            _ => return,
+        };
+
+        if !self.pending_gas {
+            return;
+        }
+        if let (
+            Some(log),
+            Some(Snapshot {
+                gas_limit,
+                memory_gas,
+                used_gas,
+                ..
+            }),
+        ) = (self.struct_logs.last_mut(), snapshot)
+        {
+            log.gas = U256::from(gas_limit.saturating_sub(used_gas + memory_gas));
+            log.gas_cost = U256::from(cost);
+            self.pending_gas = false;
+        }
+    }
+}
+
+#[derive(Clone)]
+struct Listener(Rc<RefCell<Tracer>>);
+
+impl evm::tracing::EventListener for Listener {
+    fn event(&mut self, event: EvmEvent) {
+        self.0.borrow_mut().on_evm(event);
+    }
+}
+
+impl evm_runtime::tracing::EventListener for Listener {
+    fn event(&mut self, event: StepEvent) {
+        self.0.borrow_mut().on_step(event);
+    }
+}
+
+impl evm_gasometer::tracing::EventListener for Listener {
+    fn event(&mut self, event: GasEvent) {
+        self.0.borrow_mut().on_gas(event);
+    }
+}
+
+fn opcode_name(op: u8) -> String {
+    let name = match op {
+        0x00 => "STOP",
+        0x01 => "ADD",
+        0x02 => "MUL",
+        0x03 => "SUB",
+        0x04 => "DIV",
+        0x05 => "SDIV",
+        0x06 => "MOD",
+        0x07 => "SMOD",
+        0x08 => "ADDMOD",
+        0x09 => "MULMOD",
+        0x0a => "EXP",
+        0x0b => "SIGNEXTEND",
+        0x10 => "LT",
+        0x11 => "GT",
+        0x12 => "SLT",
+        0x13 => "SGT",
+        0x14 => "EQ",
+        0x15 => "ISZERO",
+        0x16 => "AND",
+        0x17 => "OR",
+        0x18 => "XOR",
+        0x19 => "NOT",
+        0x1a => "BYTE",
+        0x1b => "SHL",
+        0x1c => "SHR",
+        0x1d => "SAR",
+        0x20 => "SHA3",
+        0x30 => "ADDRESS",
+        0x31 => "BALANCE",
+        0x32 => "ORIGIN",
+        0x33 => "CALLER",
+        0x34 => "CALLVALUE",
+        0x35 => "CALLDATALOAD",
+        0x36 => "CALLDATASIZE",
+        0x37 => "CALLDATACOPY",
+        0x38 => "CODESIZE",
+        0x39 => "CODECOPY",
+        0x3a => "GASPRICE",
+        0x3b => "EXTCODESIZE",
+        0x3c => "EXTCODECOPY",
+        0x3d => "RETURNDATASIZE",
+        0x3e => "RETURNDATACOPY",
+        0x3f => "EXTCODEHASH",
+        0x40 => "BLOCKHASH",
+        0x41 => "COINBASE",
+        0x42 => "TIMESTAMP",
+        0x43 => "NUMBER",
+        0x44 => "DIFFICULTY",
+        0x45 => "GASLIMIT",
+        0x46 => "CHAINID",
+        0x47 => "SELFBALANCE",
+        0x48 => "BASEFEE",
+        0x50 => "POP",
+        0x51 => "MLOAD",
+        0x52 => "MSTORE",
+        0x53 => "MSTORE8",
+        0x54 => "SLOAD",
+        0x55 => "SSTORE",
+        0x56 => "JUMP",
+        0x57 => "JUMPI",
+        0x58 => "PC",
+        0x59 => "MSIZE",
+        0x5a => "GAS",
+        0x5b => "JUMPDEST",
+        0x60..=0x7f => return format!("PUSH{}", op - 0x5f),
+        0x80..=0x8f => return format!("DUP{}", op - 0x7f),
+        0x90..=0x9f => return format!("SWAP{}", op - 0x8f),
+        0xa0..=0xa4 => return format!("LOG{}", op - 0xa0),
+        0xf0 => "CREATE",
+        0xf1 => "CALL",
+        0xf2 => "CALLCODE",
+        0xf3 => "RETURN",
+        0xf4 => "DELEGATECALL",
+        0xf5 => "CREATE2",
+        0xfa => "STATICCALL",
+        0xfd => "REVERT",
+        0xfe => "INVALID",
+        0xff => "SELFDESTRUCT",
+        _ => return format!("opcode 0x{op:x} not defined"),
+    };
+    name.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use evm::{Context, ExitRevert, ExitSucceed};
+    use evm_runtime::{Memory, Opcode, Stack};
+
+    fn context(caller: u64, address: u64) -> Context {
+        Context {
+            address: H160::from_low_u64_be(address),
+            caller: H160::from_low_u64_be(caller),
+            apparent_value: U256::zero(),
+        }
+    }
+
+    #[test]
+    fn opcode_names() {
+        assert_eq!(opcode_name(0x00), "STOP");
+        assert_eq!(opcode_name(0x60), "PUSH1");
+        assert_eq!(opcode_name(0x7f), "PUSH32");
+        assert_eq!(opcode_name(0x80), "DUP1");
+        assert_eq!(opcode_name(0x9f), "SWAP16");
+        assert_eq!(opcode_name(0xa4), "LOG4");
+        assert_eq!(opcode_name(0x0c), "opcode 0xc not defined");
+    }
+
+    #[test]
+    fn call_tree() {
+        let params = TraceParams {
+            tracer: Some(TraceParams::CALL_TRACER.to_string()),
+            ..Default::default()
+        };
+        let mut tracer = Tracer::new(&params);
+        let (root, inner) = (context(1, 2), context(2, 3));
+
+        tracer.on_evm(EvmEvent::TransactCall {
+            caller: root.caller,
+            address: root.address,
+            value: U256::zero(),
+            data: &[],
+            gas_limit: U256::from(100_000),
+        });
+        tracer.on_evm(EvmEvent::Call {
+            code_address: root.address,
+            transfer: &None,
+            input: &[1],
+            target_gas: Some(90_000),
+            is_static: false,
+            context: &root,
+        });
+        tracer.on_evm(EvmEvent::Call {
+            code_address: inner.address,
+            transfer: &None,
+            input: &[2],
+            target_gas: Some(50_000),
+            is_static: true,
+            context: &inner,
+        });
+        tracer.on_evm(EvmEvent::Exit {
+            reason: &ExitReason::Revert(ExitRevert::Reverted),
+            return_value: &[3],
+        });
+        tracer.on_evm(EvmEvent::Exit {
+            reason: &ExitReason::Succeed(ExitSucceed::Returned),
+            return_value: &[4],
+        });
+
+        let trace = tracer.into_trace(
+            &ExitReason::Succeed(ExitSucceed::Returned),
+            vec![4],
+            U256::from(30_000),
+        );
+        let root = match trace {
+            Trace::CallTree(root) => root,
+            Trace::Raw(_) => panic!("a call tree is expected"),
+        };
+        assert_eq!(root.call_type, "CALL");
+        assert_eq!(root.to, Some(H160::from_low_u64_be(2)));
+        assert_eq!(root.gas, U256::from(100_000));
+        assert_eq!(root.gas_used, U256::from(30_000));
+        assert_eq!(root.output, Bytes(vec![4]));
+        assert_eq!(root.error, None);
+
+        assert_eq!(root.calls.len(), 1);
+        let call = &root.calls[0];
+        assert_eq!(call.call_type, "STATICCALL");
+        assert_eq!(call.from, H160::from_low_u64_be(2));
+        assert_eq!(call.to, Some(H160::from_low_u64_be(3)));
+        assert_eq!(call.gas, U256::from(50_000));
+        assert_eq!(call.input, Bytes(vec![2]));
+        assert_eq!(call.output, Bytes(vec![3]));
+        assert_eq!(call.error.as_deref(), Some("execution reverted"));
+    }
+
+    #[test]
+    fn struct_logs() {
+        let params = TraceParams {
+            disable_memory: Some(true),
+            ..Default::default()
+        };
+        let mut tracer = Tracer::new(&params);
+        let ctx = context(1, 2);
+        let mut stack = Stack::new(1024);
+        let memory = Memory::new(1024);
+        let snapshot = || {
+            Some(Snapshot {
+                gas_limit: 100_000,
+                memory_gas: 0,
+                used_gas: 21_000,
+                refunded_gas: 0,
+            })
+        };
+
+        tracer.enter_frame(CallFrame::default());
+        tracer.on_step(StepEvent::Step {
+            context: &ctx,
+            opcode: Opcode(0x60),
+            position: &Ok(0),
+            stack: &stack,
+            memory: &memory,
+        });
+        tracer.on_gas(GasEvent::RecordCost {
+            cost: 3,
+            snapshot: snapshot(),
+        });
+        // only the first cost is the one of the opcode
+        tracer.on_gas(GasEvent::RecordCost {
+            cost: 100,
+            snapshot: snapshot(),
+        });
+
+        stack.push(H256::from_low_u64_be(1)).unwrap();
+        tracer.on_step(StepEvent::Step {
+            context: &ctx,
+            opcode: Opcode(0x55),
+            position: &Ok(2),
+            stack: &stack,
+            memory: &memory,
+        });
+        tracer.on_step(StepEvent::SStore {
+            address: ctx.address,
+            index: H256::from_low_u64_be(1),
+            value: H256::from_low_u64_be(5),
+        });
+
+        let trace = tracer.into_trace(
+            &ExitReason::Revert(ExitRevert::Reverted),
+            vec![],
+            U256::from(21_003),
+        );
+        let raw = match trace {
+            Trace::Raw(raw) => raw,
+            Trace::CallTree(_) => panic!("struct logs are expected"),
+        };
+        assert!(raw.failed);
+        assert_eq!(raw.gas, U256::from(21_003));
+        assert_eq!(raw.struct_logs.len(), 2);
+
+        let push = &raw.struct_logs[0];
+        assert_eq!(push.op, "PUSH1");
+        assert_eq!(push.depth, 1);
+        assert_eq!(push.gas, U256::from(79_000));
+        assert_eq!(push.gas_cost, U256::from(3));
+        assert_eq!(push.stack, Some(vec![]));
+        assert_eq!(push.memory, None);
+
+        let sstore = &raw.struct_logs[1];
+        assert_eq!(sstore.op, "SSTORE");
+        assert_eq!(sstore.pc, 2);
+        assert_eq!(sstore.stack, Some(vec![H256::from_low_u64_be(1)]));
+        assert_eq!(
+            sstore
+                .storage
+                .as_ref()
+                .unwrap()
+                .get(&H256::from_low_u64_be(1)),
+            Some(&H256::from_low_u64_be(5))
+        );
+    }
+}
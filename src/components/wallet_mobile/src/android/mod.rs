@@ -75,6 +75,35 @@ pub extern "system" fn Java_com_findora_JniApi_verifyAuthenticatedTxn(
         as jboolean
 }
 
+#[no_mangle]
+/// Given a serialized state commitment and light proof, returns the verified contents
+/// of the proof in JSON, or null if the proof is not valid for the state commitment.
+pub extern "system" fn Java_com_findora_JniApi_verifyLightProof(
+    env: JNIEnv,
+    _: JClass,
+    state_commitment: JString,
+    light_proof: JString,
+) -> jstring {
+    let state_commitment: String = env
+        .get_string(state_commitment)
+        .expect("Couldn't get java string!")
+        .into();
+
+    let light_proof: String = env
+        .get_string(light_proof)
+        .expect("Couldn't get java string!")
+        .into();
+
+    if let Ok(verified) = rs_verify_light_proof(state_commitment, light_proof) {
+        let output = env
+            .new_string(verified)
+            .expect("Couldn't create java string!");
+        **output
+    } else {
+        std::ptr::null_mut()
+    }
+}
+
 #[no_mangle]
 /// Generate mnemonic with custom length and language.
 /// - @param `wordslen`: acceptable value are one of [ 12, 15, 18, 21, 24 ]
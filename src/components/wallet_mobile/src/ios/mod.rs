@@ -58,6 +58,22 @@ pub extern "C" fn findora_ffi_verify_authenticated_txn(
     rs_verify_authenticated_txn(state_commitment, authenticated_txn).unwrap_or(false)
 }
 
+#[no_mangle]
+/// Given a serialized state commitment and light proof, returns the verified contents
+/// of the proof in JSON, or null if the proof is not valid for the state commitment.
+pub extern "C" fn findora_ffi_verify_light_proof(
+    state_commitment: *const c_char,
+    light_proof: *const c_char,
+) -> *mut c_char {
+    let state_commitment = c_char_to_string(state_commitment);
+    let light_proof = c_char_to_string(light_proof);
+    if let Ok(verified) = rs_verify_light_proof(state_commitment, light_proof) {
+        string_to_c_char(verified)
+    } else {
+        ptr::null_mut()
+    }
+}
+
 #[no_mangle]
 pub extern "C" fn findora_ffi_get_null_pk() -> *mut types::XfrPublicKey {
     let pk = get_null_pk().into();
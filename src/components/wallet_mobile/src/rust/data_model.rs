@@ -805,6 +805,20 @@ impl AssetRules {
         self
     }
 
+    /// The freezable flag determines whether the issuer can freeze non-confidential outputs of the asset.
+    /// @param {boolean} freezable - Boolean indicating whether the asset can be frozen.
+    pub fn set_freezable(mut self, freezable: bool) -> AssetRules {
+        self.rules.set_freezable(freezable);
+        self
+    }
+
+    /// The clawback flag determines whether the issuer can reclaim non-confidential outputs of the asset.
+    /// @param {boolean} clawback - Boolean indicating whether the asset can be reclaimed.
+    pub fn set_clawback(mut self, clawback: bool) -> AssetRules {
+        self.rules.set_clawback(clawback);
+        self
+    }
+
     /// Co-signature rules. Assets with co-signatue rules require additional weighted signatures to
     /// be transferred.
     /// @param {SignatureRules} multisig_rules - Co-signature restrictions.
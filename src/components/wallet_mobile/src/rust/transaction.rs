@@ -11,10 +11,11 @@ use globutils::HashOf;
 use ledger::{
     data_model::{
         gen_random_keypair, AssetTypeCode, AuthenticatedTransaction, Operation,
-        TransferType, TxOutput,
+        StateCommitmentData, TransferType, TxOutput,
     },
     staking::{td_addr_to_bytes, PartialUnDelegation, TendermintAddr},
 };
+use light_verifier::LightProof;
 use ruc::{eg, Result as RucResult};
 use serde_json::Result;
 use zei::xfr::asset_record::{open_blind_asset_record as open_bar, AssetRecordType};
@@ -33,6 +34,23 @@ pub fn rs_verify_authenticated_txn(
     Ok(authenticated_txn.is_valid(state_commitment))
 }
 
+/// Given a serialized state commitment and light proof, returns the verified contents
+/// of the proof in JSON, eg.. the records and spent status of utxos.
+pub fn rs_verify_light_proof(
+    state_commitment: String,
+    light_proof: String,
+) -> RucResult<String> {
+    let light_proof =
+        serde_json::from_str::<LightProof>(&light_proof).map_err(|e| eg!(e))?;
+    let state_commitment =
+        serde_json::from_str::<HashOf<Option<StateCommitmentData>>>(&state_commitment)
+            .map_err(|e| eg!(e))?;
+    let verified = light_proof
+        .verify(&state_commitment.0.hash.0)
+        .map_err(|e| eg!(e))?;
+    serde_json::to_string(&verified).map_err(|e| eg!(e))
+}
+
 #[derive(Clone)]
 struct FeeInput {
     // Amount
@@ -146,6 +164,8 @@ impl TransactionBuilder {
                         id: None,
                         record: new.0,
                         lien: None,
+                        version: Default::default(),
+                        lock: None,
                     },
                 });
                 base
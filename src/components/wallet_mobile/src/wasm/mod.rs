@@ -35,6 +35,24 @@ pub fn verify_authenticated_txn(
         .map_err(error_to_jsvalue)
 }
 
+#[wasm_bindgen]
+/// Given a serialized state commitment and light proof, returns the verified contents
+/// of the proof, eg.. the records and spent status of utxos.
+/// @param {string} state_commitment - String representing the state commitment.
+/// @param {string} light_proof - String representing the light proof.
+/// @see {@link module:Network~Network#getStateCommitment|Network.getStateCommitment}
+/// for instructions on fetching a ledger state commitment.
+/// @throws Will throw an error if the proof is not valid for the state commitment.
+pub fn verify_light_proof(
+    state_commitment: String,
+    light_proof: String,
+) -> Result<JsValue, JsValue> {
+    rs_verify_light_proof(state_commitment, light_proof)
+        .c(d!())
+        .map(JsValue::from)
+        .map_err(error_to_jsvalue)
+}
+
 #[wasm_bindgen]
 /// Structure that allows users to construct arbitrary transactions.
 pub struct TransactionBuilder(TxBuilder);
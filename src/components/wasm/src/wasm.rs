@@ -20,7 +20,7 @@ use {
         AttributeDefinition, ClientAssetRecord, Credential, CredentialCommitment,
         CredentialCommitmentData, CredentialCommitmentKey, CredentialIssuerKeyPair,
         CredentialPoK, CredentialRevealSig, CredentialSignature, CredentialUserKeyPair,
-        OwnerMemo, PublicParams, TracingPolicies, TxoRef,
+        OwnerMemo, PublicParams, TracingPolicies, TxoLock, TxoRef,
     },
     credentials::{
         credential_commit, credential_issuer_key_gen, credential_open_commitment,
@@ -281,6 +281,8 @@ impl TransactionBuilder {
                             id: None,
                             record: new.0,
                             lien: None,
+                            version: Default::default(),
+                            lock: None,
                         },
                     }
                     .to_json()
@@ -895,6 +897,82 @@ impl TransferOperationBuilder {
         self.add_output(amount, recipient, None, code, conf_amount, conf_type)
     }
 
+    /// Wraps around TransferOperationBuilder to add an output which can only be spent after `lock`.
+    ///
+    /// @param {BigInt} amount - amount to transfer to the recipient
+    /// @param {XfrPublicKey} recipient - public key of the recipient
+    /// @param code {string} - String representaiton of the asset token code
+    /// @param conf_amount {boolean} - `true` means the output's asset amount is confidential, and `false` means it's nonconfidential.
+    /// @param conf_type {boolean} - `true` means the output's asset type is confidential, and `false` means it's nonconfidential.
+    /// @param {TxoLock} lock - The height or time from which the output can be spent.
+    /// @throws Will throw an error if `code` fails to deserialize.
+    pub fn add_output_with_lock(
+        mut self,
+        amount: u64,
+        recipient: &XfrPublicKey,
+        code: String,
+        conf_amount: bool,
+        conf_type: bool,
+        lock: &TxoLock,
+    ) -> Result<TransferOperationBuilder, JsValue> {
+        let code = AssetTypeCode::new_from_base64(&code)
+            .c(d!())
+            .map_err(error_to_jsvalue)?;
+        let template = AssetRecordTemplate::with_no_asset_tracing(
+            amount,
+            code.val,
+            AssetRecordType::from_flags(conf_amount, conf_type),
+            *recipient,
+        );
+        self.get_builder_mut()
+            .add_output_with_lock(&template, *lock.get_lock())
+            .c(d!())
+            .map_err(error_to_jsvalue)?;
+        Ok(self)
+    }
+
+    /// Wraps around TransferOperationBuilder to add a locked input to a transfer operation builder,
+    /// the lock of the record must have been unlocked at `height` and `time`.
+    /// @param {TxoRef} txo_ref - Absolute or relative utxo reference
+    /// @param {ClientAssetRecord} asset_record - Client asset record to serve as transfer input.
+    /// @param {OwnerMemo} owner_memo - Opening parameters.
+    /// @param {XfrKeyPair} key - Key pair associated with the input.
+    /// @param {BigInt} amount - Amount of input record to transfer
+    /// @param {BigInt} height - Height of the next block.
+    /// @param {BigInt} time - Unix time of the next block, in seconds.
+    /// @throws Will throw an error if the record is still locked.
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_input_with_lock(
+        mut self,
+        txo_ref: TxoRef,
+        asset_record: &ClientAssetRecord,
+        owner_memo: Option<OwnerMemo>,
+        key: &XfrKeyPair,
+        amount: u64,
+        height: u64,
+        time: u64,
+    ) -> Result<TransferOperationBuilder, JsValue> {
+        let oar = open_bar(
+            asset_record.get_bar_ref(),
+            &owner_memo.map(|memo| memo.get_memo_ref().clone()),
+            &key,
+        )
+        .c(d!())
+        .map_err(error_to_jsvalue)?;
+        self.get_builder_mut()
+            .add_input_with_lock(
+                *txo_ref.get_txo(),
+                oar,
+                asset_record.txo.lock,
+                height,
+                time,
+                amount,
+            )
+            .c(d!())
+            .map_err(error_to_jsvalue)?;
+        Ok(self)
+    }
+
     /// Wraps around TransferOperationBuilder to ensure the transfer inputs and outputs are balanced.
     /// This function will add change outputs for all unspent portions of input records.
     /// @throws Will throw an error if the transaction cannot be balanced.
@@ -9,7 +9,7 @@ use {
     ledger::data_model::{
         AssetRules as PlatformAssetRules, AssetType as PlatformAssetType,
         AuthenticatedUtxo, SignatureRules as PlatformSignatureRules, TxOutput,
-        TxoRef as PlatformTxoRef, TxoSID,
+        TxoLock as PlatformTxoLock, TxoRef as PlatformTxoRef, TxoSID,
     },
     rand_chacha::ChaChaRng,
     rand_core::SeedableRng,
@@ -101,6 +101,38 @@ impl TxoRef {
     }
 }
 
+#[wasm_bindgen]
+/// The condition to spend a transfer output.
+#[derive(Copy, Clone)]
+pub struct TxoLock {
+    pub(crate) lock: PlatformTxoLock,
+}
+
+#[wasm_bindgen]
+impl TxoLock {
+    /// Creates a lock which is unlocked from the block at `height`.
+    /// @param {BigInt} height - Block height.
+    pub fn height(height: u64) -> Self {
+        TxoLock {
+            lock: PlatformTxoLock::Height(height),
+        }
+    }
+
+    /// Creates a lock which is unlocked from the first block whose time is not less than `time`.
+    /// @param {BigInt} time - Unix time in seconds.
+    pub fn time(time: u64) -> Self {
+        TxoLock {
+            lock: PlatformTxoLock::Time(time),
+        }
+    }
+}
+
+impl TxoLock {
+    pub fn get_lock(&self) -> &PlatformTxoLock {
+        &self.lock
+    }
+}
+
 /// Object representing an authenticable asset record. Clients can validate authentication proofs
 /// against a ledger state commitment.
 #[wasm_bindgen]
@@ -691,6 +723,20 @@ impl AssetRules {
         self
     }
 
+    /// The freezable flag determines whether the issuer can freeze non-confidential outputs of the asset.
+    /// @param {boolean} freezable - Boolean indicating whether the asset can be frozen.
+    pub fn set_freezable(mut self, freezable: bool) -> AssetRules {
+        self.rules.set_freezable(freezable);
+        self
+    }
+
+    /// The clawback flag determines whether the issuer can reclaim non-confidential outputs of the asset.
+    /// @param {boolean} clawback - Boolean indicating whether the asset can be reclaimed.
+    pub fn set_clawback(mut self, clawback: bool) -> AssetRules {
+        self.rules.set_clawback(clawback);
+        self
+    }
+
     /// Co-signature rules. Assets with co-signatue rules require additional weighted signatures to
     /// be transferred.
     /// @param {SignatureRules} multisig_rules - Co-signature restrictions.
@@ -3,13 +3,23 @@
 use crate::data_model::{
     NoReplayToken, Operation, Transaction, ASSET_TYPE_FRA, BLACK_HOLE_PUBKEY_STAKING,
 };
-use config::abci::global_cfg::CFG;
+use config::abci::upgrade::{is_active, Feature};
 use fp_types::{crypto::MultiSigner, H160};
+use rand_chacha::ChaChaRng;
+use rand_core::{CryptoRng, RngCore, SeedableRng};
 use ruc::*;
 use serde::{Deserialize, Serialize};
-use zei::xfr::{
-    sig::XfrPublicKey,
-    structs::{AssetType, XfrAmount, XfrAssetType},
+use zei::{
+    setup::PublicParams,
+    xfr::{
+        asset_record::AssetRecordType,
+        lib::{gen_xfr_body, verify_xfr_body, XfrNotePolicies},
+        sig::XfrPublicKey,
+        structs::{
+            AssetRecord, AssetRecordTemplate, AssetType, BlindAssetRecord, OwnerMemo,
+            TracingPolicies, XfrAmount, XfrAssetType, XfrBody,
+        },
+    },
 };
 
 #[inline(always)]
@@ -88,7 +98,7 @@ pub fn check_convert_account(
                 "TransferUTXOsToEVM error: nonce mismatch no_replay_token"
             ));
         }
-        if CFG.checkpoint.utxo_checktx_height > height {
+        if !is_active(Feature::UtxoSignatureMap, height as u64) {
             if tx.check_has_signature(&ca.signer).is_err() {
                 return Err(eg!("TransferUTXOsToEVM error: invalid signature"));
             }
@@ -128,9 +138,16 @@ pub fn check_convert_account(
             if matches!(o.record.asset_type, XfrAssetType::Confidential(_))
                 || matches!(o.record.amount, XfrAmount::Confidential(_))
             {
-                return Err(eg!(
+                // the converted outputs must be public, the others, like the change,
+                // can be confidential since `Feature::ConfidentialBridge`.
+                if o.record.public_key == *BLACK_HOLE_PUBKEY_STAKING
+                    || !is_active(Feature::ConfidentialBridge, height as u64)
+                {
+                    return Err(eg!(
                         "TransferUTXOsToEVM error: only support non-confidential UTXOs transfer to an evm account"
                     ));
+                }
+                continue;
             }
             if let XfrAssetType::NonConfidential(ty) = o.record.asset_type {
                 if o.record.public_key == *BLACK_HOLE_PUBKEY_STAKING
@@ -159,3 +176,212 @@ pub fn check_convert_account(
         expected_lowlevel,
     ))
 }
+
+/// Build the transfer of an evm output to a confidential utxo, from a public
+/// record of `amount` of `asset` to a record of `target` of `record_type`.
+///
+/// It's built by the sender, so the blinds of the minted record are only known
+/// to the sender, and the owner memo in it is encrypted to `target`.
+pub fn build_utxo_transfer<R: CryptoRng + RngCore>(
+    prng: &mut R,
+    target: XfrPublicKey,
+    amount: u64,
+    asset: AssetType,
+    record_type: AssetRecordType,
+) -> Result<XfrBody> {
+    // the public input is never spent, its owner doesn't matter
+    let records = [
+        AssetRecordType::NonConfidentialAmount_NonConfidentialAssetType,
+        record_type,
+    ]
+    .iter()
+    .map(|ty| {
+        let t = AssetRecordTemplate::with_no_asset_tracing(amount, asset, *ty, target);
+        AssetRecord::from_template_no_identity_tracing(prng, &t).c(d!())
+    })
+    .collect::<Result<Vec<_>>>()?;
+
+    gen_xfr_body(prng, &records[..1], &records[1..]).c(d!())
+}
+
+/// Check the transfer of an evm output to a confidential utxo against the
+/// burned `amount` of `asset`, returns the record to mint and its owner memo.
+pub fn check_utxo_transfer(
+    transfer: &XfrBody,
+    target: &XfrPublicKey,
+    amount: u64,
+    asset: AssetType,
+) -> Result<(BlindAssetRecord, Option<OwnerMemo>)> {
+    if transfer.inputs.len() != 1
+        || transfer.outputs.len() != 1
+        || transfer.owners_memos.len() != 1
+    {
+        return Err(eg!("utxo transfer error: must be one input and one output"));
+    }
+
+    let input = &transfer.inputs[0];
+    if input.amount != XfrAmount::NonConfidential(amount)
+        || input.asset_type != XfrAssetType::NonConfidential(asset)
+    {
+        return Err(eg!("utxo transfer error: input mismatch the burned asset"));
+    }
+
+    let output = &transfer.outputs[0];
+    if output.public_key != *target {
+        return Err(eg!("utxo transfer error: output mismatch the target"));
+    }
+
+    let no_policies = TracingPolicies::new();
+    let policies = XfrNotePolicies::new(
+        vec![no_policies.clone()],
+        vec![None],
+        vec![no_policies],
+        vec![None],
+    );
+    verify_xfr_body(
+        &mut ChaChaRng::from_entropy(),
+        &mut PublicParams::default(),
+        transfer,
+        &policies.to_ref(),
+    )
+    .c(d!("utxo transfer error"))?;
+
+    Ok((output.clone(), transfer.owners_memos[0].clone()))
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::*,
+        crate::data_model::{
+            TransferAsset, TransferAssetBody, TransferType, TxoRef, TxoSID,
+        },
+        zei::xfr::{
+            asset_record::{build_blind_asset_record, open_blind_asset_record},
+            sig::XfrKeyPair,
+        },
+    };
+
+    // a conversion of 100 FRA, with a change of 50 FRA of `change_type`,
+    // the converted output is of `convert_type`
+    fn convert_tx(
+        convert_type: AssetRecordType,
+        change_type: AssetRecordType,
+    ) -> Transaction {
+        let mut prng = ChaChaRng::from_seed([0; 32]);
+        let kp = XfrKeyPair::generate(&mut prng);
+
+        let template = |amount, ty, pk| {
+            AssetRecordTemplate::with_no_asset_tracing(amount, ASSET_TYPE_FRA, ty, pk)
+        };
+        let input = template(
+            150,
+            AssetRecordType::NonConfidentialAmount_NonConfidentialAssetType,
+            kp.get_pk(),
+        );
+        let pc_gens = PublicParams::default().pc_gens;
+        let (ba, _, memo) =
+            build_blind_asset_record(&mut prng, &pc_gens, &input, vec![]);
+        let input = open_blind_asset_record(&ba, &memo, &kp).unwrap();
+        let outputs = [
+            template(100, convert_type, *BLACK_HOLE_PUBKEY_STAKING),
+            template(50, change_type, kp.get_pk()),
+        ]
+        .iter()
+        .map(|t| AssetRecord::from_template_no_identity_tracing(&mut prng, t).unwrap())
+        .collect::<Vec<_>>();
+
+        let mut transfer = TransferAsset::new(
+            TransferAssetBody::new(
+                &mut prng,
+                vec![TxoRef::Absolute(TxoSID(0))],
+                &[AssetRecord::from_open_asset_record_no_asset_tracing(input)],
+                &outputs,
+                None,
+                vec![],
+                TransferType::Standard,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        transfer.sign(&kp);
+
+        let mut tx = Transaction::from_operation(Operation::TransferAsset(transfer), 0);
+        tx.add_operation(Operation::ConvertAccount(ConvertAccount {
+            signer: kp.get_pk(),
+            nonce: tx.body.no_replay_token,
+            receiver: MultiSigner::from(H160::zero()),
+            value: 100,
+            asset_type: None,
+            lowlevel_data: None,
+        }));
+        tx.sign(&kp);
+        tx.sign_to_map(&kp);
+        tx
+    }
+
+    #[test]
+    fn convert_with_confidential_change() {
+        let height = 1;
+        let public = AssetRecordType::NonConfidentialAmount_NonConfidentialAssetType;
+        let confidential = AssetRecordType::ConfidentialAmount_NonConfidentialAssetType;
+
+        let tx = convert_tx(public, public);
+        let (_, _, value, asset, _) = check_convert_account(&tx, height).unwrap();
+        assert_eq!((value, asset), (100, ASSET_TYPE_FRA));
+
+        // the change can be confidential since `Feature::ConfidentialBridge`
+        let tx = convert_tx(public, confidential);
+        assert_eq!(
+            check_convert_account(&tx, height).is_ok(),
+            is_active(Feature::ConfidentialBridge, height as u64)
+        );
+
+        // the converted output never
+        let tx = convert_tx(confidential, public);
+        assert!(check_convert_account(&tx, height).is_err());
+    }
+
+    #[test]
+    fn check_confidential_utxo_transfer() {
+        let mut prng = ChaChaRng::from_seed([1; 32]);
+        let kp = XfrKeyPair::generate(&mut prng);
+        let other = XfrKeyPair::generate(&mut prng).get_pk();
+        let ty = AssetRecordType::ConfidentialAmount_ConfidentialAssetType;
+
+        let transfer =
+            build_utxo_transfer(&mut prng, kp.get_pk(), 100, ASSET_TYPE_FRA, ty)
+                .unwrap();
+        let (record, memo) =
+            check_utxo_transfer(&transfer, &kp.get_pk(), 100, ASSET_TYPE_FRA).unwrap();
+        assert!(matches!(record.amount, XfrAmount::Confidential(_)));
+
+        // only the target can open it, to the burned amount
+        let opened = open_blind_asset_record(&record, &memo, &kp).unwrap();
+        assert_eq!(
+            (opened.get_amount(), opened.get_asset_type()),
+            (&100, &ASSET_TYPE_FRA)
+        );
+
+        // not the burned amount, nor asset, nor target
+        assert!(
+            check_utxo_transfer(&transfer, &kp.get_pk(), 101, ASSET_TYPE_FRA).is_err()
+        );
+        assert!(
+            check_utxo_transfer(&transfer, &kp.get_pk(), 100, AssetType([9; 32]))
+                .is_err()
+        );
+        assert!(check_utxo_transfer(&transfer, &other, 100, ASSET_TYPE_FRA).is_err());
+
+        // a tampered output breaks the proofs
+        let mut forged = transfer.clone();
+        forged.outputs[0] =
+            build_utxo_transfer(&mut prng, kp.get_pk(), 1000, ASSET_TYPE_FRA, ty)
+                .unwrap()
+                .outputs[0]
+                .clone();
+        assert!(
+            check_utxo_transfer(&forged, &kp.get_pk(), 100, ASSET_TYPE_FRA).is_err()
+        );
+    }
+}
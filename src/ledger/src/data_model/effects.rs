@@ -1,21 +1,26 @@
 use {
     crate::{
         data_model::{
-            AssetType, AssetTypeCode, DefineAsset, IssueAsset, IssuerPublicKey, Memo,
-            NoReplayToken, Operation, Transaction, TransferAsset, TransferType,
-            TxOutput, TxnTempSID, TxoRef, TxoSID, UpdateMemo,
+            AssetType, AssetTypeCode, ClawbackAsset, ClawbackAssetBody, DefineAsset,
+            FreezeAsset, IssueAsset, IssuerPublicKey, Memo, NoReplayToken, Operation,
+            Transaction, TransferAsset, TransferType, TxOutput, TxnTempSID, TxoRef,
+            TxoSID, UpdateMemo,
         },
+        error::LedgerError,
         staking::{
             self,
             ops::{
                 claim::ClaimOps, delegation::DelegationOps,
                 fra_distribution::FraDistributionOps, governance::GovernanceOps,
-                replace_staker::ReplaceStakerOps, undelegation::UnDelegationOps,
+                proposal::ProposalOps, replace_staker::ReplaceStakerOps,
+                schedule_upgrade::ScheduleUpgradeOps, undelegation::UnDelegationOps,
+                unjail::UnjailOps, update_penalty_rule::UpdatePenaltyRuleOps,
                 update_staker::UpdateStakerOps, update_validator::UpdateValidatorOps,
+                vote::VoteOps,
             },
         },
     },
-    config::abci::global_cfg::CFG,
+    config::abci::upgrade::{is_active, Feature},
     globutils::HashOf,
     lazy_static::lazy_static,
     parking_lot::Mutex,
@@ -76,6 +81,10 @@ pub struct TxnEffect {
     pub asset_types_involved: HashSet<AssetTypeCode>,
     /// Memo updates
     pub memo_updates: Vec<(AssetTypeCode, XfrPublicKey, Memo)>,
+    /// Freezes and unfreezes by the issuers
+    pub freezes: Vec<FreezeAsset>,
+    /// Reclaimed outputs, also recorded in `input_txos`
+    pub clawbacks: Vec<ClawbackAsset>,
 
     /// Staking operations
     pub delegations: Vec<DelegationOps>,
@@ -86,6 +95,16 @@ pub struct TxnEffect {
     /// Staking operations
     pub governances: Vec<GovernanceOps>,
     /// Staking operations
+    pub update_penalty_rules: Vec<UpdatePenaltyRuleOps>,
+    /// Staking operations
+    pub proposals: Vec<ProposalOps>,
+    /// Staking operations
+    pub votes: Vec<VoteOps>,
+    /// Staking operations
+    pub unjails: Vec<UnjailOps>,
+    /// Staking operations
+    pub schedule_upgrades: Vec<ScheduleUpgradeOps>,
+    /// Staking operations
     pub update_validators: HashMap<staking::BlockHeight, UpdateValidatorOps>,
     /// Staking operations
     pub fra_distributions: Vec<FraDistributionOps>,
@@ -126,7 +145,9 @@ impl TxnEffect {
             macro_rules! check_nonce {
                 ($i: expr) => {
                     if $i.get_nonce() != txn.body.no_replay_token {
-                        return Err(eg!(("nonce does not match")));
+                        return Err(eg!(
+                            LedgerError::InvalidNonce.with("nonce does not match")
+                        ));
                     }
                 };
             }
@@ -186,10 +207,40 @@ impl TxnEffect {
                 Operation::UpdateMemo(update_memo) => {
                     te.add_update_memo(&txn, update_memo).c(d!())?;
                 }
+                Operation::FreezeAsset(freeze) => {
+                    te.add_freeze_asset(&txn, freeze).c(d!())?;
+                }
+                Operation::ClawbackAsset(clawback) => {
+                    te.add_clawback_asset(&txn, clawback, &mut txo_count)
+                        .c(d!())?;
+                }
                 Operation::Governance(i) => {
                     check_nonce!(i);
                     te.governances.push(i.clone());
                 }
+                Operation::UpdatePenaltyRule(i) => {
+                    check_nonce!(i);
+                    te.update_penalty_rules.push(i.clone());
+                }
+                Operation::Proposal(i) => {
+                    check_nonce!(i);
+                    i.verify().c(d!())?;
+                    te.proposals.push(i.clone());
+                }
+                Operation::Vote(i) => {
+                    check_nonce!(i);
+                    i.verify().c(d!())?;
+                    te.votes.push(i.clone());
+                }
+                Operation::Unjail(i) => {
+                    check_nonce!(i);
+                    i.verify().c(d!())?;
+                    te.unjails.push(i.clone());
+                }
+                Operation::ScheduleUpgrade(i) => {
+                    check_nonce!(i);
+                    te.schedule_upgrades.push(i.clone());
+                }
                 Operation::FraDistribution(i) => {
                     check_nonce!(i);
                     te.fra_distributions.push(i.clone());
@@ -211,7 +262,9 @@ impl TxnEffect {
     //         - Partially checked here
     fn add_define_asset(&mut self, def: &DefineAsset) -> Result<()> {
         // (1)
-        def.signature.verify(&def.pubkey.key, &def.body).c(d!())?;
+        def.signature
+            .verify(&def.pubkey.key, &def.body)
+            .c(d!(LedgerError::InvalidSignature))?;
 
         let code = def.body.asset.code;
 
@@ -272,12 +325,14 @@ impl TxnEffect {
         iss_nums.push(seq_num);
 
         // (2)
-        iss.signature.verify(&iss.pubkey.key, &iss.body).c(d!())?;
+        iss.signature
+            .verify(&iss.pubkey.key, &iss.body)
+            .c(d!(LedgerError::InvalidSignature))?;
 
         // (3)
         if let Some(prior_key) = self.issuance_keys.get(&code) {
             if iss.pubkey != *prior_key {
-                return Err(eg!());
+                return Err(eg!(LedgerError::IssuerMismatch));
             }
         } else {
             self.issuance_keys.insert(code, iss.pubkey);
@@ -296,6 +351,8 @@ impl TxnEffect {
                     id: None,
                     record: output.record.clone(),
                     lien: None,
+                    version: Default::default(),
+                    lock: None,
                 })
             {
                 return Err(eg!());
@@ -333,6 +390,9 @@ impl TxnEffect {
     //            external checks later
     //          - For simplicity, only Standard transfers are allowed
     //            to have lien assignments
+    //     5) Locked inputs have been unlocked
+    //          - Recorded in `input_txos` and `internally_spent_txos`,
+    //            checked in check_txn_effects
     fn add_transfer_asset(
         &mut self,
         trn: &TransferAsset,
@@ -403,7 +463,7 @@ impl TxnEffect {
                 // (1a) all body signatures are valid
                 for sig in &trn.body_signatures {
                     if !trn.body.verify_body_signature(sig) {
-                        return Err(eg!());
+                        return Err(eg!(LedgerError::InvalidSignature));
                     }
                     input_keys.insert(sig.address.key.zei_to_bytes());
                 }
@@ -411,7 +471,9 @@ impl TxnEffect {
                 // (1b) all input record owners have signed
                 for record in trn.body.transfer.inputs.iter() {
                     if !input_keys.contains(&record.public_key.zei_to_bytes()) {
-                        return Err(eg!());
+                        return Err(eg!(
+                            LedgerError::InvalidSignature.with("input not signed")
+                        ));
                     }
                 }
 
@@ -466,7 +528,7 @@ impl TxnEffect {
                 TxoRef::Absolute(txo_sid) => {
                     // (2).(a), partially
                     if self.input_txos.contains_key(&txo_sid) {
-                        return Err(eg!());
+                        return Err(eg!(LedgerError::DoubleSpend));
                     }
 
                     self.input_txos.insert(
@@ -475,6 +537,9 @@ impl TxnEffect {
                             id: None,
                             record: record.clone(),
                             lien: lien.cloned(),
+                            version: Default::default(),
+                            // checked against the ledger later
+                            lock: None,
                         },
                     );
                 }
@@ -483,7 +548,14 @@ impl TxnEffect {
 
         self.txos.reserve(trn.body.transfer.outputs.len());
         let mut conf_transfer = false;
-        for (out, lien) in trn.body.transfer.outputs.iter().zip(lien_outputs) {
+        for ((out, lien), output) in trn
+            .body
+            .transfer
+            .outputs
+            .iter()
+            .zip(lien_outputs)
+            .zip(trn.body.outputs.iter())
+        {
             if let XfrAssetType::Confidential(_) = out.asset_type {
                 conf_transfer = true;
             }
@@ -491,10 +563,13 @@ impl TxnEffect {
                 self.asset_types_involved
                     .insert(AssetTypeCode { val: out_code });
             }
+            output.check_version().c(d!())?;
             self.txos.push(Some(TxOutput {
                 id: None,
                 record: out.clone(),
                 lien: lien.cloned(),
+                version: output.version,
+                lock: output.lock,
             }));
             *txo_count += 1;
         }
@@ -520,13 +595,13 @@ impl TxnEffect {
     ) -> Result<()> {
         let pk = update_memo.pubkey;
         if txn.body.no_replay_token != update_memo.body.no_replay_token {
-            return Err(eg!("replay token not match"));
+            return Err(eg!(LedgerError::InvalidNonce.with("replay token not match")));
         }
         // 1)
         update_memo
             .signature
             .verify(&pk, &update_memo.body)
-            .c(d!())?;
+            .c(d!(LedgerError::InvalidSignature))?;
         self.memo_updates.push((
             update_memo.body.asset_type,
             pk,
@@ -535,6 +610,90 @@ impl TxnEffect {
 
         Ok(())
     }
+
+    // A freeze is valid iff:
+    // 1) The signature is valid.
+    // 2) The asset type is freezable (checked later).
+    // 3) The signing key is the asset issuer key (checked later).
+    // 4) The target output is an unspent output of the asset (checked later).
+    fn add_freeze_asset(
+        &mut self,
+        txn: &Transaction,
+        freeze: &FreezeAsset,
+    ) -> Result<()> {
+        if txn.body.no_replay_token != freeze.body.no_replay_token {
+            return Err(eg!(LedgerError::InvalidNonce.with("replay token not match")));
+        }
+        // 1)
+        freeze
+            .signature
+            .verify(&freeze.pubkey.key, &freeze.body)
+            .c(d!(LedgerError::InvalidSignature))?;
+
+        self.freezes.push(freeze.clone());
+
+        Ok(())
+    }
+
+    // A clawback is valid iff:
+    // 1) The signature is valid.
+    // 2) The input is a non-confidential output of the asset,
+    //    and the output has the same amount and asset type.
+    // 3) The input exists unspent on the ledger.
+    //     - Partially checked here, recorded in `input_txos`
+    // 4) The asset type has the clawback rule (checked later).
+    // 5) The signing key is the asset issuer key (checked later).
+    fn add_clawback_asset(
+        &mut self,
+        txn: &Transaction,
+        clawback: &ClawbackAsset,
+        txo_count: &mut usize,
+    ) -> Result<()> {
+        let body = &clawback.body;
+        if txn.body.no_replay_token != body.no_replay_token {
+            return Err(eg!(LedgerError::InvalidNonce.with("replay token not match")));
+        }
+        // 1)
+        clawback
+            .signature
+            .verify(&clawback.pubkey.key, body)
+            .c(d!(LedgerError::InvalidSignature))?;
+
+        // 2)
+        let expected = ClawbackAssetBody::new(
+            body.code,
+            body.input,
+            body.input_record.clone(),
+            body.output.record.public_key,
+            body.no_replay_token,
+        )
+        .c(d!())?;
+        if expected != *body {
+            return Err(eg!("invalid clawback output"));
+        }
+
+        // 3), within this transaction
+        if self.input_txos.contains_key(&body.input) {
+            return Err(eg!(LedgerError::DoubleSpend));
+        }
+        self.input_txos.insert(
+            body.input,
+            TxOutput {
+                id: None,
+                version: Default::default(),
+                lock: None,
+                ..body.input_record.clone()
+            },
+        );
+
+        body.output.check_version().c(d!())?;
+        self.txos.push(Some(body.output.clone()));
+        *txo_count += 1;
+
+        self.clawbacks.push(clawback.clone());
+
+        Ok(())
+    }
 }
 
 /// Check tx in the context of a block, partially.
@@ -564,6 +723,8 @@ pub struct BlockEffect {
     pub issuance_keys: HashMap<AssetTypeCode, IssuerPublicKey>,
     /// Memo updates
     pub memo_updates: HashMap<AssetTypeCode, Memo>,
+    /// Freezes and unfreezes, in order
+    pub freezes: Vec<FreezeAsset>,
     /// counter for consensus integration; will add to a running count when applied.
     pub pulse_count: u64,
     /// simulator for safety
@@ -614,14 +775,45 @@ impl BlockEffect {
             self.memo_updates.insert(code, memo);
         }
 
+        self.freezes.extend(txn_effect.freezes);
+
         Ok(temp_sid)
     }
 
     fn check_txn_effect(&mut self, txn_effect: &TxnEffect) -> Result<()> {
+        self.check_txn_conflicts(txn_effect).c(d!())?;
+
+        // NOTE: set at the last position
+        self.check_staking(&txn_effect).c(d!())?;
+
+        Ok(())
+    }
+
+    /// Check that a TxnEffect does not conflict with the block so far,
+    /// except for its staking operations, nothing is changed.
+    pub fn check_txn_conflicts(&self, txn_effect: &TxnEffect) -> Result<()> {
         // Check that no inputs are consumed twice
         for (input_sid, _) in txn_effect.input_txos.iter() {
             if self.input_txos.contains_key(&input_sid) {
-                return Err(eg!());
+                return Err(eg!(LedgerError::DoubleSpend));
+            }
+        }
+
+        // Check that no inputs are frozen by the transactions before it,
+        // the clawbacks in the same transaction are checked by the ledger
+        for (input_sid, input) in txn_effect.input_txos.iter() {
+            if txn_effect
+                .clawbacks
+                .iter()
+                .any(|c| c.body.input == *input_sid)
+            {
+                continue;
+            }
+            let frozen = self.freezes.iter().fold(false, |frozen, f| {
+                alt!(f.covers(*input_sid, &input.record), f.body.frozen, frozen)
+            });
+            if frozen {
+                return Err(eg!(LedgerError::AssetFrozen));
             }
         }
 
@@ -660,7 +852,7 @@ impl BlockEffect {
         // Check that no operations are duplicated as in a replay attack
         // Note that we need to check here as well as in LedgerStatus::check_txn_effect
         let mut flag = true;
-        if self.staking_simulator.cur_height > CFG.checkpoint.fix_check_replay
+        if is_active(Feature::FixCheckReplay, self.staking_simulator.cur_height)
             && txn_effect.txn.body.operations.len() == 1
         {
             if let Some(Operation::MintFra(_)) = txn_effect.txn.body.operations.get(0) {
@@ -670,53 +862,18 @@ impl BlockEffect {
         if flag {
             for txn in self.txns.iter() {
                 if txn.body.no_replay_token == txn_effect.txn.body.no_replay_token {
-                    return Err(eg!());
+                    return Err(eg!(LedgerError::ReplayedNonce));
                 }
             }
         }
 
-        // NOTE: set at the last position
-        self.check_staking(&txn_effect).c(d!())?;
-
         Ok(())
     }
 
     fn check_staking(&mut self, txn_effect: &TxnEffect) -> Result<()> {
-        for i in txn_effect.update_stakers.iter() {
-            i.check_run(&mut self.staking_simulator, &txn_effect.txn)
-                .c(d!())?;
-        }
-
-        for i in txn_effect.replace_stakers.iter() {
-            i.check_run(&mut self.staking_simulator, &txn_effect.txn)
-                .c(d!())?;
-        }
-
-        for i in txn_effect.delegations.iter() {
-            i.check_run(&mut self.staking_simulator, &txn_effect.txn)
-                .c(d!())?;
-        }
-
-        for i in txn_effect.undelegations.iter() {
-            i.check_run(&mut self.staking_simulator, &txn_effect.txn)
-                .c(d!())?;
-        }
-
-        for i in txn_effect.claims.iter() {
-            i.check_run(&mut self.staking_simulator).c(d!())?;
-        }
-
-        for i in txn_effect.update_validators.values() {
-            i.check_run(&mut self.staking_simulator).c(d!())?;
-        }
-
-        for i in txn_effect.governances.iter() {
-            i.check_run(&mut self.staking_simulator).c(d!())?;
-        }
-
-        for i in txn_effect.fra_distributions.iter() {
-            i.check_run(&mut self.staking_simulator, &txn_effect.txn)
-                .c(d!())?;
+        let tx = &txn_effect.txn;
+        for (_, op) in staking_ops(tx) {
+            check_staking_op(&mut self.staking_simulator, op, tx).c(d!())?;
         }
 
         Ok(())
@@ -729,7 +886,7 @@ impl BlockEffect {
 
         println!("Current height is: {height}");
 
-        if (CFG.checkpoint.utxo_checktx_height as u64) < height {
+        if is_active(Feature::UtxoCheckTx, height) {
             HashOf::new(&self.txns)
         } else {
             let txns: Vec<Transaction> = self
@@ -752,3 +909,66 @@ impl BlockEffect {
         &mut self.staking_simulator
     }
 }
+
+// The position of a kind of staking operations in the execution,
+// `None` if it is not a staking operation.
+fn staking_order(op: &Operation) -> Option<u8> {
+    let o = match op {
+        Operation::UpdateStaker(_) => 0,
+        Operation::ReplaceStaker(_) => 1,
+        Operation::Delegation(_) => 2,
+        Operation::UnDelegation(_) => 3,
+        Operation::Claim(_) => 4,
+        Operation::UpdateValidator(_) => 5,
+        // new rules should take effect before the governances in the same tx
+        Operation::UpdatePenaltyRule(_) => 6,
+        Operation::Governance(_) => 7,
+        Operation::FraDistribution(_) => 8,
+        // a proposal can be voted on in the same tx that submits it
+        Operation::Proposal(_) => 9,
+        Operation::Vote(_) => 10,
+        Operation::Unjail(_) => 11,
+        Operation::ScheduleUpgrade(_) => 12,
+        _ => return None,
+    };
+    Some(o)
+}
+
+/// The staking operations of a transaction along with their indexes,
+/// in the order of execution: by their kinds, then by their indexes.
+pub fn staking_ops(tx: &Transaction) -> Vec<(usize, &Operation)> {
+    let mut ops = tx
+        .body
+        .operations
+        .iter()
+        .enumerate()
+        .filter_map(|(index, op)| staking_order(op).map(|o| (o, index, op)))
+        .collect::<Vec<_>>();
+    ops.sort_by_key(|(o, index, _)| (*o, *index));
+    ops.into_iter().map(|(_, index, op)| (index, op)).collect()
+}
+
+/// Run a staking operation of `tx` in a staking simulator,
+/// other operations are ignored.
+pub fn check_staking_op(
+    staking: &mut staking::Staking,
+    op: &Operation,
+    tx: &Transaction,
+) -> Result<()> {
+    match op {
+        Operation::UpdateStaker(i) => i.check_run(staking, tx).c(d!()),
+        Operation::ReplaceStaker(i) => i.check_run(staking, tx).c(d!()),
+        Operation::Delegation(i) => i.check_run(staking, tx).c(d!()),
+        Operation::UnDelegation(i) => i.check_run(staking, tx).c(d!()),
+        Operation::Claim(i) => i.check_run(staking).c(d!()),
+        Operation::UpdateValidator(i) => i.check_run(staking).c(d!()),
+        Operation::UpdatePenaltyRule(i) => i.check_run(staking).c(d!()),
+        Operation::Governance(i) => i.check_run(staking).c(d!()),
+        Operation::FraDistribution(i) => i.check_run(staking, tx).c(d!()),
+        Operation::Proposal(i) => i.check_run(staking).c(d!()),
+        Operation::Vote(i) => i.check_run(staking).c(d!()),
+        Operation::Unjail(i) => i.check_run(staking).c(d!()),
+        Operation::ScheduleUpgrade(i) => i.check_run(staking).c(d!()),
+        _ => Ok(()),
+    }
+}
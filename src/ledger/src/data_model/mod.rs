@@ -9,20 +9,23 @@ mod __trash__;
 mod effects;
 mod test;
 
-pub use effects::{BlockEffect, TxnEffect};
+pub use effects::{check_staking_op, staking_ops, BlockEffect, TxnEffect};
 use noah_algebra::bls12_381::BLSScalar;
 use noah_algebra::prelude::Scalar;
 use noah_crypto::basic::anemoi_jive::{AnemoiJive, AnemoiJive381};
 
 use {
     crate::converter::ConvertAccount,
+    crate::error::LedgerError,
     crate::staking::{
         ops::{
             claim::ClaimOps, delegation::DelegationOps,
             fra_distribution::FraDistributionOps, governance::GovernanceOps,
-            mint_fra::MintFraOps, replace_staker::ReplaceStakerOps,
-            undelegation::UnDelegationOps, update_staker::UpdateStakerOps,
-            update_validator::UpdateValidatorOps,
+            mint_fra::MintFraOps, proposal::ProposalOps,
+            replace_staker::ReplaceStakerOps, schedule_upgrade::ScheduleUpgradeOps,
+            undelegation::UnDelegationOps, unjail::UnjailOps,
+            update_penalty_rule::UpdatePenaltyRuleOps, update_staker::UpdateStakerOps,
+            update_validator::UpdateValidatorOps, vote::VoteOps,
         },
         Staking,
     },
@@ -553,6 +556,14 @@ pub struct AssetRules {
     pub max_units: Option<u64>,
     /// Decimals: default to FRA_DECIMALS
     pub decimals: u8,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_default")]
+    /// Freezable: Whether the issuer can freeze non-confidential outputs of the asset.
+    pub freezable: bool,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_default")]
+    /// Clawback: Whether the issuer can reclaim non-confidential outputs of the asset.
+    pub clawback: bool,
 }
 impl Default for AssetRules {
     #[inline(always)]
@@ -564,6 +575,8 @@ impl Default for AssetRules {
             max_units: None,
             transfer_multisig_rules: None,
             decimals: FRA_DECIMALS,
+            freezable: false,
+            clawback: false,
         }
     }
 }
@@ -607,6 +620,20 @@ impl AssetRules {
         self
     }
 
+    #[inline(always)]
+    #[allow(missing_docs)]
+    pub fn set_freezable(&mut self, freezable: bool) -> &mut Self {
+        self.freezable = freezable;
+        self
+    }
+
+    #[inline(always)]
+    #[allow(missing_docs)]
+    pub fn set_clawback(&mut self, clawback: bool) -> &mut Self {
+        self.clawback = clawback;
+        self
+    }
+
     #[inline(always)]
     #[allow(missing_docs)]
     pub fn set_decimals(&mut self, decimals: u8) -> Result<&mut Self> {
@@ -782,6 +809,44 @@ pub struct BlockSID(pub usize);
 #[derive(Clone, Copy, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
 pub struct TxnTempSID(pub usize);
 
+/// The condition to spend an output
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum TxoLock {
+    /// Spendable from the block at this height
+    Height(u64),
+    /// Spendable from the first block whose time, in unix seconds, is not less than this
+    Time(u64),
+}
+
+impl TxoLock {
+    /// Whether the output can be spent in the block at `height` and `time`.
+    #[inline(always)]
+    pub fn is_unlocked(&self, height: u64, time: u64) -> bool {
+        match *self {
+            TxoLock::Height(h) => height >= h,
+            TxoLock::Time(t) => time >= t,
+        }
+    }
+}
+
+/// The format of a `TxOutput`
+#[derive(
+    Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Ord, PartialOrd, Serialize,
+)]
+pub enum TxOutputVersion {
+    /// The original format, without a lock
+    V1,
+    /// Outputs with an optional `lock`
+    V2,
+}
+
+impl Default for TxOutputVersion {
+    #[inline(always)]
+    fn default() -> Self {
+        TxOutputVersion::V1
+    }
+}
+
 #[allow(missing_docs)]
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct TxOutput {
@@ -790,6 +855,36 @@ pub struct TxOutput {
     #[serde(default)]
     #[serde(skip_serializing_if = "is_default")]
     pub lien: Option<HashOf<Vec<TxOutput>>>,
+    /// `V1` is not serialized, so the outputs of the first version keep
+    /// their encoding and hashes
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_default")]
+    pub version: TxOutputVersion,
+    /// Only valid since `TxOutputVersion::V2`
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_default")]
+    pub lock: Option<TxoLock>,
+}
+
+impl TxOutput {
+    /// Set the lock of the output, and the version it requires.
+    #[inline(always)]
+    pub fn set_lock(&mut self, lock: Option<TxoLock>) -> &mut Self {
+        if lock.is_some() {
+            self.version = self.version.max(TxOutputVersion::V2);
+        }
+        self.lock = lock;
+        self
+    }
+
+    /// Check that the fields of the output are supported by its version.
+    pub fn check_version(&self) -> Result<()> {
+        if self.lock.is_some() && self.version < TxOutputVersion::V2 {
+            return Err(eg!(LedgerError::InvalidFormat
+                .with("locked outputs need the version 2 format")));
+        }
+        Ok(())
+    }
 }
 
 #[allow(missing_docs)]
@@ -929,6 +1024,8 @@ impl TransferAssetBody {
                 id: None,
                 record: rec.clone(),
                 lien: None,
+                version: Default::default(),
+                lock: None,
             })
             .collect();
         Ok(TransferAssetBody {
@@ -1284,6 +1381,141 @@ impl UpdateMemo {
     }
 }
 
+/// The target of a `FreezeAsset` operation
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum FreezeTarget {
+    /// A non-confidential output of the asset
+    Txo(TxoSID),
+    /// All the outputs of the asset owned by an address
+    Address(XfrPublicKey),
+}
+
+#[allow(missing_docs)]
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct FreezeAssetBody {
+    pub code: AssetTypeCode,
+    pub target: FreezeTarget,
+    /// `false` to unfreeze the target
+    pub frozen: bool,
+    pub no_replay_token: NoReplayToken,
+}
+
+/// Operation data for the issuer of a freezable asset to freeze or unfreeze its outputs
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct FreezeAsset {
+    #[allow(missing_docs)]
+    pub body: FreezeAssetBody,
+    /// The issuer of the asset
+    pub pubkey: IssuerPublicKey,
+    #[allow(missing_docs)]
+    pub signature: SignatureOf<FreezeAssetBody>,
+}
+
+impl FreezeAsset {
+    #[inline(always)]
+    #[allow(missing_docs)]
+    pub fn new(body: FreezeAssetBody, keypair: &IssuerKeyPair) -> FreezeAsset {
+        let signature = SignatureOf::new(keypair.keypair, &body);
+        FreezeAsset {
+            body,
+            pubkey: IssuerPublicKey {
+                key: *keypair.keypair.get_pk_ref(),
+            },
+            signature,
+        }
+    }
+
+    /// Whether `record`, spent as `sid`, is the target of this operation.
+    pub fn covers(&self, sid: TxoSID, record: &BlindAssetRecord) -> bool {
+        record.asset_type == XfrAssetType::NonConfidential(self.body.code.val)
+            && match self.body.target {
+                FreezeTarget::Txo(id) => id == sid,
+                FreezeTarget::Address(pk) => pk == record.public_key,
+            }
+    }
+}
+
+#[allow(missing_docs)]
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ClawbackAssetBody {
+    pub code: AssetTypeCode,
+    /// The output to reclaim
+    pub input: TxoSID,
+    /// The record of `input` on the ledger
+    pub input_record: TxOutput,
+    /// Same amount and asset type as the input, owned by the receiver
+    pub output: TxOutput,
+    pub no_replay_token: NoReplayToken,
+}
+
+impl ClawbackAssetBody {
+    /// Reclaim a non-confidential output to `receiver`,
+    /// `input_record` should be queried from the ledger.
+    pub fn new(
+        code: AssetTypeCode,
+        input: TxoSID,
+        input_record: TxOutput,
+        receiver: XfrPublicKey,
+        no_replay_token: NoReplayToken,
+    ) -> Result<Self> {
+        let asset_type = XfrAssetType::NonConfidential(code.val);
+        let amount = match input_record.record.amount {
+            XfrAmount::NonConfidential(am)
+                if input_record.record.asset_type == asset_type =>
+            {
+                am
+            }
+            _ => return Err(eg!("only non-confidential outputs can be reclaimed")),
+        };
+
+        let output = TxOutput {
+            id: None,
+            record: BlindAssetRecord {
+                amount: XfrAmount::NonConfidential(amount),
+                asset_type,
+                public_key: receiver,
+            },
+            lien: None,
+            version: Default::default(),
+            lock: None,
+        };
+
+        Ok(ClawbackAssetBody {
+            code,
+            input,
+            input_record,
+            output,
+            no_replay_token,
+        })
+    }
+}
+
+/// Operation data for the issuer of an asset with the clawback rule to reclaim an output
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ClawbackAsset {
+    #[allow(missing_docs)]
+    pub body: ClawbackAssetBody,
+    /// The issuer of the asset
+    pub pubkey: IssuerPublicKey,
+    #[allow(missing_docs)]
+    pub signature: SignatureOf<ClawbackAssetBody>,
+}
+
+impl ClawbackAsset {
+    #[inline(always)]
+    #[allow(missing_docs)]
+    pub fn new(body: ClawbackAssetBody, keypair: &IssuerKeyPair) -> ClawbackAsset {
+        let signature = SignatureOf::new(keypair.keypair, &body);
+        ClawbackAsset {
+            body,
+            pubkey: IssuerPublicKey {
+                key: *keypair.keypair.get_pk_ref(),
+            },
+            signature,
+        }
+    }
+}
+
 /// Operation list supported in findora network
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub enum Operation {
@@ -1315,6 +1547,20 @@ pub enum Operation {
     ConvertAccount(ConvertAccount),
     ///replace staker.
     ReplaceStaker(ReplaceStakerOps),
+    /// Update the penalty rules of byzantine behaviors
+    UpdatePenaltyRule(UpdatePenaltyRuleOps),
+    /// Propose to change a staking parameter
+    Proposal(ProposalOps),
+    /// Vote on a staking parameter proposal
+    Vote(VoteOps),
+    /// Return a jailed validator to the active set
+    Unjail(UnjailOps),
+    /// Schedule the activation heights of protocol features
+    ScheduleUpgrade(ScheduleUpgradeOps),
+    /// Freeze or unfreeze the outputs of a freezable custom asset
+    FreezeAsset(FreezeAsset),
+    /// Reclaim an output of a custom asset with the clawback rule
+    ClawbackAsset(ClawbackAsset),
 }
 
 fn set_no_replay_token(op: &mut Operation, no_replay_token: NoReplayToken) {
@@ -1340,7 +1586,24 @@ fn set_no_replay_token(op: &mut Operation, no_replay_token: NoReplayToken) {
         Operation::Governance(i) => {
             i.set_nonce(no_replay_token);
         }
+        Operation::UpdatePenaltyRule(i) => {
+            i.set_nonce(no_replay_token);
+        }
+        Operation::Proposal(i) => {
+            i.set_nonce(no_replay_token);
+        }
+        Operation::Vote(i) => {
+            i.set_nonce(no_replay_token);
+        }
+        Operation::Unjail(i) => {
+            i.set_nonce(no_replay_token);
+        }
+        Operation::ScheduleUpgrade(i) => {
+            i.set_nonce(no_replay_token);
+        }
         Operation::UpdateMemo(i) => i.body.no_replay_token = no_replay_token,
+        Operation::FreezeAsset(i) => i.body.no_replay_token = no_replay_token,
+        Operation::ClawbackAsset(i) => i.body.no_replay_token = no_replay_token,
         Operation::ConvertAccount(i) => i.set_nonce(no_replay_token),
         _ => {}
     }
@@ -1646,6 +1909,7 @@ impl FinalizedTransaction {
                 Operation::IssueAsset(d) => {
                     d.body.records.iter_mut().map(|(o, _)| o).collect()
                 }
+                Operation::ClawbackAsset(d) => vec![&mut d.body.output],
                 _ => Vec::new(),
             })
             .zip(ids.iter())
@@ -1655,6 +1919,39 @@ impl FinalizedTransaction {
 
         self.txo_ids = ids;
     }
+
+    /// The JSON pointer of the output record at `position` in the JSON
+    /// of `(TxnSID, Transaction)`, in the same order as `set_txo_id`.
+    pub fn get_output_pointer(&self, position: usize) -> Option<String> {
+        self.txn
+            .body
+            .operations
+            .iter()
+            .enumerate()
+            .flat_map(|(i, op)| {
+                let prefix = format!("/1/body/operations/{i}");
+                match op {
+                    Operation::TransferAsset(d) => (0..d.body.outputs.len())
+                        .map(|j| {
+                            format!("{prefix}/TransferAsset/body/outputs/{j}/record")
+                        })
+                        .collect(),
+                    Operation::MintFra(d) => (0..d.entries.len())
+                        .map(|j| format!("{prefix}/MintFra/entries/{j}/utxo/record"))
+                        .collect(),
+                    Operation::IssueAsset(d) => (0..d.body.records.len())
+                        .map(|j| {
+                            format!("{prefix}/IssueAsset/body/records/{j}/0/record")
+                        })
+                        .collect(),
+                    Operation::ClawbackAsset(_) => {
+                        vec![format!("{prefix}/ClawbackAsset/body/output/record")]
+                    }
+                    _ => Vec::new(),
+                }
+            })
+            .nth(position)
+    }
 }
 
 /// Use pure zero bytes(aka [0, 0, ... , 0]) to express FRA.
@@ -1674,6 +1971,25 @@ lazy_static! {
 /// see [**mainnet-v0.1 defination**](https://www.notion.so/findora/Transaction-Fees-Analysis-d657247b70f44a699d50e1b01b8a2287)
 pub const TX_FEE_MIN: u64 = 1_0000;
 
+/// The fees of UTXO transactions by the recent fullness of blocks,
+/// only used by the mempool, `TX_FEE_MIN` is still the rule of consensus.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct FeeInfo {
+    /// Transactions paying less are rejected by the mempool.
+    pub min_fee: u64,
+    /// A fee that is likely to be accepted in the next few blocks.
+    pub suggested_fee: u64,
+}
+
+impl Default for FeeInfo {
+    fn default() -> Self {
+        FeeInfo {
+            min_fee: TX_FEE_MIN,
+            suggested_fee: TX_FEE_MIN,
+        }
+    }
+}
+
 impl Transaction {
     #[inline(always)]
     #[allow(missing_docs)]
@@ -1690,6 +2006,21 @@ impl Transaction {
         self.check_fee() && !self.is_coinbase_tx()
     }
 
+    /// Same as `valid_in_abci`, with the typed reason
+    pub fn check_in_abci(&self) -> Result<()> {
+        if !self.check_fee() {
+            Err(eg!(
+                LedgerError::InsufficientFee.with("Should not appear in ABCI")
+            ))
+        } else if self.is_coinbase_tx() {
+            Err(eg!(
+                LedgerError::NotAllowed.with("Should not appear in ABCI")
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
     /// A simple fee checker
     ///
     /// The check logic is as follows:
@@ -1704,35 +2035,51 @@ impl Transaction {
         //
         // But it seems enough when we combine it with limiting
         // the payload size of submission-server's http-requests.
+        self.is_fee_exempt() || self.fee_outputs().any(|am| am > (TX_FEE_MIN - 1))
+    }
+
+    /// Transactions that need NOT fee, see [check_fee](Self::check_fee).
+    pub fn is_fee_exempt(&self) -> bool {
         self.is_coinbase_tx()
-            || self.body.operations.iter().any(|ops| {
-                if let Operation::TransferAsset(ref x) = ops {
-                    return x.body.outputs.iter().any(|o| {
-                        if let XfrAssetType::NonConfidential(ty) = o.record.asset_type {
-                            if ty == ASSET_TYPE_FRA
-                                && *BLACK_HOLE_PUBKEY == o.record.public_key
-                            {
-                                if let XfrAmount::NonConfidential(am) = o.record.amount {
-                                    if am > (TX_FEE_MIN - 1) {
-                                        return true;
-                                    }
-                                }
-                            }
-                        }
-                        false
-                    });
-                } else if let Operation::DefineAsset(ref x) = ops {
-                    if x.body.asset.code.val == ASSET_TYPE_FRA {
-                        return true;
-                    }
-                } else if let Operation::IssueAsset(ref x) = ops {
-                    if x.body.code.val == ASSET_TYPE_FRA {
-                        return true;
-                    }
-                } else if matches!(ops, Operation::UpdateValidator(_)) {
-                    return true;
+            || self.body.operations.iter().any(|ops| match ops {
+                Operation::DefineAsset(x) => x.body.asset.code.val == ASSET_TYPE_FRA,
+                Operation::IssueAsset(x) => x.body.code.val == ASSET_TYPE_FRA,
+                Operation::UpdateValidator(_) => true,
+                _ => false,
+            })
+    }
+
+    /// The total amount of fee paid by this transaction.
+    #[inline(always)]
+    pub fn fee_amount(&self) -> u64 {
+        self.fee_outputs().fold(0, u64::saturating_add)
+    }
+
+    /// The priority in the mempool, i.e. the fee paid per KB.
+    #[inline(always)]
+    pub fn fee_priority(&self, size: usize) -> i64 {
+        let p = self.fee_amount() as u128 * 1024 / size.max(1) as u128;
+        i64::try_from(p).unwrap_or(i64::MAX)
+    }
+
+    // the amounts of all the non-confidential FRA outputs paid to `BLACK_HOLE_PUBKEY`
+    fn fee_outputs(&self) -> impl Iterator<Item = u64> + '_ {
+        self.body
+            .operations
+            .iter()
+            .filter_map(|ops| match ops {
+                Operation::TransferAsset(x) => Some(x.body.outputs.iter()),
+                _ => None,
+            })
+            .flatten()
+            .filter_map(|o| match (&o.record.asset_type, &o.record.amount) {
+                (XfrAssetType::NonConfidential(ty), XfrAmount::NonConfidential(am))
+                    if *ty == ASSET_TYPE_FRA
+                        && *BLACK_HOLE_PUBKEY == o.record.public_key =>
+                {
+                    Some(*am)
                 }
-                false
+                _ => None,
             })
     }
 
@@ -1841,6 +2188,9 @@ impl Transaction {
                 Operation::IssueAsset(issue_asset) => {
                     memos.append(&mut issue_asset.get_owner_memos_ref());
                 }
+                Operation::ClawbackAsset(_) => {
+                    memos.push(None);
+                }
                 _ => {}
             }
         }
@@ -1906,11 +2256,12 @@ impl Transaction {
     #[inline(always)]
     pub fn check_tx(&self) -> Result<()> {
         let select_check = |tx: &Transaction, pk: &XfrPublicKey| -> Result<()> {
-            if tx.signatures.is_empty() {
+            let res = if tx.signatures.is_empty() {
                 tx.check_has_signature_from_map(pk)
             } else {
                 tx.check_has_signature(pk)
-            }
+            };
+            res.c(d!(LedgerError::InvalidSignature))
         };
 
         for operation in self.body.operations.iter() {
@@ -1929,6 +2280,12 @@ impl Transaction {
                 Operation::UpdateMemo(o) => {
                     select_check(self, &o.pubkey).c(d!())?;
                 }
+                Operation::FreezeAsset(o) => {
+                    select_check(self, &o.pubkey.key).c(d!())?;
+                }
+                Operation::ClawbackAsset(o) => {
+                    select_check(self, &o.pubkey.key).c(d!())?;
+                }
                 Operation::UpdateStaker(o) => {
                     select_check(self, &o.pubkey).c(d!())?;
                 }
@@ -1941,8 +2298,19 @@ impl Transaction {
                 Operation::Claim(o) => {
                     select_check(self, &o.pubkey).c(d!())?;
                 }
+                Operation::Proposal(o) => {
+                    select_check(self, &o.pubkey).c(d!())?;
+                }
+                Operation::Vote(o) => {
+                    select_check(self, &o.pubkey).c(d!())?;
+                }
+                Operation::Unjail(o) => {
+                    select_check(self, &o.pubkey).c(d!())?;
+                }
                 Operation::UpdateValidator(_) => {}
                 Operation::Governance(_) => {}
+                Operation::UpdatePenaltyRule(_) => {}
+                Operation::ScheduleUpgrade(_) => {}
                 Operation::FraDistribution(_) => {}
                 Operation::MintFra(_) => {}
                 Operation::ConvertAccount(o) => {
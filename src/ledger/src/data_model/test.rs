@@ -284,6 +284,8 @@ fn gen_fee_operation(
                     public_key: dest_pubkey,
                 },
                 lien: None,
+                version: Default::default(),
+                lock: None,
             }],
             lien_assignments: Vec::new(),
             transfer: Box::new(XfrBody {
@@ -377,3 +379,81 @@ fn test_check_fee() {
     tx.add_operation(invalid_destination_not_black_hole);
     assert!(tx.check_fee());
 }
+
+#[test]
+fn test_fee_amount() {
+    let mut tx = gen_sample_tx();
+    assert!(!tx.is_fee_exempt());
+    assert_eq!(0, tx.fee_amount());
+    assert_eq!(0, tx.fee_priority(1024));
+
+    let fra = Some(ZeiAssetType([0; ASSET_TYPE_LENGTH]));
+    tx.add_operation(gen_fee_operation(Some(TX_FEE_MIN), fra, *BLACK_HOLE_PUBKEY));
+    tx.add_operation(gen_fee_operation(
+        Some(TX_FEE_MIN - 1),
+        fra,
+        *BLACK_HOLE_PUBKEY,
+    ));
+    tx.add_operation(gen_fee_operation(None, fra, *BLACK_HOLE_PUBKEY));
+    tx.add_operation(gen_fee_operation(
+        Some(TX_FEE_MIN),
+        Some(ZeiAssetType([9; ASSET_TYPE_LENGTH])),
+        *BLACK_HOLE_PUBKEY,
+    ));
+    tx.add_operation(gen_fee_operation(
+        Some(TX_FEE_MIN),
+        fra,
+        XfrPublicKey::zei_from_bytes(&[9; ed25519_dalek::PUBLIC_KEY_LENGTH][..])
+            .unwrap(),
+    ));
+
+    assert_eq!(2 * TX_FEE_MIN - 1, tx.fee_amount());
+    assert_eq!((2 * TX_FEE_MIN - 1) as i64, tx.fee_priority(1024));
+    assert_eq!(2 * (2 * TX_FEE_MIN - 1) as i64, tx.fee_priority(512));
+    assert_eq!(tx.fee_priority(1), tx.fee_priority(0));
+}
+
+#[test]
+fn test_error_code() {
+    let mut codes = LedgerError::ALL
+        .iter()
+        .map(|e| e.code())
+        .collect::<Vec<_>>();
+    codes.sort_unstable();
+    codes.dedup();
+    assert_eq!(LedgerError::ALL.len(), codes.len());
+
+    let tx = gen_sample_tx();
+    assert!(!tx.valid_in_abci());
+    let e = tx.check_in_abci().c(d!("some context")).unwrap_err();
+    let detail = crate::error::ErrorDetail::new(e.to_string());
+    assert_eq!(LedgerError::InsufficientFee, detail.error);
+    assert_eq!(LedgerError::InsufficientFee.code(), detail.code);
+
+    assert_eq!(LedgerError::Unknown, LedgerError::of("[E] [E0] no tag"));
+}
+
+#[test]
+fn test_txo_version() {
+    let mut output =
+        match gen_fee_operation(Some(1), Some(ASSET_TYPE_FRA), *BLACK_HOLE_PUBKEY) {
+            Operation::TransferAsset(op) => op.body.outputs[0].clone(),
+            _ => unreachable!(),
+        };
+
+    // the first version is encoded as before
+    let json = serde_json::to_string(&output).unwrap();
+    assert!(!json.contains("version") && !json.contains("lock"));
+    assert!(output.check_version().is_ok());
+
+    // a lock is only valid in the second version
+    output.lock = Some(TxoLock::Height(10));
+    let e = output.check_version().unwrap_err();
+    assert_eq!(LedgerError::InvalidFormat, LedgerError::of(&e.to_string()));
+
+    output.set_lock(Some(TxoLock::Height(10)));
+    assert_eq!(TxOutputVersion::V2, output.version);
+    assert!(output.check_version().is_ok());
+    let json = serde_json::to_string(&output).unwrap();
+    assert_eq!(output, serde_json::from_str::<TxOutput>(&json).unwrap());
+}
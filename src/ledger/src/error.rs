@@ -0,0 +1,156 @@
+//!
+//! # Error Codes
+//!
+//! Stable codes of the reasons why a transaction is rejected.
+//!
+//! The errors of the ledger are `ruc` chains of messages, so the origin of
+//! an error tags its message with `[E<code>]` by `LedgerError::with`,
+//! and `LedgerError::of` finds the code back from the whole chain,
+//! no matter how many contexts have been added on the way.
+//!
+
+use {
+    serde::{Deserialize, Serialize},
+    std::fmt,
+};
+
+macro_rules! errors {
+    ($($(#[$attr:meta])* $err: ident => ($code: expr, $desc: expr)),+ $(,)?) => {
+        /// All the typed reasons of rejections.
+        #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+        #[serde(rename_all = "snake_case")]
+        pub enum LedgerError {
+            $($(#[$attr])* $err,)+
+        }
+
+        impl LedgerError {
+            /// All the declared errors.
+            pub const ALL: &'static [LedgerError] = &[$(LedgerError::$err,)+];
+
+            /// The numeric code, also used as the ABCI code.
+            pub fn code(self) -> u32 {
+                match self {
+                    $(LedgerError::$err => $code,)+
+                }
+            }
+
+            /// A short description for humans.
+            pub fn description(self) -> &'static str {
+                match self {
+                    $(LedgerError::$err => $desc,)+
+                }
+            }
+        }
+    };
+}
+
+errors! {
+    /// Any error without a typed code, same as the legacy ABCI code.
+    Unknown => (1, "unknown error"),
+    /// The transaction can not be decoded.
+    InvalidFormat => (100, "invalid format"),
+    /// The transaction is not allowed to be submitted, eg. a coinbase one.
+    NotAllowed => (101, "not allowed"),
+    /// The fee is less than the minimum.
+    InsufficientFee => (102, "insufficient fee"),
+    /// The transaction has been seen before.
+    HistoricalTx => (103, "historical transaction"),
+    /// More signatures than needed.
+    TooManySignatures => (104, "too many signatures"),
+    /// The nonce has been used before.
+    ReplayedNonce => (110, "replayed nonce"),
+    /// The nonce does not match the transaction, or is out of the window.
+    InvalidNonce => (111, "invalid nonce"),
+    /// A signature is invalid or missing.
+    InvalidSignature => (112, "invalid signature"),
+    /// An input has been spent or does not exist.
+    InputSpent => (113, "input is spent or does not exist"),
+    /// An input does not match the record on the ledger.
+    InputMismatch => (114, "input does not match the ledger"),
+    /// An input is spent more than once in the same block.
+    DoubleSpend => (115, "double spending"),
+    /// An input is locked until a later height or time.
+    OutputLocked => (116, "output locked"),
+    /// The asset can only be transferred by its issuer.
+    AssetNotTransferable => (120, "asset not transferable"),
+    /// The asset type has been defined.
+    AssetAlreadyDefined => (121, "asset already defined"),
+    /// The issuance exceeds the `max_units` of the asset.
+    MaxUnitsExceeded => (122, "max units exceeded"),
+    /// The key is not the issuer of the asset.
+    IssuerMismatch => (123, "issuer mismatch"),
+    /// The asset can not be issued or transferred confidentially.
+    ConfidentialRestricted => (124, "confidential restricted"),
+    /// The memo of the asset can not be updated.
+    MemoNotUpdatable => (125, "memo not updatable"),
+    /// The output is frozen by the issuer of the asset.
+    AssetFrozen => (126, "asset frozen"),
+    /// The asset can not be frozen or reclaimed by its issuer.
+    FreezeNotAllowed => (127, "freeze or clawback not allowed"),
+    /// The delegation amount is below the minimum.
+    DelegationBelowMinimum => (130, "delegation below minimum"),
+    /// The delegation amount is above the maximum.
+    DelegationAboveMaximum => (131, "delegation above maximum"),
+    /// The validator does not exist.
+    ValidatorNotFound => (132, "validator not found"),
+    /// The validator is jailed.
+    ValidatorJailed => (133, "validator jailed"),
+}
+
+impl LedgerError {
+    /// Find the error by its code.
+    pub fn from_code(code: u32) -> Option<Self> {
+        Self::ALL.iter().copied().find(|e| e.code() == code)
+    }
+
+    /// Find the first tagged error in a message, `Unknown` if none.
+    pub fn of(msg: &str) -> Self {
+        msg.match_indices("[E")
+            .filter_map(|(i, _)| {
+                let rest = &msg[i + 2..];
+                rest.find(']').and_then(|j| rest[..j].parse::<u32>().ok())
+            })
+            .find_map(Self::from_code)
+            .unwrap_or(LedgerError::Unknown)
+    }
+
+    /// The tagged message with some details.
+    pub fn with(self, detail: impl fmt::Display) -> String {
+        format!("{self}: {detail}")
+    }
+}
+
+impl fmt::Display for LedgerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "[E{}] {}", self.code(), self.description())
+    }
+}
+
+/// The JSON detail of a rejection.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ErrorDetail {
+    #[allow(missing_docs)]
+    pub code: u32,
+    #[allow(missing_docs)]
+    pub error: LedgerError,
+    /// The full message, including all the contexts.
+    pub message: String,
+}
+
+impl ErrorDetail {
+    /// Classify an error message by its tag.
+    pub fn new(message: String) -> Self {
+        let error = LedgerError::of(&message);
+        ErrorDetail {
+            code: error.code(),
+            error,
+            message,
+        }
+    }
+}
+
+impl From<LedgerError> for ErrorDetail {
+    fn from(e: LedgerError) -> Self {
+        ErrorDetail::new(e.to_string())
+    }
+}
@@ -70,6 +70,94 @@ where
         Ok(())
     }
 
+    /// Return a copy without any signatures,
+    /// which can be passed to each signer independently.
+    #[inline(always)]
+    pub fn to_draft(&self) -> Self
+    where
+        T: Clone,
+    {
+        CoSigOp {
+            data: self.data.clone(),
+            nonce: self.nonce,
+            cosigs: BTreeMap::new(),
+        }
+    }
+
+    /// Merge the signatures of another copy of the same operation,
+    /// invalid signatures and signatures filed under other keys will be rejected.
+    pub fn merge(&mut self, other: Self) -> Result<()> {
+        let msg = bincode::serialize(&(self.nonce, &self.data)).c(d!())?;
+        let other_msg = bincode::serialize(&(other.nonce, &other.data)).c(d!())?;
+        if msg != other_msg {
+            return Err(eg!("can not merge different operations"));
+        }
+
+        if other
+            .cosigs
+            .iter()
+            .any(|(pk, sig)| *pk != sig.pk || sig.pk.verify(&msg, &sig.sig).is_err())
+        {
+            return Err(eg!(CoSigErr::SigInvalid));
+        }
+
+        self.cosigs.extend(other.cosigs);
+        Ok(())
+    }
+
+    /// Report the signing progress based on the given validators,
+    /// useful when collecting signatures before submission.
+    pub fn check_cosigs_report(&self, vd: &ValidatorData) -> Result<CoSigReport> {
+        let msg = bincode::serialize(&(self.nonce, &self.data)).c(d!())?;
+
+        let mut report = CoSigReport {
+            total_weight: vd.body.values().map(|v| v.td_power as u128).sum(),
+            threshold: vd.cosig_rule.threshold,
+            ..Default::default()
+        };
+
+        // keep the same behavior as `check_cosigs`
+        if vd.body.is_empty() {
+            return Ok(report);
+        }
+
+        for (pk, sig) in self.cosigs.iter() {
+            if let Some(v) = vd.body.get(pk) {
+                if sig.pk.verify(&msg, &sig.sig).is_ok() {
+                    report.signed_weight += v.td_power as u128;
+                } else {
+                    report.invalid_sigs.push(*pk);
+                }
+            } else {
+                report.unknown_keys.push(*pk);
+            }
+        }
+
+        report.unsigned = vd
+            .body
+            .keys()
+            .filter(|k| !self.cosigs.contains_key(k))
+            .copied()
+            .collect();
+
+        // the minimum weight that satisfies the threshold, rounded up
+        let t = [report.threshold[0] as u128, report.threshold[1] as u128];
+        let required = if 0 == t[1] {
+            0
+        } else {
+            report
+                .total_weight
+                .checked_mul(t[0])
+                .c(d!())?
+                .checked_add(t[1] - 1)
+                .c(d!())?
+                / t[1]
+        };
+        report.missing_weight = required.saturating_sub(report.signed_weight);
+
+        Ok(report)
+    }
+
     /// Check if a cosig is valid.
     pub fn check_cosigs(&self, vd: &ValidatorData) -> Result<()> {
         if vd.body.is_empty() {
@@ -180,6 +268,35 @@ impl CoSigRule {
     }
 }
 
+/// The signing progress of a `CoSigOp`.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+pub struct CoSigReport {
+    /// sum of the weights of all validators
+    pub total_weight: u128,
+    /// sum of the weights of validators who have signed correctly
+    pub signed_weight: u128,
+    /// how much weight is still needed to satisfy the threshold
+    pub missing_weight: u128,
+    #[allow(missing_docs)]
+    pub threshold: [u64; 2],
+    /// validators who have not signed
+    pub unsigned: Vec<XfrPublicKey>,
+    /// signers that are not in the validator set
+    pub unknown_keys: Vec<XfrPublicKey>,
+    /// signers whose signature is invalid
+    pub invalid_sigs: Vec<XfrPublicKey>,
+}
+
+impl CoSigReport {
+    /// Whether `check_cosigs` will pass.
+    #[inline(always)]
+    pub fn is_ready(&self) -> bool {
+        0 == self.missing_weight
+            && self.unknown_keys.is_empty()
+            && self.invalid_sigs.is_empty()
+    }
+}
+
 #[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
 pub(crate) struct CoSig {
     pk: XfrPublicKey,
@@ -216,13 +333,13 @@ impl fmt::Display for CoSigErr {
 mod test {
     use {
         super::*,
-        crate::staking::{Validator, ValidatorKind},
+        crate::staking::{Validator, ValidatorKind, COSIG_THRESHOLD_DEFAULT},
         rand_chacha::ChaChaRng,
         rand_core::SeedableRng,
         ruc::pnk,
     };
 
-    #[derive(Default, Debug, Deserialize, Serialize)]
+    #[derive(Clone, Default, Debug, Deserialize, Serialize)]
     struct Data {
         a: [i32; 12],
         b: [f32; 3],
@@ -257,7 +374,7 @@ mod test {
                 )
             })
             .collect::<Result<Vec<_>>>();
-        let mut vd = pnk!(ValidatorData::new(1, pnk!(vs)));
+        let mut vd = pnk!(ValidatorData::new(1, pnk!(vs), COSIG_THRESHOLD_DEFAULT));
 
         // threshold: 75%
         vd.cosig_rule = pnk!(CoSigRule::new([75, 100]));
@@ -297,4 +414,78 @@ mod test {
         });
         assert!(data.check_cosigs(&vd).is_err());
     }
+
+    #[test]
+    fn staking_cosig_merge() {
+        let kps = gen_keypairs(100);
+        let vs = kps
+            .iter()
+            .map(|kp| {
+                Validator::new(
+                    vec![],
+                    999,
+                    kp.get_pk(),
+                    [1, 5],
+                    Default::default(),
+                    ValidatorKind::Initiator,
+                )
+            })
+            .collect::<Result<Vec<_>>>();
+        let mut vd = pnk!(ValidatorData::new(1, pnk!(vs), COSIG_THRESHOLD_DEFAULT));
+
+        // threshold: 75%
+        vd.cosig_rule = pnk!(CoSigRule::new([75, 100]));
+
+        let draft = CoSigOp::create(Data::default(), no_replay_token());
+
+        // every group of signers works on its own copy
+        let partials = [(0, 40), (40, 70), (70, 80)]
+            .iter()
+            .map(|(start, end)| {
+                let mut partial = draft.to_draft();
+                kps[*start..*end].iter().for_each(|kp| {
+                    pnk!(partial.sign(kp));
+                });
+                partial
+            })
+            .collect::<Vec<_>>();
+
+        let mut merged = draft.to_draft();
+        let report = pnk!(merged.check_cosigs_report(&vd));
+        assert_eq!(report.signed_weight, 0);
+        assert_eq!(report.missing_weight, 75 * 999);
+        assert_eq!(report.unsigned.len(), 100);
+
+        pnk!(merged.merge(partials[0].clone()));
+        pnk!(merged.merge(partials[1].clone()));
+        let report = pnk!(merged.check_cosigs_report(&vd));
+        assert!(!report.is_ready());
+        assert_eq!(report.signed_weight, 70 * 999);
+        assert_eq!(report.missing_weight, 5 * 999);
+        assert!(merged.check_cosigs(&vd).is_err());
+
+        pnk!(merged.merge(partials[2].clone()));
+        assert!(pnk!(merged.check_cosigs_report(&vd)).is_ready());
+        assert!(merged.check_cosigs(&vd).is_ok());
+
+        // signatures of other operations can not be merged
+        let mut other = CoSigOp::create(Data::default(), no_replay_token());
+        pnk!(other.sign(&kps[90]));
+        assert!(merged.merge(other).is_err());
+
+        // signers outside of the validator set are reported
+        let mut outsider = draft.to_draft();
+        pnk!(outsider.sign(&gen_keypairs(1)[0]));
+        pnk!(merged.merge(outsider));
+        let report = pnk!(merged.check_cosigs_report(&vd));
+        assert_eq!(report.unknown_keys.len(), 1);
+        assert!(!report.is_ready());
+
+        // a valid signature filed under another key can not be merged
+        let mut forged = draft.to_draft();
+        pnk!(forged.sign(&kps[95]));
+        let sig = pnk!(forged.cosigs.remove(&kps[95].get_pk()));
+        forged.cosigs.insert(kps[96].get_pk(), sig);
+        assert!(merged.merge(forged).is_err());
+    }
 }
@@ -18,15 +18,22 @@ use {num_bigint::BigUint, std::convert::TryFrom};
 pub mod cosig;
 pub mod init;
 pub mod ops;
+pub mod proposal;
+pub mod upgrade;
+pub mod uptime;
 
 use {
     crate::{
         data_model::{
             ConsensusRng, Operation, Transaction, TransferAsset, TxoRef, FRA_DECIMALS,
         },
+        error::LedgerError,
         SNAPSHOT_ENTRIES_DIR,
     },
-    config::abci::global_cfg::CFG,
+    config::abci::{
+        global_cfg::CFG,
+        upgrade::{is_active, Feature, UpgradeSchedule},
+    },
     cosig::CoSigRule,
     cryptohash::sha256::{self, Digest},
     fbnc::{new_mapx, Mapx},
@@ -35,9 +42,11 @@ use {
     lazy_static::lazy_static,
     ops::{
         fra_distribution::FraDistributionOps,
+        governance::{default_rules, ByzantineKind, Rule, RuleSet},
         mint_fra::{MintKind, MINT_AMOUNT_LIMIT},
     },
     parking_lot::Mutex,
+    proposal::{ProposalInfo, StakingParams},
     rand::random,
     ruc::*,
     serde::{Deserialize, Serialize},
@@ -50,6 +59,7 @@ use {
             Arc,
         },
     },
+    uptime::UptimeInfo,
     zei::xfr::sig::{XfrKeyPair, XfrPublicKey},
 };
 
@@ -145,7 +155,8 @@ pub const MIN_DELEGATION_AMOUNT: Amount = 1;
 /// Maximum allowable delegation amount.
 pub const MAX_DELEGATION_AMOUNT: Amount = FRA_TOTAL_AMOUNT;
 
-/// The minimum investment to become a validator through staking.
+/// The minimum investment to become a validator through staking,
+/// initial value of `StakingParams::validator_min_power`.
 pub const STAKING_VALIDATOR_MIN_POWER: Power = 1_0000 * FRA;
 
 /// The highest height in the context of tendermint.
@@ -162,7 +173,8 @@ pub const BLOCK_HEIGHT_MAX: u64 = i64::MAX as u64;
 pub const MAX_TOTAL_POWER: Amount = Amount::MAX / 8;
 
 /// The max vote power of any validator
-/// can not exceed 20% of global power.
+/// can not exceed 20% of global power,
+/// initial value of `StakingParams::max_power_percent_per_validator`.
 pub const MAX_POWER_PERCENT_PER_VALIDATOR: [u128; 2] = [1, 5];
 
 lazy_static! {
@@ -179,11 +191,13 @@ lazy_static! {
 /// The lock time after the delegation expires, about 21 days.
 //pub const UNBOND_BLOCK_CNT: u64 = 3600 * 24 * 21 / BLOCK_INTERVAL;
 
-// minimal number of validators
+// minimal number of validators,
+// initial value of `StakingParams::validators_min`.
 pub const VALIDATORS_MIN: usize = 5;
 
 /// The minimum weight threshold required
-/// when updating validator information, 9/10.
+/// when updating validator information, 9/10,
+/// initial value of `StakingParams::cosig_threshold`.
 pub const COSIG_THRESHOLD_DEFAULT: [u64; 2] = [9, 10];
 
 /// block height of tendermint
@@ -232,6 +246,21 @@ pub struct Staking {
     // FRA CoinBase.
     coinbase: CoinBase,
     cr: ConsensusRng,
+    // penalty rules updated by on-chain governance,
+    // kinds that are not listed here will use the default rules.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    penalty_rules: RuleSet,
+    // staking parameters updated by on-chain proposals.
+    #[serde(default, skip_serializing_if = "StakingParams::is_default")]
+    params: StakingParams,
+    #[serde(default, skip_serializing_if = "ProposalInfo::is_empty")]
+    proposals: ProposalInfo,
+    // signing records of validators in a rolling window.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    uptime: UptimeInfo,
+    // activation heights of protocol features scheduled on chain.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    upgrades: UpgradeSchedule,
 }
 
 impl Default for Staking {
@@ -253,6 +282,13 @@ impl Staking {
         XfrKeyPair::generate(cr).get_pk()
     }
 
+    /// Generate a temporary delegator for a partial un-delegation,
+    /// in a deterministic way among all nodes.
+    #[inline(always)]
+    pub fn gen_tmp_delegator_id(&mut self) -> XfrPublicKey {
+        Self::gen_consensus_tmp_pubkey(&mut self.cr)
+    }
+
     #[inline(always)]
     #[allow(missing_docs)]
     pub fn new() -> Self {
@@ -264,6 +300,11 @@ impl Staking {
             cur_height: 0,
             coinbase: CoinBase::gen(),
             cr: ConsensusRng::default(),
+            penalty_rules: BTreeMap::new(),
+            params: StakingParams::default(),
+            proposals: ProposalInfo::default(),
+            uptime: BTreeMap::new(),
+            upgrades: BTreeMap::new(),
         }
     }
 
@@ -422,6 +463,9 @@ impl Staking {
                 vs.body.iter_mut().for_each(|(k, v)| {
                     if let Some(pv) = prev.body.remove(k) {
                         v.td_power = pv.td_power;
+                        // a jailed validator keeps out of the active set
+                        // until it is unjailed explicitly
+                        v.jailed_until = pv.jailed_until;
                     }
                 });
                 // out-dated validators should be removed from tendermint,
@@ -468,7 +512,7 @@ impl Staking {
             .map(|ovd| {
                 ovd.body
                     .iter()
-                    .filter(|(_, v)| 0 == v.td_power)
+                    .filter(|(_, v)| 0 == v.td_power && !v.is_jailed())
                     .map(|(k, _)| *k)
                     .collect::<BTreeSet<_>>()
             })
@@ -692,12 +736,11 @@ impl Staking {
             return Err(eg!("global power overflow"));
         }
 
+        let percent = self.params.max_power_percent_per_validator;
         if ((power + new_power) as u128)
-            .checked_mul(MAX_POWER_PERCENT_PER_VALIDATOR[1])
+            .checked_mul(percent[1])
             .c(d!())?
-            > MAX_POWER_PERCENT_PER_VALIDATOR[0]
-                .checked_mul(global_power as u128)
-                .c(d!())?
+            > percent[0].checked_mul(global_power as u128).c(d!())?
         {
             return Err(eg!("validator power overflow"));
         }
@@ -749,8 +792,15 @@ impl Staking {
             } else {
                 return Err(eg!("self-delegation has not been finished"));
             }
-            check_delegation_amount(am, true).c(d!())?;
+            check_delegation_amount(am, true, &self.params).c(d!())?;
             self.validator_check_power(am, &validator).c(d!())?;
+            if self
+                .validator_get_current_one_by_id(&validator)
+                .map(|v| v.is_jailed())
+                .unwrap_or(false)
+            {
+                return Err(eg!(LedgerError::ValidatorJailed));
+            }
         }
 
         let h = self.cur_height;
@@ -934,15 +984,14 @@ impl Staking {
             .validator_td_addr_to_app_pk(&td_addr_to_string(&pu.target_validator))
             .c(d!("Invalid target validator"))?;
 
+        let min_power = self.params.validator_min_power;
         let actual_am;
         if let Some(d) = self
             .delegation_info
             .global_delegation_records_map
             .get_mut(addr)
         {
-            if is_validator
-                && STAKING_VALIDATOR_MIN_POWER > d.amount().saturating_sub(pu.am)
-            {
+            if is_validator && min_power > d.amount().saturating_sub(pu.am) {
                 return Err(eg!("Requested amount exceeds limits"));
             }
 
@@ -1098,6 +1147,12 @@ impl Staking {
             .get(&addr)
     }
 
+    /// Get all the delegation instances.
+    #[inline(always)]
+    pub fn delegation_get_all(&self) -> &BTreeMap<XfrPublicKey, Delegation> {
+        &self.delegation_info.global_delegation_records_map
+    }
+
     /// Get the delegation instance of `addr`.
     #[inline(always)]
     pub fn delegation_get_mut(
@@ -1288,10 +1343,10 @@ impl Staking {
     //
     // @param h: included
     fn delegation_process_finished_before_height(&mut self, h: BlockHeight) {
-        let r = if CFG.checkpoint.fix_unpaid_delegation_height > h {
-            self.delegation_info.end_height_map.range(0..=h)
-        } else {
+        let r = if is_active(Feature::FixUnpaidDelegation, h) {
             self.delegation_info.end_height_map.range(0..h)
+        } else {
+            self.delegation_info.end_height_map.range(0..=h)
         };
 
         r.map(|(k, v)| (k.to_owned(), (*v).clone()))
@@ -1373,6 +1428,90 @@ impl Staking {
         Ok(())
     }
 
+    /// Jail a validator for `period` blocks at least,
+    /// its power is set to zero, so it will be removed from the active set.
+    pub fn validator_jail(
+        &mut self,
+        vid: &XfrPublicKey,
+        period: BlockHeight,
+    ) -> Result<()> {
+        let until = self.cur_height.saturating_add(period);
+        self.validator_get_current_mut_one_by_id(vid)
+            .c(d!("validator not found"))
+            .map(|v| {
+                v.td_power = 0;
+                v.jailed_until = Some(v.jailed_until.unwrap_or(0).max(until));
+            })
+    }
+
+    /// Return a jailed validator to the active set after its jail period,
+    /// its power is recovered from its self-delegation and delegators.
+    pub fn validator_unjail(&mut self, vid: &XfrPublicKey) -> Result<()> {
+        let h = self.cur_height;
+
+        let self_delegation_am = self
+            .delegation_get(vid)
+            .filter(|d| BLOCK_HEIGHT_MAX == d.end_height)
+            .and_then(|d| d.validator_entry(vid))
+            .c(d!("self-delegation not found or is unbonding"))?;
+
+        let v = self
+            .validator_get_current_one_by_id(vid)
+            .c(d!(LedgerError::ValidatorNotFound))?;
+        let power = self_delegation_am + v.delegators.values().sum::<Amount>();
+        match v.jailed_until {
+            None => return Err(eg!("validator is not jailed")),
+            Some(until) if h < until => {
+                return Err(eg!(
+                    LedgerError::ValidatorJailed.with(format!("until {until}"))
+                ));
+            }
+            _ => {}
+        }
+
+        // the recovered power is added to the global power again
+        self.validator_check_power(power, vid).c(d!())?;
+
+        let v = self.validator_get_current_mut_one_by_id(vid).c(d!())?;
+        v.jailed_until = None;
+        v.td_power = power;
+
+        Ok(())
+    }
+
+    /// The rewards of a jailed validator and its delegators are paused
+    /// until it is unjailed.
+    #[inline(always)]
+    pub fn validator_rewards_paused(&self, vid: &XfrPublicKey) -> bool {
+        self.validator_get_current_one_by_id(vid)
+            .map(|v| v.is_jailed())
+            .unwrap_or(false)
+    }
+
+    /// Get the effective penalty rule of a kind of byzantine behavior.
+    #[inline(always)]
+    pub fn governance_rule_get(&self, kind: &ByzantineKind) -> Option<Rule> {
+        self.penalty_rules
+            .get(kind)
+            .or_else(|| default_rules().get(kind))
+            .cloned()
+    }
+
+    /// Get all the effective penalty rules.
+    pub fn governance_rules_get_all(&self) -> RuleSet {
+        let mut rules = default_rules().clone();
+        self.penalty_rules.iter().for_each(|(k, r)| {
+            rules.insert(k.clone(), r.clone());
+        });
+        rules
+    }
+
+    /// Override the penalty rules of the listed kinds.
+    #[inline(always)]
+    pub fn governance_rules_update(&mut self, rules: RuleSet) {
+        self.penalty_rules.extend(rules);
+    }
+
     #[inline(always)]
     fn governance_penalty_sub_amount(
         &mut self,
@@ -1426,7 +1565,7 @@ impl Staking {
             // NOTE:
             // the current height is greater than the specified height before execution,
             // because to ensure the compatibility of historical data
-            if CFG.checkpoint.fix_delegators_am_height < self.cur_height {
+            if is_active(Feature::FixDelegatorsAm, self.cur_height) {
                 if let Some(v) = validator {
                     self.validator_get_effective_at_height_mut(self.cur_height)
                         .c(d!("failed to get effective validators at current height"))
@@ -1461,7 +1600,8 @@ impl Staking {
                 vd.addr_td_to_app
                     .get(addr)
                     .copied()
-                    .c(d!(format!("Failed to get pk {addr}")))
+                    .c(d!(LedgerError::ValidatorNotFound
+                        .with(format!("Failed to get pk {addr}"))))
             })
     }
 
@@ -1618,6 +1758,10 @@ impl Staking {
     ) -> Result<()> {
         // Get rate from based on LastCommitInfo and hardcoded PROPOSER_REWARDS_RATE_RULE
         // This is rewards B (bonus proposer reward )
+        if self.validator_rewards_paused(proposer) {
+            return Ok(());
+        }
+
         let p = Self::get_proposer_rewards_rate(vote_percent).c(d!())?;
         let h = self.cur_height;
         let cbl = self.coinbase_balance();
@@ -1797,7 +1941,7 @@ impl Default for ValidatorData {
     fn default() -> Self {
         ValidatorData {
             height: 1,
-            cosig_rule: pnk!(Self::gen_cosig_rule()),
+            cosig_rule: pnk!(Self::gen_cosig_rule(COSIG_THRESHOLD_DEFAULT)),
             body: BTreeMap::new(),
             addr_td_to_app: BTreeMap::new(),
         }
@@ -1805,8 +1949,13 @@ impl Default for ValidatorData {
 }
 
 impl ValidatorData {
-    #[allow(missing_docs)]
-    pub fn new(h: BlockHeight, v_set: Vec<Validator>) -> Result<Self> {
+    /// `cosig_threshold` is usually the `StakingParams::cosig_threshold` in effect,
+    /// a `UpdateValidator` operation with a lower one will be rejected.
+    pub fn new(
+        h: BlockHeight,
+        v_set: Vec<Validator>,
+        cosig_threshold: [u64; 2],
+    ) -> Result<Self> {
         if h < 1 {
             return Err(eg!("invalid start height"));
         }
@@ -1820,7 +1969,7 @@ impl ValidatorData {
             }
         }
 
-        let cosig_rule = Self::gen_cosig_rule().c(d!())?;
+        let cosig_rule = Self::gen_cosig_rule(cosig_threshold).c(d!())?;
 
         Ok(ValidatorData {
             height: h,
@@ -1830,13 +1979,14 @@ impl ValidatorData {
         })
     }
 
-    fn gen_cosig_rule() -> Result<CoSigRule> {
-        CoSigRule::new(COSIG_THRESHOLD_DEFAULT)
+    fn gen_cosig_rule(threshold: [u64; 2]) -> Result<CoSigRule> {
+        CoSigRule::new(threshold)
     }
 
-    /// The initial weight of every validators is equal(vote power == 1).
-    pub fn set_cosig_rule(&mut self) -> Result<()> {
-        Self::gen_cosig_rule().c(d!()).map(|rule| {
+    /// Reset the rule with `threshold`, usually `StakingParams::cosig_threshold`,
+    /// the initial weight of every validators is equal(vote power == 1).
+    pub fn set_cosig_rule(&mut self, threshold: [u64; 2]) -> Result<()> {
+        Self::gen_cosig_rule(threshold).c(d!()).map(|rule| {
             self.cosig_rule = rule;
         })
     }
@@ -1949,6 +2099,10 @@ pub struct Validator {
     pub signed_last_block: bool,
     /// how many blocks has the validator signed
     pub signed_cnt: u64,
+    /// the validator is jailed until this height,
+    /// it can be unjailed by an `Unjail` operation after that
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jailed_until: Option<BlockHeight>,
 
     /// delegator pubkey => amount
     ///   - delegator entries on current block height
@@ -1979,6 +2133,7 @@ impl Validator {
             kind,
             signed_last_block: false,
             signed_cnt: 0,
+            jailed_until: None,
             delegators: IndexMap::new(),
         })
     }
@@ -2013,6 +2168,12 @@ impl Validator {
         self.commission_rate
     }
 
+    #[inline(always)]
+    #[allow(missing_docs)]
+    pub fn is_jailed(&self) -> bool {
+        self.jailed_until.is_some()
+    }
+
     #[inline(always)]
     #[allow(missing_docs)]
     pub fn staking_is_basic_valid(&self) -> bool {
@@ -2161,7 +2322,7 @@ impl Delegation {
             .c(d!())
             .and_then(|mut am| {
                 if 0 == am {
-                    if CFG.checkpoint.zero_amount_fix_height < cur_height {
+                    if is_active(Feature::ZeroAmountFix, cur_height) {
                         return Ok(0);
                     } else {
                         return Err(eg!("set rewards on zero amount"));
@@ -2240,13 +2401,13 @@ fn calculate_delegation_rewards(
     // #[cfg(not(feature = "debug_env"))]
     // const SECOND_FIX_HEIGHT: BlockHeight = 142_9000;
 
-    if CFG.checkpoint.overflow_fix_height < cur_height {
+    if is_active(Feature::OverflowFix, cur_height) {
         let am = BigUint::from(amount);
         let total_am = BigUint::from(total_amount);
         let global_am = BigUint::from(global_amount);
         let block_itv = *BLOCK_INTERVAL as u128;
 
-        let second_per_year: u128 = if CFG.checkpoint.second_fix_height < cur_height {
+        let second_per_year: u128 = if is_active(Feature::SecondFix, cur_height) {
             365 * 24 * 3600
         } else {
             356 * 24 * 3600
@@ -2259,7 +2420,7 @@ fn calculate_delegation_rewards(
             a1 / a2
         };
 
-        let n = if CFG.checkpoint.apy_fix_height < cur_height {
+        let n = if is_active(Feature::ApyFix, cur_height) {
             if is_delegation_rwd {
                 // global_amount * am * return_rate[0] * block_itv / (return_rate[1] * (365 * 24 * 3600) * total_amount)
                 let a1 = global_am * am * return_rate[0] * block_itv;
@@ -2291,7 +2452,7 @@ fn calculate_delegation_rewards(
                 })
         };
 
-        if CFG.checkpoint.apy_fix_height < cur_height {
+        if is_active(Feature::ApyFix, cur_height) {
             if is_delegation_rwd {
                 // # For delegation rewards:
                 //
@@ -2435,22 +2596,26 @@ pub fn td_addr_to_bytes(td_addr: TendermintAddrRef) -> Result<Vec<u8>> {
     hex::decode(td_addr).c(d!())
 }
 
+/// Check the amount of a delegation, or of a staking if `is_append` is false,
+/// against the `params` voted on chain.
 #[inline(always)]
-#[allow(missing_docs)]
-pub fn check_delegation_amount(am: Amount, is_append: bool) -> Result<()> {
-    let lowb = alt!(
-        is_append,
-        MIN_DELEGATION_AMOUNT,
-        STAKING_VALIDATOR_MIN_POWER
-    );
-    if (lowb..=MAX_DELEGATION_AMOUNT).contains(&am) {
-        Ok(())
+pub fn check_delegation_amount(
+    am: Amount,
+    is_append: bool,
+    params: &StakingParams,
+) -> Result<()> {
+    let lowb = alt!(is_append, MIN_DELEGATION_AMOUNT, params.validator_min_power);
+    let e = if am < lowb {
+        LedgerError::DelegationBelowMinimum
+    } else if am > MAX_DELEGATION_AMOUNT {
+        LedgerError::DelegationAboveMaximum
     } else {
-        let msg = format!(
-            "Invalid delegation amount: {am} (min: {lowb}, max: {MAX_DELEGATION_AMOUNT})",
-        );
-        Err(eg!(msg))
-    }
+        return Ok(());
+    };
+    let msg = format!(
+        "Invalid delegation amount: {am} (min: {lowb}, max: {MAX_DELEGATION_AMOUNT})",
+    );
+    Err(eg!(e.with(msg)))
 }
 
 #[inline(always)]
@@ -2518,6 +2683,355 @@ mod test {
         });
     }
 
+    #[test]
+    fn staking_penalty_rules() {
+        let mut s = Staking::new();
+        assert_eq!(s.governance_rules_get_all(), *default_rules());
+
+        let orig = pnk!(s.governance_rule_get(&ByzantineKind::DuplicateVote));
+        s.governance_rules_update(map! {B
+            ByzantineKind::OffLine => Rule::new([1, 100])
+        });
+
+        assert_eq!(
+            pnk!(s.governance_rule_get(&ByzantineKind::OffLine)).gen_penalty_percent(),
+            [1, 100]
+        );
+        assert_eq!(
+            pnk!(s.governance_rule_get(&ByzantineKind::DuplicateVote)),
+            orig
+        );
+        assert_ne!(s.governance_rules_get_all(), *default_rules());
+    }
+
+    #[test]
+    fn staking_param_proposal() {
+        use {
+            crate::data_model::NoReplayToken,
+            ops::update_validator::UpdateValidatorOps,
+            proposal::{
+                ParamChange, ProposalStatus, VoteOption, PROPOSAL_TIMELOCK_MIN,
+                PROPOSAL_VOTING_PERIOD, VALIDATORS_MAX,
+            },
+            rand_chacha::ChaChaRng,
+            rand_core::SeedableRng,
+        };
+
+        let mut prng = ChaChaRng::from_entropy();
+        let kps = (0..5)
+            .map(|_| XfrKeyPair::generate(&mut prng))
+            .collect::<Vec<_>>();
+        let vs = kps
+            .iter()
+            .take(4)
+            .map(|kp| {
+                Validator::new(
+                    vec![],
+                    100,
+                    kp.get_pk(),
+                    [1, 5],
+                    Default::default(),
+                    ValidatorKind::Initiator,
+                )
+            })
+            .collect::<Result<Vec<_>>>();
+        let vs = pnk!(vs);
+
+        let mut s = Staking::new();
+        pnk!(s.validator_set_at_height(
+            1,
+            pnk!(ValidatorData::new(1, vs.clone(), COSIG_THRESHOLD_DEFAULT))
+        ));
+        s.set_custom_block_height(1);
+
+        assert!(ParamChange::CosigThreshold([2, 3]).check().is_err());
+        assert!(ParamChange::CosigThreshold([3, 4]).check().is_ok());
+        assert!(ParamChange::ValidatorsMin(0).check().is_err());
+        assert!(ParamChange::ValidatorsMin(VALIDATORS_MAX + 1)
+            .check()
+            .is_err());
+        assert!(ParamChange::ValidatorsMin(VALIDATORS_MAX).check().is_ok());
+        assert!(ParamChange::MaxPowerPercentPerValidator([1, 3])
+            .check()
+            .is_err());
+        assert!(ParamChange::MaxPowerPercentPerValidator([1, 101])
+            .check()
+            .is_err());
+        assert!(ParamChange::MaxPowerPercentPerValidator([1, 0])
+            .check()
+            .is_err());
+        assert!(ParamChange::MaxPowerPercentPerValidator([1, 4])
+            .check()
+            .is_ok());
+
+        let change = ParamChange::ValidatorsMin(4);
+        let activation_height = 1 + PROPOSAL_VOTING_PERIOD + PROPOSAL_TIMELOCK_MIN;
+
+        // not a validator
+        assert!(s
+            .proposal_add(kps[4].get_pk(), change.clone(), activation_height)
+            .is_err());
+        // activation height is too low
+        assert!(s
+            .proposal_add(kps[0].get_pk(), change.clone(), activation_height - 1)
+            .is_err());
+
+        let id = pnk!(s.proposal_add(kps[0].get_pk(), change, activation_height));
+        assert!(s
+            .proposal_add(
+                kps[0].get_pk(),
+                ParamChange::ValidatorsMin(3),
+                activation_height
+            )
+            .is_err());
+
+        pnk!(s.proposal_vote(kps[1].get_pk(), id, VoteOption::Yes));
+        assert!(!pnk!(s.proposal_tally(id)).passed);
+        pnk!(s.proposal_vote(kps[2].get_pk(), id, VoteOption::Yes));
+        pnk!(s.proposal_vote(kps[3].get_pk(), id, VoteOption::No));
+        assert!(s
+            .proposal_vote(kps[4].get_pk(), id, VoteOption::No)
+            .is_err());
+
+        let tally = pnk!(s.proposal_tally(id));
+        assert_eq!(
+            [tally.yes_power, tally.no_power, tally.total_power],
+            [300, 100, 400]
+        );
+        assert!(tally.passed);
+
+        s.set_custom_block_height(1 + PROPOSAL_VOTING_PERIOD);
+        s.proposal_process();
+        assert_eq!(pnk!(s.proposal_get(id)).status, ProposalStatus::Passed);
+        assert!(s
+            .proposal_vote(kps[3].get_pk(), id, VoteOption::Yes)
+            .is_err());
+        assert_eq!(s.params().validators_min, VALIDATORS_MIN);
+
+        s.set_custom_block_height(activation_height);
+        s.proposal_process();
+        assert_eq!(pnk!(s.proposal_get(id)).status, ProposalStatus::Executed);
+        assert_eq!(s.params().validators_min, 4);
+
+        // the validators in effect follow the voted cosig threshold
+        let start_height = activation_height;
+        let activation_height =
+            start_height + PROPOSAL_VOTING_PERIOD + PROPOSAL_TIMELOCK_MIN;
+        let id = pnk!(s.proposal_add(
+            kps[0].get_pk(),
+            ParamChange::CosigThreshold([19, 20]),
+            activation_height
+        ));
+        pnk!(s.proposal_vote(kps[1].get_pk(), id, VoteOption::Yes));
+        pnk!(s.proposal_vote(kps[2].get_pk(), id, VoteOption::Yes));
+
+        s.set_custom_block_height(start_height + PROPOSAL_VOTING_PERIOD);
+        s.proposal_process();
+        s.set_custom_block_height(activation_height);
+        s.proposal_process();
+        assert_eq!(s.params().cosig_threshold, [19, 20]);
+        assert_eq!(
+            pnk!(s.validator_get_current()).get_cosig_rule().threshold,
+            [19, 20]
+        );
+
+        // and a new validator set is built with it
+        let signers = kps.iter().take(4).collect::<Vec<_>>();
+        let nonce = NoReplayToken::unsafe_new(1, 1);
+        let h = activation_height + 1;
+        let lower = pnk!(UpdateValidatorOps::new(
+            &signers,
+            h,
+            vs.clone(),
+            COSIG_THRESHOLD_DEFAULT,
+            nonce
+        ));
+        assert!(lower.check_run(&mut s.clone()).is_err());
+        let op = pnk!(UpdateValidatorOps::new(
+            &signers,
+            h,
+            vs,
+            s.params().cosig_threshold,
+            nonce
+        ));
+        pnk!(op.check_run(&mut s));
+        assert_eq!(
+            pnk!(s.validator_get_effective_at_height(h))
+                .get_cosig_rule()
+                .threshold,
+            [19, 20]
+        );
+
+        // so is the minimum amount of staking
+        let min_power = s.params().validator_min_power;
+        assert!(check_delegation_amount(min_power - 1, false, s.params()).is_err());
+        assert!(check_delegation_amount(min_power - 1, true, s.params()).is_ok());
+    }
+
+    #[test]
+    fn staking_jail() {
+        use {
+            ops::governance::JAIL_PERIOD_MIN, rand_chacha::ChaChaRng,
+            rand_core::SeedableRng,
+        };
+
+        assert!(Rule::new([1, 100])
+            .with_jail_period(JAIL_PERIOD_MIN - 1)
+            .check()
+            .is_err());
+        assert!(Rule::new([1, 100])
+            .with_jail_period(JAIL_PERIOD_MIN)
+            .check()
+            .is_ok());
+
+        let mut prng = ChaChaRng::from_entropy();
+        let kp = XfrKeyPair::generate(&mut prng);
+        let new_v = || {
+            Validator::new(
+                vec![1; 32],
+                0,
+                kp.get_pk(),
+                [1, 5],
+                Default::default(),
+                ValidatorKind::Initiator,
+            )
+        };
+        let td_addr = td_addr_to_string(&pnk!(new_v()).td_addr);
+
+        let mut s = Staking::new();
+        pnk!(s.validator_set_at_height(
+            1,
+            pnk!(ValidatorData::new(
+                1,
+                vec![pnk!(new_v())],
+                COSIG_THRESHOLD_DEFAULT
+            ))
+        ));
+        s.set_custom_block_height(1);
+        pnk!(s.delegate(kp.get_pk(), &td_addr, 100 * FRA));
+        assert_eq!(pnk!(s.validator_get_power(&kp.get_pk())), 100 * FRA);
+
+        assert!(s.validator_unjail(&kp.get_pk()).is_err());
+        assert!(!s.validator_rewards_paused(&kp.get_pk()));
+        pnk!(s.validator_jail(&kp.get_pk(), JAIL_PERIOD_MIN));
+        assert_eq!(pnk!(s.validator_get_power(&kp.get_pk())), 0);
+        assert!(s.validator_rewards_paused(&kp.get_pk()));
+        assert!(s.delegate(kp.get_pk(), &td_addr, FRA).is_err());
+
+        // the jail state is kept in a new validator set
+        pnk!(s.validator_set_at_height(
+            2,
+            pnk!(ValidatorData::new(
+                2,
+                vec![pnk!(new_v())],
+                COSIG_THRESHOLD_DEFAULT
+            ))
+        ));
+        s.set_custom_block_height(2);
+        s.validator_apply_current();
+        let v = pnk!(s.validator_get_current_one_by_id(&kp.get_pk()));
+        assert_eq!(v.jailed_until, Some(1 + JAIL_PERIOD_MIN));
+        assert_eq!(v.td_power, 0);
+
+        // the jail period has not passed
+        assert!(s.validator_unjail(&kp.get_pk()).is_err());
+
+        s.set_custom_block_height(1 + JAIL_PERIOD_MIN);
+        pnk!(s.validator_unjail(&kp.get_pk()));
+        let v = pnk!(s.validator_get_current_one_by_id(&kp.get_pk()));
+        assert!(!v.is_jailed());
+        assert_eq!(v.td_power, 100 * FRA);
+        assert!(!s.validator_rewards_paused(&kp.get_pk()));
+    }
+
+    #[test]
+    fn staking_uptime() {
+        use uptime::{UPTIME_MAX_MISSED, UPTIME_WINDOW};
+
+        let mut s = Staking::new();
+        let (a, b) = ("A".to_owned(), "B".to_owned());
+        let run = |s: &mut Staking, heights: std::ops::RangeInclusive<u64>| {
+            heights.for_each(|h| {
+                s.set_custom_block_height(h);
+                // `b` misses the first `UPTIME_MAX_MISSED + 1` blocks
+                let votes = [(a.clone(), true), (b.clone(), h > UPTIME_MAX_MISSED + 1)];
+                s.uptime_record(&votes, alt!(0 == h % 2, &a, &b));
+            });
+        };
+
+        run(&mut s, 1..=UPTIME_MAX_MISSED + 1);
+        assert!(!pnk!(s.uptime_get(&a)).missed_too_many());
+        assert!(pnk!(s.uptime_get(&b)).missed_too_many());
+        assert_eq!(
+            pnk!(s.uptime_get(&b)).stats().missed_in_a_row,
+            UPTIME_MAX_MISSED + 1
+        );
+
+        // the early missed blocks have slid out of the window
+        run(&mut s, UPTIME_MAX_MISSED + 2..=UPTIME_WINDOW + 10);
+        let stats = pnk!(s.uptime_get(&b)).stats();
+        assert!(!pnk!(s.uptime_get(&b)).missed_too_many());
+        assert_eq!(stats.window_start, 10);
+        assert_eq!(stats.missed_cnt, UPTIME_MAX_MISSED + 1 - 10);
+        assert_eq!(
+            stats.signing_ratio,
+            [UPTIME_WINDOW - stats.missed_cnt, UPTIME_WINDOW]
+        );
+        assert_eq!(stats.missed_in_a_row, 0);
+        assert_eq!(stats.missed_total, UPTIME_MAX_MISSED + 1);
+        assert_eq!(
+            pnk!(s.uptime_get(&a)).stats().proposed_total,
+            (UPTIME_WINDOW + 10) / 2
+        );
+        assert_eq!(
+            pnk!(s.uptime_get(&a)).stats().proposed_cnt,
+            UPTIME_WINDOW / 2
+        );
+
+        // `b` has left the validator set for a whole window
+        (UPTIME_WINDOW + 11..=2 * UPTIME_WINDOW + 10).for_each(|h| {
+            s.set_custom_block_height(h);
+            s.uptime_record(&[(a.clone(), true)], &a);
+        });
+        assert!(s.uptime_get(&b).is_none());
+        assert_eq!(pnk!(s.uptime_get(&a)).stats().signed_cnt, UPTIME_WINDOW);
+    }
+
+    #[test]
+    fn staking_upgrade() {
+        use upgrade::UPGRADE_DELAY_MIN;
+
+        let mut s = Staking::new();
+
+        // `DisableEvm` is active since `disable_evm_block_height + 1`
+        let f = Feature::DisableEvm;
+        let cur = f.default_height() - 1;
+        let h = cur + UPGRADE_DELAY_MIN;
+        s.set_custom_block_height(cur);
+        assert_eq!(s.upgrade_height(f), f.default_height());
+
+        assert!(s.upgrade_schedule_update(map! {B f => h - 1}).is_err());
+        assert!(s
+            .upgrade_schedule_update(map! {B f => h, Feature::EnableFrc20 => cur})
+            .is_err());
+        assert!(s.upgrade_schedule().is_empty());
+
+        pnk!(s.upgrade_schedule_update(map! {B f => h}));
+        assert_eq!(s.upgrade_height(f), h);
+
+        // rescheduled before activation
+        pnk!(s.upgrade_schedule_update(map! {B f => h + 1}));
+        assert_eq!(s.upgrade_height(f), h + 1);
+
+        s.set_custom_block_height(h + 1);
+        assert!(s
+            .upgrade_schedule_update(map! {B f => h + UPGRADE_DELAY_MIN + 1})
+            .is_err());
+
+        assert_eq!(pnk!(f.to_string().parse::<Feature>()), f);
+        assert!("unknown_feature".parse::<Feature>().is_err());
+    }
+
     fn gen_round_vote_percent(lower_bound: u64, upper_bound: u64) -> [u64; 2] {
         let itv = upper_bound - lower_bound;
         let lb = if 0 == itv {
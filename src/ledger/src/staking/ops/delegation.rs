@@ -12,7 +12,7 @@ use {
         },
         staking::{
             deny_relative_inputs, td_addr_to_string, Amount, Staking, TendermintAddr,
-            Validator, STAKING_VALIDATOR_MIN_POWER,
+            Validator,
         },
     },
     ed25519_dalek::Signer,
@@ -93,7 +93,7 @@ impl DelegationOps {
             let h = staking.cur_height;
 
             if !v.staking_is_basic_valid()
-                || am < STAKING_VALIDATOR_MIN_POWER
+                || am < staking.params().validator_min_power
                 || self.body.validator != td_addr_to_string(&v.td_addr)
             {
                 return Err(eg!("invalid"));
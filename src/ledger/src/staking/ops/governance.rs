@@ -10,8 +10,11 @@
 use {
     crate::{
         data_model::NoReplayToken,
-        staking::{cosig::CoSigOp, Staking, TendermintAddrRef, BLOCK_HEIGHT_MAX},
+        staking::{
+            cosig::CoSigOp, BlockHeight, Staking, TendermintAddrRef, BLOCK_HEIGHT_MAX,
+        },
     },
+    config::abci::upgrade::{is_active, Feature},
     lazy_static::lazy_static,
     ruc::*,
     serde::{Deserialize, Serialize},
@@ -20,12 +23,12 @@ use {
 };
 
 lazy_static! {
-    // The current MVP version is a fixed rule,
-    // and it will be upgraded to a mechanism
-    // that can update rules by sending a specific transaction.
-    static ref RULES: BTreeMap<ByzantineKind, Rule> = {
+    // The initial rules, they will be used until
+    // they are overridden by an `UpdatePenaltyRule` operation.
+    static ref DEFAULT_RULES: RuleSet = {
         map! { B
-            ByzantineKind::DuplicateVote => Rule::new([5, 100]),
+            ByzantineKind::DuplicateVote =>
+                Rule::new([5, 100]).with_jail_period(JAIL_PERIOD_MIN),
             ByzantineKind::LightClientAttack => Rule::new([1, 100]),
             ByzantineKind::Unknown => Rule::new([30, 100]),
             // we should set this percent to a very small value
@@ -34,6 +37,9 @@ lazy_static! {
     };
 }
 
+/// The minimum number of blocks a validator will be jailed for, about 1 day.
+pub const JAIL_PERIOD_MIN: BlockHeight = 24 * 3600 / 16;
+
 /// Used as the inner object of a `Governance Operation`.
 pub type GovernanceOps = CoSigOp<Data>;
 
@@ -48,7 +54,7 @@ impl GovernanceOps {
     pub fn apply(&self, staking: &mut Staking) -> Result<()> {
         self.verify(staking)
             .c(d!())
-            .and_then(|_| RULES.get(&self.data.kind).ok_or(eg!()))
+            .and_then(|_| staking.governance_rule_get(&self.data.kind).c(d!()))
             .and_then(|rule| {
                 staking
                     .governance_penalty_by_pubkey(
@@ -136,11 +142,45 @@ pub enum ByzantineKind {
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct Rule {
     penalty_percent: [u64; 2],
+    // zero means no jailing
+    #[serde(default, skip_serializing_if = "is_zero")]
+    jail_period: BlockHeight,
+}
+
+#[inline(always)]
+fn is_zero(n: &BlockHeight) -> bool {
+    0 == *n
 }
 
 impl Rule {
-    fn new(penalty_percent: [u64; 2]) -> Self {
-        Rule { penalty_percent }
+    #[inline(always)]
+    #[allow(missing_docs)]
+    pub fn new(penalty_percent: [u64; 2]) -> Self {
+        Rule {
+            penalty_percent,
+            jail_period: 0,
+        }
+    }
+
+    /// Also jail the validator for some blocks.
+    #[inline(always)]
+    pub fn with_jail_period(mut self, jail_period: BlockHeight) -> Self {
+        self.jail_period = jail_period;
+        self
+    }
+
+    /// A valid percent must be in the range of `[0, 1]`,
+    /// and a validator can not be jailed for less than `JAIL_PERIOD_MIN` blocks.
+    #[inline(always)]
+    pub fn check(&self) -> Result<()> {
+        let p = self.penalty_percent;
+        if 0 == p[1] || p[1] > i64::MAX as u64 || p[0] > p[1] {
+            Err(eg!("invalid penalty percent"))
+        } else if 0 != self.jail_period && JAIL_PERIOD_MIN > self.jail_period {
+            Err(eg!("jail period is too short"))
+        } else {
+            Ok(())
+        }
     }
 
     #[inline(always)]
@@ -148,9 +188,22 @@ impl Rule {
     pub fn gen_penalty_percent(&self) -> [u64; 2] {
         self.penalty_percent
     }
+
+    #[inline(always)]
+    #[allow(missing_docs)]
+    pub fn get_jail_period(&self) -> BlockHeight {
+        self.jail_period
+    }
 }
 
-/// Penalize the FRAs by a specified address.
+/// The rule set used before any on-chain updates.
+#[inline(always)]
+pub fn default_rules() -> &'static RuleSet {
+    &DEFAULT_RULES
+}
+
+/// Penalize the FRAs by a specified address,
+/// and jail the validator if the rule requires.
 ///
 /// Any validator who has unstaked itself should not be punished,
 /// its delegators should not be punished also.
@@ -160,7 +213,7 @@ pub fn governance_penalty_tendermint_auto(
     addr: TendermintAddrRef,
     bz_kind: &ByzantineKind,
 ) -> Result<()> {
-    let rule = RULES.get(bz_kind).ok_or(eg!())?;
+    let rule = staking.governance_rule_get(bz_kind).c(d!())?;
     staking
         .validator_td_addr_to_app_pk(addr)
         .c(d!())
@@ -175,7 +228,17 @@ pub fn governance_penalty_tendermint_auto(
                     }
                     staking
                         .governance_penalty_by_pubkey(&pk, rule.gen_penalty_percent())
-                        .c(d!())
+                        .c(d!())?;
+
+                    if 0 < rule.get_jail_period()
+                        && is_active(Feature::ValidatorJail, staking.cur_height())
+                    {
+                        staking
+                            .validator_jail(&pk, rule.get_jail_period())
+                            .c(d!())?;
+                    }
+
+                    Ok(())
                 })
         })
 }
@@ -18,7 +18,7 @@ use {
         xfr::{
             asset_record::{build_blind_asset_record, AssetRecordType},
             sig::XfrPublicKey,
-            structs::{AssetRecordTemplate, AssetType, OwnerMemo},
+            structs::{AssetRecordTemplate, AssetType, BlindAssetRecord, OwnerMemo},
         },
     },
 };
@@ -49,7 +49,7 @@ impl MintFraOps {
     #[inline(always)]
     #[allow(missing_docs)]
     pub fn get_owner_memos_ref(&self) -> Vec<Option<&OwnerMemo>> {
-        vec![None; self.entries.len()]
+        self.entries.iter().map(|e| e.owner_memo.as_ref()).collect()
     }
 }
 
@@ -61,6 +61,9 @@ pub struct MintEntry {
     pub amount: Amount,
     pub utxo: TxOutput,
     pub asset_type: AssetType,
+    /// The owner memo of a confidential `utxo`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner_memo: Option<OwnerMemo>,
 }
 
 impl MintEntry {
@@ -87,6 +90,8 @@ impl MintEntry {
             id: None,
             record: ba,
             lien: None,
+            version: Default::default(),
+            lock: None,
         };
 
         MintEntry {
@@ -95,6 +100,37 @@ impl MintEntry {
             amount,
             utxo,
             asset_type,
+            owner_memo: None,
+        }
+    }
+
+    /// Mint a confidential `record` built by the sender, see
+    /// `converter::check_utxo_transfer`, `amount` and `asset_type` of the entry
+    /// are zero if they are hidden in the record.
+    pub fn new_confidential(
+        kind: MintKind,
+        target_pk: XfrPublicKey,
+        record: BlindAssetRecord,
+        owner_memo: Option<OwnerMemo>,
+    ) -> Self {
+        let amount = record.amount.get_amount().unwrap_or(0);
+        let asset_type = record.asset_type.get_asset_type().unwrap_or_default();
+
+        let utxo = TxOutput {
+            id: None,
+            record,
+            lien: None,
+            version: Default::default(),
+            lock: None,
+        };
+
+        MintEntry {
+            kind,
+            target_pk,
+            amount,
+            utxo,
+            asset_type,
+            owner_memo,
         }
     }
 }
@@ -106,3 +142,70 @@ pub enum MintKind {
     UnStake,
     Other,
 }
+
+#[cfg(test)]
+mod test {
+    use {
+        super::*,
+        crate::{
+            converter::{build_utxo_transfer, check_utxo_transfer},
+            data_model::ASSET_TYPE_FRA,
+        },
+        zei::xfr::{
+            asset_record::open_blind_asset_record,
+            sig::XfrKeyPair,
+            structs::{XfrAmount, XfrAssetType},
+        },
+    };
+
+    #[test]
+    fn mint_confidential_entry() {
+        let mut prng = ChaChaRng::from_seed([0; 32]);
+        let kp = XfrKeyPair::generate(&mut prng);
+        let asset_type = AssetType([9; 32]);
+
+        for (conf_amount, conf_type) in [(true, false), (false, true), (true, true)] {
+            let transfer = build_utxo_transfer(
+                &mut prng,
+                kp.get_pk(),
+                100,
+                asset_type,
+                AssetRecordType::from_flags(conf_amount, conf_type),
+            )
+            .unwrap();
+            let (record, memo) =
+                check_utxo_transfer(&transfer, &kp.get_pk(), 100, asset_type).unwrap();
+            let entry =
+                MintEntry::new_confidential(MintKind::Other, kp.get_pk(), record, memo);
+            let record = &entry.utxo.record;
+
+            assert_eq!(
+                conf_amount,
+                matches!(record.amount, XfrAmount::Confidential(_))
+            );
+            assert_eq!(
+                conf_type,
+                matches!(record.asset_type, XfrAssetType::Confidential(_))
+            );
+            assert_eq!(entry.amount, if conf_amount { 0 } else { 100 });
+            assert_eq!(
+                entry.asset_type,
+                if conf_type {
+                    ASSET_TYPE_FRA
+                } else {
+                    asset_type
+                }
+            );
+            assert!(entry.owner_memo.is_some());
+
+            // only the memo opens it
+            let oar = open_blind_asset_record(record, &entry.owner_memo, &kp).unwrap();
+            assert_eq!(*oar.get_amount(), 100);
+            assert_eq!(*oar.get_asset_type(), asset_type);
+            assert!(open_blind_asset_record(record, &None, &kp).is_err());
+
+            let ops = MintFraOps::new(1, vec![entry.clone()]);
+            assert_eq!(ops.get_owner_memos_ref(), vec![entry.owner_memo.as_ref()]);
+        }
+    }
+}
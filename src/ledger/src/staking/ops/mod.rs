@@ -12,7 +12,12 @@ pub mod delegation;
 pub mod fra_distribution;
 pub mod governance;
 pub mod mint_fra;
+pub mod proposal;
 pub mod replace_staker;
+pub mod schedule_upgrade;
 pub mod undelegation;
+pub mod unjail;
+pub mod update_penalty_rule;
 pub mod update_staker;
 pub mod update_validator;
+pub mod vote;
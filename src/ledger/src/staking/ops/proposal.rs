@@ -0,0 +1,132 @@
+//!
+//! # Proposal
+//!
+//! A validator proposes to change a staking parameter,
+//! see `staking::proposal` for the whole process.
+//!
+
+use {
+    crate::{
+        data_model::NoReplayToken,
+        staking::{
+            proposal::{ParamChange, ProposalId},
+            BlockHeight, Staking,
+        },
+    },
+    ruc::*,
+    serde::{Deserialize, Serialize},
+    zei::xfr::sig::{XfrKeyPair, XfrPublicKey, XfrSignature},
+};
+
+/// Used as the inner object of a `Proposal Operation`.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ProposalOps {
+    pub(crate) body: Data,
+    pub(crate) pubkey: XfrPublicKey,
+    signature: XfrSignature,
+}
+
+impl ProposalOps {
+    /// Check the validity of an operation by running it in a staking simulator.
+    #[inline(always)]
+    pub fn check_run(&self, staking_simulator: &mut Staking) -> Result<()> {
+        self.apply(staking_simulator).c(d!()).map(|_| ())
+    }
+
+    /// Add a new proposal to the target `Staking` instance.
+    pub fn apply(&self, staking: &mut Staking) -> Result<ProposalId> {
+        self.verify().c(d!()).and_then(|_| {
+            staking
+                .proposal_add(
+                    self.pubkey,
+                    self.body.change.clone(),
+                    self.body.activation_height,
+                )
+                .c(d!())
+        })
+    }
+
+    /// Verify signature.
+    #[inline(always)]
+    pub fn verify(&self) -> Result<()> {
+        self.pubkey
+            .verify(&self.body.to_bytes(), &self.signature)
+            .c(d!())
+    }
+
+    #[inline(always)]
+    #[allow(missing_docs)]
+    pub fn get_related_pubkeys(&self) -> Vec<XfrPublicKey> {
+        vec![self.pubkey]
+    }
+
+    #[inline(always)]
+    #[allow(missing_docs)]
+    pub fn new(
+        keypair: &XfrKeyPair,
+        change: ParamChange,
+        activation_height: BlockHeight,
+        nonce: NoReplayToken,
+    ) -> Result<Self> {
+        change.check().c(d!())?;
+        let body = Data::new(change, activation_height, nonce);
+        let signature = keypair.sign(&body.to_bytes());
+        Ok(ProposalOps {
+            body,
+            pubkey: keypair.get_pk(),
+            signature,
+        })
+    }
+
+    #[inline(always)]
+    #[allow(missing_docs)]
+    pub fn set_nonce(&mut self, nonce: NoReplayToken) {
+        self.body.set_nonce(nonce);
+    }
+
+    #[inline(always)]
+    #[allow(missing_docs)]
+    pub fn get_nonce(&self) -> NoReplayToken {
+        self.body.get_nonce()
+    }
+}
+
+/// The body of a proposal operation.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Data {
+    /// the proposed parameter change
+    pub change: ParamChange,
+    /// the height at which the change takes effect if passed
+    pub activation_height: BlockHeight,
+    nonce: NoReplayToken,
+}
+
+impl Data {
+    #[inline(always)]
+    fn new(
+        change: ParamChange,
+        activation_height: BlockHeight,
+        nonce: NoReplayToken,
+    ) -> Self {
+        Data {
+            change,
+            activation_height,
+            nonce,
+        }
+    }
+
+    #[inline(always)]
+    fn to_bytes(&self) -> Vec<u8> {
+        pnk!(bincode::serialize(self))
+    }
+
+    #[inline(always)]
+    fn set_nonce(&mut self, nonce: NoReplayToken) {
+        self.nonce = nonce;
+    }
+
+    #[inline(always)]
+    fn get_nonce(&self) -> NoReplayToken {
+        self.nonce
+    }
+}
@@ -0,0 +1,85 @@
+//!
+//! # Schedule Protocol Upgrades
+//!
+//! set the activation heights of some protocol features
+//! by using a multi-signature transaction.
+//!
+//! **NOTE**: always use the same multi-signature rules as `UpdateValidator`.
+//!
+
+use {
+    crate::{
+        data_model::NoReplayToken,
+        staking::{cosig::CoSigOp, BlockHeight, Staking},
+    },
+    config::abci::upgrade::{Feature, UpgradeSchedule},
+    ruc::*,
+    serde::{Deserialize, Serialize},
+    zei::xfr::sig::{XfrKeyPair, XfrPublicKey},
+};
+
+/// Used as the inner object of a `ScheduleUpgrade Operation`.
+pub type ScheduleUpgradeOps = CoSigOp<Data>;
+
+impl ScheduleUpgradeOps {
+    /// Check the validity of an operation by running it in a staking simulator.
+    #[inline(always)]
+    pub fn check_run(&self, staking_simulator: &mut Staking) -> Result<()> {
+        self.apply(staking_simulator).c(d!())
+    }
+
+    /// Apply the new schedule to the target `Staking` instance,
+    /// features that are not listed in the operation will keep their current heights.
+    pub fn apply(&self, staking: &mut Staking) -> Result<()> {
+        self.verify(staking)
+            .c(d!())
+            .and_then(|_| self.check_context().c(d!()))
+            .and_then(|_| {
+                staking
+                    .upgrade_schedule_update(self.data.schedule.clone())
+                    .c(d!())
+            })
+    }
+
+    #[inline(always)]
+    fn check_context(&self) -> Result<()> {
+        if self.data.schedule.is_empty() {
+            return Err(eg!("empty schedule"));
+        }
+        Ok(())
+    }
+
+    #[inline(always)]
+    #[allow(missing_docs)]
+    pub fn get_related_pubkeys(&self) -> Vec<XfrPublicKey> {
+        self.cosigs.keys().copied().collect()
+    }
+
+    #[inline(always)]
+    #[allow(missing_docs)]
+    pub fn new(
+        kps: &[&XfrKeyPair],
+        schedule: Vec<(Feature, BlockHeight)>,
+        nonce: NoReplayToken,
+    ) -> Result<Self> {
+        let mut op = CoSigOp::create(Data::new(schedule.into_iter().collect()), nonce);
+        op.check_context()
+            .c(d!())
+            .and_then(|_| op.batch_sign(kps).c(d!()))
+            .map(|_| op)
+    }
+}
+
+/// The body of a `ScheduleUpgrade Operation`.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Data {
+    /// New activation heights of the listed features.
+    pub schedule: UpgradeSchedule,
+}
+
+impl Data {
+    #[inline(always)]
+    fn new(schedule: UpgradeSchedule) -> Self {
+        Data { schedule }
+    }
+}
@@ -60,6 +60,12 @@ impl UnDelegationOps {
         vec![self.pubkey]
     }
 
+    #[inline(always)]
+    #[allow(missing_docs)]
+    pub fn get_partial_undelegation(&self) -> Option<&PartialUnDelegation> {
+        self.body.pu.as_ref()
+    }
+
     #[inline(always)]
     #[allow(missing_docs)]
     pub fn new(
@@ -0,0 +1,103 @@
+//!
+//! # Update Penalty Rules
+//!
+//! update the penalty percent of some kinds of byzantine behaviors
+//! by using a multi-signature transaction.
+//!
+//! **NOTE**: always use the same multi-signature rules as `UpdateValidator`.
+//!
+
+use {
+    crate::{
+        data_model::NoReplayToken,
+        staking::{
+            cosig::CoSigOp,
+            ops::governance::{ByzantineKind, Rule, RuleSet},
+            Staking,
+        },
+    },
+    ruc::*,
+    serde::{Deserialize, Serialize},
+    zei::xfr::sig::{XfrKeyPair, XfrPublicKey},
+};
+
+/// Used as the inner object of a `UpdatePenaltyRule Operation`.
+pub type UpdatePenaltyRuleOps = CoSigOp<Data>;
+
+impl UpdatePenaltyRuleOps {
+    /// Check the validity of an operation by running it in a staking simulator.
+    #[inline(always)]
+    pub fn check_run(&self, staking_simulator: &mut Staking) -> Result<()> {
+        self.apply(staking_simulator).c(d!())
+    }
+
+    /// Apply new rules to the target `Staking` instance,
+    /// kinds that are not listed in the operation will keep their current rules.
+    pub fn apply(&self, staking: &mut Staking) -> Result<()> {
+        self.verify(staking)
+            .c(d!())
+            .and_then(|_| self.check_context().c(d!()))
+            .map(|_| staking.governance_rules_update(self.data.rules.clone()))
+    }
+
+    #[inline(always)]
+    fn check_context(&self) -> Result<()> {
+        if self.data.rules.is_empty() {
+            return Err(eg!("empty rule set"));
+        }
+
+        for rule in self.data.rules.values() {
+            rule.check().c(d!())?;
+        }
+
+        Ok(())
+    }
+
+    #[inline(always)]
+    #[allow(missing_docs)]
+    pub fn get_related_pubkeys(&self) -> Vec<XfrPublicKey> {
+        self.cosigs.keys().copied().collect()
+    }
+
+    #[inline(always)]
+    #[allow(missing_docs)]
+    pub fn new(
+        kps: &[&XfrKeyPair],
+        rules: RuleSet,
+        nonce: NoReplayToken,
+    ) -> Result<Self> {
+        let mut op = CoSigOp::create(Data::new(rules), nonce);
+        op.check_context()
+            .c(d!())
+            .and_then(|_| op.batch_sign(kps).c(d!()))
+            .map(|_| op)
+    }
+
+    /// Create an operation from some `(kind, percent)` pairs.
+    #[inline(always)]
+    pub fn from_percents(
+        kps: &[&XfrKeyPair],
+        percents: Vec<(ByzantineKind, [u64; 2])>,
+        nonce: NoReplayToken,
+    ) -> Result<Self> {
+        let rules = percents
+            .into_iter()
+            .map(|(kind, percent)| (kind, Rule::new(percent)))
+            .collect();
+        Self::new(kps, rules, nonce).c(d!())
+    }
+}
+
+/// The body of a `UpdatePenaltyRule Operation`.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Data {
+    /// New rules of the listed byzantine kinds.
+    pub rules: RuleSet,
+}
+
+impl Data {
+    #[inline(always)]
+    fn new(rules: RuleSet) -> Self {
+        Data { rules }
+    }
+}
@@ -8,10 +8,7 @@
 use {
     crate::{
         data_model::NoReplayToken,
-        staking::{
-            cosig::CoSigOp, BlockHeight, Staking, Validator, ValidatorData,
-            COSIG_THRESHOLD_DEFAULT, VALIDATORS_MIN,
-        },
+        staking::{cosig::CoSigOp, BlockHeight, Staking, Validator, ValidatorData},
     },
     ruc::*,
     zei::xfr::sig::{XfrKeyPair, XfrPublicKey},
@@ -32,7 +29,7 @@ impl UpdateValidatorOps {
     pub fn apply(&self, staking: &mut Staking) -> Result<()> {
         self.verify(staking)
             .c(d!())
-            .and_then(|_| self.check_context().c(d!()))
+            .and_then(|_| self.check_context(staking).c(d!()))
             .and_then(|_| {
                 staking
                     .validator_set_at_height(self.data.height, self.data.clone())
@@ -46,20 +43,22 @@ impl UpdateValidatorOps {
     pub fn apply_force(self, staking: &mut Staking) -> Result<()> {
         self.verify(staking)
             .c(d!())
-            .and_then(|_| self.check_context().c(d!()))
+            .and_then(|_| self.check_context(staking).c(d!()))
             .map(|_| staking.validator_set_at_height_force(self.data.height, self.data))
     }
 
     #[inline(always)]
-    fn check_context(&self) -> Result<()> {
-        if VALIDATORS_MIN > self.data.body.len() {
+    fn check_context(&self, staking: &Staking) -> Result<()> {
+        let params = staking.params();
+
+        if params.validators_min > self.data.body.len() {
             return Err(eg!("too few validators"));
         }
 
         let t1 = self.data.cosig_rule.threshold;
-        let t2 = COSIG_THRESHOLD_DEFAULT;
+        let t2 = params.cosig_threshold;
 
-        // threshold must be bigger than COSIG_THRESHOLD_DEFAULT
+        // threshold must be bigger than the current `cosig_threshold`
         if t1[0] * t2[1] < t1[1] * t2[0] {
             return Err(eg!("invalid cosig threshold"));
         }
@@ -77,15 +76,17 @@ impl UpdateValidatorOps {
             .collect()
     }
 
+    /// `cosig_threshold` should not be lower than the `StakingParams::cosig_threshold`
+    /// in effect when the operation is applied.
     #[inline(always)]
-    #[allow(missing_docs)]
     pub fn new(
         kps: &[&XfrKeyPair],
         h: BlockHeight,
         v_set: Vec<Validator>,
+        cosig_threshold: [u64; 2],
         nonce: NoReplayToken,
     ) -> Result<Self> {
-        Data::new(h, v_set)
+        Data::new(h, v_set, cosig_threshold)
             .c(d!())
             .map(|d| CoSigOp::create(d, nonce))
             .and_then(|mut op| op.batch_sign(kps).c(d!()).map(|_| op))
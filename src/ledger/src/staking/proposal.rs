@@ -0,0 +1,400 @@
+//!
+//! # On-chain Parameter Proposals
+//!
+//! Some staking parameters can be changed by the validators themselves,
+//! without a binary upgrade:
+//!
+//! 1. a validator submits a typed `ParamChange` with a future activation height
+//! 2. validators vote on it, every vote is weighted by the voter's `td_power`
+//! 3. when the voting period ends, the proposal passes if the 'yes' power
+//!    reaches the quorum of the global power
+//! 4. a passed proposal takes effect at its activation height
+//!
+
+use {
+    super::{
+        Amount, BlockHeight, Staking, COSIG_THRESHOLD_DEFAULT, MAX_DELEGATION_AMOUNT,
+        MAX_POWER_PERCENT_PER_VALIDATOR, STAKING_VALIDATOR_MIN_POWER, VALIDATORS_MIN,
+    },
+    ruc::*,
+    serde::{Deserialize, Serialize},
+    std::collections::BTreeMap,
+    zei::xfr::sig::XfrPublicKey,
+};
+
+/// The max number of validators in effect, it bounds
+/// `StakingParams::validators_min` and `max_power_percent_per_validator`.
+pub const VALIDATORS_MAX: usize = 100;
+
+/// How many blocks a proposal can be voted on, about 3 days.
+pub const PROPOSAL_VOTING_PERIOD: BlockHeight = 3 * 24 * 3600 / 16;
+
+/// The minimum number of blocks between the end of voting
+/// and the activation of a passed proposal, about 1 day.
+pub const PROPOSAL_TIMELOCK_MIN: BlockHeight = 24 * 3600 / 16;
+
+/// The maximum number of blocks between the end of voting
+/// and the activation of a passed proposal, about 30 days.
+pub const PROPOSAL_TIMELOCK_MAX: BlockHeight = 30 * 24 * 3600 / 16;
+
+/// The minimum percent of global power that must vote 'yes', 2/3.
+pub const PROPOSAL_QUORUM: [u128; 2] = [2, 3];
+
+/// Identifier of a proposal, increased one by one.
+pub type ProposalId = u64;
+
+/// Staking parameters that can be changed by proposals.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct StakingParams {
+    /// The max vote power percent of any validator.
+    pub max_power_percent_per_validator: [u128; 2],
+    /// The minimum investment to become a validator through staking.
+    pub validator_min_power: Amount,
+    /// Minimal number of validators in a `UpdateValidator` operation.
+    pub validators_min: usize,
+    /// The minimum weight threshold required when updating validator information.
+    pub cosig_threshold: [u64; 2],
+}
+
+impl Default for StakingParams {
+    fn default() -> Self {
+        StakingParams {
+            max_power_percent_per_validator: MAX_POWER_PERCENT_PER_VALIDATOR,
+            validator_min_power: STAKING_VALIDATOR_MIN_POWER,
+            validators_min: VALIDATORS_MIN,
+            cosig_threshold: COSIG_THRESHOLD_DEFAULT,
+        }
+    }
+}
+
+impl StakingParams {
+    #[inline(always)]
+    pub(crate) fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    #[inline(always)]
+    fn apply_change(&mut self, change: &ParamChange) {
+        match change {
+            ParamChange::MaxPowerPercentPerValidator(p) => {
+                self.max_power_percent_per_validator = *p;
+            }
+            ParamChange::ValidatorMinPower(am) => {
+                self.validator_min_power = *am;
+            }
+            ParamChange::ValidatorsMin(n) => {
+                self.validators_min = *n;
+            }
+            ParamChange::CosigThreshold(t) => {
+                self.cosig_threshold = *t;
+            }
+        }
+    }
+}
+
+/// A typed change of one staking parameter.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum ParamChange {
+    /// Change `StakingParams::max_power_percent_per_validator`.
+    MaxPowerPercentPerValidator([u128; 2]),
+    /// Change `StakingParams::validator_min_power`.
+    ValidatorMinPower(Amount),
+    /// Change `StakingParams::validators_min`.
+    ValidatorsMin(usize),
+    /// Change `StakingParams::cosig_threshold`.
+    CosigThreshold([u64; 2]),
+}
+
+impl ParamChange {
+    /// Check if the new value is reasonable.
+    pub fn check(&self) -> Result<()> {
+        match self {
+            ParamChange::MaxPowerPercentPerValidator(p) => {
+                // an even split of `VALIDATORS_MAX` validators must be allowed,
+                // and no validator can reach 1/3 to break the BFT guarantee
+                if 0 == p[0]
+                    || p[0].saturating_mul(VALIDATORS_MAX as u128) < p[1]
+                    || p[0].saturating_mul(3) >= p[1]
+                {
+                    return Err(eg!("invalid power percent"));
+                }
+            }
+            ParamChange::ValidatorMinPower(am) => {
+                if 0 == *am || MAX_DELEGATION_AMOUNT < *am {
+                    return Err(eg!("invalid validator min power"));
+                }
+            }
+            ParamChange::ValidatorsMin(n) => {
+                if 0 == *n || VALIDATORS_MAX < *n {
+                    return Err(eg!("invalid validators min"));
+                }
+            }
+            ParamChange::CosigThreshold(t) => {
+                // must be bigger than 2/3 to keep the BFT guarantee
+                if t[0] > t[1] || (t[0] as u128) * 3 <= (t[1] as u128) * 2 {
+                    return Err(eg!("invalid cosig threshold"));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The choice of a vote.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum VoteOption {
+    #[allow(missing_docs)]
+    Yes,
+    #[allow(missing_docs)]
+    No,
+}
+
+/// The lifecycle of a proposal.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum ProposalStatus {
+    /// Accept votes until `voting_end_height`.
+    Voting,
+    /// Quorum reached, waiting for `activation_height`.
+    Passed,
+    /// Quorum not reached.
+    Rejected,
+    /// The change has been applied.
+    Executed,
+}
+
+/// A parameter change proposal.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Proposal {
+    #[allow(missing_docs)]
+    pub id: ProposalId,
+    /// The validator who submitted this proposal.
+    pub proposer: XfrPublicKey,
+    #[allow(missing_docs)]
+    pub change: ParamChange,
+    #[allow(missing_docs)]
+    pub start_height: BlockHeight,
+    #[allow(missing_docs)]
+    pub voting_end_height: BlockHeight,
+    #[allow(missing_docs)]
+    pub activation_height: BlockHeight,
+    /// Votes of validators, a later vote overrides the previous one.
+    pub votes: BTreeMap<XfrPublicKey, VoteOption>,
+    #[allow(missing_docs)]
+    pub status: ProposalStatus,
+    /// The final tally, set when the voting period ends.
+    pub final_tally: Option<ProposalTally>,
+}
+
+/// The vote-power based tally of a proposal.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ProposalTally {
+    #[allow(missing_docs)]
+    pub yes_power: Amount,
+    #[allow(missing_docs)]
+    pub no_power: Amount,
+    /// Global vote-power when tallying.
+    pub total_power: Amount,
+    /// Whether the 'yes' power reaches `PROPOSAL_QUORUM`.
+    pub passed: bool,
+}
+
+/// All proposals ever submitted.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ProposalInfo {
+    next_id: ProposalId,
+    proposals: BTreeMap<ProposalId, Proposal>,
+}
+
+impl ProposalInfo {
+    #[inline(always)]
+    pub(crate) fn is_empty(&self) -> bool {
+        0 == self.next_id && self.proposals.is_empty()
+    }
+}
+
+impl Staking {
+    /// Get the effective staking parameters.
+    #[inline(always)]
+    pub fn params(&self) -> &StakingParams {
+        &self.params
+    }
+
+    /// Submit a new proposal, return its id.
+    pub fn proposal_add(
+        &mut self,
+        proposer: XfrPublicKey,
+        change: ParamChange,
+        activation_height: BlockHeight,
+    ) -> Result<ProposalId> {
+        change.check().c(d!())?;
+        self.proposal_check_voter(&proposer).c(d!())?;
+
+        let h = self.cur_height;
+        let voting_end_height = h + PROPOSAL_VOTING_PERIOD;
+        if activation_height < voting_end_height + PROPOSAL_TIMELOCK_MIN
+            || activation_height > voting_end_height + PROPOSAL_TIMELOCK_MAX
+        {
+            return Err(eg!("invalid activation height"));
+        }
+
+        // one validator can only have one proposal in voting at the same time
+        if self
+            .proposals
+            .proposals
+            .values()
+            .any(|p| p.proposer == proposer && ProposalStatus::Voting == p.status)
+        {
+            return Err(eg!("too many proposals in voting"));
+        }
+
+        let id = self.proposals.next_id;
+        self.proposals.next_id += 1;
+        self.proposals.proposals.insert(
+            id,
+            Proposal {
+                id,
+                proposer,
+                change,
+                start_height: h,
+                voting_end_height,
+                activation_height,
+                votes: map! {B proposer => VoteOption::Yes},
+                status: ProposalStatus::Voting,
+                final_tally: None,
+            },
+        );
+
+        Ok(id)
+    }
+
+    /// Vote on a proposal that is still in its voting period.
+    pub fn proposal_vote(
+        &mut self,
+        voter: XfrPublicKey,
+        id: ProposalId,
+        option: VoteOption,
+    ) -> Result<()> {
+        self.proposal_check_voter(&voter).c(d!())?;
+
+        let h = self.cur_height;
+        let p = self
+            .proposals
+            .proposals
+            .get_mut(&id)
+            .c(d!("proposal not found"))?;
+
+        if ProposalStatus::Voting != p.status || h >= p.voting_end_height {
+            return Err(eg!("voting period is over"));
+        }
+
+        p.votes.insert(voter, option);
+
+        Ok(())
+    }
+
+    // only current validators with non-zero power can propose or vote.
+    #[inline(always)]
+    fn proposal_check_voter(&self, pk: &XfrPublicKey) -> Result<()> {
+        self.validator_get_current_one_by_id(pk)
+            .filter(|v| 0 < v.td_power)
+            .map(|_| ())
+            .c(d!("not a validator"))
+    }
+
+    #[inline(always)]
+    #[allow(missing_docs)]
+    pub fn proposal_get(&self, id: ProposalId) -> Option<&Proposal> {
+        self.proposals.proposals.get(&id)
+    }
+
+    #[inline(always)]
+    #[allow(missing_docs)]
+    pub fn proposal_get_all(&self) -> impl Iterator<Item = &Proposal> {
+        self.proposals.proposals.values()
+    }
+
+    /// Tally a proposal with the current vote-power of validators,
+    /// the final tally is used if the voting period has ended.
+    pub fn proposal_tally(&self, id: ProposalId) -> Option<ProposalTally> {
+        self.proposal_get(id).map(|p| {
+            p.final_tally
+                .clone()
+                .unwrap_or_else(|| self.proposal_tally_current(p))
+        })
+    }
+
+    fn proposal_tally_current(&self, p: &Proposal) -> ProposalTally {
+        let mut tally = ProposalTally {
+            total_power: self.validator_global_power(),
+            ..Default::default()
+        };
+
+        if let Some(vd) = self.validator_get_current() {
+            p.votes.iter().for_each(|(pk, opt)| {
+                let power = vd.body.get(pk).map(|v| v.td_power).unwrap_or(0);
+                match opt {
+                    VoteOption::Yes => tally.yes_power += power,
+                    VoteOption::No => tally.no_power += power,
+                }
+            });
+        }
+
+        tally.passed = 0 < tally.total_power
+            && (tally.yes_power as u128) * PROPOSAL_QUORUM[1]
+                >= (tally.total_power as u128) * PROPOSAL_QUORUM[0];
+
+        tally
+    }
+
+    /// Should be called at the end of every block:
+    /// - tally the proposals whose voting period has ended
+    /// - apply the passed proposals whose activation height has arrived
+    pub fn proposal_process(&mut self) {
+        let h = self.cur_height;
+
+        let ended = self
+            .proposals
+            .proposals
+            .values()
+            .filter(|p| ProposalStatus::Voting == p.status && h >= p.voting_end_height)
+            .map(|p| (p.id, self.proposal_tally_current(p)))
+            .collect::<Vec<_>>();
+
+        ended.into_iter().for_each(|(id, tally)| {
+            let p = pnk!(self.proposals.proposals.get_mut(&id));
+            p.status = alt!(
+                tally.passed,
+                ProposalStatus::Passed,
+                ProposalStatus::Rejected
+            );
+            p.final_tally = Some(tally);
+        });
+
+        let threshold = self.params.cosig_threshold;
+
+        let params = &mut self.params;
+        self.proposals
+            .proposals
+            .values_mut()
+            .filter(|p| ProposalStatus::Passed == p.status && h >= p.activation_height)
+            .for_each(|p| {
+                params.apply_change(&p.change);
+                p.status = ProposalStatus::Executed;
+            });
+
+        // the current and the scheduled validator sets co-sign with the new threshold
+        if threshold != self.params.cosig_threshold {
+            let threshold = self.params.cosig_threshold;
+            let start = self
+                .validator_info
+                .range(0..=h)
+                .next_back()
+                .map(|(k, _)| *k)
+                .unwrap_or(h);
+            self.validator_info
+                .range_mut(start..)
+                .for_each(|(_, vd)| ruc::info_omit!(vd.set_cosig_rule(threshold)));
+        }
+    }
+}
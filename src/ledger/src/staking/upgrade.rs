@@ -0,0 +1,56 @@
+//!
+//! # Protocol Upgrade Schedule
+//!
+//! Activation heights of the protocol features voted on chain,
+//! features that are not listed here follow the local `CheckPointConfig`.
+//!
+//! Only inactive features can be (re)scheduled, and the new height must
+//! leave enough time for all the nodes to know it.
+//!
+
+use {
+    super::{BlockHeight, Staking},
+    ruc::*,
+};
+
+pub use config::abci::upgrade::{Feature, UpgradeSchedule};
+
+/// A new activation height must be at least this many blocks later
+/// than the current height, about 1 day.
+pub const UPGRADE_DELAY_MIN: BlockHeight = 24 * 3600 / 16;
+
+impl Staking {
+    /// The activation heights scheduled on chain.
+    #[inline(always)]
+    pub fn upgrade_schedule(&self) -> &UpgradeSchedule {
+        &self.upgrades
+    }
+
+    /// The effective activation height of a feature.
+    #[inline(always)]
+    pub fn upgrade_height(&self, feature: Feature) -> BlockHeight {
+        self.upgrades
+            .get(&feature)
+            .copied()
+            .unwrap_or_else(|| feature.default_height())
+    }
+
+    /// Schedule new activation heights, all or nothing.
+    pub fn upgrade_schedule_update(&mut self, schedule: UpgradeSchedule) -> Result<()> {
+        let h = self.cur_height;
+
+        for (feature, height) in schedule.iter() {
+            if self.upgrade_height(*feature) <= h {
+                return Err(eg!(format!("feature {feature} is already active")));
+            }
+            if h.saturating_add(UPGRADE_DELAY_MIN) > *height {
+                return Err(eg!(format!(
+                    "feature {feature}: the activation height is too close"
+                )));
+            }
+        }
+
+        self.upgrades.extend(schedule);
+        Ok(())
+    }
+}
@@ -0,0 +1,223 @@
+//!
+//! # Validator Uptime
+//!
+//! Signing statistics of validators, collected from the `LastCommitInfo`
+//! of every block and kept in a rolling window of the latest blocks.
+//!
+//! A validator which leaves the validator set for some blocks will start
+//! a new window when it comes back, the lifetime counters are kept.
+//!
+
+use {
+    super::{BlockHeight, Staking, TendermintAddr, TendermintAddrRef},
+    serde::{Deserialize, Serialize},
+    std::collections::BTreeMap,
+};
+
+/// How many latest blocks are counted in the window, about 1 day.
+pub const UPTIME_WINDOW: BlockHeight = 24 * 3600 / 16;
+
+/// An offline validator will be punished only if it has missed
+/// more blocks than this value in the window, 5% of the window.
+pub const UPTIME_MAX_MISSED: u64 = UPTIME_WINDOW / 20;
+
+const WORD_BITS: u64 = u64::BITS as u64;
+const WINDOW_WORDS: usize = ((UPTIME_WINDOW + WORD_BITS - 1) / WORD_BITS) as usize;
+
+/// A ring of one bit per block in the window,
+/// height `h` takes the bit `h % UPTIME_WINDOW`.
+///
+/// Nothing is allocated while all bits are clear,
+/// which is the case of most validators.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+struct WindowBits(Vec<u64>);
+
+impl WindowBits {
+    #[inline(always)]
+    fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    fn set(&mut self, h: BlockHeight, value: bool) {
+        let idx = h % UPTIME_WINDOW;
+        let (word, bit) = ((idx / WORD_BITS) as usize, idx % WORD_BITS);
+
+        if value {
+            if self.0.is_empty() {
+                self.0 = vec![0; WINDOW_WORDS];
+            }
+            self.0[word] |= 1 << bit;
+        } else if !self.0.is_empty() {
+            self.0[word] &= !(1 << bit);
+            if self.0.iter().all(|w| 0 == *w) {
+                self.clear();
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn clear(&mut self) {
+        self.0 = vec![];
+    }
+
+    #[inline(always)]
+    fn count(&self) -> u64 {
+        self.0.iter().map(|w| w.count_ones() as u64).sum()
+    }
+}
+
+/// Signing records of a validator.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SigningInfo {
+    // the first height of the continuous records
+    start_height: BlockHeight,
+    // the latest height recorded
+    last_height: BlockHeight,
+    // missed blocks in the window
+    #[serde(default, skip_serializing_if = "WindowBits::is_empty")]
+    missed: WindowBits,
+    // proposed blocks in the window, which ends at the next block
+    #[serde(default, skip_serializing_if = "WindowBits::is_empty")]
+    proposed: WindowBits,
+    missed_in_a_row: u64,
+    missed_total: u64,
+    proposed_total: u64,
+}
+
+impl SigningInfo {
+    #[inline(always)]
+    fn new(h: BlockHeight) -> Self {
+        SigningInfo {
+            start_height: h,
+            last_height: h,
+            ..Default::default()
+        }
+    }
+
+    fn record(&mut self, h: BlockHeight, signed: bool) {
+        // absent from some blocks, start a new window
+        if h > self.last_height + 1 {
+            self.start_height = h;
+            self.missed.clear();
+            self.proposed.clear();
+            self.missed_in_a_row = 0;
+        }
+        self.last_height = h;
+
+        // the bits of the heights that slide out of the window are reused
+        self.missed.set(h, !signed);
+        self.proposed.set(h + 1, false);
+
+        if signed {
+            self.missed_in_a_row = 0;
+        } else {
+            self.missed_in_a_row += 1;
+            self.missed_total += 1;
+        }
+    }
+
+    #[inline(always)]
+    fn record_proposal(&mut self, h: BlockHeight) {
+        self.proposed.set(h, true);
+        self.proposed_total += 1;
+    }
+
+    #[inline(always)]
+    fn window_start(&self) -> BlockHeight {
+        self.start_height
+            .max((self.last_height + 1).saturating_sub(UPTIME_WINDOW))
+    }
+
+    /// Whether it has missed too many blocks to be tolerated.
+    #[inline(always)]
+    pub fn missed_too_many(&self) -> bool {
+        self.missed.count() > UPTIME_MAX_MISSED
+    }
+
+    /// Summarize the records in the window.
+    pub fn stats(&self) -> UptimeStats {
+        let window_start = self.window_start();
+        let total = self.last_height + 1 - window_start;
+        let missed_cnt = self.missed.count();
+        UptimeStats {
+            window_start,
+            window_end: self.last_height,
+            signed_cnt: total - missed_cnt,
+            missed_cnt,
+            proposed_cnt: self.proposed.count(),
+            signing_ratio: [total - missed_cnt, total],
+            missed_in_a_row: self.missed_in_a_row,
+            missed_total: self.missed_total,
+            proposed_total: self.proposed_total,
+        }
+    }
+}
+
+/// The signing statistics of a validator.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct UptimeStats {
+    /// The first height of the window.
+    pub window_start: BlockHeight,
+    /// The last height of the window, included.
+    pub window_end: BlockHeight,
+    /// Blocks signed in the window.
+    pub signed_cnt: u64,
+    /// Blocks missed in the window.
+    pub missed_cnt: u64,
+    /// Blocks proposed in the window.
+    pub proposed_cnt: u64,
+    /// `[signed blocks, all blocks]` in the window.
+    pub signing_ratio: [u64; 2],
+    /// Blocks missed continuously until the end of the window.
+    pub missed_in_a_row: u64,
+    /// Blocks missed since the first record.
+    pub missed_total: u64,
+    /// Blocks proposed since the first record.
+    pub proposed_total: u64,
+}
+
+/// Signing records of all validators.
+pub type UptimeInfo = BTreeMap<TendermintAddr, SigningInfo>;
+
+impl Staking {
+    /// Should be called at the end of every block:
+    /// - record the signing status of the validators in the last block
+    /// - record the proposer of the current block
+    ///
+    /// The proposer is not recorded if it has not signed any blocks.
+    pub fn uptime_record(
+        &mut self,
+        votes: &[(TendermintAddr, bool)],
+        proposer: TendermintAddrRef,
+    ) {
+        let h = self.cur_height;
+        let last_height = h.saturating_sub(1);
+
+        votes.iter().for_each(|(addr, signed)| {
+            self.uptime
+                .entry(addr.clone())
+                .or_insert_with(|| SigningInfo::new(last_height))
+                .record(last_height, *signed);
+        });
+
+        if let Some(si) = self.uptime.get_mut(proposer) {
+            si.record_proposal(h);
+        }
+
+        // validators that have left the validator set for a whole window
+        self.uptime
+            .retain(|_, si| si.last_height + UPTIME_WINDOW > last_height);
+    }
+
+    #[inline(always)]
+    #[allow(missing_docs)]
+    pub fn uptime_get(&self, addr: TendermintAddrRef) -> Option<&SigningInfo> {
+        self.uptime.get(addr)
+    }
+
+    #[inline(always)]
+    #[allow(missing_docs)]
+    pub fn uptime_get_all(&self) -> &UptimeInfo {
+        &self.uptime
+    }
+}
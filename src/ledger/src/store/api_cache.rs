@@ -4,27 +4,212 @@
 use {
     crate::{
         data_model::{
-            AssetTypeCode, AssetTypePrefix, DefineAsset, IssueAsset, IssuerPublicKey,
-            Operation, Transaction, TxOutput, TxnIDHash, TxnSID, TxoSID, XfrAddress,
-            ASSET_TYPE_FRA,
+            AssetTypeCode, AssetTypePrefix, DefineAsset, FreezeTarget, IssueAsset,
+            IssuerPublicKey, Operation, Transaction, TxOutput, TxnIDHash, TxnSID,
+            TxoSID, XfrAddress, ASSET_TYPE_FRA,
         },
         staking::{
-            ops::mint_fra::MintEntry, Amount, BlockHeight, DelegationRwdDetail,
-            CHAN_D_AMOUNT_HIST, CHAN_GLOB_RATE_HIST, CHAN_V_SELF_D_HIST, KEEP_HIST,
+            ops::mint_fra::MintEntry, td_addr_to_string, Amount, BlockHeight,
+            Delegation, DelegationRwdDetail, DelegationState, Staking, TendermintAddr,
+            Validator, CHAN_D_AMOUNT_HIST, CHAN_GLOB_RATE_HIST, CHAN_V_SELF_D_HIST,
+            KEEP_HIST,
         },
         store::LedgerState,
     },
-    config::abci::global_cfg::CFG,
+    config::abci::upgrade::{is_active, Feature},
     fbnc::{new_mapx, new_mapxnk, Mapx, Mapxnk},
     globutils::wallet,
     ruc::*,
     serde::{Deserialize, Serialize},
-    std::collections::HashSet,
+    std::collections::{BTreeMap, BTreeSet, HashSet},
     zei::xfr::{sig::XfrPublicKey, structs::OwnerMemo},
 };
 
 type Issuances = Vec<(TxOutput, Option<OwnerMemo>)>;
 
+/// The max number of related transactions scanned for one page of history,
+/// the rest can be scanned with the returned cursor.
+pub const TXN_HIST_SCAN_MAX: usize = 4096;
+
+/// Indexed data of a transaction, used to filter the history of an address.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TxnSummary {
+    /// the block height at which the transaction was committed
+    pub height: BlockHeight,
+    /// kinds of the operations, eg.. "TransferAsset"
+    pub kinds: BTreeSet<String>,
+    /// `NonConfidential` asset types involved in the transaction
+    pub assets: BTreeSet<AssetTypeCode>,
+    /// owners of the inputs and signers of the operations
+    pub senders: BTreeSet<XfrAddress>,
+    /// owners of the outputs
+    pub receivers: BTreeSet<XfrAddress>,
+}
+
+impl TxnSummary {
+    /// The direction of the transaction from the view of `address`,
+    /// a transaction sent by `address` is always outgoing.
+    pub fn direction(&self, address: &XfrAddress) -> Option<TxnDirection> {
+        if self.senders.contains(address) {
+            Some(TxnDirection::Out)
+        } else if self.receivers.contains(address) {
+            Some(TxnDirection::In)
+        } else {
+            None
+        }
+    }
+}
+
+#[allow(missing_docs)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TxnDirection {
+    In,
+    Out,
+}
+
+/// Conditions of the transaction history query,
+/// all of them should be satisfied.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TxnHistFilter {
+    /// operation kind, eg.. "TransferAsset"
+    pub kind: Option<String>,
+    /// `NonConfidential` asset type
+    pub asset: Option<AssetTypeCode>,
+    #[allow(missing_docs)]
+    pub direction: Option<TxnDirection>,
+    /// the lowest block height, inclusive
+    pub from_height: Option<BlockHeight>,
+    /// the highest block height, inclusive
+    pub to_height: Option<BlockHeight>,
+}
+
+impl TxnHistFilter {
+    #[inline(always)]
+    fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    // transactions without a summary only match an empty filter
+    fn matches(&self, address: &XfrAddress, summary: Option<&TxnSummary>) -> bool {
+        let s = if let Some(s) = summary {
+            s
+        } else {
+            return self.is_empty();
+        };
+
+        self.kind.as_ref().map_or(true, |k| s.kinds.contains(k))
+            && self.asset.as_ref().map_or(true, |a| s.assets.contains(a))
+            && self
+                .direction
+                .map_or(true, |d| Some(d) == s.direction(address))
+            && self.from_height.map_or(true, |h| s.height >= h)
+            && self.to_height.map_or(true, |h| s.height <= h)
+    }
+}
+
+/// A related transaction of an address.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TxnHistEntry {
+    #[allow(missing_docs)]
+    pub txn_sid: TxnSID,
+    #[allow(missing_docs)]
+    pub txn_hash: Option<String>,
+    /// `None` if the transaction was not indexed
+    pub direction: Option<TxnDirection>,
+    /// `None` if the transaction was not indexed
+    pub summary: Option<TxnSummary>,
+}
+
+/// One page of the transaction history of an address.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TxnHistPage {
+    #[allow(missing_docs)]
+    pub txns: Vec<TxnHistEntry>,
+    /// pass it back to get the next page,
+    /// `None` means there are no more transactions
+    pub next_cursor: Option<TxnSID>,
+}
+
+/// The delegation state of an address at the end of a block.
+///
+/// Rewards grow in every block, a history of them would be a full copy of
+/// all delegators per block, so a snapshot is only stored when a delegator
+/// is touched, by a change of its delegations or a claim of its rewards,
+/// and `rwd_amount` is the rewards at `rwd_height`, when it was taken.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct DelegationSnapshot {
+    /// validator pubkey => principal
+    pub delegations: BTreeMap<XfrPublicKey, Amount>,
+    /// principal of the temporary partial undelegations
+    pub unbond_amount: Amount,
+    #[allow(missing_docs)]
+    pub state: DelegationState,
+    /// unpaid rewards at `rwd_height`
+    #[serde(default)]
+    pub rwd_amount: Amount,
+    /// the height at which the snapshot was taken
+    #[serde(default)]
+    pub rwd_height: BlockHeight,
+}
+
+impl DelegationSnapshot {
+    /// The snapshot of `d` at the end of block `h`.
+    pub fn new(d: &Delegation, h: BlockHeight) -> Self {
+        DelegationSnapshot {
+            delegations: d.delegations.clone(),
+            unbond_amount: d.tmp_delegators.values().sum(),
+            state: d.state,
+            rwd_amount: d.rwd_amount,
+            rwd_height: h,
+        }
+    }
+
+    // the delegation has been paid and removed from staking
+    #[inline(always)]
+    fn removed(h: BlockHeight) -> Self {
+        DelegationSnapshot {
+            state: DelegationState::Paid,
+            rwd_height: h,
+            ..Default::default()
+        }
+    }
+
+    // the delegator is touched since `last`, rewards only decrease
+    // when they are claimed or paid
+    #[inline(always)]
+    fn touched_since(&self, last: &Self) -> bool {
+        self.delegations != last.delegations
+            || self.unbond_amount != last.unbond_amount
+            || self.state != last.state
+            || self.rwd_amount < last.rwd_amount
+    }
+}
+
+/// The vote power of a validator at the end of a block.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ValidatorSnapshot {
+    /// public key of validator, aka 'Validator ID'.
+    pub id: XfrPublicKey,
+    /// node address in the context of tendermint
+    pub td_addr: TendermintAddr,
+    /// vote power in the context of Staking
+    pub td_power: Amount,
+    #[allow(missing_docs)]
+    pub commission_rate: [u64; 2],
+}
+
+impl From<&Validator> for ValidatorSnapshot {
+    fn from(v: &Validator) -> Self {
+        ValidatorSnapshot {
+            id: v.id,
+            td_addr: td_addr_to_string(&v.td_addr),
+            td_power: v.td_power,
+            commission_rate: v.commission_rate,
+        }
+    }
+}
+
 /// Used in APIs
 #[derive(Clone, Deserialize, Serialize)]
 pub struct ApiCache {
@@ -67,6 +252,21 @@ pub struct ApiCache {
         Mapx<XfrPublicKey, Mapxnk<BlockHeight, DelegationRwdDetail>>,
     /// there are no transactions lost before last_sid
     pub last_sid: Mapx<String, u64>,
+    /// - non-confidential balance history of an address
+    /// - only the heights at which it changed are recorded
+    pub balance_hist: Mapx<XfrAddress, Mapxnk<BlockHeight, u64>>,
+    /// - delegation history of a delegator
+    /// - only the heights at which it changed are recorded
+    pub staking_delegation_hist:
+        Mapx<XfrPublicKey, Mapxnk<BlockHeight, DelegationSnapshot>>,
+    /// - the latest snapshot of every delegator in staking
+    /// - the history is only written when it differs from this one
+    pub staking_delegation_last: Mapx<XfrPublicKey, DelegationSnapshot>,
+    /// - validator set history
+    /// - only the heights at which it changed are recorded
+    pub staking_validator_hist: Mapxnk<BlockHeight, Vec<ValidatorSnapshot>>,
+    /// indexed data of transactions, used to filter the history of an address
+    pub txn_summaries: Mapxnk<TxnSID, TxnSummary>,
 }
 
 impl ApiCache {
@@ -108,6 +308,17 @@ impl ApiCache {
                 "api_cache/{prefix}staking_delegation_rwd_hist",
             )),
             last_sid: new_mapx!(format!("api_cache/{prefix}last_sid",)),
+            balance_hist: new_mapx!(format!("api_cache/{prefix}balance_hist",)),
+            staking_delegation_hist: new_mapx!(format!(
+                "api_cache/{prefix}staking_delegation_hist",
+            )),
+            staking_delegation_last: new_mapx!(format!(
+                "api_cache/{prefix}staking_delegation_last",
+            )),
+            staking_validator_hist: new_mapxnk!(format!(
+                "api_cache/{prefix}staking_validator_hist",
+            )),
+            txn_summaries: new_mapxnk!(format!("api_cache/{prefix}txn_summaries",)),
         }
     }
 
@@ -116,7 +327,7 @@ impl ApiCache {
     pub fn add_created_asset(&mut self, creation: &DefineAsset, cur_height: u64) {
         let asset_code = creation.body.asset.code;
         let code = if asset_code.val == ASSET_TYPE_FRA
-            || CFG.checkpoint.utxo_asset_prefix_height > cur_height
+            || !is_active(Feature::UtxoAssetPrefix, cur_height)
         {
             creation.body.asset.code
         } else {
@@ -225,6 +436,188 @@ impl ApiCache {
         // );
         //         });
     }
+
+    /// Record the non-confidential balance of an address at the end of a block,
+    /// nothing will be stored if it has not changed.
+    pub fn cache_balance(&mut self, address: XfrAddress, h: BlockHeight, balance: u64) {
+        let prefix = self.prefix.clone();
+        #[allow(unused_mut)]
+        let mut hist = self.balance_hist.entry(address).or_insert_with(|| {
+            new_mapxnk!(format!(
+                "api_cache/{}balance_hist/{}",
+                prefix,
+                address.to_base64()
+            ))
+        });
+        if hist.get_closest_smaller(&h).map(|(_, b)| b) != Some(balance) {
+            hist.insert(h, balance);
+        }
+    }
+
+    /// Record the delegations and the validators at the end of a block,
+    /// only the items that differ from their latest snapshots are stored,
+    /// so every delegator has a baseline at the first recorded block.
+    pub fn cache_staking_snapshot(&mut self, staking: &Staking, h: BlockHeight) {
+        let prefix = self.prefix.clone();
+        let delegations = staking.delegation_get_all();
+
+        // paid delegations have been removed from staking
+        let mut changes = self
+            .staking_delegation_last
+            .iter()
+            .filter(|(pk, _)| !delegations.contains_key(pk))
+            .map(|(pk, _)| (pk, DelegationSnapshot::removed(h)))
+            .collect::<Vec<_>>();
+        changes.extend(delegations.iter().filter_map(|(pk, d)| {
+            let snapshot = DelegationSnapshot::new(d, h);
+            match self.staking_delegation_last.get(pk) {
+                Some(last) if !snapshot.touched_since(&last) => None,
+                _ => Some((*pk, snapshot)),
+            }
+        }));
+
+        for (pk, snapshot) in changes {
+            if delegations.contains_key(&pk) {
+                self.staking_delegation_last.insert(pk, snapshot.clone());
+            } else {
+                self.staking_delegation_last.remove(&pk);
+            }
+
+            #[allow(unused_mut)]
+            let mut hist = self.staking_delegation_hist.entry(pk).or_insert_with(|| {
+                new_mapxnk!(format!(
+                    "api_cache/{}staking_delegation_hist/{}",
+                    prefix,
+                    wallet::public_key_to_base64(&pk)
+                ))
+            });
+            hist.insert(h, snapshot);
+        }
+
+        if let Some(vd) = staking.validator_get_current() {
+            let validators = vd
+                .body
+                .values()
+                .map(ValidatorSnapshot::from)
+                .collect::<Vec<_>>();
+            if self
+                .staking_validator_hist
+                .get_closest_smaller(&h)
+                .map(|(_, v)| v)
+                .as_ref()
+                != Some(&validators)
+            {
+                self.staking_validator_hist.insert(h, validators);
+            }
+        }
+    }
+
+    /// Get the non-confidential balance of an address at the end of a block.
+    #[inline(always)]
+    pub fn get_balance_at_height(
+        &self,
+        address: &XfrAddress,
+        h: BlockHeight,
+    ) -> Option<u64> {
+        self.balance_hist
+            .get(address)
+            .and_then(|hist| hist.get_closest_smaller(&h))
+            .map(|(_, b)| b)
+    }
+
+    /// Get the delegation state of an address at the end of a block.
+    #[inline(always)]
+    pub fn get_delegation_at_height(
+        &self,
+        pk: &XfrPublicKey,
+        h: BlockHeight,
+    ) -> Option<DelegationSnapshot> {
+        self.staking_delegation_hist
+            .get(pk)
+            .and_then(|hist| hist.get_closest_smaller(&h))
+            .map(|(_, d)| d)
+    }
+
+    /// Get one page of the related transactions of an address,
+    /// the transactions after(or before, if `order_desc`) `cursor` will be scanned.
+    pub fn get_related_txns_page(
+        &self,
+        address: &XfrAddress,
+        filter: &TxnHistFilter,
+        cursor: Option<TxnSID>,
+        limit: usize,
+        order_desc: bool,
+    ) -> TxnHistPage {
+        let mut page = TxnHistPage::default();
+
+        let hist = if let Some(hist) = self.related_transactions.get(address) {
+            hist
+        } else {
+            return page;
+        };
+
+        let next = |cur: Option<TxnSID>| {
+            match (order_desc, cur) {
+                (true, None) => hist.get_closest_smaller(&TxnSID(usize::MAX)),
+                (true, Some(TxnSID(n))) => n
+                    .checked_sub(1)
+                    .and_then(|n| hist.get_closest_smaller(&TxnSID(n))),
+                (false, None) => hist.get_closest_larger(&TxnSID(0)),
+                (false, Some(TxnSID(n))) => n
+                    .checked_add(1)
+                    .and_then(|n| hist.get_closest_larger(&TxnSID(n))),
+            }
+            .map(|(sid, _)| sid)
+        };
+
+        let mut cur = cursor;
+        for _ in 0..TXN_HIST_SCAN_MAX {
+            let sid = if let Some(sid) = next(cur) {
+                sid
+            } else {
+                return page;
+            };
+            cur = Some(sid);
+
+            let summary = self.txn_summaries.get(&sid);
+
+            // sids are increasing along with heights,
+            // so nothing can be found beyond the height range
+            if let Some(s) = summary.as_ref() {
+                if (order_desc && filter.from_height.map_or(false, |h| s.height < h))
+                    || (!order_desc && filter.to_height.map_or(false, |h| s.height > h))
+                {
+                    return page;
+                }
+            }
+
+            if filter.matches(address, summary.as_ref()) {
+                page.txns.push(TxnHistEntry {
+                    txn_sid: sid,
+                    txn_hash: self.txn_sid_to_hash.get(&sid),
+                    direction: summary.as_ref().and_then(|s| s.direction(address)),
+                    summary,
+                });
+                if page.txns.len() >= limit {
+                    break;
+                }
+            }
+        }
+
+        page.next_cursor = cur;
+        page
+    }
+
+    /// Get the validators and their vote powers at the end of a block.
+    #[inline(always)]
+    pub fn get_validators_at_height(
+        &self,
+        h: BlockHeight,
+    ) -> Option<Vec<ValidatorSnapshot>> {
+        self.staking_validator_hist
+            .get_closest_smaller(&h)
+            .map(|(_, v)| v)
+    }
 }
 
 /// An xfr address is related to a transaction if it is one of the following:
@@ -233,6 +626,7 @@ impl ApiCache {
 /// 3. Signer of a an issuance txn
 /// 4. Signer of a kv_update txn
 /// 5. Signer of a memo_update txn
+/// 6. Issuer and target of a freeze, issuer and owners of a clawback
 pub fn get_related_addresses<F>(
     txn: &Transaction,
     mut classify: F,
@@ -260,6 +654,11 @@ where
             Operation::Claim(i) => staking_gen!(i),
             Operation::UpdateValidator(i) => staking_gen!(i),
             Operation::Governance(i) => staking_gen!(i),
+            Operation::UpdatePenaltyRule(i) => staking_gen!(i),
+            Operation::Proposal(i) => staking_gen!(i),
+            Operation::Vote(i) => staking_gen!(i),
+            Operation::Unjail(i) => staking_gen!(i),
+            Operation::ScheduleUpgrade(i) => staking_gen!(i),
             Operation::FraDistribution(i) => staking_gen!(i),
             Operation::MintFra(i) => staking_gen!(i),
 
@@ -296,11 +695,114 @@ where
                     key: update_memo.pubkey,
                 });
             }
+            Operation::FreezeAsset(freeze) => {
+                related_addresses.insert(XfrAddress {
+                    key: freeze.pubkey.key,
+                });
+                if let FreezeTarget::Address(pk) = freeze.body.target {
+                    related_addresses.insert(XfrAddress { key: pk });
+                }
+            }
+            Operation::ClawbackAsset(clawback) => {
+                related_addresses.insert(XfrAddress {
+                    key: clawback.pubkey.key,
+                });
+                related_addresses.insert(XfrAddress {
+                    key: clawback.body.input_record.record.public_key,
+                });
+                related_addresses.insert(XfrAddress {
+                    key: clawback.body.output.record.public_key,
+                });
+            }
         }
     }
     related_addresses
 }
 
+/// The kind of an operation, aka the name of its variant.
+pub fn get_operation_kind(op: &Operation) -> &'static str {
+    match op {
+        Operation::TransferAsset(_) => "TransferAsset",
+        Operation::IssueAsset(_) => "IssueAsset",
+        Operation::DefineAsset(_) => "DefineAsset",
+        Operation::UpdateMemo(_) => "UpdateMemo",
+        Operation::UpdateStaker(_) => "UpdateStaker",
+        Operation::Delegation(_) => "Delegation",
+        Operation::UnDelegation(_) => "UnDelegation",
+        Operation::Claim(_) => "Claim",
+        Operation::UpdateValidator(_) => "UpdateValidator",
+        Operation::Governance(_) => "Governance",
+        Operation::FraDistribution(_) => "FraDistribution",
+        Operation::MintFra(_) => "MintFra",
+        Operation::ConvertAccount(_) => "ConvertAccount",
+        Operation::ReplaceStaker(_) => "ReplaceStaker",
+        Operation::UpdatePenaltyRule(_) => "UpdatePenaltyRule",
+        Operation::Proposal(_) => "Proposal",
+        Operation::Vote(_) => "Vote",
+        Operation::Unjail(_) => "Unjail",
+        Operation::ScheduleUpgrade(_) => "ScheduleUpgrade",
+        Operation::FreezeAsset(_) => "FreezeAsset",
+        Operation::ClawbackAsset(_) => "ClawbackAsset",
+    }
+}
+
+/// Returns the summary of a transaction,
+/// `related_addresses` and `receivers` should come from the same transaction.
+pub fn get_txn_summary(
+    txn: &Transaction,
+    height: BlockHeight,
+    related_addresses: &HashSet<XfrAddress>,
+    receivers: &[XfrAddress],
+) -> TxnSummary {
+    let receivers = receivers.iter().copied().collect::<BTreeSet<_>>();
+
+    // the owner of an input may also receive the change,
+    // so inputs are collected explicitly
+    let mut senders = related_addresses
+        .iter()
+        .filter(|a| !receivers.contains(a))
+        .copied()
+        .collect::<BTreeSet<_>>();
+    let mut assets = get_transferred_nonconfidential_assets(txn)
+        .into_iter()
+        .collect::<BTreeSet<_>>();
+
+    for op in txn.body.operations.iter() {
+        match op {
+            Operation::TransferAsset(transfer) => {
+                transfer.body.transfer.inputs.iter().for_each(|i| {
+                    senders.insert(XfrAddress { key: i.public_key });
+                });
+                transfer.body.transfer.outputs.iter().for_each(|o| {
+                    if let Some(asset_type) = o.asset_type.get_asset_type() {
+                        assets.insert(AssetTypeCode { val: asset_type });
+                    }
+                });
+            }
+            Operation::IssueAsset(issue_asset) => {
+                assets.insert(issue_asset.body.code);
+            }
+            Operation::DefineAsset(define_asset) => {
+                assets.insert(define_asset.body.asset.code);
+            }
+            _ => {}
+        }
+    }
+
+    TxnSummary {
+        height,
+        kinds: txn
+            .body
+            .operations
+            .iter()
+            .map(|op| get_operation_kind(op).to_owned())
+            .collect(),
+        assets,
+        senders,
+        receivers,
+    }
+}
+
 /// Returns the set of nonconfidential assets transferred in a transaction.
 pub fn get_transferred_nonconfidential_assets(
     txn: &Transaction,
@@ -473,6 +975,13 @@ pub fn update_api_cache(ledger: &mut LedgerState) -> Result<()> {
 
     ledger.api_cache.as_mut().unwrap().cache_hist_data();
 
+    let h = ledger.status.td_commit_height;
+    ledger
+        .api_cache
+        .as_mut()
+        .unwrap()
+        .cache_staking_snapshot(&ledger.status.staking, h);
+
     let block = if let Some(b) = ledger.blocks.last() {
         b
     } else {
@@ -481,6 +990,9 @@ pub fn update_api_cache(ledger: &mut LedgerState) -> Result<()> {
 
     let prefix = ledger.api_cache.as_mut().unwrap().prefix.clone();
 
+    // addresses whose balance may have been changed in this block
+    let mut balance_changed = HashSet::new();
+
     // Update ownership status
     for (txn_sid, txo_sids) in block.txns.iter().map(|v| (v.tx_id, v.txo_ids.as_slice()))
     {
@@ -554,6 +1066,27 @@ pub fn update_api_cache(ledger: &mut LedgerState) -> Result<()> {
         // Update related addresses
         // Apply classify_op for each operation in curr_txn
         let related_addresses = get_related_addresses(&curr_txn, classify_op);
+        balance_changed.extend(related_addresses.iter().copied());
+        balance_changed.extend(addresses.iter().copied());
+
+        // the last block may be processed again in an empty block,
+        // keep the height of its first commitment
+        if !ledger
+            .api_cache
+            .as_ref()
+            .unwrap()
+            .txn_summaries
+            .contains_key(&txn_sid)
+        {
+            let summary = get_txn_summary(&curr_txn, h, &related_addresses, &addresses);
+            ledger
+                .api_cache
+                .as_mut()
+                .unwrap()
+                .txn_summaries
+                .insert(txn_sid, summary);
+        }
+
         for address in &related_addresses {
             ledger
                 .api_cache
@@ -651,5 +1184,16 @@ pub fn update_api_cache(ledger: &mut LedgerState) -> Result<()> {
         }
     }
 
+    // Record the balances at the end of this block
+    let api_cache = ledger.api_cache.as_mut().unwrap();
+    for address in balance_changed {
+        let balance = ledger
+            .status
+            .nonconfidential_balances
+            .get(&address.key)
+            .unwrap_or(0);
+        api_cache.cache_balance(address, h, balance);
+    }
+
     Ok(())
 }
@@ -0,0 +1,199 @@
+//!
+//! # Ledger Events
+//!
+//! Events generated while building a block, by `LedgerState::finish_block`
+//! and the system penalties, they are held until `flush` is called
+//! after the block has been committed, and will only be generated
+//! if someone has subscribed.
+//!
+
+use {
+    crate::{
+        data_model::{FinalizedBlock, Operation, TxnSID, TxoSID, XfrAddress},
+        staking::{
+            ops::governance::ByzantineKind, Amount, BlockHeight, PartialUnDelegation,
+            TendermintAddr,
+        },
+    },
+    lazy_static::lazy_static,
+    parking_lot::Mutex,
+    serde::{Deserialize, Serialize},
+    std::sync::mpsc::{channel, Receiver, Sender},
+    zei::xfr::sig::XfrPublicKey,
+};
+
+lazy_static! {
+    static ref SINKS: Mutex<Vec<Sender<LedgerEvent>>> = Mutex::new(vec![]);
+    static ref PENDING: Mutex<Vec<LedgerEvent>> = Mutex::new(vec![]);
+}
+
+/// Something happened in a committed block.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LedgerEvent {
+    /// A new block has been committed.
+    Block {
+        #[allow(missing_docs)]
+        height: BlockHeight,
+        #[allow(missing_docs)]
+        block: FinalizedBlock,
+    },
+    /// A new UTXO has been created.
+    UtxoCreated {
+        #[allow(missing_docs)]
+        height: BlockHeight,
+        /// owner of the UTXO
+        address: XfrAddress,
+        #[allow(missing_docs)]
+        txo_sid: TxoSID,
+        #[allow(missing_docs)]
+        txn_sid: TxnSID,
+    },
+    /// A UTXO has been spent.
+    UtxoSpent {
+        #[allow(missing_docs)]
+        height: BlockHeight,
+        /// owner of the UTXO
+        address: XfrAddress,
+        #[allow(missing_docs)]
+        txo_sid: TxoSID,
+    },
+    /// A staking operation has been committed,
+    /// or a penalty has been applied by the system.
+    Staking {
+        #[allow(missing_docs)]
+        height: BlockHeight,
+        /// `None` if it is generated by the system
+        txn_sid: Option<TxnSID>,
+        #[allow(missing_docs)]
+        event: StakingEvent,
+    },
+}
+
+impl LedgerEvent {
+    /// The kind of the event, same as the `type` field in its JSON form.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            LedgerEvent::Block { .. } => "block",
+            LedgerEvent::UtxoCreated { .. } => "utxo_created",
+            LedgerEvent::UtxoSpent { .. } => "utxo_spent",
+            LedgerEvent::Staking { .. } => "staking",
+        }
+    }
+
+    /// Check if the event is related to `address`,
+    /// block events are related to all addresses.
+    pub fn is_related_to(&self, address: &XfrPublicKey) -> bool {
+        match self {
+            LedgerEvent::Block { .. } => true,
+            LedgerEvent::UtxoCreated { address: a, .. }
+            | LedgerEvent::UtxoSpent { address: a, .. } => a.key == *address,
+            LedgerEvent::Staking { event, .. } => {
+                event.get_related_pubkeys().contains(address)
+            }
+        }
+    }
+}
+
+/// Staking changes that users may be interested in.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StakingEvent {
+    #[allow(missing_docs)]
+    Delegation {
+        delegator: XfrPublicKey,
+        validator: TendermintAddr,
+        amount: Amount,
+    },
+    #[allow(missing_docs)]
+    UnDelegation {
+        delegator: XfrPublicKey,
+        /// `None` if all the principal will be undelegated
+        partial: Option<PartialUnDelegation>,
+    },
+    #[allow(missing_docs)]
+    Claim {
+        delegator: XfrPublicKey,
+        /// `None` if all the rewards will be claimed
+        amount: Option<Amount>,
+    },
+    #[allow(missing_docs)]
+    Penalty {
+        validator: TendermintAddr,
+        /// `None` if the validator has been removed
+        validator_id: Option<XfrPublicKey>,
+        kind: ByzantineKind,
+    },
+    /// A jailed validator has returned to the active set.
+    Unjail { validator_id: XfrPublicKey },
+}
+
+impl StakingEvent {
+    /// Generate a event from an operation, if it is a related one.
+    pub fn from_operation(op: &Operation) -> Option<Self> {
+        match op {
+            Operation::Delegation(i) => Some(StakingEvent::Delegation {
+                delegator: i.pubkey,
+                validator: i.body.validator.clone(),
+                amount: i.body.amount,
+            }),
+            Operation::UnDelegation(i) => Some(StakingEvent::UnDelegation {
+                delegator: i.pubkey,
+                partial: i.get_partial_undelegation().cloned(),
+            }),
+            Operation::Claim(i) => Some(StakingEvent::Claim {
+                delegator: i.pubkey,
+                amount: i.body.amount,
+            }),
+            Operation::Unjail(i) => Some(StakingEvent::Unjail {
+                validator_id: i.pubkey,
+            }),
+            _ => None,
+        }
+    }
+
+    #[allow(missing_docs)]
+    pub fn get_related_pubkeys(&self) -> Vec<XfrPublicKey> {
+        match self {
+            StakingEvent::Delegation { delegator, .. }
+            | StakingEvent::UnDelegation { delegator, .. }
+            | StakingEvent::Claim { delegator, .. } => vec![*delegator],
+            StakingEvent::Penalty { validator_id, .. } => {
+                validator_id.iter().copied().collect()
+            }
+            StakingEvent::Unjail { validator_id } => vec![*validator_id],
+        }
+    }
+}
+
+/// Subscribe the events of all the blocks committed later,
+/// drop the receiver to unsubscribe.
+pub fn subscribe() -> Receiver<LedgerEvent> {
+    let (sink, stream) = channel();
+    SINKS.lock().push(sink);
+    stream
+}
+
+/// Check if anyone is waiting for events.
+#[inline(always)]
+pub fn has_subscribers() -> bool {
+    !SINKS.lock().is_empty()
+}
+
+/// Hold events of the block being built, until it is committed.
+pub fn notify(evs: impl IntoIterator<Item = LedgerEvent>) {
+    if !has_subscribers() {
+        return;
+    }
+    PENDING.lock().extend(evs);
+}
+
+/// Send the held events to all the subscribers,
+/// should be called after the block has been committed.
+pub fn flush() {
+    let evs = std::mem::take(&mut *PENDING.lock());
+    let mut sinks = SINKS.lock();
+    evs.into_iter().for_each(|ev| {
+        sinks.retain(|sink| sink.send(ev.clone()).is_ok());
+    });
+}
@@ -177,6 +177,8 @@ pub fn create_issue_and_transfer_txn(
                 id: None,
                 record: ba.clone(),
                 lien: None,
+                version: Default::default(),
+                lock: None,
             },
             None,
         )],
@@ -265,6 +267,8 @@ pub fn create_issue_and_transfer_txn_with_asset_tracing(
                 id: None,
                 record: ba.clone(),
                 lien: None,
+                version: Default::default(),
+                lock: None,
             },
             None,
         )],
@@ -353,6 +357,8 @@ pub fn create_issuance_txn(
                 id: None,
                 record: ba,
                 lien: None,
+                version: Default::default(),
+                lock: None,
             },
             None,
         )],
@@ -3,7 +3,10 @@
 //!
 
 pub mod api_cache;
+pub mod events;
 pub mod helpers;
+pub mod simulate;
+pub mod snapshot;
 mod test;
 pub mod utils;
 
@@ -14,23 +17,27 @@ use {
         data_model::{
             AssetType, AssetTypeCode, AssetTypePrefix, AuthenticatedBlock,
             AuthenticatedTransaction, AuthenticatedUtxo, AuthenticatedUtxoStatus,
-            BlockEffect, BlockSID, FinalizedBlock, FinalizedTransaction, IssuerKeyPair,
-            IssuerPublicKey, OutputPosition, StateCommitmentData, Transaction,
-            TransferType, TxnEffect, TxnSID, TxnTempSID, TxoSID, UnAuthenticatedUtxo,
-            Utxo, UtxoStatus, ASSET_TYPE_FRA, BLACK_HOLE_PUBKEY,
+            BlockEffect, BlockSID, FinalizedBlock, FinalizedTransaction, FreezeAsset,
+            FreezeTarget, IssuerKeyPair, IssuerPublicKey, OutputPosition,
+            StateCommitmentData, Transaction, TransferType, TxOutput, TxnEffect, TxnSID,
+            TxnTempSID, TxoSID, UnAuthenticatedUtxo, Utxo, UtxoStatus, XfrAddress,
+            ASSET_TYPE_FRA, BLACK_HOLE_PUBKEY,
         },
+        error::LedgerError,
         staking::{
-            Amount, Power, Staking, TendermintAddrRef, FF_PK_EXTRA_120_0000, FF_PK_LIST,
-            FRA_TOTAL_AMOUNT, KEEP_HIST,
+            Amount, BlockHeight, Power, Staking, TendermintAddrRef,
+            FF_PK_EXTRA_120_0000, FF_PK_LIST, FRA_TOTAL_AMOUNT, KEEP_HIST,
         },
         LSSED_VAR, SNAPSHOT_ENTRIES_DIR,
     },
-    api_cache::ApiCache,
+    api_cache::{ApiCache, DelegationSnapshot, ValidatorSnapshot},
     bitmap::{BitMap, SparseMap},
-    config::abci::global_cfg::CFG,
-    cryptohash::sha256::Digest as BitDigest,
+    config::abci::upgrade::{is_active, Feature},
+    cryptohash::sha256::{self, Digest as BitDigest},
+    events::{LedgerEvent, StakingEvent},
     fbnc::{new_mapx, new_mapxnk, new_vecx, Mapx, Mapxnk, Vecx},
     globutils::{HashOf, ProofOf},
+    light_verifier::{LightProof, MerklePath, TxnProof, UtxoProof},
     merkle_tree::AppendOnlyMerkle,
     parking_lot::RwLock,
     rand_chacha::ChaChaRng,
@@ -39,7 +46,7 @@ use {
     serde::{Deserialize, Serialize},
     sliding_set::SlidingSet,
     std::{
-        collections::{BTreeMap, HashMap, HashSet},
+        collections::{BTreeMap, BTreeSet, HashMap, HashSet},
         env,
         fs::{self, OpenOptions},
         io::ErrorKind,
@@ -50,7 +57,9 @@ use {
     zei::xfr::{
         lib::XfrNotePolicies,
         sig::XfrPublicKey,
-        structs::{OwnerMemo, TracingPolicies, TracingPolicy},
+        structs::{
+            BlindAssetRecord, OwnerMemo, TracingPolicies, TracingPolicy, XfrAssetType,
+        },
     },
 };
 
@@ -117,7 +126,7 @@ impl LedgerState {
     ) -> Result<TxnTempSID> {
         let tx = txe.txn.clone();
         self.status
-            .check_txn_effects(&txe)
+            .check_txn_effects(&txe, &block.freezes)
             .c(d!())
             .and_then(|_| block.add_txn_effect(txe).c(d!()))
             .map(|tmpid| {
@@ -198,7 +207,7 @@ impl LedgerState {
             let merkle_id = {
                 let mut txn = txn.clone();
 
-                if (CFG.checkpoint.utxo_checktx_height as u64) > height {
+                if !is_active(Feature::UtxoSignatureMap, height) {
                     txn.pubkey_sign_map = Default::default();
                 }
 
@@ -261,12 +270,84 @@ impl LedgerState {
             }
         }
 
+        // inputs will be drained when applying the block
+        let spent = alt!(
+            events::has_subscribers(),
+            Some(
+                block
+                    .input_txos
+                    .iter()
+                    .map(|(sid, utxo)| (*sid, utxo.record.public_key))
+                    .collect::<Vec<_>>()
+            ),
+            None
+        );
+
         let (tsm, base_sid, max_sid) = self.status.apply_block_effects(&mut block);
 
         self.update_utxo_map(base_sid, max_sid, &block.temp_sids, &tsm)
             .c(d!())
             .and_then(|_| self.update_state(block, &tsm).c(d!()))
-            .map(|_| tsm)
+            .map(|_| {
+                if let Some(spent) = spent {
+                    self.notify_block_events(spent);
+                }
+                tsm
+            })
+    }
+
+    // Generate events for the last finished block, sent once it is committed.
+    fn notify_block_events(&self, spent: Vec<(TxoSID, XfrPublicKey)>) {
+        let block = if let Some(b) = self.blocks.last() {
+            b.clone()
+        } else {
+            return;
+        };
+        let height = self.get_staking().cur_height();
+
+        let mut evs = vec![];
+        for tx in block.txns.iter() {
+            tx.txo_ids
+                .iter()
+                .filter_map(|sid| {
+                    self.get_utxo_light(*sid)
+                        .or_else(|| self.get_spent_utxo_light(*sid))
+                        .map(|u| (*sid, u.utxo.0.record.public_key))
+                })
+                .for_each(|(txo_sid, key)| {
+                    evs.push(LedgerEvent::UtxoCreated {
+                        height,
+                        address: XfrAddress { key },
+                        txo_sid,
+                        txn_sid: tx.tx_id,
+                    });
+                });
+            tx.txn
+                .body
+                .operations
+                .iter()
+                .filter_map(StakingEvent::from_operation)
+                .for_each(|event| {
+                    evs.push(LedgerEvent::Staking {
+                        height,
+                        txn_sid: Some(tx.tx_id),
+                        event,
+                    });
+                });
+        }
+        spent.into_iter().for_each(|(txo_sid, key)| {
+            evs.push(LedgerEvent::UtxoSpent {
+                height,
+                address: XfrAddress { key },
+                txo_sid,
+            });
+        });
+
+        events::notify(
+            [LedgerEvent::Block { height, block }]
+                .into_iter()
+                .chain(evs.into_iter()),
+        );
     }
 
     #[inline(always)]
@@ -287,6 +368,18 @@ impl LedgerState {
         self.status.td_commit_height
     }
 
+    /// Set the time of the current block, in unix seconds
+    #[inline(always)]
+    pub fn set_block_time(&mut self, time: u64) {
+        self.status.block_time = time;
+    }
+
+    #[inline(always)]
+    #[allow(missing_docs)]
+    pub fn get_block_time(&self) -> u64 {
+        self.status.block_time
+    }
+
     #[inline(always)]
     #[allow(missing_docs)]
     pub fn get_next_txn(&self) -> TxnSID {
@@ -333,14 +426,14 @@ impl LedgerState {
     }
 
     fn compute_and_save_state_commitment_data(&mut self, pulse_count: u64) {
-        let staking_data = if self.get_tendermint_height()
-            < CFG.checkpoint.remove_fake_staking_hash
-            && self.get_staking().has_been_inited()
-        {
-            Some(HashOf::new(self.get_staking()))
-        } else {
-            None
-        };
+        let staking_data =
+            if !is_active(Feature::RemoveFakeStakingHash, self.get_tendermint_height())
+                && self.get_staking().has_been_inited()
+            {
+                Some(HashOf::new(self.get_staking()))
+            } else {
+                None
+            };
 
         let state_commitment_data = StateCommitmentData {
             bitmap: self.utxo_map.write().compute_checksum(),
@@ -484,6 +577,12 @@ impl LedgerState {
         // Fetch public key for current nodes (The node on which the binary is running), Tendermint key .
         let pk = s.validator_td_addr_to_app_pk(addr).c(d!())?;
 
+        // the rewards of a jailed validator and its delegators are paused,
+        // it may still propose some blocks before tendermint removes it
+        if s.validator_rewards_paused(&pk) {
+            return Ok(());
+        }
+
         // Check validator and fetch commission_rate
         let commission_rate = if let Some(v) = s.validator_get_current_one_by_id(&pk) {
             v.commission_rate
@@ -562,7 +661,7 @@ impl LedgerState {
         // #[cfg(not(feature = "debug_env"))]
         // const APY_V7_UPGRADE_HEIGHT: BlockHeight = 142_9000;
 
-        if CFG.checkpoint.apy_v7_upgrade_height < self.get_tendermint_height() {
+        if is_active(Feature::ApyV7Upgrade, self.get_tendermint_height()) {
             // This is an equal conversion of `1 / p% * 0.0536`
             let mut a0 = p[1] * 536;
             let mut a1 = p[0] * 10000;
@@ -607,7 +706,7 @@ impl LedgerState {
 
         let s = self.get_staking();
 
-        let extras = if CFG.checkpoint.ff_addr_extra_fix_height < s.cur_height {
+        let extras = if is_active(Feature::FfAddrExtraFix, s.cur_height) {
             vec![*BLACK_HOLE_PUBKEY, *FF_PK_EXTRA_120_0000]
         } else {
             vec![*BLACK_HOLE_PUBKEY]
@@ -641,9 +740,10 @@ impl LedgerState {
         // #[cfg(not(feature = "debug_env"))]
         // const NONCONFIDENTIAL_BALANCE_FIX_HEIGHT: BlockHeight = 121_0000;
 
-        if CFG.checkpoint.nonconfidential_balance_fix_height
-            < self.get_tendermint_height()
-        {
+        if is_active(
+            Feature::NonconfidentialBalanceFix,
+            self.get_tendermint_height(),
+        ) {
             self.get_nonconfidential_balance(addr).c(d!())
         } else {
             Ok(0)
@@ -788,6 +888,57 @@ impl LedgerState {
         self.status.nonconfidential_balances.get(addr)
     }
 
+    /// Get the non-confidential balance of `addr` at the end of block `h`,
+    /// heights before the tip are only available when `KEEP_HIST` is enabled.
+    pub fn get_nonconfidential_balance_at_height(
+        &self,
+        addr: &XfrPublicKey,
+        h: BlockHeight,
+    ) -> Option<u64> {
+        if h >= self.get_tendermint_height() {
+            return self.get_nonconfidential_balance(addr);
+        }
+        self.api_cache
+            .as_ref()
+            .and_then(|ac| ac.get_balance_at_height(&XfrAddress { key: *addr }, h))
+    }
+
+    /// Get the delegation state of `addr` at the end of block `h`,
+    /// heights before the tip are only available when `KEEP_HIST` is enabled.
+    pub fn get_delegation_at_height(
+        &self,
+        addr: &XfrPublicKey,
+        h: BlockHeight,
+    ) -> Option<DelegationSnapshot> {
+        let tip = self.get_tendermint_height();
+        if h >= tip {
+            return self
+                .get_staking()
+                .delegation_get(addr)
+                .map(|d| DelegationSnapshot::new(d, tip));
+        }
+        self.api_cache
+            .as_ref()
+            .and_then(|ac| ac.get_delegation_at_height(addr, h))
+    }
+
+    /// Get the validators and their vote powers at the end of block `h`,
+    /// heights before the tip are only available when `KEEP_HIST` is enabled.
+    pub fn get_validators_at_height(
+        &self,
+        h: BlockHeight,
+    ) -> Option<Vec<ValidatorSnapshot>> {
+        if h >= self.get_tendermint_height() {
+            return self
+                .get_staking()
+                .validator_get_current()
+                .map(|vd| vd.body.values().map(ValidatorSnapshot::from).collect());
+        }
+        self.api_cache
+            .as_ref()
+            .and_then(|ac| ac.get_validators_at_height(h))
+    }
+
     /// Get unspent utxos owned by a findora account
     pub fn get_owned_utxos(
         &self,
@@ -830,6 +981,18 @@ impl LedgerState {
         self.status.get_asset_type(code)
     }
 
+    /// The unspent outputs of an asset frozen by its issuer
+    #[inline(always)]
+    pub fn get_frozen_txos(&self, code: &AssetTypeCode) -> Vec<TxoSID> {
+        self.status.frozen.get_txos(code)
+    }
+
+    /// The addresses whose outputs of an asset are frozen by its issuer
+    #[inline(always)]
+    pub fn get_frozen_addresses(&self, code: &AssetTypeCode) -> Vec<XfrPublicKey> {
+        self.status.frozen.get_addresses(code)
+    }
+
     #[inline(always)]
     #[allow(missing_docs)]
     pub fn insert_asset_type(&mut self, code: AssetTypeCode, at: AssetType) {
@@ -882,6 +1045,103 @@ impl LedgerState {
         }
     }
 
+    /// Get the proof of a transaction for light clients,
+    /// it can be verified by the `light_verifier` crate.
+    pub fn get_txn_light_proof(&self, id: TxnSID) -> Result<LightProof> {
+        let state_commitment_data = self.get_state_commitment_data_json().c(d!())?;
+        self.get_txn_proof(id).c(d!()).map(|(_, txn)| LightProof {
+            state_commitment_data,
+            txns: vec![txn],
+            ..Default::default()
+        })
+    }
+
+    /// Get the proofs of some UTXOs(spent or not) for light clients,
+    /// all of them share one partially serialized bitmap.
+    pub fn get_utxo_light_proof(&self, ids: &[TxoSID]) -> Result<LightProof> {
+        let state_commitment_data = self.get_state_commitment_data_json().c(d!())?;
+
+        let utxos = ids
+            .iter()
+            .map(|sid| {
+                let (txn_sid, position) = self
+                    .status
+                    .txo_to_txn_location
+                    .get(sid)
+                    .c(d!("utxo not found"))?;
+                let (tx, txn) = self.get_txn_proof(txn_sid).c(d!())?;
+                let record_pointer = tx
+                    .get_output_pointer(position.0)
+                    .c(d!("unsupported output"))?;
+                Ok(UtxoProof {
+                    utxo_sid: sid.0,
+                    record_pointer,
+                    txn,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let utxo_map = self
+            .utxo_map
+            .write()
+            .serialize_partial(ids.iter().map(|sid| sid.0 as usize).collect(), 0);
+
+        Ok(LightProof {
+            state_commitment_data,
+            utxo_map: Some(utxo_map),
+            txns: vec![],
+            utxos,
+        })
+    }
+
+    /// Get the proofs of all the UTXOs owned by `addr`,
+    /// so that wallets can verify its balance.
+    pub fn get_owned_utxo_light_proof(&self, addr: &XfrPublicKey) -> Result<LightProof> {
+        let mut sids = self.status.get_owned_utxos(addr);
+        sids.sort_unstable();
+        self.get_utxo_light_proof(&sids).c(d!())
+    }
+
+    // The exact JSON hashed to the state commitment.
+    fn get_state_commitment_data_json(&self) -> Result<String> {
+        self.status
+            .state_commitment_data
+            .as_ref()
+            .c(d!())
+            .and_then(|data| serde_json::to_string(data).c(d!()))
+    }
+
+    fn get_txn_proof(&self, id: TxnSID) -> Result<(FinalizedTransaction, TxnProof)> {
+        let tx = self.get_transaction_light(id).c(d!())?;
+        let proof = self.txn_merkle.read().get_proof(tx.merkle_id, 0).c(d!())?;
+
+        // The signature map of early transactions is not a part of
+        // the merkle leaf, see `update_state`.
+        let mut txn = tx.txn.clone();
+        let mut leaf = serde_json::to_string(&(tx.tx_id, &txn)).c(d!())?;
+        if !proof.is_valid_proof(sha256::hash(leaf.as_bytes()).into()) {
+            txn.pubkey_sign_map = Default::default();
+            leaf = serde_json::to_string(&(tx.tx_id, &txn)).c(d!())?;
+            if !proof.is_valid_proof(sha256::hash(leaf.as_bytes()).into()) {
+                return Err(eg!("can not rebuild the merkle leaf"));
+            }
+        }
+
+        let merkle_path = MerklePath {
+            leaf_index: proof.tx_id,
+            root: proof.root_hash.hash,
+            siblings: proof.hash_array.iter().map(|h| h.hash).collect(),
+        };
+
+        Ok((
+            tx,
+            TxnProof {
+                txn: leaf,
+                merkle_path,
+            },
+        ))
+    }
+
     #[inline(always)]
     #[allow(missing_docs)]
     pub fn get_staking(&self) -> &Staking {
@@ -965,6 +1225,81 @@ impl LedgerState {
     }
 }
 
+/// Outputs frozen by the issuers of freezable assets
+#[derive(Deserialize, Serialize, Clone, PartialEq, Debug, Default)]
+pub struct FrozenAssets {
+    txos: BTreeMap<TxoSID, AssetTypeCode>,
+    addrs: BTreeSet<(AssetTypeCode, XfrPublicKey)>,
+}
+
+impl FrozenAssets {
+    /// Whether `record`, spent as `sid`, is frozen.
+    pub fn is_frozen(&self, sid: Option<TxoSID>, record: &BlindAssetRecord) -> bool {
+        self.is_frozen_after(&[], sid, record)
+    }
+
+    /// Whether `record`, spent as `sid`, is frozen once the `pending`
+    /// freezes and unfreezes of the current block are applied in order.
+    pub fn is_frozen_after(
+        &self,
+        pending: &[FreezeAsset],
+        sid: Option<TxoSID>,
+        record: &BlindAssetRecord,
+    ) -> bool {
+        let code = match record.asset_type {
+            XfrAssetType::NonConfidential(val) => AssetTypeCode { val },
+            XfrAssetType::Confidential(_) => return false,
+        };
+        let last = |target: FreezeTarget| {
+            pending
+                .iter()
+                .rev()
+                .find(|f| f.body.code == code && f.body.target == target)
+                .map(|f| f.body.frozen)
+        };
+        let txo = sid.map_or(false, |sid| {
+            last(FreezeTarget::Txo(sid))
+                .unwrap_or_else(|| self.txos.get(&sid).map_or(false, |c| *c == code))
+        });
+        let addr = last(FreezeTarget::Address(record.public_key))
+            .unwrap_or_else(|| self.addrs.contains(&(code, record.public_key)));
+        txo || addr
+    }
+
+    fn set(&mut self, code: AssetTypeCode, target: FreezeTarget, frozen: bool) {
+        match (target, frozen) {
+            (FreezeTarget::Txo(sid), true) => {
+                self.txos.insert(sid, code);
+            }
+            (FreezeTarget::Txo(sid), false) => {
+                self.txos.remove(&sid);
+            }
+            (FreezeTarget::Address(pk), true) => {
+                self.addrs.insert((code, pk));
+            }
+            (FreezeTarget::Address(pk), false) => {
+                self.addrs.remove(&(code, pk));
+            }
+        }
+    }
+
+    fn get_txos(&self, code: &AssetTypeCode) -> Vec<TxoSID> {
+        self.txos
+            .iter()
+            .filter(|(_, c)| *c == code)
+            .map(|(sid, _)| *sid)
+            .collect()
+    }
+
+    fn get_addresses(&self, code: &AssetTypeCode) -> Vec<XfrPublicKey> {
+        self.addrs
+            .iter()
+            .filter(|(c, _)| c == code)
+            .map(|(_, pk)| *pk)
+            .collect()
+    }
+}
+
 /// The main LedgerStatus of findora ledger
 #[derive(Deserialize, Serialize, Clone, PartialEq, Debug)]
 pub struct LedgerStatus {
@@ -1003,6 +1338,12 @@ pub struct LedgerStatus {
     staking: Staking,
     // tendermint commit height
     td_commit_height: u64,
+    // Outputs frozen by the asset issuers
+    #[serde(default)]
+    frozen: FrozenAssets,
+    // time of the current tendermint block, in unix seconds
+    #[serde(default)]
+    block_time: u64,
 }
 
 impl LedgerStatus {
@@ -1109,6 +1450,8 @@ impl LedgerStatus {
             block_commit_count: 0,
             staking: Staking::new(),
             td_commit_height: 0,
+            frozen: FrozenAssets::default(),
+            block_time: 0,
         };
 
         Ok(ledger)
@@ -1129,33 +1472,106 @@ impl LedgerStatus {
     //
     //  ledger.check_txn_effects(txn_effect);
     //  block.add_txn_effect(txn_effect);
-    fn check_txn_effects(&self, txn_effect: &TxnEffect) -> Result<()> {
+    //
+    // `pending` are the freezes of the block being built, they take effect
+    // on top of the committed frozen set.
+    fn check_txn_effects(
+        &self,
+        txn_effect: &TxnEffect,
+        pending: &[FreezeAsset],
+    ) -> Result<()> {
         // The current transactions seq_id must be within the sliding window over seq_ids
         let (rand, seq_id) = (
             txn_effect.txn.body.no_replay_token.get_rand(),
             txn_effect.txn.body.no_replay_token.get_seq_id(),
         );
         if seq_id > self.block_commit_count {
-            return Err(eg!(("Transaction seq_id ahead of block_count")));
+            return Err(eg!(LedgerError::InvalidNonce
+                .with("Transaction seq_id ahead of block_count")));
         } else if seq_id + TRANSACTION_WINDOW_WIDTH < self.block_commit_count {
-            return Err(eg!(("Transaction seq_id too far behind block_count")));
+            return Err(eg!(LedgerError::InvalidNonce
+                .with("Transaction seq_id too far behind block_count")));
         } else {
             // Check to see that this nrpt has not been seen before
             if self.sliding_set.has_key_at(seq_id as usize, rand) {
-                return Err(eg!(format!(
+                return Err(eg!(LedgerError::ReplayedNonce.with(format!(
                     "No replay token ({rand:?}, {seq_id})seen before at  possible replay",
-                )));
+                ))));
             }
         }
 
+        let clawback_inputs = txn_effect
+            .clawbacks
+            .iter()
+            .map(|c| c.body.input)
+            .collect::<HashSet<_>>();
+
+        // Features and locks are checked against the block being built
+        let (height, time) = (self.staking.cur_height(), self.block_time);
+        if !is_active(Feature::TxoLock, height)
+            && txn_effect.txos.iter().flatten().any(|o| o.lock.is_some())
+        {
+            return Err(eg!(
+                LedgerError::NotAllowed.with("locked outputs are not active")
+            ));
+        }
+        if !is_active(Feature::AssetFreeze, height)
+            && (!txn_effect.freezes.is_empty()
+                || !txn_effect.clawbacks.is_empty()
+                || txn_effect.new_asset_codes.values().any(|a| {
+                    let rules = &a.properties.asset_rules;
+                    rules.freezable || rules.clawback
+                }))
+        {
+            return Err(eg!(
+                LedgerError::NotAllowed.with("asset freezing is not active")
+            ));
+        }
+        if !is_active(Feature::PenaltyRuleUpdate, height)
+            && !txn_effect.update_penalty_rules.is_empty()
+        {
+            return Err(eg!(
+                LedgerError::NotAllowed.with("updating penalty rules is not active")
+            ));
+        }
+        if !is_active(Feature::StakingProposal, height)
+            && (!txn_effect.proposals.is_empty() || !txn_effect.votes.is_empty())
+        {
+            return Err(eg!(
+                LedgerError::NotAllowed.with("staking proposals are not active")
+            ));
+        }
+        if !is_active(Feature::ValidatorJail, height) && !txn_effect.unjails.is_empty() {
+            return Err(eg!(LedgerError::NotAllowed.with("unjailing is not active")));
+        }
+        if !is_active(Feature::ScheduleUpgrade, height)
+            && !txn_effect.schedule_upgrades.is_empty()
+        {
+            return Err(eg!(
+                LedgerError::NotAllowed.with("scheduling upgrades is not active")
+            ));
+        }
+
         // 1. Each input must be unspent and correspond to the claimed record
         // 2. Inputs with transfer restrictions can only be owned by the asset issuer
+        // 3. Inputs must not be frozen, unless reclaimed by the issuer
+        // 4. Locked inputs must have been unlocked, unless reclaimed by the issuer
         for (inp_sid, inp_record) in txn_effect.input_txos.iter() {
             // (1)
-            let inp_utxo = self.utxos.get(inp_sid).c(d!("Input must be unspent"))?;
+            let inp_utxo = self
+                .utxos
+                .get(inp_sid)
+                .c(d!(LedgerError::InputSpent.with("Input must be unspent")))?;
             let record = &(inp_utxo.0);
+            // the lock is not a part of the claimed record
+            let lock = record.lock;
+            let record = &TxOutput {
+                version: Default::default(),
+                lock: None,
+                ..record.clone()
+            };
             if record != inp_record {
-                return Err(eg!((format!(
+                return Err(eg!(LedgerError::InputMismatch.with(format!(
                     "Input must correspond to claimed record: {} != {}",
                     serde_json::to_string(&record).c(d!())?,
                     serde_json::to_string(inp_record).unwrap()
@@ -1175,10 +1591,28 @@ impl LedgerStatus {
                     .c(d!())?;
                 if !asset_type.properties.asset_rules.transferable
                     && asset_type.properties.issuer.deref() != &record.record.public_key
+                    && !clawback_inputs.contains(inp_sid)
                 {
-                    return Err(eg!(
-                        ("Non-transferable asset type must be owned by asset issuer")
-                    ));
+                    return Err(eg!(LedgerError::AssetNotTransferable.with(
+                        "Non-transferable asset type must be owned by asset issuer"
+                    )));
+                }
+            }
+            // (3)
+            if !clawback_inputs.contains(inp_sid)
+                && self
+                    .frozen
+                    .is_frozen_after(pending, Some(*inp_sid), &record.record)
+            {
+                return Err(eg!(LedgerError::AssetFrozen));
+            }
+            // (4)
+            if let Some(lock) = lock {
+                if !clawback_inputs.contains(inp_sid) && !lock.is_unlocked(height, time)
+                {
+                    return Err(
+                        eg!(LedgerError::OutputLocked.with(format!("{lock:?}"))),
+                    );
                 }
             }
         }
@@ -1199,9 +1633,19 @@ impl LedgerStatus {
                 if !asset_type.properties.asset_rules.transferable
                     && asset_type.properties.issuer.deref() != &record.record.public_key
                 {
-                    return Err(eg!(
-                        ("Non-transferable asset type must be owned by asset issuer")
-                    ));
+                    return Err(eg!(LedgerError::AssetNotTransferable.with(
+                        "Non-transferable asset type must be owned by asset issuer"
+                    )));
+                }
+            }
+            if self.frozen.is_frozen_after(pending, None, &record.record) {
+                return Err(eg!(LedgerError::AssetFrozen));
+            }
+            if let Some(lock) = record.lock {
+                if !lock.is_unlocked(height, time) {
+                    return Err(
+                        eg!(LedgerError::OutputLocked.with(format!("{lock:?}"))),
+                    );
                 }
             }
         }
@@ -1209,13 +1653,14 @@ impl LedgerStatus {
         // New asset types must not already exist
         for (code, _asset_type) in txn_effect.new_asset_codes.iter() {
             if self.asset_types.contains_key(&code) {
-                return Err(eg!(format!("Asset type {:?} already defined", &code)));
+                return Err(eg!(LedgerError::AssetAlreadyDefined
+                    .with(format!("Asset type {:?} already defined", &code))));
             }
             if self.issuance_num.contains_key(&code) {
-                return Err(eg!(format!(
+                return Err(eg!(LedgerError::AssetAlreadyDefined.with(format!(
                     "Asset type {:?} is being defined after issue",
                     &code
-                )));
+                ))));
             }
 
             // Asset issuance should match the currently registered key
@@ -1238,9 +1683,8 @@ impl LedgerStatus {
                 .c(d!())?;
             let proper_key = asset_type.properties.issuer;
             if *iss_key != proper_key {
-                return Err(eg!(
-                    ("Issuance key is not the same as key of properties issuer")
-                ));
+                return Err(eg!(LedgerError::IssuerMismatch
+                    .with("Issuance key is not the same as key of properties issuer")));
             }
 
             if seq_nums.is_empty() {
@@ -1271,7 +1715,9 @@ impl LedgerStatus {
             if let Some(cap) = asset_type.properties.asset_rules.max_units {
                 let current_amount = self.issuance_amounts.get(code).unwrap_or(0);
                 if current_amount.checked_add(*amount).c(d!())? > cap {
-                    return Err(eg!(("Amount exceeds asset cap")));
+                    return Err(eg!(
+                        LedgerError::MaxUnitsExceeded.with("Amount exceeds asset cap")
+                    ));
                 }
             }
         }
@@ -1284,7 +1730,8 @@ impl LedgerStatus {
                 .or_else(|| txn_effect.new_asset_codes.get(&code).cloned())
                 .c(d!())?;
             if asset_type.has_issuance_restrictions() {
-                return Err(eg!(("This asset type has issuance restrictions")));
+                return Err(eg!(LedgerError::ConfidentialRestricted
+                    .with("This asset type has issuance restrictions")));
             }
         }
 
@@ -1296,7 +1743,63 @@ impl LedgerStatus {
             if !asset.properties.asset_rules.updatable
                 || asset.properties.issuer != (IssuerPublicKey { key: memo_update.1 })
             {
-                return Err(eg!(("Non updatable asset or issuer mismatch")));
+                return Err(eg!(LedgerError::MemoNotUpdatable
+                    .with("Non updatable asset or issuer mismatch")));
+            }
+        }
+
+        // Freezes
+        // (1) The asset must be freezable and the key must be the issuer
+        // (2) A single output must be an unspent output of the asset
+        for freeze in txn_effect.freezes.iter() {
+            let code = freeze.body.code;
+            let asset = self
+                .asset_types
+                .get(&code)
+                .or_else(|| txn_effect.new_asset_codes.get(&code).cloned())
+                .c(d!())?;
+            // (1)
+            if !asset.properties.asset_rules.freezable {
+                return Err(eg!(
+                    LedgerError::FreezeNotAllowed.with("asset type is not freezable")
+                ));
+            }
+            if asset.properties.issuer != freeze.pubkey {
+                return Err(eg!(LedgerError::IssuerMismatch
+                    .with("Freeze key is not the same as key of properties issuer")));
+            }
+            // (2)
+            if let FreezeTarget::Txo(sid) = freeze.body.target {
+                let utxo =
+                    self.utxos
+                        .get(&sid)
+                        .c(d!(LedgerError::InputSpent
+                            .with("Frozen output must be unspent")))?;
+                if utxo.0.record.asset_type != XfrAssetType::NonConfidential(code.val) {
+                    return Err(eg!(LedgerError::InputMismatch.with(
+                        "Frozen output must be a non-confidential output of the asset"
+                    )));
+                }
+            }
+        }
+
+        // Clawbacks
+        // The asset must have the clawback rule and the key must be the issuer,
+        // the inputs have been checked above
+        for clawback in txn_effect.clawbacks.iter() {
+            let code = clawback.body.code;
+            let asset = self
+                .asset_types
+                .get(&code)
+                .or_else(|| txn_effect.new_asset_codes.get(&code).cloned())
+                .c(d!())?;
+            if !asset.properties.asset_rules.clawback {
+                return Err(eg!(LedgerError::FreezeNotAllowed
+                    .with("asset type can not be reclaimed")));
+            }
+            if asset.properties.issuer != clawback.pubkey {
+                return Err(eg!(LedgerError::IssuerMismatch
+                    .with("Clawback key is not the same as key of properties issuer")));
             }
         }
 
@@ -1310,9 +1813,9 @@ impl LedgerStatus {
                 .or_else(|| txn_effect.new_asset_codes.get(&code).cloned())
                 .c(d!())?;
             if asset_type.has_transfer_restrictions() {
-                return Err(eg!(
-                    ("non-confidential assets with transfer restrictions can't become confidential")
-                ));
+                return Err(eg!(LedgerError::ConfidentialRestricted.with(
+                    "non-confidential assets with transfer restrictions can't become confidential"
+                )));
             }
         }
 
@@ -1328,7 +1831,7 @@ impl LedgerStatus {
     fn apply_block_effects(&mut self, block: &mut BlockEffect) -> (TmpSidMap, u64, u64) {
         let base_sid = self.next_txo.0;
         let handle_asset_type_code = |code: AssetTypeCode| -> AssetTypeCode {
-            if CFG.checkpoint.utxo_asset_prefix_height > self.td_commit_height
+            if !is_active(Feature::UtxoAssetPrefix, self.td_commit_height)
                 || code.val == ASSET_TYPE_FRA
             {
                 code
@@ -1350,6 +1853,12 @@ impl LedgerStatus {
         }
         block.no_replay_tokens.clear();
 
+        // Apply freezes, in order, before the consumed UTXOs are removed
+        for freeze in block.freezes.drain(..) {
+            self.frozen
+                .set(freeze.body.code, freeze.body.target, freeze.body.frozen);
+        }
+
         // Remove consumed UTXOs
         for (inp_sid, utxo) in block.input_txos.drain() {
             if let Some(mut v) = self.owned_utxos.get_mut(&utxo.record.public_key) {
@@ -1365,6 +1874,7 @@ impl LedgerStatus {
                 }
                 self.spent_utxos.insert(inp_sid, v);
             }
+            self.frozen.txos.remove(&inp_sid);
         }
 
         // Apply memo updates
@@ -0,0 +1,115 @@
+//!
+//! # Transaction Simulation
+//!
+//! Run a transaction through all the checks of the ledger and the staking
+//! simulator, without touching any committed state, so that clients can know
+//! whether it will pass before submitting it.
+//!
+
+use {
+    super::LedgerState,
+    crate::{
+        data_model::{
+            check_staking_op, staking_ops, BlockEffect, Operation, Transaction,
+            TxOutput, TxnEffect,
+        },
+        error::ErrorDetail,
+        staking::Staking,
+    },
+    ruc::*,
+    serde::{Deserialize, Serialize},
+};
+
+/// The result of a simulated transaction.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct TxnSimulation {
+    /// `true` if the transaction will be accepted by the current state.
+    pub valid: bool,
+    /// The first error of the transaction as a whole.
+    pub error: Option<ErrorDetail>,
+    /// The errors of the staking operations, in the order of execution.
+    pub op_errors: Vec<OpError>,
+    /// The new outputs, internally spent ones excluded.
+    pub outputs: Vec<TxOutput>,
+    /// The FRA fee paid by the transaction.
+    pub fee: u64,
+}
+
+/// An error of a single operation.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct OpError {
+    /// The index of the operation in the transaction body.
+    pub index: usize,
+    #[allow(missing_docs)]
+    pub error: ErrorDetail,
+}
+
+impl LedgerState {
+    /// Check `tx` against the committed state and `block`,
+    /// which should be the block in progress, if any.
+    ///
+    /// Nothing is cloned except the staking simulator,
+    /// and only if `tx` contains staking operations.
+    pub fn simulate_transaction(
+        &self,
+        block: Option<&BlockEffect>,
+        tx: Transaction,
+    ) -> TxnSimulation {
+        let mut res = TxnSimulation {
+            fee: tx.fee_amount(),
+            ..Default::default()
+        };
+
+        let ops = staking_ops(&tx);
+        if !ops.is_empty() {
+            let mut staking = block
+                .map(|b| &b.staking_simulator)
+                .unwrap_or_else(|| self.get_staking())
+                .clone();
+            res.op_errors = check_staking_ops(&mut staking, &tx, ops);
+        }
+
+        let checked = tx
+            .check_in_abci()
+            .c(d!())
+            .and_then(|_| {
+                TxnEffect::compute_effect(tx).c(d!("Failed to compute txn effect"))
+            })
+            .and_then(|txe| {
+                res.outputs = txe.txos.iter().flatten().cloned().collect();
+                let pending = block.map_or(&[][..], |b| &b.freezes[..]);
+                self.status
+                    .check_txn_effects(&txe, pending)
+                    .c(d!())
+                    .and_then(|_| {
+                        block
+                            .map(|b| b.check_txn_conflicts(&txe).c(d!()))
+                            .unwrap_or(Ok(()))
+                    })
+            });
+
+        if let Err(e) = checked {
+            res.error = Some(ErrorDetail::new(e.to_string()));
+        }
+        res.valid = res.error.is_none() && res.op_errors.is_empty();
+
+        res
+    }
+}
+
+// Run the staking operations one by one, in the same order as
+// `BlockEffect::check_staking`, and collect all the errors instead of the first.
+fn check_staking_ops(
+    staking: &mut Staking,
+    tx: &Transaction,
+    ops: Vec<(usize, &Operation)>,
+) -> Vec<OpError> {
+    ops.into_iter()
+        .filter_map(|(index, op)| {
+            check_staking_op(staking, op, tx).err().map(|e| OpError {
+                index,
+                error: ErrorDetail::new(e.to_string()),
+            })
+        })
+        .collect()
+}
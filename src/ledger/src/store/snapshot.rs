@@ -0,0 +1,363 @@
+//!
+//! # Ledger Snapshot
+//!
+//! Export all the data of a node at its last committed height into a
+//! checksummed archive, and import it on another node to skip the replay
+//! from genesis. The archive includes everything under the ledger dir:
+//! - `LedgerStatus`, including the staking state
+//! - the UTXO bitmap and the merkle trees of blocks and transactions
+//! - the fbnc data, and the EVM chain state of `BaseApp`
+//!
+//! The ledger part is verified against the state commitment on import,
+//! the EVM part is checked by tendermint through the app hash at startup.
+//!
+//! The state commitment only covers the UTXO bitmap, the merkle trees,
+//! the TXO count and, on old heights, the staking state. The other data,
+//! such as the UTXO set, the balances, the asset types and the API cache,
+//! can not be verified, so the archive itself must come from a trusted source,
+//! a trusted commitment alone does not make a forged archive safe.
+//!
+//! **NOTE**: both sides must be done while the node is stopped.
+//!
+
+use {
+    super::{flush_data, LedgerState},
+    crate::{
+        data_model::{StateCommitmentData, TxoSID},
+        staking::Staking,
+    },
+    bitmap::BitMap,
+    globutils::HashOf,
+    merkle_tree::AppendOnlyMerkle,
+    ruc::*,
+    serde::{Deserialize, Serialize},
+    sha2::{Digest, Sha256},
+    std::{
+        fs::{self, File, OpenOptions},
+        io::{self, Read},
+        path::{Path, PathBuf},
+    },
+    tar::{Archive, Builder, Header},
+};
+
+/// Bump it when the layout of the archive changes.
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+const MANIFEST: &str = "MANIFEST";
+const DATA_DIR: &str = "data";
+
+const LEDGER_STATUS: &str = "ledger_status";
+const UTXO_MAP: &str = "utxo_map";
+const TXN_MERKLE: &str = "txn_merkle";
+const BLOCK_MERKLE: &str = "block_merkle";
+
+/// Description of an archive, stored as its first entry.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotManifest {
+    #[allow(missing_docs)]
+    pub version: u32,
+    /// The tendermint height of the data.
+    pub height: u64,
+    /// The state commitment at `height`.
+    pub state_commitment: HashOf<Option<StateCommitmentData>>,
+    /// All the files, relative to the ledger dir.
+    pub files: Vec<SnapshotFile>,
+    /// sha256 of all the fields above, in hex.
+    pub checksum: String,
+}
+
+/// A file in the archive.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotFile {
+    #[allow(missing_docs)]
+    pub path: String,
+    #[allow(missing_docs)]
+    pub size: u64,
+    /// sha256 of the content, in hex.
+    pub sha256: String,
+}
+
+impl SnapshotManifest {
+    fn new(
+        height: u64,
+        state_commitment: HashOf<Option<StateCommitmentData>>,
+        files: Vec<SnapshotFile>,
+    ) -> Result<Self> {
+        let mut m = SnapshotManifest {
+            version: SNAPSHOT_VERSION,
+            height,
+            state_commitment,
+            files,
+            checksum: String::new(),
+        };
+        m.checksum = m.compute_checksum().c(d!())?;
+        Ok(m)
+    }
+
+    fn compute_checksum(&self) -> Result<String> {
+        serde_json::to_vec(&(
+            self.version,
+            self.height,
+            &self.state_commitment,
+            &self.files,
+        ))
+        .c(d!())
+        .map(|bytes| hex::encode(Sha256::digest(&bytes)))
+    }
+
+    fn check(&self) -> Result<()> {
+        if SNAPSHOT_VERSION != self.version {
+            return Err(eg!(format!("unsupported version: {}", self.version)));
+        }
+        if self.compute_checksum().c(d!())? != self.checksum {
+            return Err(eg!("invalid manifest checksum"));
+        }
+        Ok(())
+    }
+}
+
+impl LedgerState {
+    /// Export all the data under `basedir` into a new archive at `archive`,
+    /// `basedir` must be the dir where this ledger is loaded from.
+    pub fn export_snapshot(
+        &self,
+        basedir: &Path,
+        archive: &Path,
+    ) -> Result<SnapshotManifest> {
+        flush_data();
+        let (state_commitment, _) = self.get_state_commitment();
+        export(
+            basedir,
+            archive,
+            self.get_tendermint_height(),
+            state_commitment,
+        )
+        .c(d!())
+    }
+}
+
+/// Pack all the files under `basedir` into a new archive.
+pub fn export(
+    basedir: &Path,
+    archive: &Path,
+    height: u64,
+    state_commitment: HashOf<Option<StateCommitmentData>>,
+) -> Result<SnapshotManifest> {
+    let mut paths = vec![];
+    list_files(basedir, &mut paths).c(d!())?;
+
+    let files = paths
+        .iter()
+        .map(|p| {
+            let path = p
+                .strip_prefix(basedir)
+                .c(d!())?
+                .to_str()
+                .c(d!("non-utf8 path"))?
+                .to_owned();
+            let (size, sha256) = hash_file(p).c(d!())?;
+            Ok(SnapshotFile { path, size, sha256 })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let manifest = SnapshotManifest::new(height, state_commitment, files).c(d!())?;
+
+    let f = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(archive)
+        .c(d!())?;
+    let mut builder = Builder::new(f);
+
+    let bytes = serde_json::to_vec_pretty(&manifest).c(d!())?;
+    let mut header = Header::new_gnu();
+    header.set_size(bytes.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    builder
+        .append_data(&mut header, MANIFEST, bytes.as_slice())
+        .c(d!())?;
+
+    for (p, file) in paths.iter().zip(manifest.files.iter()) {
+        builder
+            .append_path_with_name(p, Path::new(DATA_DIR).join(&file.path))
+            .c(d!())?;
+    }
+    builder.into_inner().c(d!())?.sync_all().c(d!())?;
+
+    Ok(manifest)
+}
+
+/// Unpack an archive into `basedir`, which must not contain any data,
+/// `trusted` is the state commitment at the same height from a trusted source.
+///
+/// Only the committed part of the ledger is verified(see the module doc),
+/// the archive must also come from a trusted source.
+///
+/// Nothing is left in `basedir` if any of the checks fails.
+pub fn import(
+    archive: &Path,
+    basedir: &Path,
+    trusted: Option<&HashOf<Option<StateCommitmentData>>>,
+) -> Result<SnapshotManifest> {
+    if fs::read_dir(basedir).is_ok_and(|mut d| d.next().is_some()) {
+        return Err(eg!(format!("{} is not empty", basedir.display())));
+    }
+
+    let staging = basedir.with_extension("importing");
+    let _ = fs::remove_dir_all(&staging);
+    fs::create_dir_all(&staging).c(d!())?;
+
+    let res = unpack(archive, &staging).c(d!()).and_then(|m| {
+        if let Some(t) = trusted {
+            if *t != m.state_commitment {
+                return Err(eg!("state commitment mismatch"));
+            }
+        }
+        verify_ledger(&staging.join(DATA_DIR), &m).c(d!())?;
+        Ok(m)
+    });
+
+    match res {
+        Ok(m) => {
+            let _ = fs::remove_dir(basedir);
+            fs::rename(staging.join(DATA_DIR), basedir).c(d!())?;
+            let _ = fs::remove_dir_all(&staging);
+            Ok(m)
+        }
+        Err(e) => {
+            let _ = fs::remove_dir_all(&staging);
+            Err(e)
+        }
+    }
+}
+
+/// Unpack an archive into `dir` and check all the files in it.
+pub fn unpack(archive: &Path, dir: &Path) -> Result<SnapshotManifest> {
+    let mut archive = Archive::new(File::open(archive).c(d!())?);
+    let mut entries = archive.entries().c(d!())?;
+
+    let manifest = {
+        let mut entry = entries.next().c(d!("empty archive"))?.c(d!())?;
+        if Path::new(MANIFEST) != entry.path().c(d!())? {
+            return Err(eg!("manifest not found"));
+        }
+        let mut bytes = vec![];
+        entry.read_to_end(&mut bytes).c(d!())?;
+        serde_json::from_slice::<SnapshotManifest>(&bytes).c(d!())?
+    };
+    manifest.check().c(d!())?;
+
+    for entry in entries {
+        let mut entry = entry.c(d!())?;
+        if !entry.path().c(d!())?.starts_with(DATA_DIR) {
+            return Err(eg!("unexpected entry"));
+        }
+        // `false` if the path tries to escape from `dir`
+        if !entry.unpack_in(dir).c(d!())? {
+            return Err(eg!("invalid entry path"));
+        }
+    }
+
+    let data_dir = dir.join(DATA_DIR);
+    let mut paths = vec![];
+    list_files(&data_dir, &mut paths).c(d!())?;
+    if paths.len() != manifest.files.len() {
+        return Err(eg!("the files are not same as the manifest"));
+    }
+
+    for file in manifest.files.iter() {
+        let (size, sha256) =
+            hash_file(&data_dir.join(&file.path)).c(d!(file.path.clone()))?;
+        if size != file.size || sha256 != file.sha256 {
+            return Err(eg!(format!("invalid file: {}", file.path)));
+        }
+    }
+
+    Ok(manifest)
+}
+
+// Check the ledger files against the state commitment in the manifest.
+fn verify_ledger(dir: &Path, manifest: &SnapshotManifest) -> Result<()> {
+    let status = fs::read(dir.join(LEDGER_STATUS))
+        .c(d!())
+        .and_then(|s| serde_json::from_slice::<serde_json::Value>(&s).c(d!()))?;
+
+    if Some(manifest.height) != status["td_commit_height"].as_u64() {
+        return Err(eg!("height mismatch"));
+    }
+
+    let scd = serde_json::from_value::<Option<StateCommitmentData>>(
+        status["state_commitment_data"].clone(),
+    )
+    .c(d!())?
+    .c(d!("no state commitment"))?;
+    if scd.compute_commitment() != manifest.state_commitment {
+        return Err(eg!("state commitment mismatch"));
+    }
+
+    let next_txo =
+        serde_json::from_value::<TxoSID>(status["next_txo"].clone()).c(d!())?;
+    if next_txo.0 != scd.txo_count {
+        return Err(eg!("txo count mismatch"));
+    }
+
+    // only committed before `RemoveFakeStakingHash`
+    if let Some(h) = scd.staking.as_ref() {
+        let staking =
+            serde_json::from_value::<Staking>(status["staking"].clone()).c(d!())?;
+        if HashOf::new(&staking) != *h {
+            return Err(eg!("staking mismatch"));
+        }
+    }
+
+    let path = |name: &str| dir.join(name).to_string_lossy().into_owned();
+
+    let utxo_map = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(path(UTXO_MAP))
+        .c(d!())?;
+    if BitMap::open(utxo_map).c(d!())?.compute_checksum() != scd.bitmap {
+        return Err(eg!("utxo map mismatch"));
+    }
+
+    let txn_merkle = AppendOnlyMerkle::open(&path(TXN_MERKLE)).c(d!())?;
+    if txn_merkle.get_root_hash() != scd.transaction_merkle_commitment {
+        return Err(eg!("transaction merkle mismatch"));
+    }
+
+    let block_merkle = AppendOnlyMerkle::open(&path(BLOCK_MERKLE)).c(d!())?;
+    if block_merkle.get_root_hash() != scd.block_merkle {
+        return Err(eg!("block merkle mismatch"));
+    }
+
+    Ok(())
+}
+
+// All the regular files under `dir`, in a stable order.
+fn list_files(dir: &Path, res: &mut Vec<PathBuf>) -> Result<()> {
+    let mut entries = fs::read_dir(dir)
+        .c(d!())?
+        .map(|e| e.map(|e| e.path()).c(d!()))
+        .collect::<Result<Vec<_>>>()?;
+    entries.sort();
+
+    for p in entries {
+        let ft = fs::symlink_metadata(&p).c(d!())?.file_type();
+        if ft.is_dir() {
+            list_files(&p, res).c(d!())?;
+        } else if ft.is_file() {
+            res.push(p);
+        }
+    }
+
+    Ok(())
+}
+
+/// The size and the sha256 (in hex) of a file.
+pub fn hash_file(path: &Path) -> Result<(u64, String)> {
+    let mut hasher = Sha256::new();
+    let size = io::copy(&mut File::open(path).c(d!())?, &mut hasher).c(d!())?;
+    Ok((size, hex::encode(hasher.finalize())))
+}
@@ -2,11 +2,19 @@
 #![allow(missing_docs)]
 
 use {
-    super::{helpers::*, *},
-    crate::data_model::{
-        AssetRules, AssetTypeCode, IssueAsset, IssueAssetBody, Memo, Operation,
-        Transaction, TransferAsset, TransferAssetBody, TxOutput, TxnEffect, TxoRef,
-        TxoSID, ASSET_TYPE_FRA, BLACK_HOLE_PUBKEY, TX_FEE_MIN,
+    super::{
+        api_cache::{TxnDirection, TxnHistFilter, TxnHistPage, TxnSummary},
+        helpers::*,
+        *,
+    },
+    crate::{
+        data_model::{
+            AssetRules, AssetTypeCode, ClawbackAsset, ClawbackAssetBody, FreezeAsset,
+            FreezeAssetBody, FreezeTarget, IssueAsset, IssueAssetBody, Memo, Operation,
+            Transaction, TransferAsset, TransferAssetBody, TxOutput, TxnEffect, TxoRef,
+            TxoSID, ASSET_TYPE_FRA, BLACK_HOLE_PUBKEY, TX_FEE_MIN,
+        },
+        error::LedgerError,
     },
     rand_core::SeedableRng,
     zei::{
@@ -16,7 +24,7 @@ use {
                 build_blind_asset_record, open_blind_asset_record, AssetRecordType,
             },
             sig::XfrKeyPair,
-            structs::{AssetRecord, AssetRecordTemplate},
+            structs::{AssetRecord, AssetRecordTemplate, XfrAmount},
         },
     },
 };
@@ -92,7 +100,7 @@ fn test_asset_creation_valid() {
         None,
         None,
     );
-    if CFG.checkpoint.utxo_asset_prefix_height > state.get_tendermint_height() {
+    if !is_active(Feature::UtxoAssetPrefix, state.get_tendermint_height()) {
         token_code = code;
     }
     let asset_create = asset_creation_operation(&asset_body, &keypair);
@@ -158,7 +166,7 @@ fn test_asset_transfer() {
         ledger.get_block_commit_count(),
     )
     .unwrap();
-    if CFG.checkpoint.utxo_asset_prefix_height > ledger.get_tendermint_height() {
+    if !is_active(Feature::UtxoAssetPrefix, ledger.get_tendermint_height()) {
         new_code = code;
     }
     let effect = TxnEffect::compute_effect(tx).unwrap();
@@ -193,6 +201,8 @@ fn test_asset_transfer() {
                     id: None,
                     record: ba,
                     lien: None,
+                    version: Default::default(),
+                    lock: None,
                 },
                 None,
             ),
@@ -201,6 +211,8 @@ fn test_asset_transfer() {
                     id: None,
                     record: second_ba,
                     lien: None,
+                    version: Default::default(),
+                    lock: None,
                 },
                 None,
             ),
@@ -296,6 +308,31 @@ fn test_asset_transfer() {
     assert!(!input_bar_proof.is_valid(state_commitment));
     assert!(utxo_status.status == UtxoStatus::Spent);
 
+    // Light clients can verify the same results with a trusted commitment
+    let state_commitment = ledger.get_state_commitment().0 .0.hash.0;
+    let verified = ledger
+        .get_utxo_light_proof(&[txo_sid, second_txo_id])
+        .unwrap()
+        .verify(&state_commitment)
+        .unwrap();
+    assert!(verified.utxos[0].spent);
+    assert!(!verified.utxos[1].spent);
+    assert_eq!(
+        serde_json::to_value(&input_bar).unwrap(),
+        verified.utxos[0].record
+    );
+    let verified = ledger
+        .get_txn_light_proof(TxnSID(1))
+        .unwrap()
+        .verify(&state_commitment)
+        .unwrap();
+    assert_eq!(1, verified.txns[0].txn_sid);
+    assert!(ledger
+        .get_txn_light_proof(TxnSID(1))
+        .unwrap()
+        .verify(&[0; 32])
+        .is_err());
+
     // Adversary will attempt to spend the same blind asset record at another index
     second_transfer.body.inputs = vec![TxoRef::Absolute(second_txo_id)];
 
@@ -355,7 +392,7 @@ fn asset_issued() {
     )
     .unwrap();
 
-    if CFG.checkpoint.utxo_asset_prefix_height > ledger.get_tendermint_height() {
+    if !is_active(Feature::UtxoAssetPrefix, ledger.get_tendermint_height()) {
         new_token_code = token_code;
     }
 
@@ -384,6 +421,8 @@ fn asset_issued() {
                 id: None,
                 record: ba,
                 lien: None,
+                version: Default::default(),
+                lock: None,
             },
             None,
         )],
@@ -492,7 +531,7 @@ pub fn test_transferable() {
         seq_id,
     )
     .unwrap();
-    if CFG.checkpoint.utxo_asset_prefix_height > ledger.get_tendermint_height() {
+    if !is_active(Feature::UtxoAssetPrefix, ledger.get_tendermint_height()) {
         new_code = code;
     }
     apply_transaction(&mut ledger, tx);
@@ -645,7 +684,7 @@ pub fn test_max_units() {
         seq_id,
     )
     .unwrap();
-    if CFG.checkpoint.utxo_asset_prefix_height > ledger.get_tendermint_height() {
+    if !is_active(Feature::UtxoAssetPrefix, ledger.get_tendermint_height()) {
         new_code = code;
     }
     apply_transaction(&mut ledger, tx);
@@ -799,3 +838,531 @@ fn test_check_fee_with_ledger() {
     let mut block = ledger.start_block().unwrap();
     assert!(ledger.apply_transaction(&mut block, effect).is_err());
 }
+
+#[test]
+fn test_simulate_transaction() {
+    let mut ledger = LedgerState::tmp_ledger();
+    let fra_owner_kp = XfrKeyPair::generate(&mut ChaChaRng::from_entropy());
+
+    let tx = utils::fra_gen_initial_tx(&fra_owner_kp);
+    let effect = TxnEffect::compute_effect(tx).unwrap();
+    let mut block = ledger.start_block().unwrap();
+    let tmp_sid = ledger.apply_transaction(&mut block, effect).unwrap();
+    let txo_sid = ledger
+        .finish_block(block)
+        .unwrap()
+        .remove(&tmp_sid)
+        .unwrap()
+        .1[0];
+
+    let tx2 = Transaction::from_operation(
+        gen_fee_operation(&mut ledger, txo_sid, &fra_owner_kp),
+        1,
+    );
+    let res = ledger.simulate_transaction(None, tx2.clone());
+    assert!(res.valid, "{:?}", res.error);
+    assert!(res.op_errors.is_empty());
+    assert_eq!(TX_FEE_MIN, res.fee);
+    assert_eq!(2, res.outputs.len());
+
+    // nothing is changed by the simulation
+    assert!(ledger.get_utxo_light(txo_sid).is_some());
+
+    let effect = TxnEffect::compute_effect(tx2.clone()).unwrap();
+    let mut block = ledger.start_block().unwrap();
+    ledger.apply_transaction(&mut block, effect).unwrap();
+
+    // the input is spent by the block in progress
+    let tx3 = Transaction::from_operation(
+        gen_fee_operation(&mut ledger, txo_sid, &fra_owner_kp),
+        2,
+    );
+    assert!(ledger.simulate_transaction(None, tx3.clone()).valid);
+    let res = ledger.simulate_transaction(Some(&block), tx3);
+    assert!(!res.valid);
+    assert!(res.error.is_some());
+
+    ledger.finish_block(block).unwrap();
+
+    // the input has been spent
+    let res = ledger.simulate_transaction(None, tx2);
+    assert!(!res.valid);
+    assert!(res.error.is_some());
+}
+
+#[test]
+fn test_api_cache_hist_at_height() {
+    let mut api_cache = ApiCache::new("test_api_cache_hist_at_height/");
+    let kp = XfrKeyPair::generate(&mut ChaChaRng::from_entropy());
+    let addr = XfrAddress {
+        key: *kp.get_pk_ref(),
+    };
+
+    api_cache.cache_balance(addr, 10, 100);
+    api_cache.cache_balance(addr, 11, 100);
+    api_cache.cache_balance(addr, 20, 50);
+
+    assert!(api_cache.get_balance_at_height(&addr, 9).is_none());
+    assert_eq!(Some(100), api_cache.get_balance_at_height(&addr, 10));
+    assert_eq!(Some(100), api_cache.get_balance_at_height(&addr, 19));
+    assert_eq!(Some(50), api_cache.get_balance_at_height(&addr, 20));
+    assert_eq!(Some(50), api_cache.get_balance_at_height(&addr, 1000));
+
+    // unchanged balances are not stored
+    assert_eq!(2, api_cache.balance_hist.get(&addr).unwrap().len());
+}
+
+#[test]
+fn test_api_cache_staking_snapshot_diffs() {
+    use crate::staking::{
+        td_addr_to_string, Validator, ValidatorData, ValidatorKind,
+        COSIG_THRESHOLD_DEFAULT, FRA,
+    };
+
+    let mut api_cache = ApiCache::new("test_api_cache_staking_snapshot_diffs/");
+    let kp = XfrKeyPair::generate(&mut ChaChaRng::from_entropy());
+    let pk = kp.get_pk();
+    let v = pnk!(Validator::new(
+        vec![1; 32],
+        0,
+        pk,
+        [1, 5],
+        Default::default(),
+        ValidatorKind::Initiator,
+    ));
+    let td_addr = td_addr_to_string(&v.td_addr);
+
+    let mut s = Staking::new();
+    pnk!(s.validator_set_at_height(
+        1,
+        pnk!(ValidatorData::new(1, vec![v], COSIG_THRESHOLD_DEFAULT))
+    ));
+    s.set_custom_block_height(1);
+    pnk!(s.delegate(pk, &td_addr, 100 * FRA));
+
+    api_cache.cache_staking_snapshot(&s, 1);
+    api_cache.cache_staking_snapshot(&s, 2);
+
+    // the growing rewards alone don't touch a delegator
+    pnk!(s.delegation_get_mut(&pk)).rwd_amount += 2 * FRA;
+    api_cache.cache_staking_snapshot(&s, 3);
+    assert_eq!(1, api_cache.staking_delegation_hist.get(&pk).unwrap().len());
+
+    pnk!(s.delegate(pk, &td_addr, 10 * FRA));
+    api_cache.cache_staking_snapshot(&s, 4);
+    assert_eq!(2, api_cache.staking_delegation_hist.get(&pk).unwrap().len());
+
+    // a claim does
+    pnk!(s.claim(pk, Some(FRA)));
+    api_cache.cache_staking_snapshot(&s, 5);
+    assert_eq!(3, api_cache.staking_delegation_hist.get(&pk).unwrap().len());
+
+    let principal = |h| {
+        api_cache
+            .get_delegation_at_height(&pk, h)
+            .map(|d| d.delegations.values().sum::<u64>())
+    };
+    assert!(principal(0).is_none());
+    assert_eq!(Some(100 * FRA), principal(3));
+    assert_eq!(Some(110 * FRA), principal(4));
+
+    // the rewards at the height the snapshot was taken
+    let rewards = |h| {
+        api_cache
+            .get_delegation_at_height(&pk, h)
+            .map(|d| (d.rwd_amount, d.rwd_height))
+    };
+    assert_eq!(Some((0, 1)), rewards(3));
+    assert_eq!(Some((2 * FRA, 4)), rewards(4));
+    assert_eq!(Some((FRA, 5)), rewards(5));
+}
+
+#[test]
+fn test_api_cache_txn_hist_page() {
+    let mut api_cache = ApiCache::new("test_api_cache_txn_hist_page/");
+    let kp = XfrKeyPair::generate(&mut ChaChaRng::from_entropy());
+    let addr = XfrAddress {
+        key: *kp.get_pk_ref(),
+    };
+
+    // sid `n` is committed at height `n / 2`, every third one is sent by `addr`
+    let mut related = new_mapxnk!("test_api_cache_txn_hist_page/related");
+    for n in 0..10 {
+        related.insert(TxnSID(n), true);
+        let mut summary = TxnSummary {
+            height: n as u64 / 2,
+            ..Default::default()
+        };
+        alt!(
+            0 == n % 3,
+            summary.senders.insert(addr),
+            summary.receivers.insert(addr)
+        );
+        api_cache.txn_summaries.insert(TxnSID(n), summary);
+    }
+    api_cache.related_transactions.insert(addr, related);
+
+    let sids =
+        |page: &TxnHistPage| page.txns.iter().map(|t| t.txn_sid.0).collect::<Vec<_>>();
+
+    let filter = TxnHistFilter::default();
+    let page = api_cache.get_related_txns_page(&addr, &filter, None, 4, true);
+    assert_eq!(vec![9, 8, 7, 6], sids(&page));
+    let page =
+        api_cache.get_related_txns_page(&addr, &filter, page.next_cursor, 4, true);
+    assert_eq!(vec![5, 4, 3, 2], sids(&page));
+    let page =
+        api_cache.get_related_txns_page(&addr, &filter, page.next_cursor, 4, true);
+    assert_eq!(vec![1, 0], sids(&page));
+    assert!(page.next_cursor.is_none());
+
+    let filter = TxnHistFilter {
+        direction: Some(TxnDirection::Out),
+        from_height: Some(1),
+        to_height: Some(3),
+        ..Default::default()
+    };
+    let page = api_cache.get_related_txns_page(&addr, &filter, None, 10, false);
+    assert_eq!(vec![3, 6], sids(&page));
+    assert!(page.next_cursor.is_none());
+}
+
+#[test]
+fn test_ledger_events() {
+    let evs = events::subscribe();
+
+    let mut ledger = LedgerState::tmp_ledger();
+    let fra_owner_kp = XfrKeyPair::generate(&mut ChaChaRng::from_entropy());
+
+    let tx = utils::fra_gen_initial_tx(&fra_owner_kp);
+    let effect = TxnEffect::compute_effect(tx).unwrap();
+    let mut block = ledger.start_block().unwrap();
+    let tmp_sid = ledger.apply_transaction(&mut block, effect).unwrap();
+    let (txn_sid, txo_sids) = ledger
+        .finish_block(block)
+        .unwrap()
+        .remove(&tmp_sid)
+        .unwrap();
+
+    // nothing is sent before the block is committed
+    assert!(evs.try_iter().all(|ev| !matches!(
+        ev,
+        LedgerEvent::Block { block, .. }
+            if block.txns.iter().any(|tx| tx.tx_id == txn_sid)
+    )));
+    events::flush();
+
+    // other tests may commit blocks at the same time
+    let evs = evs.try_iter().collect::<Vec<_>>();
+    assert!(evs.iter().any(|ev| matches!(
+        ev,
+        LedgerEvent::Block { block, .. }
+            if block.txns.iter().any(|tx| tx.tx_id == txn_sid)
+    )));
+    assert!(evs.iter().any(|ev| matches!(
+        ev,
+        LedgerEvent::UtxoCreated { address, txo_sid, .. }
+            if address.key == fra_owner_kp.get_pk() && *txo_sid == txo_sids[0]
+    )));
+}
+
+#[test]
+fn test_ledger_snapshot() {
+    let src = globutils::fresh_tmp_dir();
+    fs::create_dir_all(src.join("__bnc__/sub")).unwrap();
+    fs::write(src.join("block_merkle"), b"merkle").unwrap();
+    fs::write(src.join("__bnc__/sub/data"), b"snapshot data").unwrap();
+
+    let commitment = HashOf::new(&None);
+    let archive = globutils::fresh_tmp_dir().join("snapshot.tar");
+    let manifest = snapshot::export(&src, &archive, 10, commitment.clone()).unwrap();
+    assert_eq!(10, manifest.height);
+    assert_eq!(
+        vec!["__bnc__/sub/data", "block_merkle"],
+        manifest
+            .files
+            .iter()
+            .map(|f| f.path.as_str())
+            .collect::<Vec<_>>()
+    );
+
+    // never overwrite an existing archive
+    assert!(snapshot::export(&src, &archive, 10, commitment.clone()).is_err());
+
+    let dir = globutils::fresh_tmp_dir();
+    assert_eq!(manifest, snapshot::unpack(&archive, &dir).unwrap());
+    assert_eq!(
+        b"snapshot data".to_vec(),
+        fs::read(dir.join("data/__bnc__/sub/data")).unwrap()
+    );
+
+    // not a ledger, nothing should be left after the failed import
+    let dst = globutils::fresh_tmp_dir().join("ledger");
+    assert!(snapshot::import(&archive, &dst, Some(&commitment)).is_err());
+    assert!(!dst.exists());
+
+    let mut bytes = fs::read(&archive).unwrap();
+    let idx = bytes
+        .windows(13)
+        .position(|w| w == b"snapshot data")
+        .unwrap();
+    bytes[idx] ^= 1;
+    fs::write(&archive, bytes).unwrap();
+    assert!(snapshot::unpack(&archive, &globutils::fresh_tmp_dir()).is_err());
+}
+
+#[test]
+fn test_asset_freeze_and_clawback() {
+    let mut ledger = LedgerState::tmp_ledger();
+    let params = PublicParams::default();
+    let mut prng = ChaChaRng::from_entropy();
+    let issuer = XfrKeyPair::generate(&mut prng);
+    let holder = XfrKeyPair::generate(&mut prng);
+
+    let code = AssetTypeCode::gen_random();
+    let mut rules = AssetRules::default();
+    rules.set_freezable(true).set_clawback(true);
+    let (tx, mut new_code) = create_definition_transaction(
+        &code,
+        &issuer,
+        rules,
+        None,
+        ledger.get_block_commit_count(),
+    )
+    .unwrap();
+    if !is_active(Feature::UtxoAssetPrefix, ledger.get_tendermint_height()) {
+        new_code = code;
+    }
+    if !is_active(Feature::AssetFreeze, ledger.get_staking().cur_height()) {
+        let effect = TxnEffect::compute_effect(tx).unwrap();
+        let err = ledger.status.check_txn_effects(&effect, &[]).unwrap_err();
+        assert_eq!(LedgerError::NotAllowed, LedgerError::of(&err.to_string()));
+        return;
+    }
+    apply_transaction(&mut ledger, tx);
+
+    let (tx, _) = create_issue_and_transfer_txn(
+        &mut ledger,
+        &params,
+        &new_code,
+        100,
+        &issuer,
+        &holder.get_pk(),
+        0,
+    );
+    let sid = apply_transaction(&mut ledger, tx).1[0];
+
+    let freeze_tx = |ledger: &LedgerState, kp: &XfrKeyPair, frozen: bool| {
+        let mut tx = Transaction::from_seq_id(ledger.get_block_commit_count());
+        let body = FreezeAssetBody {
+            code: new_code,
+            target: FreezeTarget::Txo(sid),
+            frozen,
+            no_replay_token: tx.body.no_replay_token,
+        };
+        tx.add_operation(Operation::FreezeAsset(FreezeAsset::new(
+            body,
+            &IssuerKeyPair { keypair: kp },
+        )));
+        tx
+    };
+    let check = |ledger: &LedgerState, tx: Transaction| {
+        let effect = TxnEffect::compute_effect(tx).unwrap();
+        ledger.status.check_txn_effects(&effect, &[])
+    };
+
+    // only the issuer can freeze
+    let err = check(&ledger, freeze_tx(&ledger, &holder, true)).unwrap_err();
+    assert_eq!(
+        LedgerError::IssuerMismatch,
+        LedgerError::of(&err.to_string())
+    );
+
+    let tx = freeze_tx(&ledger, &issuer, true);
+    apply_transaction(&mut ledger, tx);
+    assert_eq!(vec![sid], ledger.get_frozen_txos(&new_code));
+
+    // the holder can not spend a frozen output
+    let record = ledger.status.utxos.get(&sid).unwrap().0;
+    let output = AssetRecord::from_template_no_identity_tracing(
+        &mut ledger.get_prng(),
+        &AssetRecordTemplate::with_no_asset_tracing(
+            100,
+            new_code.val,
+            AssetRecordType::NonConfidentialAmount_NonConfidentialAssetType,
+            issuer.get_pk(),
+        ),
+    )
+    .unwrap();
+    let mut transfer = TransferAsset::new(
+        TransferAssetBody::new(
+            &mut ledger.get_prng(),
+            vec![TxoRef::Absolute(sid)],
+            &[AssetRecord::from_open_asset_record_no_asset_tracing(
+                open_blind_asset_record(&record.record, &None, &holder).unwrap(),
+            )],
+            &[output],
+            None,
+            vec![],
+            TransferType::Standard,
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    transfer.sign(&holder);
+    let transfer_tx = Transaction::from_operation(
+        Operation::TransferAsset(transfer),
+        ledger.get_block_commit_count(),
+    );
+    let err = check(&ledger, transfer_tx.clone()).unwrap_err();
+    assert_eq!(LedgerError::AssetFrozen, LedgerError::of(&err.to_string()));
+
+    // unfrozen outputs can be spent again
+    let tx = freeze_tx(&ledger, &issuer, false);
+    apply_transaction(&mut ledger, tx);
+    assert!(ledger.get_frozen_txos(&new_code).is_empty());
+    assert!(check(&ledger, transfer_tx.clone()).is_ok());
+
+    // a freeze earlier in the same block applies as well
+    let pending = TxnEffect::compute_effect(freeze_tx(&ledger, &issuer, true))
+        .unwrap()
+        .freezes;
+    let effect = TxnEffect::compute_effect(transfer_tx).unwrap();
+    let err = ledger
+        .status
+        .check_txn_effects(&effect, &pending)
+        .unwrap_err();
+    assert_eq!(LedgerError::AssetFrozen, LedgerError::of(&err.to_string()));
+
+    // the issuer reclaims the frozen output
+    let tx = freeze_tx(&ledger, &issuer, true);
+    apply_transaction(&mut ledger, tx);
+    let mut tx = Transaction::from_seq_id(ledger.get_block_commit_count());
+    let body = ClawbackAssetBody::new(
+        new_code,
+        sid,
+        record,
+        issuer.get_pk(),
+        tx.body.no_replay_token,
+    )
+    .unwrap();
+    tx.add_operation(Operation::ClawbackAsset(ClawbackAsset::new(
+        body,
+        &IssuerKeyPair { keypair: &issuer },
+    )));
+    let new_sid = apply_transaction(&mut ledger, tx).1[0];
+
+    assert!(!ledger.status.is_unspent_txo(sid));
+    assert!(ledger.get_frozen_txos(&new_code).is_empty());
+    let reclaimed = ledger.status.utxos.get(&new_sid).unwrap().0;
+    assert_eq!(issuer.get_pk(), reclaimed.record.public_key);
+    assert_eq!(XfrAmount::NonConfidential(100), reclaimed.record.amount);
+}
+
+#[test]
+fn test_locked_outputs() {
+    let mut ledger = LedgerState::tmp_ledger();
+    let params = PublicParams::default();
+    let mut prng = ChaChaRng::from_entropy();
+    let issuer = XfrKeyPair::generate(&mut prng);
+    let holder = XfrKeyPair::generate(&mut prng);
+
+    let code = AssetTypeCode::gen_random();
+    let (tx, mut new_code) = create_definition_transaction(
+        &code,
+        &issuer,
+        AssetRules::default(),
+        None,
+        ledger.get_block_commit_count(),
+    )
+    .unwrap();
+    if !is_active(Feature::UtxoAssetPrefix, ledger.get_tendermint_height()) {
+        new_code = code;
+    }
+    apply_transaction(&mut ledger, tx);
+
+    let art = AssetRecordType::NonConfidentialAmount_NonConfidentialAssetType;
+    let tx = create_issuance_txn(&mut ledger, &params, &new_code, 100, 0, art, &issuer);
+    let sid = apply_transaction(&mut ledger, tx).1[0];
+
+    // move `sid` from `kp` to `to`, with `lock` on the output
+    let transfer = |ledger: &mut LedgerState,
+                    sid: TxoSID,
+                    kp: &XfrKeyPair,
+                    to: &XfrKeyPair,
+                    lock: Option<TxoLock>| {
+        let record = ledger.status.utxos.get(&sid).unwrap().0.record;
+        let output = AssetRecord::from_template_no_identity_tracing(
+            &mut ledger.get_prng(),
+            &AssetRecordTemplate::with_no_asset_tracing(
+                100,
+                new_code.val,
+                art,
+                to.get_pk(),
+            ),
+        )
+        .unwrap();
+        let mut body = TransferAssetBody::new(
+            &mut ledger.get_prng(),
+            vec![TxoRef::Absolute(sid)],
+            &[AssetRecord::from_open_asset_record_no_asset_tracing(
+                open_blind_asset_record(&record, &None, kp).unwrap(),
+            )],
+            &[output],
+            None,
+            vec![],
+            TransferType::Standard,
+        )
+        .unwrap();
+        body.outputs[0].set_lock(lock);
+        let mut transfer = TransferAsset::new(body).unwrap();
+        transfer.sign(kp);
+        Transaction::from_operation(
+            Operation::TransferAsset(transfer),
+            ledger.get_block_commit_count(),
+        )
+    };
+
+    let tx = transfer(
+        &mut ledger,
+        sid,
+        &issuer,
+        &holder,
+        Some(TxoLock::Height(10)),
+    );
+    if !is_active(Feature::TxoLock, ledger.get_staking().cur_height()) {
+        let effect = TxnEffect::compute_effect(tx).unwrap();
+        let err = ledger.status.check_txn_effects(&effect, &[]).unwrap_err();
+        assert_eq!(LedgerError::NotAllowed, LedgerError::of(&err.to_string()));
+        return;
+    }
+    let locked = apply_transaction(&mut ledger, tx).1[0];
+    assert_eq!(
+        Some(TxoLock::Height(10)),
+        ledger.status.utxos.get(&locked).unwrap().0.lock
+    );
+
+    let tx = transfer(&mut ledger, locked, &holder, &issuer, None);
+    let effect = TxnEffect::compute_effect(tx).unwrap();
+    ledger.get_staking_mut().set_custom_block_height(9);
+    let err = ledger.status.check_txn_effects(&effect, &[]).unwrap_err();
+    assert_eq!(LedgerError::OutputLocked, LedgerError::of(&err.to_string()));
+    ledger.get_staking_mut().set_custom_block_height(10);
+    assert!(ledger.status.check_txn_effects(&effect, &[]).is_ok());
+
+    // time locks work with the time of the block
+    let tx = transfer(
+        &mut ledger,
+        locked,
+        &holder,
+        &holder,
+        Some(TxoLock::Time(1000)),
+    );
+    let locked = apply_transaction(&mut ledger, tx).1[0];
+    let tx = transfer(&mut ledger, locked, &holder, &issuer, None);
+    let effect = TxnEffect::compute_effect(tx).unwrap();
+    ledger.set_block_time(999);
+    assert!(ledger.status.check_txn_effects(&effect, &[]).is_err());
+    ledger.set_block_time(1000);
+    assert!(ledger.status.check_txn_effects(&effect, &[]).is_ok());
+}
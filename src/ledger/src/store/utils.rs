@@ -73,6 +73,8 @@ pub fn fra_gen_initial_tx(fra_owner_kp: &XfrKeyPair) -> Transaction {
                     id: None,
                     record: ba,
                     lien: None,
+                    version: Default::default(),
+                    lock: None,
                 },
                 None,
             )